@@ -0,0 +1,198 @@
+use crate::cell::{Cell, action_name};
+use crate::neural_network::argmax;
+use macroquad::prelude::*;
+
+// Panel layout (bottom-center): one column per layer, one bar per neuron
+const COLUMN_WIDTH: f32 = 110.0;
+const COLUMN_GAP: f32 = 16.0;
+const PANEL_MARGIN: f32 = 20.0;
+const PADDING: f32 = 10.0;
+const TITLE_HEIGHT: f32 = 24.0;
+const INPUT_ROW_HEIGHT: f32 = 6.0;
+const OUTPUT_ROW_HEIGHT: f32 = 22.0;
+// Tallest the hidden column gets; rows shrink to fit wide hidden layers
+const HIDDEN_COLUMN_HEIGHT: f32 = 180.0;
+
+const POSITIVE_COLOR: Color = Color::new(0.3, 0.9, 0.4, 0.9);
+const NEGATIVE_COLOR: Color = Color::new(0.9, 0.3, 0.3, 0.9);
+const HIDDEN_COLOR: Color = Color::new(0.4, 0.7, 1.0, 0.9);
+const CHOSEN_COLOR: Color = Color::new(1.0, 0.84, 0.0, 1.0);
+
+/// One decision of the selected cell's brain, recomputed every frame from its
+/// current sensors: normalized inputs, hidden activations and output activations
+pub struct BrainInspection {
+    pub cell_id: u64,
+    pub color: Color,
+    pub inputs: Vec<f32>,
+    pub hidden: Vec<f32>,
+    pub outputs: Vec<f32>,
+    pub chosen: usize, // Action the brain picks from these outputs
+}
+
+impl BrainInspection {
+    pub fn of(cell: &Cell) -> Self {
+        let inputs = cell.normalize_sensors().as_slice().to_vec();
+        let (hidden, outputs) = cell.brain.forward_detailed(&inputs);
+        BrainInspection {
+            cell_id: cell.id,
+            color: cell.color,
+            chosen: argmax(&outputs),
+            inputs,
+            hidden,
+            outputs,
+        }
+    }
+
+    // Hidden activations (ReLU, so >= 0) scaled by the strongest one, 0..1
+    fn hidden_levels(&self) -> Vec<f32> {
+        let peak = self.hidden.iter().copied().fold(0.0_f32, f32::max);
+        self.hidden
+            .iter()
+            .map(|&value| if peak > 0.0 { value / peak } else { 0.0 })
+            .collect()
+    }
+
+    // Outputs (unbounded) scaled by the largest magnitude, -1..1
+    fn output_levels(&self) -> Vec<f32> {
+        let peak = self
+            .outputs
+            .iter()
+            .map(|value| value.abs())
+            .fold(0.0_f32, f32::max);
+        self.outputs
+            .iter()
+            .map(|&value| if peak > 0.0 { value / peak } else { 0.0 })
+            .collect()
+    }
+}
+
+// Horizontal bar centered on `center_x`, right for positive values, left for negative
+fn draw_signed_bar(center_x: f32, y: f32, half_width: f32, height: f32, value: f32) {
+    let width = half_width * value.clamp(-1.0, 1.0);
+    let (x, color) = if width >= 0.0 {
+        (center_x, POSITIVE_COLOR)
+    } else {
+        (center_x + width, NEGATIVE_COLOR)
+    };
+    draw_rectangle(x, y, width.abs(), height, color);
+}
+
+pub fn render(inspection: &BrainInspection, font: Option<&Font>) {
+    let hidden_row_height =
+        (HIDDEN_COLUMN_HEIGHT / inspection.hidden.len().max(1) as f32).clamp(1.0, INPUT_ROW_HEIGHT);
+    let column_height = (inspection.inputs.len() as f32 * INPUT_ROW_HEIGHT)
+        .max(inspection.hidden.len() as f32 * hidden_row_height)
+        .max(inspection.outputs.len() as f32 * OUTPUT_ROW_HEIGHT);
+    let width = PADDING * 2.0 + COLUMN_WIDTH * 3.0 + COLUMN_GAP * 2.0;
+    let height = PADDING * 2.0 + TITLE_HEIGHT * 2.0 + column_height;
+    let x = (screen_width() - width) / 2.0;
+    let y = screen_height() - height - PANEL_MARGIN;
+
+    draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.8));
+    draw_rectangle_lines(x, y, width, height, 2.0, inspection.color);
+
+    let text = |label: &str, text_x: f32, text_y: f32, color: Color| {
+        draw_text_ex(
+            label,
+            text_x,
+            text_y,
+            TextParams {
+                font,
+                font_size: 16,
+                color,
+                ..Default::default()
+            },
+        );
+    };
+    text(
+        &format!("Brain of cell #{} [J: close]", inspection.cell_id),
+        x + PADDING,
+        y + PADDING + 14.0,
+        WHITE,
+    );
+
+    let columns = [0, 1, 2].map(|i| x + PADDING + (COLUMN_WIDTH + COLUMN_GAP) * i as f32);
+    let labels_y = y + PADDING + TITLE_HEIGHT + 14.0;
+    text(
+        &format!("Inputs ({})", inspection.inputs.len()),
+        columns[0],
+        labels_y,
+        LIGHTGRAY,
+    );
+    text(
+        &format!("Hidden ({})", inspection.hidden.len()),
+        columns[1],
+        labels_y,
+        LIGHTGRAY,
+    );
+    text("Outputs", columns[2], labels_y, LIGHTGRAY);
+
+    let top = y + PADDING + TITLE_HEIGHT * 2.0;
+    let half_width = COLUMN_WIDTH / 2.0;
+
+    // Inputs are already normalized to -1..1
+    for (i, &value) in inspection.inputs.iter().enumerate() {
+        let row_y = top + INPUT_ROW_HEIGHT * i as f32;
+        draw_signed_bar(
+            columns[0] + half_width,
+            row_y,
+            half_width,
+            INPUT_ROW_HEIGHT - 1.0,
+            value,
+        );
+    }
+
+    for (i, level) in inspection.hidden_levels().into_iter().enumerate() {
+        let row_y = top + hidden_row_height * i as f32;
+        draw_rectangle(
+            columns[1],
+            row_y,
+            COLUMN_WIDTH * level,
+            (hidden_row_height - 1.0).max(1.0),
+            HIDDEN_COLOR,
+        );
+    }
+
+    // Outputs: action name over a bar, the chosen action in gold
+    for (i, level) in inspection.output_levels().into_iter().enumerate() {
+        let row_y = top + OUTPUT_ROW_HEIGHT * i as f32;
+        let color = if i == inspection.chosen {
+            CHOSEN_COLOR
+        } else {
+            WHITE
+        };
+        text(action_name(i as u8), columns[2], row_y + 11.0, color);
+        draw_signed_bar(
+            columns[2] + half_width,
+            row_y + 14.0,
+            half_width,
+            4.0,
+            level,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::VisionMode;
+
+    #[test]
+    fn test_inspection_matches_the_cells_decision() {
+        let cell = Cell::spawn(1000.0, 1000.0, 0, &None, VisionMode::NearestCells);
+        let inspection = BrainInspection::of(&cell);
+        assert_eq!(inspection.inputs.len(), cell.brain.input_size);
+        assert_eq!(inspection.hidden.len(), cell.brain.hidden_size);
+        assert_eq!(inspection.outputs.len(), cell.brain.output_size);
+        assert_eq!(
+            inspection.chosen,
+            cell.brain.get_best_action(&inspection.inputs)
+        );
+
+        let hidden = inspection.hidden_levels();
+        assert!(hidden.iter().all(|level| (0.0..=1.0).contains(level)));
+        let outputs = inspection.output_levels();
+        assert!(outputs.iter().all(|level| (-1.0..=1.0).contains(level)));
+        assert!(outputs.iter().any(|level| level.abs() == 1.0));
+    }
+}
@@ -1,6 +1,64 @@
-use crate::math::lerp;
+use crate::input::InputCaptured;
+use crate::math::{lerp, smoothstep};
 use macroquad::prelude::*;
 
+// Below this angle (radians) the view is treated as unrotated
+const ROTATION_EPSILON: f32 = 1e-4;
+// Zoom range (1.0 = one world unit per pixel), and how close to 1.0 counts as unzoomed
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 4.0;
+const ZOOM_EPSILON: f32 = 1e-3;
+// Smoothstep easing: ease-in progress gained per update, and how close to its target
+// (world units, or radians / zoom for angle and zoom) the camera counts as settled
+const EASE_IN_STEP: f32 = 0.1;
+const SETTLE_DISTANCE: f32 = 0.5;
+const SETTLE_EPSILON: f32 = 1e-3;
+// Ctrl+scroll zoom step per scroll unit (browsers report trackpad pinches as Ctrl+wheel)
+const SCROLL_ZOOM_STEP: f32 = 0.01;
+
+// Button starting a camera drag this frame: right or middle drag pans, and so does
+// a one-finger touch drag (macroquad reports it as a left drag). Mouse left clicks
+// are left to cell selection and the tools.
+fn pan_button_pressed() -> Option<MouseButton> {
+    if is_mouse_button_pressed(MouseButton::Right) {
+        Some(MouseButton::Right)
+    } else if is_mouse_button_pressed(MouseButton::Middle) {
+        Some(MouseButton::Middle)
+    } else if is_mouse_button_pressed(MouseButton::Left) && touches().len() == 1 {
+        Some(MouseButton::Left)
+    } else {
+        None
+    }
+}
+
+// New target zoom after a pinch moved the fingers from `previous_distance` to `distance` apart
+fn pinch_zoom(target_zoom: f32, previous_distance: f32, distance: f32) -> f32 {
+    if previous_distance <= 0.0 || distance <= 0.0 {
+        return target_zoom;
+    }
+    (target_zoom * distance / previous_distance).clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// Region to draw, in unrotated screen coordinates (world position minus camera position).
+/// When the camera is rotated this grows to cover every point the rotated screen can show.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewBounds {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+impl ViewBounds {
+    // True if a point (with a margin around it) overlaps the view
+    pub fn contains(&self, x: f32, y: f32, margin: f32) -> bool {
+        x >= self.min_x - margin
+            && x <= self.max_x + margin
+            && y >= self.min_y - margin
+            && y <= self.max_y + margin
+    }
+}
+
 pub struct Camera {
     pub x: f32,
     pub y: f32,
@@ -8,17 +66,28 @@ pub struct Camera {
     pub target_x: f32,
     pub target_y: f32,
     pub target_angle: f32,
+    pub zoom: f32, // Scale around the screen center (> 1.0 = closer)
+    pub target_zoom: f32,
     pub move_speed: f32,
     pub rotation_speed: f32,
     pub lerp_factor: f32,
-    // Drag state
-    is_dragging: bool,
+    // Ease into each move (see update) instead of starting it at full lerp speed
+    pub smoothstep_easing: bool,
+    ease: f32, // Ease-in progress (0..1) since the camera last settled
+    // Drag state: the button holding the drag, if any
+    drag_button: Option<MouseButton>,
     last_mouse_x: f32,
     last_mouse_y: f32,
     last_drag_delta_x: f32,
     last_drag_delta_y: f32,
     last_scroll_delta_x: f32,
     last_scroll_delta_y: f32,
+    // Two-finger gesture state: finger distance and midpoint on the previous frame
+    last_pinch: Option<(f32, Vec2)>,
+    // A tool uses the scroll wheel this frame (the energy brush radius): no scroll pan or zoom
+    pub scroll_locked: bool,
+    // Panned or zoomed by hand during the last handle_input (breaks the auto-director)
+    user_moved: bool,
 }
 
 impl Camera {
@@ -30,62 +99,91 @@ impl Camera {
             target_x: 0.0,
             target_y: 0.0,
             target_angle: 0.0,
+            zoom: 1.0,
+            target_zoom: 1.0,
             move_speed: 2000.0,
             rotation_speed: 2.0,
             lerp_factor: 0.1,
-            is_dragging: false,
+            smoothstep_easing: false,
+            ease: 0.0,
+            drag_button: None,
             last_mouse_x: 0.0,
             last_mouse_y: 0.0,
             last_drag_delta_x: 0.0,
             last_drag_delta_y: 0.0,
             last_scroll_delta_x: 0.0,
             last_scroll_delta_y: 0.0,
+            last_pinch: None,
+            scroll_locked: false,
+            user_moved: false,
         }
     }
 
-    pub fn handle_input(&mut self, delta_time: f32, skip_mouse_input: bool) {
+    // `captured` is whether a UI component took the pointer this frame (see
+    // World::pointer_capture): no drag starts then, though one in progress continues
+    pub fn handle_input(&mut self, delta_time: f32, captured: InputCaptured) {
+        self.user_moved = false;
         let max_move = 5.0;
 
-        // WASD for movement
+        // WASD for movement (relative to the rotated screen)
+        let step = (self.move_speed * delta_time).min(max_move);
+        let mut pan_x = 0.0;
+        let mut pan_y = 0.0;
         if is_key_down(KeyCode::W) {
-            self.target_y -= (self.move_speed * delta_time).min(max_move);
+            pan_y -= step;
         }
         if is_key_down(KeyCode::S) {
-            self.target_y += (self.move_speed * delta_time).min(max_move);
+            pan_y += step;
         }
         if is_key_down(KeyCode::A) {
-            self.target_x -= (self.move_speed * delta_time).min(max_move);
+            pan_x -= step;
         }
         if is_key_down(KeyCode::D) {
-            self.target_x += (self.move_speed * delta_time).min(max_move);
+            pan_x += step;
         }
+        self.user_moved |= pan_x != 0.0 || pan_y != 0.0;
+        let (pan_x, pan_y) = self.screen_delta_to_world(pan_x, pan_y);
+        self.target_x += pan_x;
+        self.target_y += pan_y;
 
-        // Q and E for rotation
+        // Q and E for rotation (Ctrl+E is the genome export shortcut)
+        let ctrl_down = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
         if is_key_down(KeyCode::Q) {
             self.target_angle -= self.rotation_speed * delta_time;
         }
-        if is_key_down(KeyCode::E) {
+        if is_key_down(KeyCode::E) && !ctrl_down {
             self.target_angle += self.rotation_speed * delta_time;
         }
 
+        // Two fingers: pinch to zoom and drag the midpoint to pan. One finger pans through
+        // macroquad's touch-to-mouse emulation below, so the mouse drag is suspended meanwhile.
+        let pinching = self.handle_pinch(captured);
+        self.user_moved |= pinching;
+
         // Mouse/touch drag for camera movement (direct, with momentum on release)
-        // Skip mouse input if requested (e.g., when clicking on UI elements)
-        if !skip_mouse_input {
+        if !pinching {
             let mouse_pos = mouse_position();
 
-            if is_mouse_button_pressed(MouseButton::Left) {
+            if self.drag_button.is_none()
+                && !captured.is_captured()
+                && let Some(button) = pan_button_pressed()
+            {
                 // Start dragging
-                self.is_dragging = true;
+                self.drag_button = Some(button);
                 self.last_mouse_x = mouse_pos.0;
                 self.last_mouse_y = mouse_pos.1;
                 self.last_drag_delta_x = 0.0;
                 self.last_drag_delta_y = 0.0;
             }
 
-            if is_mouse_button_down(MouseButton::Left) && self.is_dragging {
-                // Calculate delta movement
+            if let Some(button) = self.drag_button
+                && is_mouse_button_down(button)
+            {
+                // Calculate delta movement (screen space, then into world space)
                 let delta_x = (mouse_pos.0 - self.last_mouse_x).clamp(-max_move, max_move);
                 let delta_y = (mouse_pos.1 - self.last_mouse_y).clamp(-max_move, max_move);
+                self.user_moved |= delta_x != 0.0 || delta_y != 0.0;
+                let (delta_x, delta_y) = self.screen_delta_to_world(delta_x, delta_y);
 
                 // Move camera directly in opposite direction (no velocity/interpolation)
                 self.x -= delta_x;
@@ -102,9 +200,11 @@ impl Camera {
                 self.last_mouse_y = mouse_pos.1;
             }
 
-            if is_mouse_button_released(MouseButton::Left) && self.is_dragging {
+            if let Some(button) = self.drag_button
+                && is_mouse_button_released(button)
+            {
                 // Stop dragging and apply momentum
-                self.is_dragging = false;
+                self.drag_button = None;
 
                 // Apply momentum based on last drag delta
                 self.target_x = self.x - self.last_drag_delta_x;
@@ -113,13 +213,24 @@ impl Camera {
         }
 
         // Trackpad/scroll wheel for camera movement (direct, with momentum)
-        let scroll = mouse_wheel();
-        if scroll.0 != 0.0 || scroll.1 != 0.0 {
+        // Ctrl+scroll zooms instead (also what trackpad pinches send in browsers)
+        let scroll = if self.scroll_locked {
+            (0.0, 0.0)
+        } else {
+            mouse_wheel()
+        };
+        self.user_moved |= scroll.0 != 0.0 || scroll.1 != 0.0;
+        if ctrl_down && scroll.1 != 0.0 {
+            self.target_zoom =
+                (self.target_zoom * (1.0 + scroll.1 * SCROLL_ZOOM_STEP)).clamp(MIN_ZOOM, MAX_ZOOM);
+        } else if scroll.0 != 0.0 || scroll.1 != 0.0 {
             // Move camera directly with scroll (reversed for natural scrolling)
             // Scale the scroll values for appropriate speed
             let scroll_speed = 2.0;
             let scroll_delta_x = (scroll.0 * scroll_speed).clamp(-max_move, max_move);
             let scroll_delta_y = (scroll.1 * scroll_speed).clamp(-max_move, max_move);
+            let (scroll_delta_x, scroll_delta_y) =
+                self.screen_delta_to_world(scroll_delta_x, scroll_delta_y);
 
             self.x -= scroll_delta_x;
             self.y -= scroll_delta_y;
@@ -134,10 +245,257 @@ impl Camera {
         }
     }
 
+    // Returns true while two fingers are down
+    fn handle_pinch(&mut self, captured: InputCaptured) -> bool {
+        let touches = touches();
+        if touches.len() < 2 || captured.is_captured() {
+            self.last_pinch = None;
+            return false;
+        }
+
+        let (a, b) = (touches[0].position, touches[1].position);
+        let distance = a.distance(b);
+        let midpoint = (a + b) / 2.0;
+        if let Some((previous_distance, previous_midpoint)) = self.last_pinch {
+            self.target_zoom = pinch_zoom(self.target_zoom, previous_distance, distance);
+
+            let delta = midpoint - previous_midpoint;
+            let (delta_x, delta_y) = self.screen_delta_to_world(delta.x, delta.y);
+            self.x -= delta_x;
+            self.y -= delta_y;
+            self.target_x = self.x;
+            self.target_y = self.y;
+        }
+        self.last_pinch = Some((distance, midpoint));
+        // A second finger ends any one-finger drag without momentum
+        self.drag_button = None;
+        true
+    }
+
+    pub fn is_rotated(&self) -> bool {
+        self.angle.abs() > ROTATION_EPSILON
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        (self.zoom - 1.0).abs() > ZOOM_EPSILON
+    }
+
+    // Rotate a vector by the camera angle (screen rotates around its center)
+    fn rotate(&self, x: f32, y: f32, angle: f32) -> (f32, f32) {
+        let (sin, cos) = angle.sin_cos();
+        (x * cos - y * sin, x * sin + y * cos)
+    }
+
+    /// Visible region in unrotated screen coordinates.
+    /// Unrotated: the screen itself. Rotated: the square around the screen center
+    /// that contains the screen at any angle (half-diagonal in every direction).
+    /// Either way the region is scaled by 1/zoom around the screen center.
+    pub fn view_bounds(&self, screen_w: f32, screen_h: f32) -> ViewBounds {
+        let (half_w, half_h) = if self.is_rotated() {
+            let half_diagonal = (screen_w * screen_w + screen_h * screen_h).sqrt() / 2.0;
+            (half_diagonal, half_diagonal)
+        } else {
+            (screen_w / 2.0, screen_h / 2.0)
+        };
+        ViewBounds {
+            min_x: screen_w / 2.0 - half_w / self.zoom,
+            min_y: screen_h / 2.0 - half_h / self.zoom,
+            max_x: screen_w / 2.0 + half_w / self.zoom,
+            max_y: screen_h / 2.0 + half_h / self.zoom,
+        }
+    }
+
+    /// On-screen pixel position (e.g. the mouse) to world position
+    pub fn screen_to_world(&self, x: f32, y: f32, screen_w: f32, screen_h: f32) -> (f32, f32) {
+        let center_x = screen_w / 2.0;
+        let center_y = screen_h / 2.0;
+        let (rx, ry) = self.rotate(
+            (x - center_x) / self.zoom,
+            (y - center_y) / self.zoom,
+            -self.angle,
+        );
+        (self.x + center_x + rx, self.y + center_y + ry)
+    }
+
+    // Convert a screen-space movement (drag, scroll, WASD) into world space
+    fn screen_delta_to_world(&self, dx: f32, dy: f32) -> (f32, f32) {
+        self.rotate(dx / self.zoom, dy / self.zoom, -self.angle)
+    }
+
+    /// Route subsequent draw calls through the camera rotation and zoom.
+    /// Draw code keeps using unrotated, unzoomed screen coordinates (world - camera position).
+    pub fn begin_world_render(&self) {
+        if !self.is_rotated() && !self.is_zoomed() {
+            return;
+        }
+        let screen_w = screen_width();
+        let screen_h = screen_height();
+        set_camera(&Camera2D {
+            target: vec2(screen_w / 2.0, screen_h / 2.0),
+            zoom: vec2(2.0 * self.zoom / screen_w, 2.0 * self.zoom / screen_h),
+            rotation: self.angle.to_degrees(),
+            ..Default::default()
+        });
+    }
+
+    /// Back to plain screen coordinates for UI
+    pub fn end_world_render(&self) {
+        if self.is_rotated() || self.is_zoomed() {
+            set_default_camera();
+        }
+    }
+
+    /// Whether WASD, a drag, the scroll wheel or a pinch moved the camera this frame
+    pub fn user_moved(&self) -> bool {
+        self.user_moved
+    }
+
     pub fn update(&mut self) {
+        // The plain lerp starts each move at full speed. With smoothstep easing the
+        // factor ramps up from zero over the first 1 / EASE_IN_STEP updates of a
+        // move, so the camera eases in as well as out.
+        let factor = if self.smoothstep_easing {
+            self.ease = if self.is_settled() {
+                0.0
+            } else {
+                (self.ease + EASE_IN_STEP).min(1.0)
+            };
+            self.lerp_factor * smoothstep(0.0, 1.0, self.ease)
+        } else {
+            self.lerp_factor
+        };
+
         // Smoothly interpolate position and angle towards target
-        self.x = lerp(self.x, self.target_x, self.lerp_factor);
-        self.y = lerp(self.y, self.target_y, self.lerp_factor);
-        self.angle = lerp(self.angle, self.target_angle, self.lerp_factor);
+        self.x = lerp(self.x, self.target_x, factor);
+        self.y = lerp(self.y, self.target_y, factor);
+        self.angle = lerp(self.angle, self.target_angle, factor);
+        self.zoom = lerp(self.zoom, self.target_zoom, factor);
+    }
+
+    fn is_settled(&self) -> bool {
+        (self.target_x - self.x).abs() < SETTLE_DISTANCE
+            && (self.target_y - self.y).abs() < SETTLE_DISTANCE
+            && (self.target_angle - self.angle).abs() < SETTLE_EPSILON
+            && (self.target_zoom - self.zoom).abs() < SETTLE_EPSILON
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothstep_easing_starts_gently_and_still_arrives() {
+        let mut linear = Camera::new();
+        let mut eased = Camera::new();
+        eased.smoothstep_easing = true;
+        for camera in [&mut linear, &mut eased] {
+            camera.target_x = 1000.0;
+        }
+
+        linear.update();
+        eased.update();
+        assert_eq!(linear.x, 100.0);
+        // First step: factor 0.1 * smoothstep(0.1) = 0.0028
+        assert!((eased.x - 2.8).abs() < 1e-3, "{}", eased.x);
+
+        for _ in 0..200 {
+            eased.update();
+        }
+        assert!((eased.x - 1000.0).abs() < SETTLE_DISTANCE);
+
+        // Settling resets the ramp for the next move
+        eased.update();
+        assert_eq!(eased.ease, 0.0);
+    }
+
+    // World position to on-screen pixel position, the inverse of screen_to_world
+    // (drawing goes through begin_world_render instead)
+    fn world_to_screen(
+        camera: &Camera,
+        x: f32,
+        y: f32,
+        screen_w: f32,
+        screen_h: f32,
+    ) -> (f32, f32) {
+        let center_x = screen_w / 2.0;
+        let center_y = screen_h / 2.0;
+        let (rx, ry) = camera.rotate(
+            x - camera.x - center_x,
+            y - camera.y - center_y,
+            camera.angle,
+        );
+        (center_x + rx * camera.zoom, center_y + ry * camera.zoom)
+    }
+
+    #[test]
+    fn test_screen_world_round_trip_when_rotated() {
+        let mut camera = Camera::new();
+        camera.x = 1200.0;
+        camera.y = -300.0;
+        camera.angle = 0.7;
+
+        let (sx, sy) = world_to_screen(&camera, 1500.0, 100.0, 800.0, 600.0);
+        let (wx, wy) = camera.screen_to_world(sx, sy, 800.0, 600.0);
+        assert!((wx - 1500.0).abs() < 1e-3);
+        assert!((wy - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_rotation_pivots_on_screen_center() {
+        let mut camera = Camera::new();
+        camera.angle = std::f32::consts::FRAC_PI_2;
+
+        // The point under the screen center stays put
+        let (sx, sy) = world_to_screen(&camera, 400.0, 300.0, 800.0, 600.0);
+        assert!((sx - 400.0).abs() < 1e-3 && (sy - 300.0).abs() < 1e-3);
+
+        // A point right of center ends up below it after a quarter turn
+        let (sx, sy) = world_to_screen(&camera, 500.0, 300.0, 800.0, 600.0);
+        assert!((sx - 400.0).abs() < 1e-3 && (sy - 400.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_rotated_view_bounds_cover_screen_corners() {
+        let mut camera = Camera::new();
+        let unrotated = camera.view_bounds(800.0, 600.0);
+        assert_eq!(unrotated.max_x, 800.0);
+
+        camera.angle = 0.3;
+        let bounds = camera.view_bounds(800.0, 600.0);
+        for (x, y) in [(0.0, 0.0), (800.0, 0.0), (0.0, 600.0), (800.0, 600.0)] {
+            // Unrotated screen position shown at each corner of the rotated screen
+            let (wx, wy) = camera.screen_to_world(x, y, 800.0, 600.0);
+            assert!(bounds.contains(wx - camera.x, wy - camera.y, 1e-3));
+        }
+    }
+
+    #[test]
+    fn test_pinch_zoom_follows_finger_distance() {
+        // Fingers moving twice as far apart double the zoom
+        assert!((pinch_zoom(1.0, 100.0, 200.0) - 2.0).abs() < 1e-5);
+        assert!((pinch_zoom(2.0, 200.0, 100.0) - 1.0).abs() < 1e-5);
+        // Clamped, and degenerate gestures leave it alone
+        assert_eq!(pinch_zoom(3.0, 10.0, 100.0), MAX_ZOOM);
+        assert_eq!(pinch_zoom(0.5, 100.0, 10.0), MIN_ZOOM);
+        assert_eq!(pinch_zoom(1.5, 0.0, 100.0), 1.5);
+    }
+
+    #[test]
+    fn test_zoomed_view_and_round_trip() {
+        let mut camera = Camera::new();
+        camera.x = 100.0;
+        camera.zoom = 2.0;
+
+        // Zoomed in 2x: half the screen in world units, centered
+        let bounds = camera.view_bounds(800.0, 600.0);
+        assert_eq!((bounds.min_x, bounds.max_x), (200.0, 600.0));
+        assert_eq!((bounds.min_y, bounds.max_y), (150.0, 450.0));
+
+        // The screen center stays put, a screen corner maps onto the bounds corner
+        let (wx, wy) = camera.screen_to_world(0.0, 0.0, 800.0, 600.0);
+        assert!((wx - (camera.x + 200.0)).abs() < 1e-3 && (wy - 150.0).abs() < 1e-3);
+        let (sx, sy) = world_to_screen(&camera, wx, wy, 800.0, 600.0);
+        assert!(sx.abs() < 1e-3 && sy.abs() < 1e-3);
     }
 }
@@ -1,17 +1,99 @@
+use crate::camera::ViewBounds;
+use crate::cell_sprites::CellSprites;
+use crate::config::{EnergyConfig, MovementConfig, TemperatureConfig};
 use crate::neural_network::NeuralNetwork;
+use crate::pheromones::PHEROMONE_HALF_SATURATION;
+use crate::temperature::NEUTRAL_TEMPERATURE;
+use crate::vision::{
+    MEMORY_INPUTS, RAY_COUNT, RayHit, RayHitKind, SENSOR_COUNT, SensorInputs, SensorReading,
+    SensorReadings, VisionMode,
+};
+use crate::world::DEFAULT_SENSOR_RANGE;
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
 // Cell behavior constants
+// Energy rates (metabolism, hunger, corpse decay, plague drain) live in EnergyConfig
 const CONSTANT_FORWARD_FORCE: f32 = 0.1;
-const METABOLISM_ENERGY_LOSS: f32 = 0.03;
-const CORPSE_DECAY_RATE: f32 = 0.02;
-// Hunger: metabolism multiplier grows the longer a cell goes without eating.
-// Ramps from 1x up to HUNGER_MAX_MULTIPLIER over HUNGER_RAMP_TICKS ticks.
-const HUNGER_RAMP_TICKS: f32 = 300.0;
-const HUNGER_MAX_MULTIPLIER: f32 = 4.0;
 const GROWTH_AGE_THRESHOLD: f32 = 20.0;
 const ADULT_AGE_THRESHOLD: f32 = 30.0;
 const MIN_RADIUS_PERCENT: f32 = 0.1;
+// Batched rendering drops the heading line of cells smaller than this on screen
+const MIN_DIRECTION_LINE_PIXELS: f32 = 3.0;
+// Structured mutation: chance that each input-to-hidden row is scaled as a whole
+const STRUCTURED_ROW_RATE: f32 = 0.1;
+// Brain outputs, one per action
+pub const ACTION_COUNT: usize = 5;
+// Display names of the brain's actions, by output index
+const ACTION_NAMES: [&str; ACTION_COUNT] =
+    ["no-op", "turn left", "turn right", "forward", "donate"];
+// Donating: the cell pays DONATE_COST, shared evenly among the alive cells within
+// DONATE_RANGE (center to center) whose genome similarity is above DONATE_KIN_SIMILARITY
+pub const DONATE_ACTION: u8 = 4;
+pub const DONATE_COST: f32 = 10.0;
+pub const DONATE_RANGE: f32 = 30.0;
+pub const DONATE_KIN_SIMILARITY: f32 = 0.7;
+// Ticks the pulse ring of a donation takes to spread out to DONATE_RANGE
+pub const DONATE_PULSE_TICKS: f32 = 20.0;
+// Trails: positions kept per cell, sampled every TRAIL_SAMPLE_INTERVAL ticks
+pub const TRAIL_LENGTH: usize = 30;
+pub const TRAIL_SAMPLE_INTERVAL: usize = 4;
+// Trail segments widen with the speed they were travelled at, up to this many units per tick
+const TRAIL_FULL_WIDTH_SPEED: f32 = 10.0;
+const TRAIL_MIN_WIDTH: f32 = 1.0;
+const TRAIL_MAX_WIDTH: f32 = 4.0;
+// Plague: infected cells lose extra energy every tick until the infection runs out
+pub const PLAGUE_DURATION_TICKS: f32 = 600.0;
+// Parasites: energy drained from the host per tick, half of it reaching the parasite
+pub const PARASITE_DRAIN_RATE: f32 = 0.1;
+// Largest random velocity kick per tick an attached parasite gives its host
+pub const PARASITE_JOLT: f32 = 0.05;
+// Ticks a random immigrant stays highlighted after arriving (5 s)
+pub const IMMIGRANT_MARKER_TICKS: f32 = 300.0;
+// Gap between a host's body and the ring drawn for each parasite on it
+const PARASITE_RING_GAP: f32 = 3.0;
+
+// Heritable part of a cell, shared as JSON between sessions.
+// Ephemeral state (position, velocity, energy, age, stats) is not included.
+#[derive(Serialize, Deserialize)]
+struct Genome {
+    generation: usize,
+    vision_mode: VisionMode,
+    brain_tier: usize,
+    brain: NeuralNetwork,
+    color: [f32; 3], // RGB
+    radius: f32,
+    move_probability: f32,
+    turn_probability: f32,
+    speed: f32,
+    turn_rate: f32,
+    energy_chunk_size: f32,
+    species_multiplier: f32,
+    mass: f32,
+    #[serde(default)]
+    cell_type: CellType,
+}
+
+// What the cell body color represents when rendering (cycled with V)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    Inherited,  // Evolved hue (cell.color)
+    Generation, // Blue (oldest lineage) to red (newest generation alive)
+    Energy,     // Cold (empty) to hot (ready to reproduce)
+    Species,    // One hue per brain-distance species (gray until classified)
+}
+
+impl ColorMode {
+    pub fn next(self) -> Self {
+        match self {
+            ColorMode::Inherited => ColorMode::Generation,
+            ColorMode::Generation => ColorMode::Energy,
+            ColorMode::Energy => ColorMode::Species,
+            ColorMode::Species => ColorMode::Inherited,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CellState {
@@ -19,8 +101,19 @@ pub enum CellState {
     Corpse,
 }
 
+// How a cell feeds, inherited by its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CellType {
+    #[default]
+    Normal, // Eats corpses and food pellets
+    Parasite, // Attaches to an alive host and drains it (see World::feed_parasites)
+}
+
 #[derive(Clone)]
 pub struct Cell {
+    // ===== Identity =====
+    pub id: u64, // Unique, never reused; assigned by World (0 until assigned)
+
     // ===== Individual State (not inherited) =====
     pub x: f32,
     pub y: f32,
@@ -30,29 +123,40 @@ pub struct Cell {
     pub angle: f32,
     pub angle_velocity: f32,
     pub state: CellState,
-    pub age: f32, // 0 to 100+, affects energy costs and size
+    pub age: f32,              // 0 to 100+, affects energy costs and size
+    pub infected: Option<f32>, // Remaining plague ticks (contagious on contact), None = healthy
+    pub decayed_energy: f32,   // Energy lost to decay as a corpse (recyclable into food pellets)
+    pub body_energy: f32,      // Energy spent growing while young, held by the body until death
+    pub corpse_age: f32,       // Ticks spent as a corpse (see corpse_max_age)
+    pub host_id: Option<u64>,  // Host a parasite is attached to, None while searching
+    pub immigrant_marker: f32, // Ticks left of the new immigrant highlight (0 = none)
+    pub donate_pulse: f32,     // Ticks left of the last donation's pulse ring (0 = none)
 
     // ===== Stats Tracking =====
     pub total_energy_accumulated: f32, // Total energy gained throughout lifetime
     pub energy_from_cells: f32,        // Energy gained specifically from reaching other cells
+    pub energy_donated: f32,           // Energy given away to kin through the donate action
     pub children_count: usize,         // Number of children produced
     pub generation: usize,             // Generation count (0 for initial, 1+ for descendants)
     pub ticks_since_last_fed: f32,     // Drives hunger multiplier on metabolism
-    pub ticks_since_last_child: f32,   // Cooldown between spawning children
-    pub tracking_score: f32,           // Accumulated reward for turning toward corpses
+    pub lateral_transfers_received: u32, // Weight blocks swapped in from same-species neighbors
+    pub reproduction_cooldown: f32, // Ticks left before the cell may reproduce (set at birth and per child)
+    pub tracking_score: f32,        // Accumulated reward for turning toward corpses
     pub prev_target_angle: Option<f32>, // Previous angle to target (for tracking improvement)
     pub current_target_pos: Option<(f32, f32)>, // Current target position for debugging visualization
     pub current_alignment_score: f32, // Current alignment score: 1.0 at 0°, 0.0 at 90°, -1.0 at 180°
-    pub last_action: Option<u8>, // Last action taken: 0=noop, 1=turn_left, 2=turn_right, 3=forward
+    pub last_action: Option<u8>, // Last action taken: 0=noop, 1=turn_left, 2=turn_right, 3=forward, 4=donate
 
     // ===== Sensors =====
-    // Each sensor returns: (cell_index, angle_from_front, distance, mass, is_alive, energy)
-    // angle_from_front: -180..180 degrees relative to cell's facing direction
-    // distance: 0..200 units
-    // mass: target cell's mass (energy capacity)
-    // is_alive: 1.0 if alive, 0.0 if dead/corpse
-    // energy: target cell's current energy
-    pub nearest_cells: Vec<(usize, f32, f32, f32, f32, f32)>, // (index, angle, distance, mass, is_alive, energy) for 5 nearest cells
+    // Range the sensors were last refreshed with (SimulationConfig::sensor_range),
+    // so normalization matches what was actually sensed
+    pub sensor_range: f32,
+    // Up to SENSOR_COUNT prioritized targets within sensor range (see SensorReading),
+    // stored inline so the per-tick refresh never allocates
+    pub nearest_cells: SensorReadings,
+    // First sensor slot as the brain saw it on the previous tick (normalized angle,
+    // distance, energy, is_alive), fed back as the last inputs
+    pub memory: [f32; MEMORY_INPUTS],
 
     // Center of mass sensors (calculated from nearest_cells)
     pub dead_alive_ratio: f32, // -1.0 = all alive, 1.0 = all dead, 0.0 = balanced
@@ -65,6 +169,20 @@ pub struct Cell {
     pub local_density: usize, // Number of cells in same bucket + neighboring buckets (includes self)
     pub density_penalty: f32, // Penalty applied when cluster > 50% of population cap
 
+    // Pheromone sensor (sampled from World::pheromones)
+    pub pheromone_level: f32, // Concentration in the cell's bucket
+    pub pheromone_angle: f32, // Gradient direction relative to the facing direction (-PI to PI), 0 when flat
+
+    // Local temperature, 0 (cold) to 1 (hot), sampled by World from the temperature zones
+    pub temperature: f32,
+
+    // Raycast vision (only filled when vision_mode is Raycast)
+    pub vision_mode: VisionMode,
+    pub ray_hits: Vec<RayHit>, // One hit per ray, left to right across the frontal arc
+
+    // ===== Trail (only filled while trails are shown) =====
+    pub trail: VecDeque<(f32, f32)>, // Sampled past positions, oldest first
+
     // ===== Neural Network Brain =====
     pub brain: NeuralNetwork,
     pub brain_tier: usize, // 0-3: determines hidden layer width and hue offset
@@ -79,6 +197,15 @@ pub struct Cell {
     pub energy_chunk_size: f32,
     pub species_multiplier: f32,
     pub mass: f32, // Max energy capacity
+    pub cell_type: CellType,
+}
+
+// Name of an action index as stored in Cell::last_action
+pub fn action_name(action: u8) -> &'static str {
+    ACTION_NAMES
+        .get(action as usize)
+        .copied()
+        .unwrap_or("unknown")
 }
 
 impl Cell {
@@ -121,7 +248,7 @@ impl Cell {
     }
 
     // Convert HSV to RGB
-    fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Color {
+    pub fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Color {
         let c = v * s;
         let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
         let m = v - c;
@@ -156,11 +283,15 @@ impl Cell {
         }
     }
 
+    // `cached_brain` is a saved brain World loaded once (cached_best_brains) with its
+    // generation: it is cloned and mutated here, storage is never read. None starts a
+    // fresh random network sized for the tier.
     pub fn spawn(
         world_width: f32,
         world_height: f32,
         brain_tier: usize,
         cached_brain: &Option<(NeuralNetwork, usize)>,
+        vision_mode: VisionMode,
     ) -> Self {
         let speed = rand::gen_range(0.2, 1.0);
         let angle = rand::gen_range(0.0, std::f32::consts::TAU);
@@ -185,12 +316,17 @@ impl Cell {
             let generation_factor = decay_factor.max(0.3);
 
             let mutation_rate = (base_rate * generation_factor).clamp(0.01, 0.05);
-            brain.mutate(mutation_rate);
+            // Weight decay is applied per generation (spawn_child), not to loaded brains
+            brain.mutate(mutation_rate, 0.0);
             (brain, *generation)
         } else {
             // No cached brain, create new random network with tier-appropriate size
             (
-                NeuralNetwork::new_with_multiplier(27, 4, hidden_multiplier),
+                NeuralNetwork::new_with_multiplier(
+                    vision_mode.input_count(),
+                    ACTION_COUNT,
+                    hidden_multiplier,
+                ),
                 0,
             )
         };
@@ -199,6 +335,8 @@ impl Cell {
         let base_hue = (180.0 + brain_tier as f32 * 90.0).rem_euclid(360.0);
 
         Cell {
+            id: 0,
+
             // Individual State
             x: rand::gen_range(0.0, world_width),
             y: rand::gen_range(0.0, world_height),
@@ -209,14 +347,23 @@ impl Cell {
             angle_velocity: rand::gen_range(-0.05, 0.05),
             state: CellState::Alive,
             age: 0.0,
+            infected: None,
+            host_id: None,
+            immigrant_marker: 0.0,
+            donate_pulse: 0.0,
+            decayed_energy: 0.0,
+            body_energy: 0.0,
+            corpse_age: 0.0,
 
             // Stats Tracking
             total_energy_accumulated: 100.0, // Start with initial energy
             energy_from_cells: 0.0,          // No energy from cells yet
+            energy_donated: 0.0,
             children_count: 0,
             generation: loaded_generation, // Use loaded generation from saved brain
             ticks_since_last_fed: 0.0,
-            ticks_since_last_child: 0.0,
+            lateral_transfers_received: 0,
+            reproduction_cooldown: 0.0,
             tracking_score: 0.0,
             prev_target_angle: None,
             current_target_pos: None,
@@ -224,14 +371,22 @@ impl Cell {
             last_action: None,
 
             // Sensors
-            nearest_cells: Vec::new(),
+            sensor_range: DEFAULT_SENSOR_RANGE,
+            nearest_cells: SensorReadings::default(),
+            memory: [-1.0; MEMORY_INPUTS],
             dead_alive_ratio: 0.0,
             dead_center_angle: 0.0,
-            dead_center_distance: crate::world::SENSOR_RANGE, // Default to max range (nothing detected)
+            dead_center_distance: DEFAULT_SENSOR_RANGE, // Default to max range (nothing detected)
             alive_center_angle: 0.0,
-            alive_center_distance: crate::world::SENSOR_RANGE,
+            alive_center_distance: DEFAULT_SENSOR_RANGE,
             local_density: 1,     // Will be updated on first sensor update
             density_penalty: 0.0, // Will be updated on first sensor update
+            pheromone_level: 0.0,
+            pheromone_angle: 0.0,
+            temperature: NEUTRAL_TEMPERATURE,
+            vision_mode,
+            ray_hits: Vec::new(),
+            trail: VecDeque::new(),
 
             // Neural Network Brain
             brain,
@@ -247,10 +402,209 @@ impl Cell {
             energy_chunk_size,
             species_multiplier,
             mass,
+            cell_type: CellType::Normal,
+        }
+    }
+
+    // Body color for a render color mode
+    // max_generation: highest generation in the world, species: cached species id if classified
+    pub fn display_color(
+        &self,
+        mode: ColorMode,
+        max_generation: usize,
+        species: Option<usize>,
+    ) -> Color {
+        match mode {
+            ColorMode::Inherited => self.color,
+            ColorMode::Generation => {
+                let t = self.generation as f32 / max_generation.max(1) as f32;
+                Self::hsv_to_rgb(240.0 * (1.0 - t.min(1.0)), 0.8, 0.9)
+            }
+            ColorMode::Energy => {
+                let t = (self.energy / crate::world::REPRODUCTION_ENERGY_THRESHOLD).clamp(0.0, 1.0);
+                Color::new(t, 0.2 + 0.4 * t * (1.0 - t), 1.0 - t, 1.0)
+            }
+            ColorMode::Species => match species {
+                // Golden angle spacing keeps neighbouring ids visually distinct
+                Some(id) => Self::hsv_to_rgb((id as f32 * 137.508).rem_euclid(360.0), 0.8, 0.9),
+                None => GRAY,
+            },
+        }
+    }
+
+    // Sample the current position into the trail; corpses drop theirs
+    pub fn record_trail(&mut self) {
+        if self.state != CellState::Alive {
+            self.trail.clear();
+            self.trail.shrink_to_fit();
+            return;
+        }
+        if self.trail.len() == TRAIL_LENGTH {
+            self.trail.pop_front();
+        }
+        self.trail.push_back((self.x, self.y));
+    }
+
+    // Line width of a trail segment covering `distance` over one sample interval:
+    // thin where the cell drifted, thick where it sped
+    fn trail_segment_width(distance: f32) -> f32 {
+        let speed = distance / TRAIL_SAMPLE_INTERVAL as f32;
+        crate::math::lerp(
+            TRAIL_MIN_WIDTH,
+            TRAIL_MAX_WIDTH,
+            (speed / TRAIL_FULL_WIDTH_SPEED).min(1.0),
+        )
+    }
+
+    // Fading polyline through the trail and up to the current position, in `color`,
+    // with each segment's width showing how fast it was travelled.
+    // Segments that jump more than half the world (wrapped around an edge) are skipped.
+    pub fn render_trail(
+        &self,
+        camera_x: f32,
+        camera_y: f32,
+        view: &ViewBounds,
+        color: Color,
+        world_width: f32,
+        world_height: f32,
+    ) {
+        if self.state != CellState::Alive || self.trail.is_empty() {
+            return;
+        }
+        // Skip trails whose cell is far off screen (a trail spans at most ~TRAIL_LENGTH samples)
+        let margin = TRAIL_LENGTH as f32 * TRAIL_SAMPLE_INTERVAL as f32 * self.speed * 4.0;
+        if !view.contains(self.x - camera_x, self.y - camera_y, margin) {
+            return;
+        }
+
+        // Consecutive pairs straight from the ring buffer, the last one ending at the cell
+        let segment_count = self.trail.len();
+        let ends = self
+            .trail
+            .iter()
+            .skip(1)
+            .copied()
+            .chain(std::iter::once((self.x, self.y)));
+        for (i, (&(x1, y1), (x2, y2))) in self.trail.iter().zip(ends).enumerate() {
+            if (x2 - x1).abs() > world_width / 2.0 || (y2 - y1).abs() > world_height / 2.0 {
+                continue;
+            }
+            let alpha = 0.6 * (i + 1) as f32 / segment_count as f32;
+            draw_line(
+                x1 - camera_x,
+                y1 - camera_y,
+                x2 - camera_x,
+                y2 - camera_y,
+                Self::trail_segment_width((x2 - x1).hypot(y2 - y1)),
+                Color::new(color.r, color.g, color.b, alpha),
+            );
+        }
+    }
+
+    // Serialize the heritable fields (brain and inherited attributes) to JSON
+    pub fn to_genome_json(&self) -> String {
+        let genome = Genome {
+            generation: self.generation,
+            vision_mode: self.vision_mode,
+            brain_tier: self.brain_tier,
+            brain: self.brain.clone(),
+            color: [self.color.r, self.color.g, self.color.b],
+            radius: self.radius,
+            move_probability: self.move_probability,
+            turn_probability: self.turn_probability,
+            speed: self.speed,
+            turn_rate: self.turn_rate,
+            energy_chunk_size: self.energy_chunk_size,
+            species_multiplier: self.species_multiplier,
+            mass: self.mass,
+            cell_type: self.cell_type,
+        };
+        serde_json::to_string_pretty(&genome).unwrap_or_default()
+    }
+
+    // Rebuild a newborn cell from an exported genome at a random position with fresh energy
+    // Fails if the JSON is invalid, the brain's weight shapes are inconsistent, or the
+    // brain topology doesn't match the genome's vision mode
+    pub fn from_genome_json(
+        json: &str,
+        world_width: f32,
+        world_height: f32,
+    ) -> Result<Cell, String> {
+        let mut genome: Genome =
+            serde_json::from_str(json).map_err(|e| format!("invalid genome JSON: {}", e))?;
+        genome
+            .brain
+            .validate()
+            .map_err(|e| format!("invalid brain: {}", e))?;
+        let expected_inputs = genome.vision_mode.input_count();
+        genome.brain.upgrade_legacy_inputs(expected_inputs);
+        if genome.brain.input_size != expected_inputs {
+            return Err(format!(
+                "brain has {} inputs but {:?} vision needs {}",
+                genome.brain.input_size, genome.vision_mode, expected_inputs
+            ));
         }
+        genome.brain.upgrade_legacy_outputs(ACTION_COUNT);
+        if genome.brain.output_size != ACTION_COUNT {
+            return Err(format!(
+                "brain has {} outputs, expected {}",
+                genome.brain.output_size, ACTION_COUNT
+            ));
+        }
+        if genome.brain_tier > 3 {
+            return Err(format!(
+                "brain tier {} is out of range 0-3",
+                genome.brain_tier
+            ));
+        }
+
+        let mut cell = Cell::spawn(
+            world_width,
+            world_height,
+            genome.brain_tier,
+            &None,
+            genome.vision_mode,
+        );
+        let [r, g, b] = genome.color;
+        cell.generation = genome.generation;
+        cell.brain = genome.brain;
+        cell.color = Color::new(r, g, b, 1.0);
+        cell.radius = genome.radius;
+        cell.move_probability = genome.move_probability;
+        cell.turn_probability = genome.turn_probability;
+        cell.speed = genome.speed;
+        cell.turn_rate = genome.turn_rate;
+        cell.energy_chunk_size = genome.energy_chunk_size;
+        cell.species_multiplier = genome.species_multiplier;
+        cell.mass = genome.mass;
+        cell.cell_type = genome.cell_type;
+        Ok(cell)
     }
 
-    pub fn spawn_child(&self) -> Self {
+    // Relatedness of two cells: cosine similarity of their brain weights (-1..1, 0 across tiers)
+    pub fn genome_similarity(&self, other: &Cell) -> f32 {
+        self.brain.cosine_similarity(&other.brain)
+    }
+
+    // Fraction of the base brain mutation rate this cell's children get (0.3 to 1.0):
+    // full mutation for the first 5 generations, then a gradual decay
+    pub fn mutation_rate_factor(&self) -> f32 {
+        let adjusted_gen = (self.generation as f32 - 5.0).max(0.0);
+        let decay_factor = 1.0 / (1.0 + adjusted_gen * 0.05);
+
+        // Clamp to maintain minimum 30% exploration (never drop below 0.3x base rate)
+        decay_factor.max(0.3)
+    }
+
+    // mutation_multiplier scales the brain mutation rate (see mutation_rate_multiplier),
+    // weight_decay shrinks the inherited weights (see SimulationConfig::weight_decay),
+    // structured also scales whole weight rows (see SimulationConfig::structured_mutation)
+    pub fn spawn_child(
+        &self,
+        mutation_multiplier: f32,
+        weight_decay: f32,
+        structured: bool,
+    ) -> Self {
         let angle = rand::gen_range(0.0, std::f32::consts::TAU);
         let offset = 15.0;
 
@@ -266,19 +620,19 @@ impl Cell {
         // Clone and mutate the parent's brain
         // Hybrid mutation: aggressive for first ~5 generations, then gradual decay
         let base_rate = rand::gen_range(0.01, 0.10);
-
-        // Keep full mutation for first 5 generations, then decay
-        let adjusted_gen = (self.generation as f32 - 5.0).max(0.0);
-        let decay_factor = 1.0 / (1.0 + adjusted_gen * 0.05);
-
-        // Clamp to maintain minimum 30% exploration (never drop below 0.3x base rate)
-        let generation_factor = decay_factor.max(0.3);
-
-        let mutation_rate = (base_rate * generation_factor).clamp(0.01, 0.10);
+        let mutation_rate = (base_rate * self.mutation_rate_factor()).clamp(0.01, 0.10)
+            * mutation_multiplier.max(0.0);
         let mut brain = self.brain.clone();
-        brain.mutate(mutation_rate);
+        if structured {
+            brain.structured_mutate(STRUCTURED_ROW_RATE, mutation_rate);
+            brain.apply_weight_decay(weight_decay);
+        } else {
+            brain.mutate(mutation_rate, weight_decay);
+        }
 
         Cell {
+            id: 0,
+
             // Individual State
             x: self.x + angle.cos() * offset,
             y: self.y + angle.sin() * offset,
@@ -289,14 +643,23 @@ impl Cell {
             angle_velocity: rand::gen_range(-0.05, 0.05),
             state: CellState::Alive,
             age: 0.0, // Start as newborn
+            infected: None,
+            host_id: None,
+            immigrant_marker: 0.0,
+            donate_pulse: 0.0,
+            decayed_energy: 0.0,
+            body_energy: 0.0,
+            corpse_age: 0.0,
 
             // Stats Tracking
             total_energy_accumulated: 0.0, // Start fresh
             energy_from_cells: 0.0,        // No energy from cells yet
+            energy_donated: 0.0,
             children_count: 0,
             generation: self.generation + 1, // Increment generation
             ticks_since_last_fed: 0.0,
-            ticks_since_last_child: 0.0,
+            lateral_transfers_received: 0,
+            reproduction_cooldown: 0.0,
             tracking_score: 0.0,
             prev_target_angle: None,
             current_target_pos: None,
@@ -304,14 +667,22 @@ impl Cell {
             last_action: None,
 
             // Sensors
-            nearest_cells: Vec::new(),
+            sensor_range: self.sensor_range,
+            nearest_cells: SensorReadings::default(),
+            memory: [-1.0; MEMORY_INPUTS],
             dead_alive_ratio: 0.0,
             dead_center_angle: 0.0,
-            dead_center_distance: crate::world::SENSOR_RANGE, // Default to max range (nothing detected)
+            dead_center_distance: self.sensor_range, // Default to max range (nothing detected)
             alive_center_angle: 0.0,
-            alive_center_distance: crate::world::SENSOR_RANGE,
+            alive_center_distance: self.sensor_range,
             local_density: 1,     // Will be updated on first sensor update
             density_penalty: 0.0, // Will be updated on first sensor update
+            pheromone_level: 0.0,
+            pheromone_angle: 0.0,
+            temperature: NEUTRAL_TEMPERATURE,
+            vision_mode: self.vision_mode,
+            ray_hits: Vec::new(),
+            trail: VecDeque::new(),
 
             // Neural Network Brain (inherited and mutated)
             brain,
@@ -327,48 +698,55 @@ impl Cell {
             energy_chunk_size: Self::mutate(self.energy_chunk_size, 45.0, 55.0),
             species_multiplier: Self::mutate(self.species_multiplier, 0.9, 2.0),
             mass: Self::mutate(self.mass, 180.0, 220.0),
+            cell_type: self.cell_type,
         }
     }
 
+    // Distance: 0..sensor_range -> 1..-1 (closer = higher value). Clamped so the
+    // "nothing detected" max-range value always reads as the far edge
+    fn normalize_distance(&self, distance: f32) -> f32 {
+        let range = self.sensor_range.max(f32::EPSILON);
+        ((range - distance) / range).clamp(0.0, 1.0) * 2.0 - 1.0
+    }
+
     // Normalize sensor inputs for neural network
-    // Each sensor returns 4 values: angle, distance, mass, is_alive
+    // NearestCells: each sensor returns 4 values: angle, distance, energy, is_alive
+    // Raycast: each ray returns 2 values: distance, kind of hit
     // Plus 1 value for current energy level
     // Plus 5 values for center of mass (dead/alive ratio, dead angle/distance, alive angle/distance)
     // Plus 1 value for local density (1 / nb_cells in bucket cluster)
-    // Total: 5 sensors × 4 values + 1 energy + 5 center of mass + 1 density = 27 inputs
-    fn normalize_sensors(&self) -> Vec<f32> {
-        use crate::world::{DEPLETED_CELL_ENERGY, REPRODUCTION_ENERGY_THRESHOLD, SENSOR_RANGE};
-        const MAX_MASS: f32 = 220.0; // Maximum mass value from spawn()
-        let mut inputs = Vec::with_capacity(27);
-
-        for i in 0..5 {
-            if i < self.nearest_cells.len() {
-                let (_index, angle, distance, mass, is_alive, _energy) = self.nearest_cells[i];
-
-                // Angle: -PI..PI -> -1..1
-                let normalized_angle = angle / std::f32::consts::PI;
-
-                // Distance: 0..SENSOR_RANGE -> -1..1 (closer = higher value)
-                let normalized_distance = (SENSOR_RANGE - distance) / SENSOR_RANGE;
-                let normalized_distance = normalized_distance * 2.0 - 1.0;
-
-                // Mass: 0..MAX_MASS -> -1..1 (normalized around expected range)
-                let normalized_mass = (mass / MAX_MASS) * 2.0 - 1.0;
-
-                // Is alive: 0 or 1 -> -1 or 1 (dead = -1, alive = 1)
-                let normalized_alive = is_alive * 2.0 - 1.0;
+    // Plus 2 values for pheromones (concentration, gradient direction)
+    // Plus 1 value for local temperature
+    // Plus 4 values remembering the previous tick's first sensor slot (see memory)
+    // Total: 5 sensors × 4 values + 14 = 34 inputs, or 7 rays × 2 values + 14 = 28 inputs
+    // Built on the stack: this runs for every alive cell every tick
+    pub fn normalize_sensors(&self) -> SensorInputs {
+        use crate::world::{DEPLETED_CELL_ENERGY, REPRODUCTION_ENERGY_THRESHOLD};
+        let mut inputs = SensorInputs::default();
+
+        if self.vision_mode == VisionMode::Raycast {
+            for i in 0..RAY_COUNT {
+                match self.ray_hits.get(i) {
+                    Some(hit) if hit.kind != RayHitKind::Nothing => {
+                        inputs.push(self.normalize_distance(hit.distance));
+                        inputs.push(hit.kind.as_input());
+                    }
+                    _ => {
+                        // Nothing hit (or sensors not updated yet)
+                        inputs.push(-1.0); // distance (far away)
+                        inputs.push(RayHitKind::Nothing.as_input());
+                    }
+                }
+            }
+        }
 
-                inputs.push(normalized_angle);
-                inputs.push(normalized_distance);
-                inputs.push(normalized_mass);
-                inputs.push(normalized_alive);
-            } else {
-                // No cell detected in this sensor slot
-                // Push default values (-1 for "nothing detected")
-                inputs.push(-1.0); // angle
-                inputs.push(-1.0); // distance (far away)
-                inputs.push(-1.0); // mass (no target)
-                inputs.push(-1.0); // is_alive (no target = dead)
+        let nearest_sensor_count = match self.vision_mode {
+            VisionMode::NearestCells => SENSOR_COUNT,
+            VisionMode::Raycast => 0,
+        };
+        for i in 0..nearest_sensor_count {
+            for value in self.sensor_slot_inputs(self.nearest_cells.get(i)) {
+                inputs.push(value);
             }
         }
 
@@ -387,33 +765,75 @@ impl Cell {
         // Angle: -PI..PI -> -1..1
         let normalized_dead_angle = self.dead_center_angle / std::f32::consts::PI;
         inputs.push(normalized_dead_angle);
-        // Distance: 0..SENSOR_RANGE -> 1..-1 (closer = higher value)
-        let normalized_dead_distance = (SENSOR_RANGE - self.dead_center_distance) / SENSOR_RANGE;
-        let normalized_dead_distance = normalized_dead_distance * 2.0 - 1.0;
-        inputs.push(normalized_dead_distance);
+        inputs.push(self.normalize_distance(self.dead_center_distance));
 
         // Alive cells center of mass
         // Angle: -PI..PI -> -1..1
         let normalized_alive_angle = self.alive_center_angle / std::f32::consts::PI;
         inputs.push(normalized_alive_angle);
-        // Distance: 0..SENSOR_RANGE -> 1..-1 (closer = higher value)
-        let normalized_alive_distance = (SENSOR_RANGE - self.alive_center_distance) / SENSOR_RANGE;
-        let normalized_alive_distance = normalized_alive_distance * 2.0 - 1.0;
-        inputs.push(normalized_alive_distance);
+        inputs.push(self.normalize_distance(self.alive_center_distance));
 
         // Local density: 1 / nb_cells (higher value = less crowded)
         // Ensures value is always > 0 and <= 1.0
         let density_input = 1.0 / self.local_density.max(1) as f32;
         inputs.push(density_input);
 
+        // Pheromones: concentration 0..inf -> -1..1 (saturating), gradient -PI..PI -> -1..1
+        let level = self.pheromone_level.max(0.0);
+        inputs.push(level / (level + PHEROMONE_HALF_SATURATION) * 2.0 - 1.0);
+        inputs.push(self.pheromone_angle / std::f32::consts::PI);
+
+        // Temperature: cold 0..1 hot -> -1..1
+        inputs.push(self.temperature * 2.0 - 1.0);
+
+        // Previous tick's first sensor slot, already normalized
+        for value in self.memory {
+            inputs.push(value);
+        }
+
         inputs
     }
 
+    // Normalized inputs of one nearest-cells sensor slot: angle, distance, energy, is_alive
+    fn sensor_slot_inputs(&self, reading: Option<&SensorReading>) -> [f32; MEMORY_INPUTS] {
+        const MAX_MASS: f32 = 220.0; // Maximum mass value from spawn(), so the most energy a target holds
+        let Some(reading) = reading else {
+            // No cell detected in this sensor slot: -1 for "nothing detected"
+            // (angle, distance far away, no energy, no target = dead)
+            return [-1.0; MEMORY_INPUTS];
+        };
+        [
+            // Angle: -PI..PI -> -1..1
+            reading.angle / std::f32::consts::PI,
+            self.normalize_distance(reading.distance),
+            // Current energy, not mass, so a depleted corpse reads differently
+            // from a fresh one: 0..MAX_MASS -> -1..1
+            (reading.energy / MAX_MASS).clamp(0.0, 1.0) * 2.0 - 1.0,
+            // Is alive: dead = -1, alive = 1
+            if reading.is_alive { 1.0 } else { -1.0 },
+        ]
+    }
+
+    // Age-based energy depletion per tick for cells over age 35
+    // Drain starts at 0 at age 35 and grows linearly: (age/100 - 0.35) per tick
+    pub fn age_energy_drain(&self) -> f32 {
+        if self.age > 35.0 {
+            self.age / 100.0 - 0.35
+        } else {
+            0.0
+        }
+    }
+
+    // Died during the last update: corpses age by 1 per tick, starting on that one
+    pub fn just_died(&self) -> bool {
+        self.state == CellState::Corpse && self.corpse_age == 1.0
+    }
+
     // Make a decision using the neural network
-    // Actions: 0 = no-op, 1 = turn_left, 2 = turn_right, 3 = forward
+    // Actions: 0 = no-op, 1 = turn_left, 2 = turn_right, 3 = forward, 4 = donate
     fn decide_action(&mut self) {
         let inputs = self.normalize_sensors();
-        let action = self.brain.get_best_action(&inputs);
+        let action = self.brain.get_best_action(inputs.as_slice());
 
         // Store the action taken for reward calculation
         self.last_action = Some(action as u8);
@@ -423,11 +843,23 @@ impl Cell {
             1 => self.turn_left(),
             2 => self.turn_right(),
             3 => self.forward(),
+            // Donating needs the neighbors: World::share_donations pays it out
+            4 => {}
             _ => {} // Should never happen, but handle gracefully
         }
     }
 
-    pub fn update(&mut self, world_width: f32, world_height: f32) {
+    // metabolism_multiplier scales the base metabolism (e.g. day/night cycle);
+    // temperature turns the cell's local temperature into metabolism and decay factors
+    pub fn update(
+        &mut self,
+        world_width: f32,
+        world_height: f32,
+        metabolism_multiplier: f32,
+        energy: &EnergyConfig,
+        movement: &MovementConfig,
+        temperature: &TemperatureConfig,
+    ) {
         // State transition: Alive -> Corpse when energy depleted
         if self.state == CellState::Alive && self.energy <= 0.0 {
             self.state = CellState::Corpse;
@@ -438,58 +870,70 @@ impl Cell {
             self.age += 0.1;
         }
 
+        self.immigrant_marker = (self.immigrant_marker - 1.0).max(0.0);
+        self.donate_pulse = (self.donate_pulse - 1.0).max(0.0);
+
+        // Plague runs its course on alive cells and corpses alike (corpses stay contagious)
+        if let Some(remaining) = self.infected {
+            if self.state == CellState::Alive {
+                self.energy -= energy.plague_energy_drain;
+            }
+            self.infected = (remaining > 1.0).then_some(remaining - 1.0);
+        }
+
         // Passive energy loss for all cells
         if self.state == CellState::Alive {
             // Hunger: metabolism scales up the longer a cell goes without eating,
             // pressuring cells to actively seek food rather than drift passively.
             self.ticks_since_last_fed += 1.0;
-            self.ticks_since_last_child += 1.0;
+            self.reproduction_cooldown = (self.reproduction_cooldown - 1.0).max(0.0);
             let hunger_multiplier = (1.0
-                + (self.ticks_since_last_fed / HUNGER_RAMP_TICKS) * (HUNGER_MAX_MULTIPLIER - 1.0))
-                .min(HUNGER_MAX_MULTIPLIER);
-            self.energy -= METABOLISM_ENERGY_LOSS * hunger_multiplier;
-
-            // Age-based energy depletion for cells over age 35
-            // Drain starts at 0 at age 35 and grows linearly: (age/100 - 0.35) per tick
-            if self.age > 35.0 {
-                let age_depletion = self.age / 100.0 - 0.35;
-                self.energy -= age_depletion;
-            }
+                + (self.ticks_since_last_fed / energy.hunger_ramp_ticks)
+                    * (energy.hunger_max_multiplier - 1.0))
+                .min(energy.hunger_max_multiplier);
+            // Bigger brains cost more (see EnergyConfig::brain_cost_per_weight)
+            self.energy -= energy.base_metabolism(self.brain.parameter_count())
+                * hunger_multiplier
+                * metabolism_multiplier
+                * temperature.metabolism_multiplier(self.temperature);
+
+            self.energy -= self.age_energy_drain();
 
             // Use neural network to decide action instead of random movement
             self.decide_action();
 
+            // Remember what the first sensor slot showed for the next decision
+            self.memory = self.sensor_slot_inputs(self.nearest_cells.first());
+
             // Reward alignment toward targets each tick.
             // Priority: dead cells (corpses) first, then weaker live cells if no corpses.
             // angle_from_front is already atan2(target)-cell_angle wrapped to -PI..PI.
             // Logarithmic reward within ±90°: precision near 0° is worth more.
             // Extreme quadratic penalty beyond ±90°.
 
-            // Try to find a corpse first (highest mass corpse)
+            // Try to find a corpse first (the one with the most energy left)
             let corpse_data = self
                 .nearest_cells
                 .iter()
-                .filter(|&&(_, _, _, _, is_alive, _)| is_alive == 0.0)
-                .max_by(|&&(_, _, _, mass_a, _, _), &&(_, _, _, mass_b, _, _)| {
-                    mass_a
-                        .partial_cmp(&mass_b)
+                .filter(|reading| !reading.is_alive)
+                .max_by(|a, b| {
+                    a.energy
+                        .partial_cmp(&b.energy)
                         .unwrap_or(std::cmp::Ordering::Equal)
                 })
-                .map(|&(_, angle, distance, _, _, _)| (angle, distance));
+                .map(|reading| (reading.angle, reading.distance));
 
             // If no corpse, find a weaker live cell (lowest energy alive cell with energy < self.energy)
             let weak_prey_data = if corpse_data.is_none() {
                 self.nearest_cells
                     .iter()
-                    .filter(|&&(_, _, _, _, is_alive, energy)| {
-                        is_alive == 1.0 && energy < self.energy
-                    })
-                    .min_by(|&&(_, _, _, _, _, energy_a), &&(_, _, _, _, _, energy_b)| {
-                        energy_a
-                            .partial_cmp(&energy_b)
+                    .filter(|reading| reading.is_alive && reading.energy < self.energy)
+                    .min_by(|a, b| {
+                        a.energy
+                            .partial_cmp(&b.energy)
                             .unwrap_or(std::cmp::Ordering::Equal)
                     })
-                    .map(|&(_, angle, distance, _, _, _)| (angle, distance))
+                    .map(|reading| (reading.angle, reading.distance))
             } else {
                 None
             };
@@ -568,8 +1012,14 @@ impl Cell {
                 self.prev_target_angle = None;
             }
         } else if self.state == CellState::Corpse {
-            // Corpse decay: lose energy per tick
-            self.energy -= CORPSE_DECAY_RATE;
+            // Corpse decay: lose energy per tick. Only energy the corpse still held
+            // counts as decayed (recyclable), not the slide below zero.
+            // Hot zones rot corpses faster, cold zones preserve them.
+            let decay_rate =
+                energy.corpse_decay_rate * temperature.corpse_decay_multiplier(self.temperature);
+            self.decayed_energy += decay_rate.min(self.energy.max(0.0));
+            self.energy -= decay_rate;
+            self.corpse_age += 1.0;
         }
 
         // Constant slow forward movement for alive cells
@@ -579,26 +1029,63 @@ impl Cell {
         }
 
         // Apply mass-based velocity slowdown
-        // Higher mass = slower movement (mass acts as inertia/drag)
-        let mass_factor = 200.0 / self.mass; // Normalize around 200
-        let slowdown = mass_factor.max(0.5); // Don't slow down too much
+        let slowdown = self.mass_slowdown(movement);
 
         // Always apply velocity (drifting continues even when dead)
         self.x += self.velocity_x * slowdown;
         self.y += self.velocity_y * slowdown;
         self.angle += self.angle_velocity;
 
-        // Boundary wrapping (inline instead of separate pass)
-        self.x = self.x.rem_euclid(world_width);
-        self.y = self.y.rem_euclid(world_height);
+        // Boundary wrapping (inline instead of separate pass), or bounce off the walls
+        if movement.wrapping {
+            self.x = self.x.rem_euclid(world_width);
+            self.y = self.y.rem_euclid(world_height);
+        } else {
+            if !(0.0..=world_width).contains(&self.x) {
+                self.x = self.x.clamp(0.0, world_width);
+                self.velocity_x = -self.velocity_x;
+            }
+            if !(0.0..=world_height).contains(&self.y) {
+                self.y = self.y.clamp(0.0, world_height);
+                self.velocity_y = -self.velocity_y;
+            }
+        }
+
+        // Friction, once per tick after moving. forward() adds to the velocity
+        // rather than setting it, so momentum builds up to a terminal velocity
+        // (see MovementConfig).
+        let retained = 1.0 - movement.friction.clamp(0.0, 1.0);
+        self.velocity_x *= retained;
+        self.velocity_y *= retained;
+        self.angle_velocity *= 1.0 - movement.angular_friction.clamp(0.0, 1.0);
+
+        // Speed limits (see MovementConfig), keeping the direction of motion
+        let velocity = self.velocity_x.hypot(self.velocity_y);
+        let max_velocity = movement.max_velocity.max(0.0);
+        if velocity > max_velocity {
+            let scale = max_velocity / velocity;
+            self.velocity_x *= scale;
+            self.velocity_y *= scale;
+        }
+        let max_turn = movement.max_angular_velocity.max(0.0);
+        self.angle_velocity = self.angle_velocity.clamp(-max_turn, max_turn);
+    }
+
+    // Fresh start for a cell spawned outside reproduction: the configured starting
+    // energy, counted as accumulated like the initial 100 of Cell::spawn
+    pub fn set_starting_energy(&mut self, energy: &EnergyConfig) {
+        self.energy = energy.starting_energy(self.mass);
+        self.total_energy_accumulated = self.energy;
+    }
 
-        self.velocity_y *= 0.95; // Friction
-        self.velocity_x *= 0.95; // Friction
-        self.angle_velocity *= 0.9; // Rotational friction
+    // Fraction of its velocity a cell actually moves by each tick.
+    // Higher mass = slower movement (mass acts as inertia/drag).
+    pub fn mass_slowdown(&self, movement: &MovementConfig) -> f32 {
+        (movement.mass_reference / self.mass).max(movement.min_mass_slowdown)
     }
 
     // Called when cell gains energy (from feeding)
-    // For young cells (age < GROWTH_AGE_THRESHOLD), energy is lost to growth
+    // For young cells (age < GROWTH_AGE_THRESHOLD), energy goes into the body (body_energy)
     pub fn gain_energy(&mut self, amount: f32) {
         // Reset hunger counter — cell has found food
         self.ticks_since_last_fed = 0.0;
@@ -610,8 +1097,9 @@ impl Cell {
         self.energy_from_cells += amount;
 
         if self.age < GROWTH_AGE_THRESHOLD {
-            // Young cells: energy goes to growth, not stored
-            // Energy is simply discarded (used for growing)
+            // Young cells: energy goes to growth, not stored. The body keeps it,
+            // and a strict-conservation corpse gets it back as food
+            self.body_energy += amount;
             return;
         }
 
@@ -619,43 +1107,41 @@ impl Cell {
         self.energy += amount;
     }
 
-    // Calculate cell's comprehensive fitness score
-    // Priority: children count (primary), energy from cells (equally important), age (secondary)
-    pub fn score(&self) -> f32 {
-        // Children count: 100 points per child (primary metric)
-        let children_score = self.children_count as f32 * 100.0;
-
-        // Energy from cells: 1 point per energy (equally important as children)
-        let energy_score = self.energy_from_cells;
-
-        // Age: 10 points per age unit (secondary metric - older cells have survived longer)
-        let age_score = self.age * 10.0;
-
-        // Tracking: reward accumulated angle-improvement toward corpses.
-        // Scale by 50 so ~100 ticks of good tracking ≈ half a child's worth of score.
-        let tracking = self.tracking_score * 50.0;
-
-        // Density penalty: discourage overcrowding (penalty applied in world.rs when cluster > 50% of cap)
-        let density_penalty_score = self.density_penalty;
+    pub fn is_parasite(&self) -> bool {
+        self.cell_type == CellType::Parasite
+    }
 
-        children_score + energy_score + age_score + tracking - density_penalty_score
+    // Alive parasites are drawn as a thin ring: around their host's body when attached
+    // (host_radius, they ride on its center), around their own small body otherwise
+    pub fn render_parasite(
+        &self,
+        camera_x: f32,
+        camera_y: f32,
+        view: &ViewBounds,
+        color: Color,
+        host_radius: Option<f32>,
+    ) {
+        let screen_x = self.x - camera_x;
+        let screen_y = self.y - camera_y;
+        let radius = match host_radius {
+            Some(host_radius) => host_radius + PARASITE_RING_GAP,
+            None => self.get_current_radius() * 0.5,
+        };
+        if !view.contains(screen_x, screen_y, radius) {
+            return;
+        }
+        draw_circle_lines(screen_x, screen_y, radius, 1.5, color);
     }
 
-    pub fn render(&self, camera_x: f32, camera_y: f32) {
+    // color: body color for the active render color mode (see display_color)
+    pub fn render(&self, camera_x: f32, camera_y: f32, view: &ViewBounds, color: Color) {
         let screen_x = self.x - camera_x;
         let screen_y = self.y - camera_y;
         let current_radius = self.get_current_radius();
 
         // Viewport culling: only render if cell is visible on screen
         let margin = current_radius * 3.0; // Increased margin for halo effect
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-
-        if screen_x < -margin
-            || screen_x > screen_w + margin
-            || screen_y < -margin
-            || screen_y > screen_h + margin
-        {
+        if !view.contains(screen_x, screen_y, margin) {
             return; // Cell is outside viewport, skip rendering
         }
 
@@ -663,26 +1149,25 @@ impl Cell {
         let nearest_corpse = self
             .nearest_cells
             .iter()
-            .filter(|&&(_, _, _, _, is_alive, _)| is_alive == 0.0)
-            .min_by(|&&(_, _, dist_a, _, _, _), &&(_, _, dist_b, _, _, _)| {
-                dist_a
-                    .partial_cmp(&dist_b)
+            .filter(|reading| !reading.is_alive)
+            .min_by(|a, b| {
+                a.distance
+                    .partial_cmp(&b.distance)
                     .unwrap_or(std::cmp::Ordering::Equal)
             });
 
         // Calculate blob deformation parameters
         let deformation_threshold = current_radius * 4.0; // Start deforming at 4x radius distance
-        let (should_deform, corpse_angle, deform_strength) =
-            if let Some(&(_, angle, distance, _, _, _)) = nearest_corpse {
-                if self.state == CellState::Alive && distance < deformation_threshold {
-                    let strength = (1.0 - (distance / deformation_threshold)).max(0.0);
-                    (true, angle, strength)
-                } else {
-                    (false, 0.0, 0.0)
-                }
+        let (should_deform, corpse_angle, deform_strength) = if let Some(corpse) = nearest_corpse {
+            if self.state == CellState::Alive && corpse.distance < deformation_threshold {
+                let strength = (1.0 - (corpse.distance / deformation_threshold)).max(0.0);
+                (true, corpse.angle, strength)
             } else {
                 (false, 0.0, 0.0)
-            };
+            }
+        } else {
+            (false, 0.0, 0.0)
+        };
 
         // Draw gradient halo effect (only for alive cells and when glow is enabled)
         if self.state == CellState::Alive {
@@ -692,7 +1177,7 @@ impl Cell {
                 let halo_radius = current_radius * (1.0 + t * 1.2);
                 let alpha = (1.0 - t) * 0.3; // Fade out from 30% to 0%
 
-                let halo_color = Color::new(self.color.r, self.color.g, self.color.b, alpha);
+                let halo_color = Color::new(color.r, color.g, color.b, alpha);
 
                 draw_circle(screen_x, screen_y, halo_radius, halo_color);
             }
@@ -717,7 +1202,7 @@ impl Cell {
                 let blob_radius = current_radius * 0.3 * (1.0 + directional_strength * 0.5);
 
                 let blob_alpha = 0.6 * directional_strength;
-                let blob_color = Color::new(self.color.r, self.color.g, self.color.b, blob_alpha);
+                let blob_color = Color::new(color.r, color.g, color.b, blob_alpha);
 
                 draw_circle(blob_x, blob_y, blob_radius, blob_color);
             }
@@ -729,17 +1214,12 @@ impl Cell {
             for i in 0..3 {
                 let aa_radius = current_radius + (i as f32 * 0.5);
                 let aa_alpha = if i == 0 { 1.0 } else { 0.3 / (i as f32) };
-                let aa_color = Color::new(self.color.r, self.color.g, self.color.b, aa_alpha);
+                let aa_color = Color::new(color.r, color.g, color.b, aa_alpha);
                 draw_circle(screen_x, screen_y, aa_radius, aa_color);
             }
         } else {
             // Corpse cells are grayed out with halo effect
-            let gray_color = Color::new(
-                self.color.r * 0.3,
-                self.color.g * 0.3,
-                self.color.b * 0.3,
-                1.0,
-            );
+            let gray_color = Color::new(color.r * 0.3, color.g * 0.3, color.b * 0.3, 1.0);
 
             // Subtle halo for corpses
             for i in 0..3 {
@@ -762,6 +1242,98 @@ impl Cell {
             let end_y = screen_y + self.angle.sin() * line_length;
             draw_line(screen_x, screen_y, end_x, end_y, 2.0, WHITE);
         }
+
+        // Sickly green ring around plague carriers
+        if self.infected.is_some() {
+            draw_circle_lines(
+                screen_x,
+                screen_y,
+                current_radius + 3.0,
+                2.0,
+                Color::new(0.4, 1.0, 0.2, 0.8),
+            );
+        }
+
+        if let Some(marker) = self.immigrant_marker_color() {
+            draw_circle_lines(screen_x, screen_y, current_radius + 6.0, 2.0, marker);
+        }
+
+        if let Some((radius, pulse)) = self.donate_pulse_ring(current_radius) {
+            draw_circle_lines(screen_x, screen_y, radius, 1.5, pulse);
+        }
+    }
+
+    // Sprite version of render() for large populations: one textured quad per
+    // shape from the shared atlas. Skips the blob deformation, and the direction
+    // line once the cell is smaller than `MIN_DIRECTION_LINE_PIXELS` on screen.
+    pub fn render_batched(
+        &self,
+        sprites: &CellSprites,
+        camera_x: f32,
+        camera_y: f32,
+        view: &ViewBounds,
+        color: Color,
+        zoom: f32,
+    ) {
+        let screen_x = self.x - camera_x;
+        let screen_y = self.y - camera_y;
+        let current_radius = self.get_current_radius();
+        if !view.contains(screen_x, screen_y, current_radius * 3.0) {
+            return;
+        }
+
+        if self.state == CellState::Alive {
+            sprites.draw_alive(screen_x, screen_y, current_radius, color);
+            if current_radius * zoom >= MIN_DIRECTION_LINE_PIXELS {
+                sprites.draw_line(
+                    screen_x,
+                    screen_y,
+                    current_radius * 1.5,
+                    self.angle,
+                    2.0,
+                    WHITE,
+                );
+            }
+        } else {
+            let gray_color = Color::new(color.r * 0.3, color.g * 0.3, color.b * 0.3, 1.0);
+            sprites.draw_corpse(screen_x, screen_y, current_radius, gray_color);
+        }
+
+        if self.infected.is_some() {
+            sprites.draw_ring(
+                screen_x,
+                screen_y,
+                current_radius + 3.0,
+                Color::new(0.4, 1.0, 0.2, 0.8),
+            );
+        }
+
+        if let Some(marker) = self.immigrant_marker_color() {
+            sprites.draw_ring(screen_x, screen_y, current_radius + 6.0, marker);
+        }
+
+        if let Some((radius, pulse)) = self.donate_pulse_ring(current_radius) {
+            sprites.draw_ring(screen_x, screen_y, radius, pulse);
+        }
+    }
+
+    // Sky blue ring around new immigrants, fading out with the marker
+    fn immigrant_marker_color(&self) -> Option<Color> {
+        (self.immigrant_marker > 0.0).then(|| {
+            let alpha = 0.9 * (self.immigrant_marker / IMMIGRANT_MARKER_TICKS).min(1.0);
+            Color::new(0.5, 0.8, 1.0, alpha)
+        })
+    }
+
+    // Golden ring spreading from the body out to DONATE_RANGE after a donation,
+    // fading as it goes: (radius, color)
+    fn donate_pulse_ring(&self, current_radius: f32) -> Option<(f32, Color)> {
+        (self.donate_pulse > 0.0).then(|| {
+            let remaining = (self.donate_pulse / DONATE_PULSE_TICKS).min(1.0);
+            let radius =
+                current_radius + (DONATE_RANGE - current_radius).max(0.0) * (1.0 - remaining);
+            (radius, Color::new(1.0, 0.84, 0.3, 0.8 * remaining))
+        })
     }
 
     pub fn turn_left(&mut self) {
@@ -780,3 +1352,255 @@ impl Cell {
         self.velocity_y += self.angle.sin() * self.speed;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_genome_json_round_trip() {
+        let mut original = Cell::spawn(1000.0, 1000.0, 2, &None, VisionMode::NearestCells);
+        original.generation = 42;
+        original.energy = 12.0;
+        original.age = 55.0;
+        original.children_count = 7;
+
+        let json = original.to_genome_json();
+        let imported = Cell::from_genome_json(&json, 1000.0, 1000.0).expect("valid genome");
+
+        // Heritable fields are preserved
+        assert_eq!(imported.generation, 42);
+        assert_eq!(imported.brain_tier, 2);
+        assert_eq!(imported.vision_mode, VisionMode::NearestCells);
+        assert_eq!(imported.to_genome_json(), json);
+        assert_eq!(imported.color, original.color);
+        assert_eq!(imported.radius, original.radius);
+        assert_eq!(imported.speed, original.speed);
+        assert_eq!(imported.species_multiplier, original.species_multiplier);
+        assert_eq!(imported.mass, original.mass);
+
+        // Ephemeral state starts fresh
+        assert_eq!(imported.energy, 100.0);
+        assert_eq!(imported.age, 0.0);
+        assert_eq!(imported.children_count, 0);
+        assert!(!json.contains("velocity"));
+    }
+
+    #[test]
+    fn test_trail_is_capped_and_cleared_on_death() {
+        let mut cell = Cell::spawn(1000.0, 1000.0, 0, &None, VisionMode::NearestCells);
+        for i in 0..TRAIL_LENGTH + 5 {
+            cell.x = i as f32;
+            cell.record_trail();
+        }
+        assert_eq!(cell.trail.len(), TRAIL_LENGTH);
+        assert_eq!(cell.trail.front(), Some(&(5.0, cell.y)));
+
+        cell.state = CellState::Corpse;
+        cell.record_trail();
+        assert!(cell.trail.is_empty());
+
+        // Segment width follows the speed it was travelled at, capped
+        let interval = TRAIL_SAMPLE_INTERVAL as f32;
+        assert_eq!(Cell::trail_segment_width(0.0), TRAIL_MIN_WIDTH);
+        let half = Cell::trail_segment_width(TRAIL_FULL_WIDTH_SPEED / 2.0 * interval);
+        assert!((half - (TRAIL_MIN_WIDTH + TRAIL_MAX_WIDTH) / 2.0).abs() < 1e-4);
+        assert_eq!(Cell::trail_segment_width(1000.0), TRAIL_MAX_WIDTH);
+    }
+
+    #[test]
+    fn test_sensor_distances_normalize_against_configured_range() {
+        use crate::vision::SensorReading;
+
+        let mut cell = Cell::spawn(1000.0, 1000.0, 0, &None, VisionMode::NearestCells);
+        cell.sensor_range = 150.0;
+        let reading = |distance| SensorReading {
+            distance,
+            is_alive: true,
+            ..SensorReading::default()
+        };
+        cell.nearest_cells =
+            SensorReadings::from_slice(&[reading(150.0), reading(0.0), reading(75.0)]);
+        cell.dead_center_distance = 150.0; // Nothing detected
+        cell.alive_center_distance = DEFAULT_SENSOR_RANGE; // Past the range: clamped
+
+        // Per sensor: angle, distance, energy, is_alive
+        let inputs = cell.normalize_sensors();
+        let inputs = inputs.as_slice();
+        assert_eq!(inputs[1], -1.0); // At the configured range: far edge
+        assert_eq!(inputs[5], 1.0); // Touching
+        assert_eq!(inputs[9], 0.0);
+
+        // Center of mass distances follow the energy and dead/alive ratio inputs
+        let centers = SENSOR_COUNT * 4 + 2;
+        assert_eq!(inputs[centers + 1], -1.0);
+        assert_eq!(inputs[centers + 3], -1.0);
+    }
+
+    #[test]
+    fn test_temperature_precedes_the_memory_inputs() {
+        for mode in [VisionMode::NearestCells, VisionMode::Raycast] {
+            let mut cell = Cell::spawn(1000.0, 1000.0, 0, &None, mode);
+            let temperature_input = |cell: &Cell| {
+                let inputs = cell.normalize_sensors();
+                inputs.as_slice()[mode.input_count() - MEMORY_INPUTS - 1]
+            };
+            assert_eq!(temperature_input(&cell), 0.0);
+
+            cell.temperature = 1.0;
+            let inputs = cell.normalize_sensors();
+            assert_eq!(inputs.as_slice().len(), mode.input_count());
+            assert_eq!(temperature_input(&cell), 1.0);
+
+            cell.temperature = 0.0;
+            assert_eq!(temperature_input(&cell), -1.0);
+        }
+    }
+
+    #[test]
+    fn test_memory_feeds_back_the_previous_first_sensor_slot() {
+        let mut cell = Cell::spawn(1000.0, 1000.0, 0, &None, VisionMode::NearestCells);
+        cell.age = 40.0;
+        cell.energy = 100.0;
+        let inputs = cell.normalize_sensors();
+        let memory_start = cell.brain.input_size - MEMORY_INPUTS;
+        assert_eq!(inputs.as_slice()[memory_start..], [-1.0; MEMORY_INPUTS]);
+
+        cell.nearest_cells = SensorReadings::from_slice(&[SensorReading {
+            angle: std::f32::consts::FRAC_PI_2,
+            distance: 0.0,
+            is_alive: true,
+            energy: 220.0,
+            ..SensorReading::default()
+        }]);
+        let expected = [0.5, 1.0, 1.0, 1.0];
+        cell.update(
+            1000.0,
+            1000.0,
+            1.0,
+            &EnergyConfig::default(),
+            &MovementConfig::default(),
+            &TemperatureConfig::default(),
+        );
+        assert_eq!(cell.memory, expected);
+
+        // The next decision sees it after the live slots, even once the target is gone
+        cell.nearest_cells = SensorReadings::default();
+        let inputs = cell.normalize_sensors();
+        assert_eq!(inputs.as_slice()[..4], [-1.0; 4]);
+        assert_eq!(inputs.as_slice()[memory_start..], expected);
+    }
+
+    #[test]
+    fn test_genome_json_rejects_invalid_input() {
+        assert!(Cell::from_genome_json("not json", 1000.0, 1000.0).is_err());
+
+        // Brain sized for the other vision mode
+        let cell = Cell::spawn(1000.0, 1000.0, 0, &None, VisionMode::NearestCells);
+        let json = cell
+            .to_genome_json()
+            .replace("\"NearestCells\"", "\"Raycast\"");
+        let Err(error) = Cell::from_genome_json(&json, 1000.0, 1000.0) else {
+            panic!("mismatched brain accepted");
+        };
+        assert!(error.contains("34 inputs"), "{}", error);
+    }
+
+    // Distance moved along +x per tick once the velocity has settled
+    fn terminal_step(speed: f32, mass: f32, movement: &MovementConfig) -> f32 {
+        let mut cell = Cell::spawn(0.0, 0.0, 0, &None, VisionMode::NearestCells);
+        // A zeroed brain biased toward forward (action 3) chooses it every tick
+        cell.brain.apply_weight_decay(1.0);
+        cell.brain.action_bias = [0.0, 0.0, 0.0, 1.0];
+        cell.energy = 1000.0;
+        cell.speed = speed;
+        cell.mass = mass;
+        cell.angle = 0.0;
+        cell.angle_velocity = 0.0;
+
+        let energy = EnergyConfig::default();
+        let mut step = 0.0;
+        for _ in 0..400 {
+            let before = cell.x;
+            cell.update(
+                1.0e6,
+                1.0e6,
+                1.0,
+                &energy,
+                movement,
+                &TemperatureConfig::default(),
+            );
+            step = cell.x - before;
+        }
+        assert_eq!(cell.last_action, Some(3));
+        step
+    }
+
+    #[test]
+    fn test_forward_every_tick_reaches_terminal_velocity() {
+        // (speed + cruise force) / friction * slowdown
+        let movement = MovementConfig::default();
+        let step = terminal_step(0.5, 200.0, &movement);
+        assert!((step - 12.0).abs() < 1e-3, "{}", step);
+
+        // Doubling the mass halves the distance, down to the slowdown floor
+        let step = terminal_step(0.5, 400.0, &movement);
+        assert!((step - 6.0).abs() < 1e-3, "{}", step);
+        let step = terminal_step(0.5, 1000.0, &movement);
+        assert!((step - 6.0).abs() < 1e-3, "{}", step);
+
+        let movement = MovementConfig {
+            friction: 0.2,
+            ..MovementConfig::default()
+        };
+        let step = terminal_step(0.9, 200.0, &movement);
+        assert!((step - 5.0).abs() < 1e-3, "{}", step);
+    }
+
+    #[test]
+    fn test_velocities_are_clamped_to_the_configured_maxima() {
+        // Almost no friction: unclamped, the impulses would pile up to ~100 units and
+        // ~10 radians per tick
+        let movement = MovementConfig {
+            friction: 0.01,
+            angular_friction: 0.01,
+            max_velocity: 4.0,
+            max_angular_velocity: 0.2,
+            ..MovementConfig::default()
+        };
+        let mut cell = Cell::spawn(0.0, 0.0, 0, &None, VisionMode::NearestCells);
+        // A zeroed brain biased toward forward (action 3) chooses it every tick
+        cell.brain.apply_weight_decay(1.0);
+        cell.brain.action_bias = [0.0, 0.0, 0.0, 1.0];
+        cell.energy = 1000.0;
+        cell.speed = 1.0;
+        cell.turn_rate = 0.15;
+        cell.angle_velocity = 0.0;
+        let tick = |cell: &mut Cell| {
+            cell.update(
+                1.0e6,
+                1.0e6,
+                1.0,
+                &EnergyConfig::default(),
+                &movement,
+                &TemperatureConfig::default(),
+            );
+            let velocity = cell.velocity_x.hypot(cell.velocity_y);
+            assert!(velocity <= 4.0 + 1e-4, "{}", velocity);
+            assert!(cell.angle_velocity.abs() <= 0.2);
+        };
+
+        // Straight ahead the velocity pins at the cap
+        for _ in 0..200 {
+            tick(&mut cell);
+        }
+        assert!((cell.velocity_x.hypot(cell.velocity_y) - 4.0).abs() < 1e-3);
+
+        // Spamming turns pins the turning velocity at its cap
+        for _ in 0..200 {
+            cell.turn_right();
+            tick(&mut cell);
+        }
+        assert_eq!(cell.angle_velocity, 0.2);
+    }
+}
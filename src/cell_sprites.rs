@@ -0,0 +1,165 @@
+use macroquad::prelude::*;
+
+// Side of one sprite in the atlas, in pixels
+const SPRITE_SIZE: u16 = 64;
+// Sprites reach this many cell radii from their center (the alive halo ends at 1.96)
+const SPRITE_EXTENT: f32 = 2.0;
+// Cell radius inside a sprite, in pixels
+const SPRITE_RADIUS: f32 = SPRITE_SIZE as f32 / 2.0 / SPRITE_EXTENT;
+
+/// Pre-rendered cell shapes for the batched renderer (World::render with a large
+/// population). All sprites are white with baked alpha, tinted per draw, and live
+/// in one texture so macroquad merges every cell into the same draw call instead
+/// of building ~160 triangles of circles per cell.
+pub struct CellSprites {
+    atlas: Texture2D,
+}
+
+#[derive(Clone, Copy)]
+enum Sprite {
+    Alive = 0,  // Body with its glow halo
+    Corpse = 1, // Outline with a faint halo
+    Ring = 2,   // Thin outline (plague marker)
+}
+
+impl CellSprites {
+    pub fn new() -> Self {
+        let size = SPRITE_SIZE as u32;
+        let mut image = Image::gen_image_color(SPRITE_SIZE * 3, SPRITE_SIZE, BLANK);
+        let profiles: [fn(f32) -> f32; 3] = [alive_alpha, corpse_alpha, ring_alpha];
+        for (slot, profile) in profiles.iter().enumerate() {
+            for y in 0..size {
+                for x in 0..size {
+                    let dx = x as f32 + 0.5 - size as f32 / 2.0;
+                    let dy = y as f32 + 0.5 - size as f32 / 2.0;
+                    let distance = dx.hypot(dy) / SPRITE_RADIUS;
+                    let alpha = profile(distance);
+                    image.set_pixel(slot as u32 * size + x, y, Color::new(1.0, 1.0, 1.0, alpha));
+                }
+            }
+        }
+        let atlas = Texture2D::from_image(&image);
+        atlas.set_filter(FilterMode::Linear);
+        CellSprites { atlas }
+    }
+
+    fn draw(&self, sprite: Sprite, x: f32, y: f32, radius: f32, color: Color) {
+        let half = radius * SPRITE_EXTENT;
+        let size = SPRITE_SIZE as f32;
+        draw_texture_ex(
+            &self.atlas,
+            x - half,
+            y - half,
+            color,
+            DrawTextureParams {
+                dest_size: Some(vec2(half * 2.0, half * 2.0)),
+                source: Some(Rect::new(sprite as usize as f32 * size, 0.0, size, size)),
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn draw_alive(&self, x: f32, y: f32, radius: f32, color: Color) {
+        self.draw(Sprite::Alive, x, y, radius, color);
+    }
+
+    pub fn draw_corpse(&self, x: f32, y: f32, radius: f32, color: Color) {
+        self.draw(Sprite::Corpse, x, y, radius, color);
+    }
+
+    pub fn draw_ring(&self, x: f32, y: f32, radius: f32, color: Color) {
+        self.draw(Sprite::Ring, x, y, radius, color);
+    }
+
+    /// A line from the solid center of the alive sprite, so it joins the same batch
+    pub fn draw_line(&self, x: f32, y: f32, length: f32, angle: f32, thickness: f32, color: Color) {
+        let center = SPRITE_SIZE as f32 / 2.0;
+        draw_texture_ex(
+            &self.atlas,
+            x,
+            y - thickness / 2.0,
+            color,
+            DrawTextureParams {
+                dest_size: Some(vec2(length, thickness)),
+                source: Some(Rect::new(center - 1.0, center - 1.0, 2.0, 2.0)),
+                rotation: angle,
+                pivot: Some(vec2(x, y)),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+// Alpha of stacked translucent circles covering `distance` (in cell radii), as the
+// individual draw_circle layers of Cell::render would blend
+fn stacked_alpha(distance: f32, layers: &[(f32, f32)]) -> f32 {
+    let transparency: f32 = layers
+        .iter()
+        .filter(|(radius, _)| distance <= *radius)
+        .map(|(_, alpha)| 1.0 - alpha)
+        .product();
+    1.0 - transparency
+}
+
+// Full inside the edge at 1.0, fading out over about one sprite pixel
+fn edge_alpha(distance: f32) -> f32 {
+    ((1.0 - distance) * SPRITE_RADIUS + 0.5).clamp(0.0, 1.0)
+}
+
+// Outline around 1.0, two sprite pixels wide
+fn outline_alpha(distance: f32) -> f32 {
+    (2.0 - (distance - 1.0).abs() * SPRITE_RADIUS).clamp(0.0, 1.0)
+}
+
+// 5 halo layers out to 1.96 radii (30% fading to 6%) under an opaque body
+fn alive_alpha(distance: f32) -> f32 {
+    let halo: Vec<(f32, f32)> = (0..5)
+        .map(|i| {
+            let t = i as f32 / 5.0;
+            (1.0 + t * 1.2, (1.0 - t) * 0.3)
+        })
+        .collect();
+    let body = edge_alpha(distance);
+    body + (1.0 - body) * stacked_alpha(distance, &halo)
+}
+
+// 3 faint halo layers out to 1.33 radii under an outline
+fn corpse_alpha(distance: f32) -> f32 {
+    let halo: Vec<(f32, f32)> = (0..3)
+        .map(|i| {
+            let t = i as f32 / 3.0;
+            (1.0 + t * 0.5, (1.0 - t) * 0.15)
+        })
+        .collect();
+    let outline = outline_alpha(distance);
+    outline + (1.0 - outline) * stacked_alpha(distance, &halo)
+}
+
+fn ring_alpha(distance: f32) -> f32 {
+    outline_alpha(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprite_profiles_match_the_layered_shapes() {
+        // Opaque body, halo fading out, nothing past the sprite edge
+        assert_eq!(alive_alpha(0.0), 1.0);
+        assert_eq!(alive_alpha(0.9), 1.0);
+        let halo = [1.1, 1.4, 1.6, 1.8].map(alive_alpha);
+        assert!(halo.windows(2).all(|pair| pair[0] > pair[1]), "{:?}", halo);
+        assert!((halo[0] - 0.48).abs() < 0.01, "{}", halo[0]);
+        assert_eq!(alive_alpha(SPRITE_EXTENT), 0.0);
+
+        // Corpses: see-through inside, solid outline, faint halo just outside
+        assert!(corpse_alpha(0.5) < 0.5);
+        assert_eq!(corpse_alpha(1.0), 1.0);
+        assert!(corpse_alpha(1.2) > 0.0 && corpse_alpha(1.2) < 0.3);
+        assert_eq!(corpse_alpha(1.5), 0.0);
+
+        assert_eq!(ring_alpha(1.0), 1.0);
+        assert_eq!(ring_alpha(0.5), 0.0);
+    }
+}
@@ -1,81 +1,1289 @@
-use std::sync::OnceLock;
+use crate::fitness::FitnessFn;
+use crate::stats::log_warn;
+use crate::vision::VisionMode;
+use crate::world::DEFAULT_SENSOR_RANGE;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
 
-static CONFIG: OnceLock<SimulationConfig> = OnceLock::new();
+// Built config of the selected scenario, None until first requested or after a switch
+static CONFIG: Mutex<Option<SimulationConfig>> = Mutex::new(None);
+static SCENARIO: Mutex<Option<String>> = Mutex::new(None);
+static SCENARIO_PENDING: AtomicBool = AtomicBool::new(false);
+static SCENARIOS: OnceLock<HashMap<&'static str, SimulationConfig>> = OnceLock::new();
 
-#[derive(Debug, Clone)]
+// Version written to config files. Bump it whenever a change needs more than
+// defaults for new fields (renames, unit changes) and add a step to migrate().
+// 1: files written before config_version existed
+pub const CURRENT_CONFIG_VERSION: u32 = 2;
+
+// Raw JSON object of a config file written by an older version
+pub type OldConfig = Map<String, Value>;
+
+// Top-level fields a running world picks up from a reloaded config file (they are read
+// live every tick or frame). Any other change only takes effect in a new world.
+pub const HOT_RELOAD_FIELDS: [&str; 18] = [
+    "energy",
+    "movement",
+    "mutation_rate_multiplier",
+    "weight_decay",
+    "structured_mutation",
+    "nutrient_recycle_fraction",
+    "reproduction_cooldown_ticks",
+    "use_dynamic_threshold",
+    "child_energy_ratio",
+    "parent_energy_ratio",
+    "night_metabolism_multiplier",
+    "show_ui",
+    "show_sensor_lines",
+    "show_density_map",
+    "show_pheromones",
+    "show_temperature",
+    "show_event_log",
+    "show_event_panel",
+];
+
+/// Changed top-level fields found when hot-reloading a config file
+#[derive(Debug, Default, PartialEq)]
+pub struct HotReload {
+    pub applied: Vec<String>,       // Now in effect
+    pub needs_restart: Vec<String>, // Left unchanged in the running world
+}
+
+/// Per-tick energy rates applied in Cell::update
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EnergyConfig {
+    pub metabolism_energy_loss: f32, // Base energy lost per tick by alive cells
+    pub corpse_decay_rate: f32,      // Energy lost per tick by corpses
+    // Hunger: metabolism multiplier ramps from 1x up to hunger_max_multiplier
+    // over hunger_ramp_ticks ticks without eating
+    pub hunger_ramp_ticks: f32,
+    pub hunger_max_multiplier: f32,
+    pub plague_energy_drain: f32, // Extra energy lost per tick by infected alive cells
+    // Extra base metabolism per brain weight and bias, so big brains have to pay off.
+    // 0 by default: with the fixed topology it would only shift the base cost.
+    pub brain_cost_per_weight: f32,
+    // Energy cells start with when spawned or respawned (not reproduction, which splits
+    // the parent's energy). With scale_initial_energy_by_mass it is
+    // initial_energy_fraction of the cell's mass instead, so big cells don't start emptier.
+    pub initial_energy: f32,
+    pub scale_initial_energy_by_mass: bool,
+    pub initial_energy_fraction: f32,
+}
+
+impl Default for EnergyConfig {
+    fn default() -> Self {
+        Self {
+            metabolism_energy_loss: 0.03,
+            corpse_decay_rate: 0.02,
+            hunger_ramp_ticks: 300.0,
+            hunger_max_multiplier: 4.0,
+            plague_energy_drain: 0.1,
+            brain_cost_per_weight: 0.0,
+            initial_energy: 100.0,
+            scale_initial_energy_by_mass: false,
+            initial_energy_fraction: 0.5, // ~100 for the ~200 average mass
+        }
+    }
+}
+
+impl EnergyConfig {
+    // Part of the base metabolism paid for a brain with `parameter_count` weights and biases
+    pub fn brain_cost(&self, parameter_count: usize) -> f32 {
+        parameter_count as f32 * self.brain_cost_per_weight
+    }
+
+    // Base metabolism per tick (before hunger and zone multipliers) for a brain
+    // with `parameter_count` weights and biases
+    pub fn base_metabolism(&self, parameter_count: usize) -> f32 {
+        self.metabolism_energy_loss + self.brain_cost(parameter_count)
+    }
+
+    // Starting energy of a spawned cell with the given mass (max energy capacity)
+    pub fn starting_energy(&self, mass: f32) -> f32 {
+        if self.scale_initial_energy_by_mass {
+            self.initial_energy_fraction * mass
+        } else {
+            self.initial_energy
+        }
+    }
+}
+
+/// Islands mode: the world is split into a columns x rows grid of regions by barriers.
+/// Each island edge has a corridor gap that opens for `corridor_open_duration` seconds
+/// every `corridor_interval` seconds, allowing occasional migration between islands.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct IslandConfig {
+    pub enabled: bool,
+    pub columns: usize,
+    pub rows: usize,
+    pub barrier_thickness: f32, // Visual only: crossing is decided by island membership
+    pub corridor_width: f32,
+    pub corridor_interval: f32,
+    pub corridor_open_duration: f32,
+}
+
+impl Default for IslandConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            columns: 2,
+            rows: 2,
+            barrier_thickness: 20.0,
+            corridor_width: 300.0,
+            corridor_interval: 60.0,
+            corridor_open_duration: 10.0,
+        }
+    }
+}
+
+/// Ecological succession (src/succession.rs): the world is split into a columns x rows
+/// grid of regions cycling Barren -> Colonizing -> Mature -> Exhausted -> Barren. Every
+/// second a region advances with probability `transition_chance`, scaled down by its
+/// alive cell density for recovery and up by it for exhaustion (full density at
+/// `crowded_cells`). Recycled food inside a region is scaled by its stage's energy
+/// factor and metabolism by the inverse.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SuccessionConfig {
+    pub enabled: bool,
+    pub columns: usize,
+    pub rows: usize,
+    pub transition_chance: f32,
+    pub crowded_cells: usize,
+    pub barren_energy: f32,
+    pub colonizing_energy: f32,
+    pub mature_energy: f32,
+    pub exhausted_energy: f32,
+}
+
+impl Default for SuccessionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            columns: 10,
+            rows: 10,
+            transition_chance: 0.02, // About 50 s per stage for an unaffected region
+            crowded_cells: 50,
+            barren_energy: 0.5,
+            colonizing_energy: 1.0,
+            mature_energy: 2.0,
+            exhausted_energy: 0.7,
+        }
+    }
+}
+
+/// Random immigrants against stagnation: once the hue diversity (World::color_diversity,
+/// alive hue species / 24, so 0.05 means a single species) has stayed below `diversity_threshold` for `stagnation_seconds` of simulation time,
+/// `fraction` of the cell cap arrives as fresh random-brain cells in a hue away from
+/// the dominant species (World::inject_immigrants). The timer then starts over.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImmigrationConfig {
+    pub enabled: bool,
+    pub diversity_threshold: f32,
+    pub stagnation_seconds: f32,
+    pub fraction: f32,
+}
+
+impl Default for ImmigrationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            diversity_threshold: 0.05, // Below 2 of the 24 possible hue species
+            stagnation_seconds: 60.0,
+            fraction: 0.1,
+        }
+    }
+}
+
+/// Brain parts frozen in spawned cells (NeuralNetwork::set_frozen_layers): mutation,
+/// weight decay and gene transfer leave them as they are
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrozenLayers {
+    pub weights_ih: bool,
+    pub bias_h: bool,
+    pub weights_ho: bool,
+}
+
+/// Auto-director (src/director.rs, toggled with Y): the camera cuts to the region of
+/// about `region_size` world units with the most recent feeding and reproduction
+/// events, zooms to `zoom` and holds the shot for `dwell_seconds` before looking again.
+/// `enabled` starts it with the simulation, for unattended demos.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DirectorConfig {
+    pub enabled: bool,
+    pub dwell_seconds: f32,
+    pub region_size: f32,
+    pub zoom: f32,
+}
+
+impl Default for DirectorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dwell_seconds: 8.0,
+            region_size: 1500.0, // About one screen at zoom 1
+            zoom: 1.0,
+        }
+    }
+}
+
+/// Pheromone field (src/pheromones.rs): alive cells deposit `deposit_amount` per tick
+/// into their spatial grid bucket; each tick the field moves `diffusion_rate` of the
+/// way toward the 4-neighbor average, then loses `decay_rate` of its concentration.
+/// When disabled the field stays empty, but the sensor inputs remain (reading zero).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PheromoneConfig {
+    pub enabled: bool,
+    pub deposit_amount: f32,
+    pub decay_rate: f32,
+    pub diffusion_rate: f32,
+}
+
+impl Default for PheromoneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            deposit_amount: 0.05,
+            decay_rate: 0.01,
+            diffusion_rate: 0.2,
+        }
+    }
+}
+
+/// Movement physics applied in Cell::update. Each tick an alive cell gets a small
+/// cruise push along its heading, plus an impulse of its `speed` when it chooses
+/// forward; it then moves by velocity * slowdown and loses `friction` of its
+/// velocity. Choosing forward every tick converges to a terminal displacement of
+/// (speed + 0.1) / friction * slowdown per tick, where
+/// slowdown = max(mass_reference / mass, min_mass_slowdown).
+/// Overlapping alive cells push each other apart (soft-body collisions, see
+/// World::push_apart_alive_cells): each gains push_force * overlap / mass of
+/// velocity away from the other, where overlap is the penetration depth over the
+/// sum of both radii (0..1). 0 lets alive cells pass through each other.
+/// With SimulationConfig::cell_collisions, overlaps are also resolved by moving the
+/// cells apart (World::separate_alive_cells), by at most max_collision_push per tick.
+/// At the end of each update the velocity is capped at max_velocity (units per tick,
+/// above the default terminal velocity of a full-speed cell, about 21) and the turning
+/// velocity at max_angular_velocity (radians per tick), so no impulse pile-up or turn
+/// spamming can make a cell race or spin implausibly fast.
+/// With wrapping off the world edges are hard walls: cells stop at them and bounce
+/// back, and sensors, collisions and crowding no longer reach across the edges.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MovementConfig {
+    pub friction: f32,           // Fraction of velocity lost per tick (0..1)
+    pub angular_friction: f32,   // Fraction of turning velocity lost per tick (0..1)
+    pub mass_reference: f32,     // Mass that moves at exactly its velocity (slowdown 1.0)
+    pub min_mass_slowdown: f32,  // Floor on the slowdown of heavy cells
+    pub push_force: f32,         // Soft-body repulsion between overlapping alive cells
+    pub max_collision_push: f32, // Cap on a cell's separation move per tick (cell_collisions)
+    pub wrapping: bool,          // Toroidal world; false for hard walls at the edges
+    pub max_velocity: f32,
+    pub max_angular_velocity: f32,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            friction: 0.05,
+            angular_friction: 0.1,
+            mass_reference: 200.0,
+            min_mass_slowdown: 0.5,
+            push_force: 20.0,
+            max_collision_push: 2.0,
+            wrapping: true,
+            max_velocity: 30.0,
+            max_angular_velocity: 0.5,
+        }
+    }
+}
+
+/// Temperature zones (src/temperature.rs): a horizontal gradient, coldest at the
+/// x = 0 edge and hottest mid-world, wrapping smoothly with the world. Each factor
+/// is interpolated from its cold value to its hot value by the local temperature:
+/// metabolism scales alive cells' base metabolism, corpse_decay scales the corpse
+/// decay rate and feeding scales the chunk eaters extract from a corpse.
+/// When disabled every factor is 1.0 and the sensor input reads neutral.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TemperatureConfig {
+    pub enabled: bool,
+    pub cold_metabolism: f32,
+    pub hot_metabolism: f32,
+    pub cold_corpse_decay: f32,
+    pub hot_corpse_decay: f32,
+    pub cold_feeding: f32,
+    pub hot_feeding: f32,
+}
+
+impl Default for TemperatureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cold_metabolism: 0.6,
+            hot_metabolism: 1.5,
+            cold_corpse_decay: 0.5,
+            hot_corpse_decay: 2.0,
+            cold_feeding: 0.6,
+            hot_feeding: 1.0,
+        }
+    }
+}
+
+/// Energy zones (src/zones.rs): `count` circles of `min_radius`..`max_radius` placed at
+/// random when the world is created, `fertile_fraction` of them fertile and the rest
+/// barren. Inside a zone the base metabolism is scaled by its metabolism factor and
+/// recycled food (nutrient_recycle_fraction) by its food factor. 0 zones disables them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ZoneConfig {
+    pub count: usize,
+    pub min_radius: f32,
+    pub max_radius: f32,
+    pub fertile_fraction: f32,
+    pub fertile_metabolism: f32,
+    pub fertile_food: f32,
+    pub barren_metabolism: f32,
+    pub barren_food: f32,
+}
+
+impl Default for ZoneConfig {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            min_radius: 1500.0,
+            max_radius: 4000.0,
+            fertile_fraction: 0.5,
+            fertile_metabolism: 0.6,
+            fertile_food: 2.0,
+            barren_metabolism: 1.5,
+            barren_food: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationConfig {
+    // Format version of a saved config (see CURRENT_CONFIG_VERSION and migrate)
+    pub config_version: u32,
     pub world_width: f32,
     pub world_height: f32,
     pub initial_cell_count: usize,
+    // Pin the population cap (disables FPS-based auto-adjustment), None = auto
+    pub max_cells: Option<usize>,
+    // Auto cap FPS band: grow while sustained above fps_grow_threshold,
+    // shrink below fps_shrink_threshold, hold in between (hysteresis)
+    pub fps_grow_threshold: f32,
+    pub fps_shrink_threshold: f32,
+    // Bounds for the population cap (auto-adjusted or pinned): the floor keeps slow
+    // machines from squeezing the population until evolution stalls, the limit keeps
+    // fast machines from growing it without bound
+    pub min_cells: usize,
+    pub max_cells_limit: usize,
     pub show_ui: bool,
     pub show_sensor_lines: bool,
+    pub show_density_map: bool, // Spatial grid density overlay (drawn only with show_ui)
+    pub show_pheromones: bool,  // Pheromone field overlay (drawn only with show_ui)
+    pub show_temperature: bool, // Temperature zone overlay (drawn only with show_ui)
+    pub show_trails: bool,      // Record and draw movement trails (costs memory per cell when on)
+    pub show_gene_stats: bool,  // Trait distribution panel (drawn only with show_ui)
+    pub show_cell_detail: bool, // Selected cell panel expanded (false = title only)
+    pub show_brain_inspector: bool, // Selected cell's brain activations (drawn only with show_ui)
+    pub show_event_log: bool,   // On-screen event log (stdout logging happens either way)
+    pub show_event_panel: bool, // Scrollable simulation event panel (drawn only with show_ui)
     pub camera_tracking_speed: f32,
+    pub camera_smoothstep: bool, // Camera eases into moves instead of starting at full speed
+    // Day/night cycle
+    pub day_length: f32, // Seconds of simulation time for a full day/night cycle
+    pub night_metabolism_multiplier: f32, // Metabolism multiplier at midnight (1.0 at noon)
+    // Feeding never creates energy: corpses give at most what they hold and are
+    // removed once empty, and a starved body holds only what it already had (its
+    // energy plus Cell::body_energy). Energy only enters at spawn: starting_energy
+    // for new cells and for the food corpses a reset seeds
+    // (false = legacy behaviour, corpses drain to DEPLETED_CELL_ENERGY)
+    pub strict_energy_conservation: bool,
+    // Ticks a corpse lasts before it is removed whatever energy it still holds
+    // (bounds the corpse population on large maps), 0.0 = no limit
+    pub corpse_max_age: f32,
+    // Sensor model feeding the brains (changes the network input size)
+    pub vision_mode: VisionMode,
+    // How far cells sense (world units); also the distance scale of the sensor inputs
+    pub sensor_range: f32,
+    // Seconds between periodic brain saves and stats log rows
+    pub auto_save_interval: f32,
+    // Load/save brains and the stats log (off for reproducible runs)
+    pub use_storage: bool,
+    // Plague: every `plague_interval` seconds, if one hue cluster holds more than
+    // `plague_dominance_threshold` of the alive population, infect a random
+    // `plague_infection_fraction` of it
+    pub plague_enabled: bool,
+    pub plague_interval: f32,
+    pub plague_dominance_threshold: f32,
+    pub plague_infection_fraction: f32,
+    // Ticks a cell must wait after being born or producing a child before reproducing
+    pub reproduction_cooldown_ticks: f32,
+    // Lower the reproduction energy threshold while the population is below the cap,
+    // down to half at half the cap or less (World::dynamic_reproduction_threshold)
+    pub use_dynamic_threshold: bool,
+    // Share of a reproducing cell's energy given to the child and kept by the parent.
+    // Their sum must not exceed 1 (the rest is lost); see validate().
+    pub child_energy_ratio: f32,
+    pub parent_energy_ratio: f32,
+    // Scales the brain mutation rate of every child (1.0 = normal)
+    pub mutation_rate_multiplier: f32,
+    // L2 regularization: every child's brain weights shrink by this fraction after
+    // mutation, keeping them off the [-2, 2] clamp over many generations
+    pub weight_decay: f32,
+    // Children's brains also get whole input-to-hidden rows scaled together
+    // (NeuralNetwork::structured_mutate), evolving coherent feature detectors
+    pub structured_mutation: bool,
+    // Added to the brain outputs [no-op, turn left, turn right, forward] of every
+    // spawned cell and inherited by its children, e.g. [-0.5, 0.0, 0.0, 0.2] nudges
+    // early generations away from standing still. Saved with each brain.
+    pub action_bias: [f32; 4],
+    // Brain parts locked in every spawned cell and inherited by its children, e.g.
+    // freeze weights_ih to keep evolved feature detectors while the policy evolves
+    pub freeze_layers: FrozenLayers,
+    // Scores cells for best cell tracking, saved brains, culling and the stats box
+    // (M cycles through the presets at runtime, see fitness::FITNESS_PRESETS)
+    pub fitness: FitnessFn,
+    // Fraction of a removed corpse's decayed energy returned to the world as food
+    // pellets: 0.0 = open system (decay is lost), 1.0 = closed (all of it recycled)
+    pub nutrient_recycle_fraction: f32,
+    // Fraction of the alive population removed by the cull command (lowest scores first)
+    pub cull_fraction: f32,
+    // Kin selection: feeding on a corpse whose brain is within this genetic distance
+    // (1 - cosine similarity of the weights) yields bonus energy
+    pub genetic_distance_threshold: f32,
+    // Report genome diversity (mean pairwise NeuralNetwork::distance over a sample of
+    // alive cells) as the diversity in the stats log and sim_get_stats, instead of
+    // the hue species share
+    pub genome_diversity: bool,
+    // When every cell died before any best genome was saved, restart with
+    // initial_cell_count fresh cells (as at startup) instead of staying empty
+    pub auto_restart_on_extinction: bool,
+    // Hard collisions between alive cells: overlaps are resolved by moving both cells
+    // apart after the movement step, heavier cells moving less (see MovementConfig)
+    pub cell_collisions: bool,
+    // Share of spawned and respawned cells that start as parasites (CellType::Parasite),
+    // which drain alive hosts instead of eating. Children inherit their parent's type.
+    pub parasite_fraction: f32,
+    // Energy rates passed to every Cell::update
+    pub energy: EnergyConfig,
+    pub movement: MovementConfig,
+    pub islands: IslandConfig,
+    pub pheromones: PheromoneConfig,
+    pub temperature: TemperatureConfig,
+    pub zones: ZoneConfig,
+    pub succession: SuccessionConfig,
+    pub immigration: ImmigrationConfig,
+    pub director: DirectorConfig,
 }
 
 impl Default for SimulationConfig {
     fn default() -> Self {
         Self {
+            config_version: CURRENT_CONFIG_VERSION,
             world_width: 60000.0,
             world_height: 40000.0,
             initial_cell_count: 5000,
+            max_cells: None,
+            fps_grow_threshold: 55.0,
+            fps_shrink_threshold: 45.0,
+            min_cells: 500,
+            max_cells_limit: 7_000,
             show_ui: true,
             show_sensor_lines: true,
+            show_density_map: false,
+            show_pheromones: false,
+            show_temperature: false,
+            show_trails: false,
+            show_gene_stats: false,
+            show_cell_detail: true,
+            show_brain_inspector: false,
+            show_event_log: true,
+            show_event_panel: false,
             camera_tracking_speed: 0.5,
+            camera_smoothstep: false,
+            day_length: 120.0,
+            night_metabolism_multiplier: 1.5,
+            strict_energy_conservation: true,
+            corpse_max_age: 3000.0,
+            vision_mode: VisionMode::NearestCells,
+            sensor_range: DEFAULT_SENSOR_RANGE,
+            auto_save_interval: 60.0,
+            use_storage: true,
+            plague_enabled: false,
+            plague_interval: 60.0,
+            plague_dominance_threshold: 0.7,
+            plague_infection_fraction: 0.3,
+            reproduction_cooldown_ticks: 30.0,
+            use_dynamic_threshold: false,
+            child_energy_ratio: 2.0 / 3.0,
+            parent_energy_ratio: 1.0 / 3.0,
+            mutation_rate_multiplier: 1.0,
+            weight_decay: 0.0001,
+            structured_mutation: false,
+            action_bias: [0.0; 4],
+            freeze_layers: FrozenLayers::default(),
+            fitness: FitnessFn::Composite,
+            nutrient_recycle_fraction: 0.0,
+            cull_fraction: 0.5,
+            genetic_distance_threshold: 0.2,
+            genome_diversity: false,
+            auto_restart_on_extinction: true,
+            cell_collisions: false,
+            parasite_fraction: 0.0,
+            energy: EnergyConfig::default(),
+            movement: MovementConfig::default(),
+            islands: IslandConfig::default(),
+            pheromones: PheromoneConfig::default(),
+            temperature: TemperatureConfig::default(),
+            zones: ZoneConfig::default(),
+            succession: SuccessionConfig::default(),
+            immigration: ImmigrationConfig::default(),
+            director: DirectorConfig::default(),
         }
     }
 }
 
 impl SimulationConfig {
+    // Keep a population cap within min_cells..=max_cells_limit (the limit wins if they cross)
+    pub fn clamp_cell_cap(&self, cap: usize) -> usize {
+        cap.max(self.min_cells).min(self.max_cells_limit)
+    }
+
+    /// Take every HOT_RELOAD_FIELDS value the file changed between `previous` and
+    /// `reloaded`, and list the other changed fields, which keep their current value
+    /// until the next restart. Fields the file left alone keep any runtime change.
+    pub fn hot_reload(&mut self, previous: &Self, reloaded: &Self) -> Result<HotReload, String> {
+        let mut current = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        let previous = serde_json::to_value(previous).map_err(|e| e.to_string())?;
+        let reloaded = serde_json::to_value(reloaded).map_err(|e| e.to_string())?;
+        let (Some(fields), Some(old_fields), Some(new_fields)) = (
+            current.as_object_mut(),
+            previous.as_object(),
+            reloaded.as_object(),
+        ) else {
+            return Err("config must be a JSON object".to_owned());
+        };
+
+        let mut result = HotReload::default();
+        for (key, value) in new_fields {
+            if old_fields.get(key) == Some(value) {
+                continue;
+            }
+            if HOT_RELOAD_FIELDS.contains(&key.as_str()) {
+                fields.insert(key.clone(), value.clone());
+                result.applied.push(key.clone());
+            } else {
+                result.needs_restart.push(key.clone());
+            }
+        }
+        *self = serde_json::from_value(current).map_err(|e| e.to_string())?;
+        Ok(result)
+    }
+
+    /// Load a config file (see from_json)
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_json(&json)
+    }
+
+    /// Parse a serialized config. Files from older versions (no `config_version`
+    /// means version 1) are migrated; files from newer versions are rejected.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let Value::Object(fields) = value else {
+            return Err("config must be a JSON object".to_owned());
+        };
+        let version = fields
+            .get("config_version")
+            .and_then(Value::as_u64)
+            .map_or(1, |v| v as u32);
+
+        let config = if version > CURRENT_CONFIG_VERSION {
+            Err(format!(
+                "config version {} is newer than supported version {}",
+                version, CURRENT_CONFIG_VERSION
+            ))
+        } else if version < CURRENT_CONFIG_VERSION {
+            Self::migrate(fields, version)
+        } else {
+            Self::with_defaults(fields)
+        }?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reject values the simulation cannot run with
+    pub fn validate(&self) -> Result<(), String> {
+        let fractions = [
+            ("child_energy_ratio", self.child_energy_ratio),
+            ("parent_energy_ratio", self.parent_energy_ratio),
+            ("parasite_fraction", self.parasite_fraction),
+        ];
+        for (name, fraction) in fractions {
+            if !(0.0..=1.0).contains(&fraction) {
+                return Err(format!(
+                    "{} must be between 0 and 1, got {}",
+                    name, fraction
+                ));
+            }
+        }
+        // Small tolerance so the default thirds still pass after rounding
+        let split = self.child_energy_ratio + self.parent_energy_ratio;
+        if split > 1.0 + 1e-6 {
+            return Err(format!(
+                "child_energy_ratio + parent_energy_ratio must not exceed 1, got {}",
+                split
+            ));
+        }
+        Ok(())
+    }
+
+    /// Bring a config from `old_version` up to CURRENT_CONFIG_VERSION: version steps
+    /// run in order, then every field missing from the old file (including inside
+    /// nested sections like `energy`) takes its default value.
+    pub fn migrate(old: OldConfig, old_version: u32) -> Result<Self, String> {
+        log_warn!(
+            "⚠ Migrating config from version {} to {}",
+            old_version,
+            CURRENT_CONFIG_VERSION
+        );
+        let mut fields = old;
+
+        // 1 -> 2: config_version added; every other new field just takes its default
+
+        fields.insert(
+            "config_version".to_owned(),
+            Value::from(CURRENT_CONFIG_VERSION),
+        );
+        Self::with_defaults(fields)
+    }
+
+    /// This config with every field present in a partial JSON object replaced
+    /// (nested sections are merged field by field)
+    pub fn with_overrides(&self, json: &str) -> Result<Self, String> {
+        let overrides: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if !overrides.is_object() {
+            return Err("config must be a JSON object".to_owned());
+        }
+        let mut merged = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        fill_from(&mut merged, overrides);
+        let mut config: Self = serde_json::from_value(merged).map_err(|e| e.to_string())?;
+        config.config_version = CURRENT_CONFIG_VERSION;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // Build from (possibly partial) fields: anything missing takes its default value,
+    // so adding a field never needs a version bump
+    fn with_defaults(fields: OldConfig) -> Result<Self, String> {
+        let mut merged = serde_json::to_value(Self::default()).map_err(|e| e.to_string())?;
+        fill_from(&mut merged, Value::Object(fields));
+        serde_json::from_value(merged).map_err(|e| e.to_string())
+    }
+
     pub fn demo() -> Self {
         Self {
+            config_version: CURRENT_CONFIG_VERSION,
             world_width: 60000.0,
             world_height: 40000.0,
             initial_cell_count: 5000,
+            max_cells: None,
+            fps_grow_threshold: 55.0,
+            fps_shrink_threshold: 45.0,
+            min_cells: 500,
+            max_cells_limit: 7_000,
             show_ui: false,
             show_sensor_lines: true,
+            show_density_map: false,
+            show_pheromones: false,
+            show_temperature: false,
+            show_trails: false,
+            show_gene_stats: false,
+            show_cell_detail: true,
+            show_brain_inspector: false,
+            show_event_log: false,
+            show_event_panel: false,
             camera_tracking_speed: 0.5,
+            camera_smoothstep: false,
+            day_length: 120.0,
+            night_metabolism_multiplier: 1.5,
+            strict_energy_conservation: true,
+            corpse_max_age: 3000.0,
+            vision_mode: VisionMode::NearestCells,
+            sensor_range: DEFAULT_SENSOR_RANGE,
+            auto_save_interval: 60.0,
+            use_storage: true,
+            plague_enabled: false,
+            plague_interval: 60.0,
+            plague_dominance_threshold: 0.7,
+            plague_infection_fraction: 0.3,
+            reproduction_cooldown_ticks: 30.0,
+            use_dynamic_threshold: false,
+            child_energy_ratio: 2.0 / 3.0,
+            parent_energy_ratio: 1.0 / 3.0,
+            mutation_rate_multiplier: 1.0,
+            weight_decay: 0.0001,
+            structured_mutation: false,
+            action_bias: [0.0; 4],
+            freeze_layers: FrozenLayers::default(),
+            fitness: FitnessFn::Composite,
+            nutrient_recycle_fraction: 0.0,
+            cull_fraction: 0.5,
+            genetic_distance_threshold: 0.2,
+            genome_diversity: false,
+            auto_restart_on_extinction: true,
+            cell_collisions: false,
+            parasite_fraction: 0.0,
+            energy: EnergyConfig::default(),
+            movement: MovementConfig::default(),
+            islands: IslandConfig::default(),
+            pheromones: PheromoneConfig::default(),
+            temperature: TemperatureConfig::default(),
+            zones: ZoneConfig::default(),
+            succession: SuccessionConfig::default(),
+            immigration: ImmigrationConfig::default(),
+            director: DirectorConfig::default(),
         }
     }
+
+    // Scarce energy for boom/bust population cycles: fast metabolism and decay, no kin
+    // bonus (relatives are just food), quick breeding and part of the decay recycled
+    pub fn predator_prey() -> Self {
+        Self {
+            config_version: CURRENT_CONFIG_VERSION,
+            world_width: 60000.0,
+            world_height: 40000.0,
+            initial_cell_count: 5000,
+            max_cells: None,
+            fps_grow_threshold: 55.0,
+            fps_shrink_threshold: 45.0,
+            min_cells: 500,
+            max_cells_limit: 7_000,
+            show_ui: true,
+            show_sensor_lines: true,
+            show_density_map: false,
+            show_pheromones: false,
+            show_temperature: false,
+            show_trails: false,
+            show_gene_stats: false,
+            show_cell_detail: true,
+            show_brain_inspector: false,
+            show_event_log: true,
+            show_event_panel: false,
+            camera_tracking_speed: 0.5,
+            camera_smoothstep: false,
+            day_length: 120.0,
+            night_metabolism_multiplier: 2.0,
+            strict_energy_conservation: true,
+            corpse_max_age: 3000.0,
+            vision_mode: VisionMode::NearestCells,
+            sensor_range: DEFAULT_SENSOR_RANGE,
+            auto_save_interval: 60.0,
+            use_storage: true,
+            plague_enabled: false,
+            plague_interval: 60.0,
+            plague_dominance_threshold: 0.7,
+            plague_infection_fraction: 0.3,
+            reproduction_cooldown_ticks: 15.0,
+            use_dynamic_threshold: false,
+            child_energy_ratio: 2.0 / 3.0,
+            parent_energy_ratio: 1.0 / 3.0,
+            mutation_rate_multiplier: 1.0,
+            weight_decay: 0.0001,
+            structured_mutation: false,
+            action_bias: [0.0; 4],
+            freeze_layers: FrozenLayers::default(),
+            fitness: FitnessFn::Composite,
+            nutrient_recycle_fraction: 0.3,
+            cull_fraction: 0.5,
+            genetic_distance_threshold: 0.0,
+            genome_diversity: false,
+            auto_restart_on_extinction: true,
+            cell_collisions: false,
+            parasite_fraction: 0.0,
+            energy: EnergyConfig {
+                metabolism_energy_loss: 0.05,
+                corpse_decay_rate: 0.05,
+                hunger_ramp_ticks: 200.0,
+                hunger_max_multiplier: 6.0,
+                plague_energy_drain: 0.1,
+                brain_cost_per_weight: 0.0,
+                ..EnergyConfig::default()
+            },
+            movement: MovementConfig::default(),
+            islands: IslandConfig::default(),
+            pheromones: PheromoneConfig::default(),
+            temperature: TemperatureConfig::default(),
+            zones: ZoneConfig::default(),
+            succession: SuccessionConfig::default(),
+            immigration: ImmigrationConfig::default(),
+            director: DirectorConfig::default(),
+        }
+    }
+
+    // A small, crowded world that evolves quickly on slow machines
+    pub fn small_world() -> Self {
+        Self {
+            config_version: CURRENT_CONFIG_VERSION,
+            world_width: 8000.0,
+            world_height: 6000.0,
+            initial_cell_count: 600,
+            max_cells: None,
+            fps_grow_threshold: 55.0,
+            fps_shrink_threshold: 45.0,
+            min_cells: 200,
+            max_cells_limit: 1_500,
+            show_ui: true,
+            show_sensor_lines: true,
+            show_density_map: false,
+            show_pheromones: false,
+            show_temperature: false,
+            show_trails: false,
+            show_gene_stats: false,
+            show_cell_detail: true,
+            show_brain_inspector: false,
+            show_event_log: true,
+            show_event_panel: false,
+            camera_tracking_speed: 0.5,
+            camera_smoothstep: false,
+            day_length: 120.0,
+            night_metabolism_multiplier: 1.5,
+            strict_energy_conservation: true,
+            corpse_max_age: 3000.0,
+            vision_mode: VisionMode::NearestCells,
+            sensor_range: DEFAULT_SENSOR_RANGE,
+            auto_save_interval: 60.0,
+            use_storage: true,
+            plague_enabled: false,
+            plague_interval: 60.0,
+            plague_dominance_threshold: 0.7,
+            plague_infection_fraction: 0.3,
+            reproduction_cooldown_ticks: 30.0,
+            use_dynamic_threshold: false,
+            child_energy_ratio: 2.0 / 3.0,
+            parent_energy_ratio: 1.0 / 3.0,
+            mutation_rate_multiplier: 1.0,
+            weight_decay: 0.0001,
+            structured_mutation: false,
+            action_bias: [0.0; 4],
+            freeze_layers: FrozenLayers::default(),
+            fitness: FitnessFn::Composite,
+            nutrient_recycle_fraction: 0.0,
+            cull_fraction: 0.5,
+            genetic_distance_threshold: 0.2,
+            genome_diversity: false,
+            auto_restart_on_extinction: true,
+            cell_collisions: false,
+            parasite_fraction: 0.0,
+            energy: EnergyConfig::default(),
+            movement: MovementConfig::default(),
+            islands: IslandConfig::default(),
+            pheromones: PheromoneConfig::default(),
+            temperature: TemperatureConfig::default(),
+            zones: ZoneConfig::default(),
+            succession: SuccessionConfig::default(),
+            immigration: ImmigrationConfig::default(),
+            director: DirectorConfig::default(),
+        }
+    }
+
+    // Three times the brain mutation rate: more exploration, less stable lineages
+    pub fn high_mutation() -> Self {
+        Self {
+            config_version: CURRENT_CONFIG_VERSION,
+            world_width: 60000.0,
+            world_height: 40000.0,
+            initial_cell_count: 5000,
+            max_cells: None,
+            fps_grow_threshold: 55.0,
+            fps_shrink_threshold: 45.0,
+            min_cells: 500,
+            max_cells_limit: 7_000,
+            show_ui: true,
+            show_sensor_lines: true,
+            show_density_map: false,
+            show_pheromones: false,
+            show_temperature: false,
+            show_trails: false,
+            show_gene_stats: false,
+            show_cell_detail: true,
+            show_brain_inspector: false,
+            show_event_log: true,
+            show_event_panel: false,
+            camera_tracking_speed: 0.5,
+            camera_smoothstep: false,
+            day_length: 120.0,
+            night_metabolism_multiplier: 1.5,
+            strict_energy_conservation: true,
+            corpse_max_age: 3000.0,
+            vision_mode: VisionMode::NearestCells,
+            sensor_range: DEFAULT_SENSOR_RANGE,
+            auto_save_interval: 60.0,
+            use_storage: true,
+            plague_enabled: false,
+            plague_interval: 60.0,
+            plague_dominance_threshold: 0.7,
+            plague_infection_fraction: 0.3,
+            reproduction_cooldown_ticks: 30.0,
+            use_dynamic_threshold: false,
+            child_energy_ratio: 2.0 / 3.0,
+            parent_energy_ratio: 1.0 / 3.0,
+            mutation_rate_multiplier: 3.0,
+            weight_decay: 0.0001,
+            structured_mutation: false,
+            action_bias: [0.0; 4],
+            freeze_layers: FrozenLayers::default(),
+            fitness: FitnessFn::Composite,
+            nutrient_recycle_fraction: 0.0,
+            cull_fraction: 0.5,
+            genetic_distance_threshold: 0.2,
+            genome_diversity: false,
+            auto_restart_on_extinction: true,
+            cell_collisions: false,
+            parasite_fraction: 0.0,
+            energy: EnergyConfig::default(),
+            movement: MovementConfig::default(),
+            islands: IslandConfig::default(),
+            pheromones: PheromoneConfig::default(),
+            temperature: TemperatureConfig::default(),
+            zones: ZoneConfig::default(),
+            succession: SuccessionConfig::default(),
+            immigration: ImmigrationConfig::default(),
+            director: DirectorConfig::default(),
+        }
+    }
+}
+
+// Overwrite `defaults` with every value present in `overrides`, recursing into objects
+// so a partial nested section keeps the defaults for its missing fields
+fn fill_from(defaults: &mut Value, overrides: Value) {
+    match (defaults, overrides) {
+        (Value::Object(defaults), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match defaults.get_mut(&key) {
+                    Some(default) => fill_from(default, value),
+                    None => {
+                        defaults.insert(key, value);
+                    }
+                }
+            }
+        }
+        (default, value) => *default = value,
+    }
+}
+
+// Named presets, in the order they are listed to users
+pub const SCENARIO_NAMES: [&str; 5] = [
+    "default",
+    "demo",
+    "predator_prey",
+    "small_world",
+    "high_mutation",
+];
+
+// Registry of every named preset (built once, on first use)
+pub fn scenarios() -> &'static HashMap<&'static str, SimulationConfig> {
+    SCENARIOS.get_or_init(|| {
+        HashMap::from([
+            ("default", SimulationConfig::default()),
+            ("demo", SimulationConfig::demo()),
+            ("predator_prey", SimulationConfig::predator_prey()),
+            ("small_world", SimulationConfig::small_world()),
+            ("high_mutation", SimulationConfig::high_mutation()),
+        ])
+    })
 }
 
-// Check if we're running in demo mode by reading from JavaScript
-fn is_demo_mode() -> bool {
+/// Select the scenario used from now on: get_config() rebuilds from it, and
+/// scenario_pending() tells a running window to restart its world.
+/// Returns false (and changes nothing) for an unknown name.
+pub fn set_scenario_by_name(name: &str) -> bool {
+    if !scenarios().contains_key(name) {
+        return false;
+    }
+    *SCENARIO.lock().unwrap() = Some(name.to_owned());
+    *CONFIG.lock().unwrap() = None;
+    SCENARIO_PENDING.store(true, Ordering::Relaxed);
+    true
+}
+
+/// Use a loaded config from now on instead of a scenario (e.g. --config <file>).
+/// Environment overrides are not applied on top of it.
+pub fn set_config(config: SimulationConfig) {
+    *CONFIG.lock().unwrap() = Some(config);
+}
+
+// A scenario was selected since the config was last built
+pub fn scenario_pending() -> bool {
+    SCENARIO_PENDING.load(Ordering::Relaxed)
+}
+
+// Scenario when none was selected: native builds keep DEMO_MODE=true as an alias for "demo"
+fn default_scenario() -> &'static str {
+    #[cfg(not(target_arch = "wasm32"))]
+    if std::env::var("DEMO_MODE").unwrap_or_default() == "true" {
+        return "demo";
+    }
+    "default"
+}
+
+// Native builds can opt into raycast vision with VISION_MODE=raycast
+fn is_raycast_vision() -> bool {
     #[cfg(target_arch = "wasm32")]
     {
-        unsafe extern "C" {
-            fn js_is_demo_mode() -> i32;
-        }
+        false
+    }
 
-        let is_demo = unsafe { js_is_demo_mode() == 1 };
-        println!("Rust: Demo mode check = {}", is_demo);
-        is_demo
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::env::var("VISION_MODE").unwrap_or_default() == "raycast"
+    }
+}
+
+// Native builds can enable islands mode with ISLANDS=<columns>x<rows> (e.g. ISLANDS=3x2)
+fn islands_grid() -> Option<(usize, usize)> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        None
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     {
-        // For native builds, read from environment variable
-        std::env::var("DEMO_MODE").unwrap_or_default() == "true"
+        let value = std::env::var("ISLANDS").ok()?;
+        let (columns, rows) = value.split_once('x')?;
+        Some((columns.trim().parse().ok()?, rows.trim().parse().ok()?))
     }
 }
 
-// Get the current configuration (cached after first call)
+// Get the current configuration (cached until another scenario is selected)
 pub fn get_config() -> SimulationConfig {
-    CONFIG
-        .get_or_init(|| {
-            let demo_mode = is_demo_mode();
-            println!("Initializing config, demo_mode={}", demo_mode);
-
-            let config = if demo_mode {
-                println!("Using DEMO config");
-                SimulationConfig::demo()
-            } else {
-                println!("Using DEFAULT config");
-                SimulationConfig::default()
-            };
-
-            println!("Config initialized: {:?}", config);
+    let mut cached = CONFIG.lock().unwrap();
+    if let Some(config) = cached.as_ref() {
+        return config.clone();
+    }
 
-            config
-        })
+    let name = SCENARIO
+        .lock()
+        .unwrap()
         .clone()
+        .unwrap_or_else(|| default_scenario().to_owned());
+    println!("Initializing config, scenario={}", name);
+    let mut config = scenarios()[name.as_str()].clone();
+
+    if is_raycast_vision() {
+        config.vision_mode = VisionMode::Raycast;
+    }
+
+    if let Some((columns, rows)) = islands_grid() {
+        config.islands.enabled = true;
+        config.islands.columns = columns;
+        config.islands.rows = rows;
+    }
+
+    println!("Config initialized: {:?}", config);
+
+    SCENARIO_PENDING.store(false, Ordering::Relaxed);
+    *cached = Some(config.clone());
+    config
+}
+
+// JavaScript API for scenarios. JS writes a name into the shared buffer and calls
+// set_scenario(scenario_buffer(), len); list_scenarios() fills the buffer with the
+// comma separated names and returns their length.
+#[cfg(target_arch = "wasm32")]
+mod js_api {
+    const BUFFER_SIZE: usize = 256;
+    static mut BUFFER: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn scenario_buffer() -> *mut u8 {
+        (&raw mut BUFFER).cast()
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn scenario_buffer_size() -> usize {
+        BUFFER_SIZE
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn list_scenarios() -> usize {
+        let names = super::SCENARIO_NAMES.join(",");
+        let len = names.len().min(BUFFER_SIZE);
+        // wasm is single threaded and JS only reads the buffer after this returns
+        unsafe { std::ptr::copy_nonoverlapping(names.as_ptr(), scenario_buffer(), len) };
+        len
+    }
+
+    /// Returns 1 if the scenario exists (the world restarts with it next frame), 0 otherwise
+    ///
+    /// # Safety
+    /// `name` must point to `name_len` readable bytes (normally scenario_buffer())
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn set_scenario(name: *const u8, name_len: usize) -> i32 {
+        let bytes = unsafe { std::slice::from_raw_parts(name, name_len) };
+        match std::str::from_utf8(bytes) {
+            Ok(name) if super::set_scenario_by_name(name) => 1,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brain_cost_is_per_weight_and_free_by_default() {
+        let mut energy = EnergyConfig::default();
+        assert_eq!(energy.brain_cost(2384), 0.0);
+        assert_eq!(energy.base_metabolism(2384), energy.metabolism_energy_loss);
+
+        energy.brain_cost_per_weight = 0.001;
+        assert!((energy.brain_cost(500) - 0.5).abs() < 1e-6);
+        assert!((energy.base_metabolism(500) - (energy.metabolism_energy_loss + 0.5)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_starting_energy_scales_with_mass_when_enabled() {
+        let mut energy = EnergyConfig::default();
+        assert_eq!(energy.starting_energy(180.0), 100.0);
+        assert_eq!(energy.starting_energy(220.0), 100.0);
+
+        energy.scale_initial_energy_by_mass = true;
+        assert_eq!(energy.starting_energy(180.0), 90.0);
+        assert_eq!(energy.starting_energy(220.0), 110.0);
+    }
+
+    #[test]
+    fn test_hot_reload_applies_live_fields_and_reports_the_rest() {
+        let file = SimulationConfig::default();
+        let mut running = file.clone();
+        let mut edited = file.clone();
+        edited.energy.metabolism_energy_loss = 0.07;
+        edited.mutation_rate_multiplier = 3.0;
+        edited.show_ui = !running.show_ui;
+        edited.world_width = 1234.0;
+        edited.initial_cell_count = 7;
+
+        let reload = running.hot_reload(&file, &edited).unwrap();
+        assert_eq!(
+            reload.applied,
+            ["energy", "mutation_rate_multiplier", "show_ui"]
+        );
+        assert_eq!(reload.needs_restart, ["initial_cell_count", "world_width"]);
+        assert_eq!(running.energy.metabolism_energy_loss, 0.07);
+        assert_eq!(running.mutation_rate_multiplier, 3.0);
+        assert_eq!(running.show_ui, edited.show_ui);
+        assert_eq!(running.world_width, SimulationConfig::default().world_width);
+        assert_eq!(
+            running.initial_cell_count,
+            SimulationConfig::default().initial_cell_count
+        );
+
+        // Reloading the same file again changes nothing
+        assert_eq!(
+            running.clone().hot_reload(&edited, &edited).unwrap(),
+            HotReload::default()
+        );
+
+        // A field toggled at runtime (P for the pheromone overlay) survives a save
+        // that only touched another field
+        running.show_pheromones = !edited.show_pheromones;
+        let mut saved = edited.clone();
+        saved.weight_decay = 0.5;
+        let reload = running.hot_reload(&edited, &saved).unwrap();
+        assert_eq!(reload.applied, ["weight_decay"]);
+        assert_eq!(running.show_pheromones, !edited.show_pheromones);
+        assert_eq!(running.weight_decay, 0.5);
+    }
+
+    #[test]
+    fn test_energy_split_is_validated_on_load() {
+        let config = SimulationConfig::default();
+        assert_eq!(config.validate(), Ok(()));
+
+        let cheap = r#"{ "child_energy_ratio": 0.25, "parent_energy_ratio": 0.5 }"#;
+        let loaded = SimulationConfig::from_json(cheap).expect("split under 1 loads");
+        assert_eq!(loaded.child_energy_ratio, 0.25);
+        assert_eq!(loaded.parent_energy_ratio, 0.5);
+
+        let over = r#"{ "child_energy_ratio": 0.8, "parent_energy_ratio": 0.5 }"#;
+        let error = SimulationConfig::from_json(over).unwrap_err();
+        assert!(error.contains("must not exceed 1"), "{}", error);
+        let negative = r#"{ "parent_energy_ratio": -0.1 }"#;
+        assert!(SimulationConfig::from_json(negative).is_err());
+        assert!(config.with_overrides(over).is_err());
+    }
+
+    #[test]
+    fn test_every_named_scenario_loads() {
+        assert_eq!(scenarios().len(), SCENARIO_NAMES.len());
+        for name in SCENARIO_NAMES {
+            let config = scenarios()
+                .get(name)
+                .unwrap_or_else(|| panic!("missing scenario {}", name));
+            assert_eq!(config.validate(), Ok(()), "{}", name);
+            assert!(config.world_width > 0.0, "{}", name);
+            assert!(config.world_height > 0.0, "{}", name);
+            assert!(config.min_cells <= config.max_cells_limit, "{}", name);
+        }
+        assert!(!set_scenario_by_name("no_such_scenario"));
+    }
+
+    #[test]
+    fn test_v1_config_migrates_with_defaults() {
+        // A config saved before versioning: no config_version, few of today's fields
+        let v1 = r#"{
+            "world_width": 8000.0,
+            "world_height": 6000.0,
+            "initial_cell_count": 300,
+            "show_ui": false,
+            "show_sensor_lines": true,
+            "camera_tracking_speed": 0.25,
+            "energy": { "metabolism_energy_loss": 0.05 }
+        }"#;
+        let config = SimulationConfig::from_json(v1).expect("v1 config migrates");
+        let defaults = SimulationConfig::default();
+
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.world_width, 8000.0);
+        assert_eq!(config.initial_cell_count, 300);
+        assert!(!config.show_ui);
+        assert_eq!(config.camera_tracking_speed, 0.25);
+        assert_eq!(config.energy.metabolism_energy_loss, 0.05);
+
+        // Fields added since version 1 take their defaults, also inside sections
+        assert_eq!(config.sensor_range, defaults.sensor_range);
+        assert_eq!(config.weight_decay, defaults.weight_decay);
+        assert_eq!(config.mutation_rate_multiplier, 1.0);
+        assert_eq!(config.vision_mode, VisionMode::NearestCells);
+        assert_eq!(config.max_cells, None);
+        assert_eq!(
+            config.energy.corpse_decay_rate,
+            defaults.energy.corpse_decay_rate
+        );
+        assert!(!config.islands.enabled);
+
+        // Current files round-trip; newer or malformed ones are rejected
+        let json = serde_json::to_string(&config).unwrap();
+        let reloaded = SimulationConfig::from_json(&json).unwrap();
+        assert_eq!(reloaded.world_height, 6000.0);
+        // Current files missing newer fields (or whole sections) load without migrating
+        let partial = format!(
+            r#"{{"config_version": {}, "day_length": 30.0}}"#,
+            CURRENT_CONFIG_VERSION
+        );
+        let partial = SimulationConfig::from_json(&partial).unwrap();
+        assert_eq!(partial.day_length, 30.0);
+        assert!(partial.pheromones.enabled);
+        let newer = json.replace(
+            &format!("\"config_version\":{}", CURRENT_CONFIG_VERSION),
+            "\"config_version\":99",
+        );
+        assert!(SimulationConfig::from_json(&newer).is_err());
+        assert!(SimulationConfig::from_json("[1, 2]").is_err());
+    }
 }
@@ -0,0 +1,100 @@
+use crate::config::SimulationConfig;
+use std::time::SystemTime;
+
+// Seconds between two checks of the watched file's modification time
+const CHECK_INTERVAL: f64 = 2.0;
+
+/// Watches the --config file while the window runs: polls its modification time and
+/// re-parses it when it changed, so tuning sessions don't need a restart
+pub struct ConfigWatcher {
+    path: String,
+    loaded: SimulationConfig, // Last successfully parsed contents of the file
+    modified: Option<SystemTime>,
+    next_check: f64,
+}
+
+impl ConfigWatcher {
+    // `loaded` is the config the file held when the world started
+    pub fn new(path: String, loaded: SimulationConfig) -> Self {
+        let modified = modified_time(&path);
+        ConfigWatcher {
+            path,
+            loaded,
+            modified,
+            next_check: CHECK_INTERVAL,
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    // The previously loaded and the re-parsed config (or why it failed to parse) when
+    // the file changed since the last check, so callers apply only what the edit
+    // changed. Checks at most once per CHECK_INTERVAL seconds of `now`.
+    pub fn poll(
+        &mut self,
+        now: f64,
+    ) -> Option<Result<(SimulationConfig, SimulationConfig), String>> {
+        if now < self.next_check {
+            return None;
+        }
+        self.next_check = now + CHECK_INTERVAL;
+
+        let modified = modified_time(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(SimulationConfig::from_file(&self.path).map(|reloaded| {
+            let previous = std::mem::replace(&mut self.loaded, reloaded.clone());
+            (previous, reloaded)
+        }))
+    }
+}
+
+// None while the file is missing (e.g. mid-save by an editor that replaces it)
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_reparses_only_after_the_file_changes() {
+        let path = std::env::temp_dir().join(format!("cells_watch_{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"show_ui": false}"#).unwrap();
+        let mut watcher = ConfigWatcher::new(
+            path.to_string_lossy().into_owned(),
+            SimulationConfig::from_file(&path.to_string_lossy()).unwrap(),
+        );
+        assert!(watcher.poll(CHECK_INTERVAL).is_none());
+
+        let touch = |json: &str, seconds: u64| {
+            std::fs::write(&path, json).unwrap();
+            let file = std::fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+                .unwrap();
+        };
+        touch(r#"{"show_ui": true}"#, 1_000);
+        assert!(watcher.poll(CHECK_INTERVAL + 1.0).is_none()); // Not time to check yet
+        let (previous, reloaded) = watcher.poll(CHECK_INTERVAL * 2.0).unwrap().unwrap();
+        assert!(!previous.show_ui);
+        assert!(reloaded.show_ui);
+        assert!(watcher.poll(CHECK_INTERVAL * 3.0).is_none());
+
+        touch("{ not json", 2_000);
+        assert!(watcher.poll(CHECK_INTERVAL * 4.0).unwrap().is_err());
+
+        // A failed parse keeps the last good contents as the baseline
+        touch(r#"{"show_ui": true, "weight_decay": 0.5}"#, 3_000);
+        let (previous, _) = watcher.poll(CHECK_INTERVAL * 5.0).unwrap().unwrap();
+        assert!(previous.show_ui);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.poll(CHECK_INTERVAL * 6.0).is_none());
+    }
+}
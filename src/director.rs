@@ -0,0 +1,174 @@
+use crate::config::DirectorConfig;
+
+// Share of the recorded activity kept each tick, so the grid reflects the last few
+// seconds (half of it fades in about 4 s at 60 ticks per second)
+const ACTIVITY_DECAY: f32 = 0.997;
+// Below this much activity a region counts as quiet
+const MIN_ACTIVITY: f32 = 1.0;
+
+/// Feeding and reproduction events per region of a coarse grid over the world,
+/// fading a little every tick
+#[derive(Debug, Clone)]
+pub struct ActivityGrid {
+    columns: usize,
+    rows: usize,
+    region_width: f32,
+    region_height: f32,
+    activity: Vec<f32>, // Row-major
+}
+
+impl ActivityGrid {
+    // Square-ish regions of about `region_size` world units
+    pub fn new(region_size: f32, world_width: f32, world_height: f32) -> Self {
+        let region_size = region_size.max(1.0);
+        let columns = ((world_width / region_size).ceil() as usize).max(1);
+        let rows = ((world_height / region_size).ceil() as usize).max(1);
+        ActivityGrid {
+            columns,
+            rows,
+            region_width: world_width / columns as f32,
+            region_height: world_height / rows as f32,
+            activity: vec![0.0; columns * rows],
+        }
+    }
+
+    fn region_of(&self, x: f32, y: f32) -> usize {
+        let column = ((x / self.region_width).floor().max(0.0) as usize).min(self.columns - 1);
+        let row = ((y / self.region_height).floor().max(0.0) as usize).min(self.rows - 1);
+        row * self.columns + column
+    }
+
+    pub fn record(&mut self, x: f32, y: f32) {
+        let region = self.region_of(x, y);
+        self.activity[region] += 1.0;
+    }
+
+    pub fn decay(&mut self) {
+        for activity in &mut self.activity {
+            *activity *= ACTIVITY_DECAY;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.activity.fill(0.0);
+    }
+
+    /// World center of the busiest region, None while every region is quiet
+    pub fn hottest(&self) -> Option<(f32, f32)> {
+        let (region, &activity) = self
+            .activity
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))?;
+        if activity < MIN_ACTIVITY {
+            return None;
+        }
+        let column = region % self.columns;
+        let row = region / self.columns;
+        Some((
+            (column as f32 + 0.5) * self.region_width,
+            (row as f32 + 0.5) * self.region_height,
+        ))
+    }
+}
+
+/// Auto-director (Y): points the camera at the busiest region, holds the shot for
+/// dwell_seconds, then looks again. World::direct_camera drives it every frame.
+#[derive(Debug, Clone)]
+pub struct AutoDirector {
+    pub activity: ActivityGrid,
+    active: bool,
+    dwell_seconds: f32,
+    dwell_left: f32, // Real seconds until the next shot is picked
+}
+
+impl AutoDirector {
+    pub fn new(config: DirectorConfig, world_width: f32, world_height: f32) -> Self {
+        AutoDirector {
+            activity: ActivityGrid::new(config.region_size, world_width, world_height),
+            active: config.enabled,
+            dwell_seconds: config.dwell_seconds,
+            dwell_left: 0.0,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    // Turning it on picks a shot right away
+    pub fn start(&mut self) {
+        self.active = true;
+        self.dwell_left = 0.0;
+    }
+
+    pub fn stop(&mut self) {
+        self.active = false;
+    }
+
+    /// Advance by `delta_time` real seconds. Returns the region center to move to once
+    /// the current shot has been held long enough; while everything is quiet it keeps
+    /// looking every frame.
+    pub fn next_shot(&mut self, delta_time: f32) -> Option<(f32, f32)> {
+        if !self.active {
+            return None;
+        }
+        self.dwell_left -= delta_time;
+        if self.dwell_left > 0.0 {
+            return None;
+        }
+        let shot = self.activity.hottest()?;
+        self.dwell_left = self.dwell_seconds;
+        Some(shot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hottest_region_follows_recent_activity() {
+        // World 1000 x 800 in 2 x 2 regions of 500 x 400
+        let mut grid = ActivityGrid::new(500.0, 1000.0, 800.0);
+        assert_eq!(grid.hottest(), None);
+        for _ in 0..3 {
+            grid.record(900.0, 700.0);
+        }
+        grid.record(10.0, 10.0);
+        assert_eq!(grid.hottest(), Some((750.0, 600.0)));
+
+        // Old events fade until the region counts as quiet
+        for _ in 0..1000 {
+            grid.decay();
+        }
+        assert_eq!(grid.hottest(), None);
+        grid.record(100.0, 799.9);
+        assert_eq!(grid.hottest(), Some((250.0, 600.0)));
+        grid.clear();
+        assert_eq!(grid.hottest(), None);
+    }
+
+    #[test]
+    fn test_director_holds_each_shot_for_the_dwell_time() {
+        let config = DirectorConfig {
+            dwell_seconds: 2.0,
+            region_size: 500.0,
+            ..DirectorConfig::default()
+        };
+        let mut director = AutoDirector::new(config, 1000.0, 800.0);
+        director.activity.record(10.0, 10.0);
+        assert_eq!(director.next_shot(0.1), None); // Off by default
+
+        director.start();
+        assert_eq!(director.next_shot(0.1), Some((250.0, 200.0)));
+        director.activity.record(900.0, 10.0);
+        director.activity.record(900.0, 10.0);
+        assert_eq!(director.next_shot(1.5), None); // Still holding
+        assert_eq!(director.next_shot(1.0), Some((750.0, 200.0)));
+
+        director.stop();
+        assert!(!director.is_active());
+        assert_eq!(director.next_shot(5.0), None);
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+/// What one era (the run between two resets) achieved, recorded when it ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EraSummary {
+    pub era: usize, // 1 for the first era of the run
+    pub ticks: usize,
+    pub peak_population: usize,
+    pub peak_fitness: f32,
+    pub reproductions: usize,
+    // Last values seen while cells were alive (at extinction both would read 0)
+    pub mean_generation: f32,
+    pub final_diversity: f32,
+}
+
+impl EraSummary {
+    // One line for the event log
+    pub fn message(&self) -> String {
+        format!(
+            "Era {}: {} ticks, peak {} cells, peak fitness {:.1}, {} births, mean gen {:.1}, diversity {:.2}",
+            self.era,
+            self.ticks,
+            self.peak_population,
+            self.peak_fitness,
+            self.reproductions,
+            self.mean_generation,
+            self.final_diversity
+        )
+    }
+}
+
+/// Running numbers of the current era plus the summaries of all finished ones
+#[derive(Debug, Default)]
+pub struct EraTracker {
+    peak_population: usize,
+    peak_fitness: f32,
+    reproductions: usize,
+    mean_generation: f32,
+    diversity: f32,
+    pub history: Vec<EraSummary>,
+}
+
+impl EraTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn current_era(&self) -> usize {
+        self.history.len() + 1
+    }
+
+    pub fn previous(&self) -> Option<&EraSummary> {
+        self.history.last()
+    }
+
+    // Fold in one stats update. An empty population keeps the last generation and
+    // diversity, so the summary describes the era before it died out.
+    pub fn observe(
+        &mut self,
+        alive_count: usize,
+        best_fitness: f32,
+        mean_generation: f32,
+        diversity: f32,
+    ) {
+        self.peak_population = self.peak_population.max(alive_count);
+        if alive_count > 0 {
+            self.peak_fitness = self.peak_fitness.max(best_fitness);
+            self.mean_generation = mean_generation;
+            self.diversity = diversity;
+        }
+    }
+
+    pub fn record_reproductions(&mut self, count: usize) {
+        self.reproductions += count;
+    }
+
+    // Close the current era after `ticks` ticks and start counting the next one
+    pub fn finish(&mut self, ticks: usize) -> &EraSummary {
+        let summary = EraSummary {
+            era: self.current_era(),
+            ticks,
+            peak_population: self.peak_population,
+            peak_fitness: self.peak_fitness,
+            reproductions: self.reproductions,
+            mean_generation: self.mean_generation,
+            final_diversity: self.diversity,
+        };
+        *self = EraTracker {
+            history: std::mem::take(&mut self.history),
+            ..Self::default()
+        };
+        self.history.push(summary);
+        &self.history[self.history.len() - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eras_keep_peaks_and_the_last_living_state() {
+        let mut tracker = EraTracker::new();
+        assert_eq!(tracker.current_era(), 1);
+        tracker.observe(50, 3.0, 2.0, 0.4);
+        tracker.observe(80, 1.0, 4.5, 0.3);
+        tracker.record_reproductions(12);
+        // Extinct: peaks and the last living generation and diversity stay
+        tracker.observe(0, 0.0, 0.0, 0.0);
+
+        let summary = tracker.finish(900).clone();
+        assert_eq!(
+            summary,
+            EraSummary {
+                era: 1,
+                ticks: 900,
+                peak_population: 80,
+                peak_fitness: 3.0,
+                reproductions: 12,
+                mean_generation: 4.5,
+                final_diversity: 0.3,
+            }
+        );
+        let line = serde_json::to_string(&summary).unwrap();
+        assert_eq!(serde_json::from_str::<EraSummary>(&line).unwrap(), summary);
+
+        // The next era starts from zero
+        assert_eq!(tracker.current_era(), 2);
+        assert_eq!(tracker.previous(), Some(&summary));
+        assert_eq!(tracker.finish(10).peak_population, 0);
+        assert_eq!(tracker.history.len(), 2);
+    }
+}
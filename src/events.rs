@@ -0,0 +1,142 @@
+/// Something notable that happened during a simulation tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimEvent {
+    CellDied { id: u64, fitness: f32 },
+    // A dead cell beat the saved best score of its brain tier
+    NewRecord { tier: usize, fitness: f32 },
+    // The alive population fell to a handful of cells
+    ExtinctionNear { alive_count: usize },
+    // The most advanced alive lineage reached a multiple of GENERATION_MILESTONE_INTERVAL
+    GenerationMilestone { generation: usize },
+    // All but the fittest few alive cells died; their offspring refilled the world
+    Bottleneck { survivors: usize, offspring: usize },
+    // Food regeneration runs at FOOD_BLOOM_MULTIPLIER times the usual rate for a while
+    FoodBloom { duration: f32 },
+}
+
+impl SimEvent {
+    pub fn message(&self) -> String {
+        match *self {
+            SimEvent::CellDied { id, fitness } => {
+                format!("Cell #{} died (fitness {:.1})", id, fitness)
+            }
+            SimEvent::NewRecord { tier, fitness } => {
+                format!("📈 New high score (tier {}): {:.1}", tier, fitness)
+            }
+            SimEvent::ExtinctionNear { alive_count } => {
+                format!("⚠ Near extinction: {} cells alive", alive_count)
+            }
+            SimEvent::GenerationMilestone { generation } => {
+                format!("🧬 Generation {} reached", generation)
+            }
+            SimEvent::Bottleneck {
+                survivors,
+                offspring,
+            } => format!(
+                "☠ Bottleneck: {} survivors repopulated {} cells",
+                survivors, offspring
+            ),
+            SimEvent::FoodBloom { duration } => {
+                format!("🌼 Food bloom! {:.0}s of plentiful food", duration)
+            }
+        }
+    }
+
+    // Worth the event log and the browser console; deaths only go to the event panel
+    pub fn is_important(&self) -> bool {
+        !matches!(self, SimEvent::CellDied { .. })
+    }
+}
+
+pub const FOOD_BLOOM_PROB_PER_TICK: f32 = 0.0001;
+pub const FOOD_BLOOM_DURATION: f32 = 30.0; // Seconds of simulation time
+pub const FOOD_BLOOM_MULTIPLIER: f32 = 4.0;
+
+/// An ongoing food bloom: corpses recycle FOOD_BLOOM_MULTIPLIER times the usual
+/// energy into pellets until the countdown runs out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FoodBloomEvent {
+    pub duration_remaining: f32,
+}
+
+impl FoodBloomEvent {
+    pub fn new() -> Self {
+        FoodBloomEvent {
+            duration_remaining: FOOD_BLOOM_DURATION,
+        }
+    }
+
+    // Count down by `delta_time`; false once the bloom is over
+    pub fn tick(&mut self, delta_time: f32) -> bool {
+        self.duration_remaining -= delta_time;
+        self.duration_remaining > 0.0
+    }
+}
+
+/// Events of the current frame, oldest first. Simulation ticks push onto it, and
+/// World::dispatch_events hands everything to the consumers (event panel, event
+/// log, browser console) once at the end of the frame, so tick code only reports
+/// what happened.
+pub struct EventBus {
+    events: Vec<SimEvent>,
+}
+
+impl EventBus {
+    pub const fn new() -> Self {
+        EventBus { events: Vec::new() }
+    }
+
+    pub fn push(&mut self, event: SimEvent) {
+        self.events.push(event);
+    }
+
+    // Everything pushed since the last drain, oldest first
+    pub fn drain(&mut self) -> std::vec::Drain<'_, SimEvent> {
+        self.events.drain(..)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+unsafe extern "C" {
+    /// console.log a string (JavaScript implementation)
+    fn console_log(message: *const u8, message_len: usize);
+}
+
+/// Print an event in the browser console (println! goes nowhere on the web)
+#[cfg(target_arch = "wasm32")]
+pub fn log_to_console(event: &SimEvent) {
+    let message = event.message();
+    unsafe {
+        console_log(message.as_ptr(), message.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bus_drains_in_order_and_empties() {
+        let mut bus = EventBus::new();
+        bus.push(SimEvent::CellDied {
+            id: 7,
+            fitness: 1.5,
+        });
+        bus.push(SimEvent::GenerationMilestone { generation: 100 });
+
+        let drained: Vec<SimEvent> = bus.drain().collect();
+        assert_eq!(drained.len(), 2);
+        assert!(!drained[0].is_important());
+        assert!(drained[1].is_important());
+        assert_eq!(drained[0].message(), "Cell #7 died (fitness 1.5)");
+        assert_eq!(bus.drain().count(), 0);
+    }
+
+    #[test]
+    fn test_food_bloom_counts_down_to_its_end() {
+        let mut bloom = FoodBloomEvent::new();
+        assert!(bloom.tick(FOOD_BLOOM_DURATION - 1.0));
+        assert!((bloom.duration_remaining - 1.0).abs() < 1e-5);
+        assert!(!bloom.tick(1.0));
+    }
+}
@@ -0,0 +1,207 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+// Runtime control from the hosting page. JS calls arrive outside the frame loop,
+// so the exports only queue commands (like config::CONFIG, a mutex the world
+// reads from); World::update drains the queue at the start of the next frame.
+static COMMANDS: CommandQueue = CommandQueue::new();
+// Latest numbers published by the world, reported by sim_get_stats
+static STATS: Mutex<Option<SimStats>> = Mutex::new(None);
+
+/// A request from the hosting page, applied by World::apply_command
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub enum SimCommand {
+    Pause(bool),
+    SetSpeed(f32),
+    Reset,
+    // Partial SimulationConfig JSON, merged over the running config
+    SetConfigJson(String),
+    // Exported genome JSON (Ctrl+E) or a bare NeuralNetwork JSON
+    InjectBrainJson(String),
+}
+
+/// Snapshot of the running world, serialized by sim_get_stats
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SimStats {
+    pub tick: usize,
+    pub population: usize,
+    pub fps: f32,
+    pub diversity: f32,
+    pub best_fitness: f32,
+    pub paused: bool,
+    pub speed: f32,
+}
+
+pub struct CommandQueue(Mutex<Vec<SimCommand>>);
+
+impl CommandQueue {
+    pub const fn new() -> Self {
+        CommandQueue(Mutex::new(Vec::new()))
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    pub fn push(&self, command: SimCommand) {
+        self.0.lock().unwrap().push(command);
+    }
+
+    // Everything queued since the last call, oldest first
+    pub fn take(&self) -> Vec<SimCommand> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub fn push_command(command: SimCommand) {
+    COMMANDS.push(command);
+}
+
+pub fn take_commands() -> Vec<SimCommand> {
+    COMMANDS.take()
+}
+
+pub fn publish_stats(stats: SimStats) {
+    *STATS.lock().unwrap() = Some(stats);
+}
+
+// "null" until the world has published once
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub fn stats_json() -> String {
+    serde_json::to_string(&*STATS.lock().unwrap()).unwrap_or_default()
+}
+
+// JavaScript API for runtime control (wrapped as window.cells in index.html).
+//
+// Memory ownership:
+// - Strings passed in belong to the caller. JS gets a buffer with sim_alloc(len),
+//   writes UTF-8 into it, makes the call and releases it with sim_free(ptr, len).
+//   The module copies the bytes before returning, so freeing right away is safe.
+// - The string returned by sim_get_stats belongs to the module. The pointer and
+//   sim_get_stats_len() stay valid until the next sim_get_stats call; JS decodes
+//   (copies) it right away and never frees it.
+#[cfg(target_arch = "wasm32")]
+mod js_api {
+    use super::{SimCommand, push_command, stats_json};
+    use std::sync::Mutex;
+
+    // Backing store of the last string returned to JS
+    static RETURNED: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+    // Copy a caller-owned UTF-8 string, None if it isn't valid UTF-8
+    unsafe fn read_string(ptr: *const u8, len: usize) -> Option<String> {
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, len) };
+        std::str::from_utf8(bytes).ok().map(str::to_owned)
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn sim_alloc(len: usize) -> *mut u8 {
+        let mut buffer = Vec::<u8>::with_capacity(len);
+        let ptr = buffer.as_mut_ptr();
+        std::mem::forget(buffer);
+        ptr
+    }
+
+    /// # Safety
+    /// `ptr` and `len` must come from one sim_alloc call, freed only once
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn sim_free(ptr: *mut u8, len: usize) {
+        drop(unsafe { Vec::from_raw_parts(ptr, 0, len) });
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn sim_pause(paused: bool) {
+        push_command(SimCommand::Pause(paused));
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn sim_set_speed(speed: f32) {
+        push_command(SimCommand::SetSpeed(speed));
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn sim_reset() {
+        push_command(SimCommand::Reset);
+    }
+
+    /// JSON {tick, population, fps, diversity, best_fitness, paused, speed}, as of
+    /// the last frame. Module-owned, see the ownership notes above.
+    #[unsafe(no_mangle)]
+    pub extern "C" fn sim_get_stats() -> *const u8 {
+        let mut returned = RETURNED.lock().unwrap();
+        *returned = stats_json().into_bytes();
+        returned.as_ptr()
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn sim_get_stats_len() -> usize {
+        RETURNED.lock().unwrap().len()
+    }
+
+    /// Returns 1 if queued, 0 if the text isn't a JSON object. Fields that don't
+    /// fit the config are reported in the event log when the command is applied.
+    ///
+    /// # Safety
+    /// `json` must point to `json_len` readable bytes (normally from sim_alloc)
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn sim_set_config_json(json: *const u8, json_len: usize) -> i32 {
+        match unsafe { read_string(json, json_len) } {
+            Some(json) if json.trim_start().starts_with('{') => {
+                push_command(SimCommand::SetConfigJson(json));
+                1
+            }
+            _ => 0,
+        }
+    }
+
+    /// Returns 1 if queued, 0 if the text isn't UTF-8. Incompatible brains are
+    /// reported in the event log when the command is applied.
+    ///
+    /// # Safety
+    /// `json` must point to `json_len` readable bytes (normally from sim_alloc)
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn sim_inject_brain_json(json: *const u8, json_len: usize) -> i32 {
+        match unsafe { read_string(json, json_len) } {
+            Some(json) => {
+                push_command(SimCommand::InjectBrainJson(json));
+                1
+            }
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_drain_in_order_and_stats_serialize() {
+        // A local queue: the global one is drained by every World::update
+        let queue = CommandQueue::new();
+        queue.push(SimCommand::Pause(true));
+        queue.push(SimCommand::SetSpeed(2.0));
+        queue.push(SimCommand::Reset);
+        assert_eq!(
+            queue.take(),
+            vec![
+                SimCommand::Pause(true),
+                SimCommand::SetSpeed(2.0),
+                SimCommand::Reset
+            ]
+        );
+        assert!(queue.take().is_empty());
+
+        let stats = SimStats {
+            tick: 42,
+            population: 7,
+            fps: 60.0,
+            diversity: 0.5,
+            best_fitness: 123.5,
+            paused: false,
+            speed: 1.5,
+        };
+        let json: serde_json::Value = serde_json::to_value(stats).unwrap();
+        assert_eq!(json["population"], 7);
+        assert_eq!(json["best_fitness"], 123.5);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::cell::Cell;
+use serde::{Deserialize, Serialize};
+
+/// How a cell's fitness is scored. The one definition behind best cell tracking,
+/// the brains saved per tier, culling and the score in the stats box.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum FitnessFn {
+    /// 100 per child, 1 per energy eaten, 10 per age unit, 50 per tracking point,
+    /// minus the density penalty
+    #[default]
+    Composite,
+    /// Current energy plus `child_weight` per child
+    EnergyPlusChildren { child_weight: f32 },
+    /// Total energy eaten over the cell's life
+    LifetimeEnergy,
+    /// Number of children
+    ChildrenOnly,
+    /// Survival time: `age_weight` per age unit
+    AgeWeighted { age_weight: f32 },
+}
+
+// Presets cycled through by the fitness hotkey, in order
+pub const FITNESS_PRESETS: [FitnessFn; 5] = [
+    FitnessFn::Composite,
+    FitnessFn::EnergyPlusChildren {
+        child_weight: 100.0,
+    },
+    FitnessFn::LifetimeEnergy,
+    FitnessFn::ChildrenOnly,
+    FitnessFn::AgeWeighted { age_weight: 10.0 },
+];
+
+impl FitnessFn {
+    pub fn score(&self, cell: &Cell) -> f32 {
+        match *self {
+            FitnessFn::Composite => {
+                let children_score = cell.children_count as f32 * 100.0;
+                let energy_score = cell.energy_from_cells;
+                let age_score = cell.age * 10.0;
+                // ~100 ticks of good tracking ≈ half a child's worth of score
+                let tracking = cell.tracking_score * 50.0;
+                children_score + energy_score + age_score + tracking - cell.density_penalty
+            }
+            FitnessFn::EnergyPlusChildren { child_weight } => {
+                cell.energy + cell.children_count as f32 * child_weight
+            }
+            FitnessFn::LifetimeEnergy => cell.energy_from_cells,
+            FitnessFn::ChildrenOnly => cell.children_count as f32,
+            FitnessFn::AgeWeighted { age_weight } => cell.age * age_weight,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FitnessFn::Composite => "composite",
+            FitnessFn::EnergyPlusChildren { .. } => "energy + children",
+            FitnessFn::LifetimeEnergy => "lifetime energy",
+            FitnessFn::ChildrenOnly => "children only",
+            FitnessFn::AgeWeighted { .. } => "age weighted",
+        }
+    }
+
+    /// The preset after this variant (wrapping), with that preset's weights
+    pub fn next_preset(&self) -> Self {
+        let current = FITNESS_PRESETS
+            .iter()
+            .position(|preset| std::mem::discriminant(preset) == std::mem::discriminant(self))
+            .unwrap_or(0);
+        FITNESS_PRESETS[(current + 1) % FITNESS_PRESETS.len()]
+    }
+}
+
+/// Fitness defined in code rather than config, for experiments (weighting survival,
+/// rewarding diversity, ...). Set with World::set_fitness_plugin.
+pub type FitnessPlugin = fn(&Cell) -> f32;
+
+/// The fitness a world scores cells with: a configured built-in, or a plugin
+/// overriding it
+#[derive(Clone, Copy)]
+pub enum Fitness {
+    Builtin(FitnessFn),
+    Plugin(FitnessPlugin),
+}
+
+impl Fitness {
+    pub fn score(&self, cell: &Cell) -> f32 {
+        match self {
+            Fitness::Builtin(fitness) => fitness.score(cell),
+            Fitness::Plugin(plugin) => plugin(cell),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fitness::Builtin(fitness) => fitness.name(),
+            Fitness::Plugin(_) => "plugin",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::VisionMode;
+
+    fn cell(energy: f32, energy_from_cells: f32, children: usize, age: f32) -> Cell {
+        let mut cell = Cell::spawn(1000.0, 1000.0, 0, &None, VisionMode::NearestCells);
+        cell.energy = energy;
+        cell.energy_from_cells = energy_from_cells;
+        cell.children_count = children;
+        cell.age = age;
+        cell
+    }
+
+    #[test]
+    fn test_composite_matches_previous_score() {
+        let mut scored = cell(500.0, 50.5, 2, 10.0);
+        assert_eq!(FitnessFn::Composite.score(&scored), 200.0 + 50.5 + 100.0);
+
+        scored.tracking_score = 2.0;
+        scored.density_penalty = 30.0;
+        assert_eq!(FitnessFn::Composite.score(&scored), 350.5 + 100.0 - 30.0);
+        assert_eq!(FitnessFn::Composite.score(&cell(0.0, 0.0, 0, 0.0)), 0.0);
+        assert_eq!(FitnessFn::Composite.score(&cell(0.0, 20.0, 1, 5.0)), 170.0);
+        assert_eq!(
+            FitnessFn::Composite.score(&cell(0.0, 10000.0, 1000, 1000.0)),
+            120000.0
+        );
+    }
+
+    #[test]
+    fn test_energy_plus_children_uses_current_energy() {
+        let fitness = FitnessFn::EnergyPlusChildren {
+            child_weight: 100.0,
+        };
+        assert_eq!(fitness.score(&cell(80.0, 999.0, 3, 50.0)), 380.0);
+        let fitness = FitnessFn::EnergyPlusChildren { child_weight: 0.5 };
+        assert_eq!(fitness.score(&cell(80.0, 999.0, 3, 50.0)), 81.5);
+    }
+
+    #[test]
+    fn test_lifetime_energy_counts_only_energy_eaten() {
+        let fitness = FitnessFn::LifetimeEnergy;
+        assert_eq!(fitness.score(&cell(80.0, 250.0, 3, 50.0)), 250.0);
+        assert!(
+            fitness.score(&cell(0.0, 300.0, 0, 1.0)) > fitness.score(&cell(900.0, 0.0, 9, 90.0))
+        );
+    }
+
+    #[test]
+    fn test_children_only_ignores_everything_else() {
+        let fitness = FitnessFn::ChildrenOnly;
+        assert_eq!(fitness.score(&cell(80.0, 250.0, 3, 50.0)), 3.0);
+        assert_eq!(fitness.score(&cell(900.0, 900.0, 0, 90.0)), 0.0);
+    }
+
+    #[test]
+    fn test_age_weighted_rewards_survival() {
+        let fitness = FitnessFn::AgeWeighted { age_weight: 10.0 };
+        assert_eq!(fitness.score(&cell(80.0, 250.0, 3, 12.5)), 125.0);
+        assert!(
+            fitness.score(&cell(0.0, 0.0, 0, 40.0)) > fitness.score(&cell(900.0, 900.0, 9, 20.0))
+        );
+    }
+
+    // Ids of `cells`, best first
+    fn ranking(fitness: Fitness, cells: &[Cell]) -> Vec<u64> {
+        let mut ranked: Vec<&Cell> = cells.iter().collect();
+        ranked.sort_by(|a, b| fitness.score(b).total_cmp(&fitness.score(a)));
+        ranked.iter().map(|cell| cell.id).collect()
+    }
+
+    #[test]
+    fn test_each_variant_ranks_cells_by_its_own_criterion() {
+        // 1: many children, 2: big eater, 3: veteran, 4: hoarder of current energy
+        let mut cells = vec![
+            cell(10.0, 50.0, 8, 5.0),
+            cell(20.0, 1500.0, 1, 10.0),
+            cell(30.0, 100.0, 0, 120.0),
+            cell(2000.0, 0.0, 2, 2.0),
+        ];
+        for (i, cell) in cells.iter_mut().enumerate() {
+            cell.id = i as u64 + 1;
+        }
+
+        let builtin = |fitness| ranking(Fitness::Builtin(fitness), &cells);
+        assert_eq!(builtin(FitnessFn::Composite), vec![2, 3, 1, 4]);
+        assert_eq!(
+            builtin(FitnessFn::EnergyPlusChildren {
+                child_weight: 100.0
+            }),
+            vec![4, 1, 2, 3]
+        );
+        assert_eq!(builtin(FitnessFn::LifetimeEnergy), vec![2, 3, 1, 4]);
+        assert_eq!(builtin(FitnessFn::ChildrenOnly), vec![1, 4, 2, 3]);
+        assert_eq!(
+            builtin(FitnessFn::AgeWeighted { age_weight: 10.0 }),
+            vec![3, 2, 1, 4]
+        );
+
+        // A plugin can score anything about the cell
+        let fewest_children: FitnessPlugin = |cell| -(cell.children_count as f32);
+        assert_eq!(
+            ranking(Fitness::Plugin(fewest_children), &cells),
+            vec![3, 2, 4, 1]
+        );
+        assert_eq!(Fitness::Plugin(fewest_children).name(), "plugin");
+    }
+
+    #[test]
+    fn test_next_preset_cycles_through_every_variant() {
+        // Weights are not carried over: cycling lands on each preset's own
+        let mut fitness = FitnessFn::EnergyPlusChildren { child_weight: 3.0 }.next_preset();
+        assert_eq!(fitness, FitnessFn::LifetimeEnergy);
+        for _ in 0..FITNESS_PRESETS.len() {
+            fitness = fitness.next_preset();
+        }
+        assert_eq!(fitness, FitnessFn::LifetimeEnergy);
+        assert_eq!(
+            FitnessFn::AgeWeighted { age_weight: 1.0 }.next_preset(),
+            FitnessFn::Composite
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::camera::ViewBounds;
+use crate::spatial_grid::{SpatialGrid, confine};
+use macroquad::prelude::*;
+
+pub const PELLETS_PER_CORPSE: usize = 3;
+pub const PELLET_RADIUS: f32 = 3.0;
+// Keeps rendering and sensing bounded; energy recycled past the cap is lost
+pub const MAX_FOOD_PELLETS: usize = 5000;
+const PELLET_COLOR: Color = Color::new(0.75, 0.9, 0.3, 0.9);
+
+/// A nutrient pellet left behind by a decayed corpse, eaten whole on contact
+#[derive(Debug, Clone, Copy)]
+pub struct FoodPellet {
+    pub id: u64, // Drawn from the world's cell ids, so it never matches a cell
+    pub x: f32,
+    pub y: f32,
+    pub energy: f32,
+}
+
+/// All food pellets in the world with their own spatial grid
+pub struct FoodPellets {
+    pub pellets: Vec<FoodPellet>,
+    grid: SpatialGrid,
+    wrapping: bool,
+}
+
+impl FoodPellets {
+    pub fn new(world_width: f32, world_height: f32) -> Self {
+        FoodPellets {
+            pellets: Vec::new(),
+            grid: SpatialGrid::new(world_width, world_height, 100.0),
+            wrapping: true,
+        }
+    }
+
+    // Follows MovementConfig::wrapping: pellets land inside the walls and are only
+    // sensed across the edges of a wrapping world
+    pub fn set_wrapping(&mut self, wrapping: bool) {
+        self.wrapping = wrapping;
+        self.grid.set_wrapping(wrapping);
+    }
+
+    /// Split `energy` evenly over PELLETS_PER_CORPSE pellets scattered within `radius`
+    /// of (x, y), wrapped (or clamped) into the world. `ids` yields a fresh id per pellet.
+    /// Returns the energy actually placed (less than `energy` once the cap is hit).
+    pub fn spawn_from_corpse(
+        &mut self,
+        (x, y): (f32, f32),
+        radius: f32,
+        energy: f32,
+        (world_width, world_height): (f32, f32),
+        mut ids: impl FnMut() -> u64,
+    ) -> f32 {
+        let room = MAX_FOOD_PELLETS.saturating_sub(self.pellets.len());
+        let count = PELLETS_PER_CORPSE.min(room);
+        if energy <= 0.0 || count == 0 {
+            return 0.0;
+        }
+
+        let pellet_energy = energy / PELLETS_PER_CORPSE as f32;
+        for _ in 0..count {
+            let angle = rand::gen_range(0.0, std::f32::consts::TAU);
+            let distance = rand::gen_range(0.0, radius);
+            self.pellets.push(FoodPellet {
+                id: ids(),
+                x: confine(x + angle.cos() * distance, world_width, self.wrapping),
+                y: confine(y + angle.sin() * distance, world_height, self.wrapping),
+                energy: pellet_energy,
+            });
+        }
+        pellet_energy * count as f32
+    }
+
+    pub fn total_energy(&self) -> f32 {
+        self.pellets.iter().map(|pellet| pellet.energy).sum()
+    }
+
+    // Call after pellets were added or removed, before querying
+    pub fn rebuild_grid(&mut self) {
+        self.grid.clear();
+        for (idx, pellet) in self.pellets.iter().enumerate() {
+            self.grid.insert(pellet.x, pellet.y, idx);
+        }
+    }
+
+    /// Pellets within `radius` of (x, y), as `visit(index, dx, dy, distance_squared)`
+    /// (see SpatialGrid::for_each_within)
+    pub fn for_each_within(
+        &self,
+        x: f32,
+        y: f32,
+        radius: f32,
+        visit: impl FnMut(usize, f32, f32, f32),
+    ) {
+        if self.pellets.is_empty() {
+            return;
+        }
+        let position = |p: usize| (self.pellets[p].x, self.pellets[p].y);
+        self.grid.for_each_within(x, y, radius, position, visit);
+    }
+
+    pub fn clear(&mut self) {
+        self.pellets.clear();
+        self.grid.clear();
+    }
+
+    // Small dots, repeated across the wrapped world edges like the cells
+    pub fn render(
+        &self,
+        camera_x: f32,
+        camera_y: f32,
+        view: &ViewBounds,
+        world_width: f32,
+        world_height: f32,
+    ) {
+        for offset_x in [-world_width, 0.0, world_width] {
+            for offset_y in [-world_height, 0.0, world_height] {
+                for pellet in &self.pellets {
+                    let x = pellet.x + offset_x - camera_x;
+                    let y = pellet.y + offset_y - camera_y;
+                    if view.contains(x, y, PELLET_RADIUS) {
+                        draw_circle(x, y, PELLET_RADIUS, PELLET_COLOR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawn_from_corpse_splits_energy_and_respects_cap() {
+        let mut food = FoodPellets::new(1000.0, 1000.0);
+        let mut next_id = 100;
+        let mut ids = || {
+            next_id += 1;
+            next_id
+        };
+
+        // Near the corner: pellets wrap into the world
+        let placed = food.spawn_from_corpse((2.0, 998.0), 10.0, 30.0, (1000.0, 1000.0), &mut ids);
+        assert_eq!(placed, 30.0);
+        assert_eq!(food.pellets.len(), PELLETS_PER_CORPSE);
+        assert!((food.total_energy() - 30.0).abs() < 1e-4);
+        assert!(food.pellets.iter().all(|p| (0.0..1000.0).contains(&p.x)));
+        assert!(food.pellets.iter().all(|p| (0.0..1000.0).contains(&p.y)));
+
+        food.rebuild_grid();
+        let mut nearby = 0;
+        food.for_each_within(0.0, 0.0, 20.0, |_, _, _, _| nearby += 1);
+        assert_eq!(nearby, PELLETS_PER_CORPSE);
+
+        // Nothing to recycle, or no room left
+        assert_eq!(
+            food.spawn_from_corpse((0.0, 0.0), 10.0, 0.0, (1000.0, 1000.0), &mut ids),
+            0.0
+        );
+        food.pellets.resize(MAX_FOOD_PELLETS, food.pellets[0]);
+        assert_eq!(
+            food.spawn_from_corpse((0.0, 0.0), 10.0, 30.0, (1000.0, 1000.0), &mut ids),
+            0.0
+        );
+    }
+}
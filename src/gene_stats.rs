@@ -0,0 +1,332 @@
+use crate::cell::{Cell, CellState};
+use macroquad::prelude::*;
+
+pub const GENE_BINS: usize = 10;
+pub const TRAIT_COUNT: usize = 5;
+
+// Panel layout (top-right corner): per trait a label line over a sparkline of the bins
+const PANEL_WIDTH: f32 = 260.0;
+const PANEL_MARGIN: f32 = 20.0;
+const PADDING: f32 = 10.0;
+const SPARKLINE_HEIGHT: f32 = 22.0;
+const LABEL_HEIGHT: f32 = 20.0;
+
+/// Heritable traits shown in the gene panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trait {
+    Speed,
+    Radius,
+    SpeciesMultiplier,
+    Mass,
+    MutationRate, // Brain mutation rate factor (Cell::mutation_rate_factor)
+}
+
+impl Trait {
+    pub const ALL: [Trait; TRAIT_COUNT] = [
+        Trait::Speed,
+        Trait::Radius,
+        Trait::SpeciesMultiplier,
+        Trait::Mass,
+        Trait::MutationRate,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Trait::Speed => "Speed",
+            Trait::Radius => "Radius",
+            Trait::SpeciesMultiplier => "Species mult.",
+            Trait::Mass => "Mass",
+            Trait::MutationRate => "Mutation rate",
+        }
+    }
+
+    // Fixed trait ranges, so bins stay comparable from one update to the next
+    // (they match the ranges of Cell::spawn and the clamps of Cell::spawn_child)
+    fn range(self) -> (f32, f32) {
+        match self {
+            Trait::Speed => (0.2, 1.0),
+            Trait::Radius => (6.0, 15.0),
+            Trait::SpeciesMultiplier => (0.9, 2.0),
+            Trait::Mass => (180.0, 220.0),
+            Trait::MutationRate => (0.3, 1.0),
+        }
+    }
+
+    fn value(self, cell: &Cell) -> f32 {
+        match self {
+            Trait::Speed => cell.speed,
+            Trait::Radius => cell.radius,
+            Trait::SpeciesMultiplier => cell.species_multiplier,
+            Trait::Mass => cell.mass,
+            Trait::MutationRate => cell.mutation_rate_factor(),
+        }
+    }
+}
+
+/// Distribution of one trait over the alive population: 10 equal bins, labelled
+/// with the observed min, mean and max (all 0 without alive cells)
+#[derive(Debug, Clone, Default)]
+pub struct TraitDistribution {
+    pub histogram: [u32; GENE_BINS],
+    // Average cell color of each bin (gray for empty bins)
+    pub colors: [Color; GENE_BINS],
+    label: String, // Built once per refresh so drawing doesn't allocate
+}
+
+/// Trait distributions plus a population turnover line (alive count, mean
+/// generation and mean age) of the alive cells
+#[derive(Debug, Clone, Default)]
+pub struct GeneStats {
+    pub traits: [TraitDistribution; TRAIT_COUNT], // In Trait::ALL order
+    summary: String,
+}
+
+// Bin of a value within (min, max); out-of-range values land in the edge bins
+fn bin_index(value: f32, (min, max): (f32, f32)) -> usize {
+    let t = (value - min) / (max - min);
+    ((t * GENE_BINS as f32) as isize).clamp(0, GENE_BINS as isize - 1) as usize
+}
+
+// Running counts, color sums and extremes of one trait
+#[derive(Clone, Copy)]
+struct TraitAccumulator {
+    counts: [u32; GENE_BINS],
+    color_sums: [(f32, f32, f32); GENE_BINS],
+    min: f32,
+    max: f32,
+    sum: f64,
+}
+
+impl TraitAccumulator {
+    fn new() -> Self {
+        TraitAccumulator {
+            counts: [0; GENE_BINS],
+            color_sums: [(0.0, 0.0, 0.0); GENE_BINS],
+            min: f32::MAX,
+            max: f32::MIN,
+            sum: 0.0,
+        }
+    }
+
+    fn add(&mut self, value: f32, range: (f32, f32), color: Color) {
+        let bin = bin_index(value, range);
+        self.counts[bin] += 1;
+        let sum = &mut self.color_sums[bin];
+        sum.0 += color.r;
+        sum.1 += color.g;
+        sum.2 += color.b;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value as f64;
+    }
+
+    fn finish(self, name: &str, count: u32) -> TraitDistribution {
+        let mut colors = [GRAY; GENE_BINS];
+        for (bin, color) in colors.iter_mut().enumerate() {
+            let count = self.counts[bin];
+            if count > 0 {
+                let (r, g, b) = self.color_sums[bin];
+                let n = count as f32;
+                *color = Color::new(r / n, g / n, b / n, 1.0);
+            }
+        }
+        let (min, mean, max) = if count > 0 {
+            (self.min, (self.sum / count as f64) as f32, self.max)
+        } else {
+            (0.0, 0.0, 0.0)
+        };
+        TraitDistribution {
+            histogram: self.counts,
+            colors,
+            label: format!("{}  {:.2} / {:.2} / {:.2}", name, min, mean, max),
+        }
+    }
+}
+
+/// Collects GeneStats one alive cell at a time, so World::update_stats can fold it
+/// into the pass over the cells it already makes
+pub struct GeneStatsAccumulator {
+    traits: [TraitAccumulator; TRAIT_COUNT],
+    alive_count: u32,
+    generation_sum: f64,
+    age_sum: f64,
+}
+
+impl GeneStatsAccumulator {
+    pub fn new() -> Self {
+        GeneStatsAccumulator {
+            traits: [TraitAccumulator::new(); TRAIT_COUNT],
+            alive_count: 0,
+            generation_sum: 0.0,
+            age_sum: 0.0,
+        }
+    }
+
+    // Callers pass alive cells only
+    pub fn add(&mut self, cell: &Cell) {
+        for (accumulator, gene) in self.traits.iter_mut().zip(Trait::ALL) {
+            accumulator.add(gene.value(cell), gene.range(), cell.color);
+        }
+        self.alive_count += 1;
+        self.generation_sum += cell.generation as f64;
+        self.age_sum += cell.age as f64;
+    }
+
+    pub fn finish(self) -> GeneStats {
+        let count = self.alive_count;
+        let mean = |sum: f64| {
+            if count > 0 {
+                (sum / count as f64) as f32
+            } else {
+                0.0
+            }
+        };
+        let (mean_generation, mean_age) = (mean(self.generation_sum), mean(self.age_sum));
+        GeneStats {
+            traits: std::array::from_fn(|i| self.traits[i].finish(Trait::ALL[i].name(), count)),
+            summary: format!(
+                "{} alive | mean gen {:.1} | mean age {:.0}",
+                count, mean_generation, mean_age
+            ),
+        }
+    }
+}
+
+impl GeneStats {
+    /// Bucket every alive cell's traits (see GeneStatsAccumulator for the one-pass form)
+    pub fn compute(cells: &[Cell]) -> GeneStats {
+        let mut accumulator = GeneStatsAccumulator::new();
+        for cell in cells.iter().filter(|c| c.state == CellState::Alive) {
+            accumulator.add(cell);
+        }
+        accumulator.finish()
+    }
+
+    // The panel walks all traits in order
+    #[cfg(test)]
+    pub fn distribution(&self, gene: Trait) -> &TraitDistribution {
+        &self.traits[gene as usize]
+    }
+
+    // One block per trait: "name  min / mean / max" over a sparkline (one bar per
+    // bin, low values on the left, heights relative to the fullest bin of that
+    // trait), under a population line. Labels are prebuilt, so drawing allocates nothing.
+    pub fn render(&self, font: Option<&Font>) {
+        let block_height = LABEL_HEIGHT + SPARKLINE_HEIGHT + PADDING;
+        let panel_height = PADDING + LABEL_HEIGHT + block_height * TRAIT_COUNT as f32;
+        let panel_x = screen_width() - PANEL_WIDTH - PANEL_MARGIN;
+        let panel_y = PANEL_MARGIN;
+        draw_rectangle(
+            panel_x,
+            panel_y,
+            PANEL_WIDTH,
+            panel_height,
+            Color::new(0.0, 0.0, 0.0, 0.75),
+        );
+        let text = |label: &str, y: f32, color: Color| {
+            draw_text_ex(
+                label,
+                panel_x + PADDING,
+                y,
+                TextParams {
+                    font,
+                    font_size: 16,
+                    color,
+                    ..Default::default()
+                },
+            );
+        };
+        text(
+            &self.summary,
+            panel_y + PADDING + LABEL_HEIGHT - 6.0,
+            LIGHTGRAY,
+        );
+
+        let bar_width = (PANEL_WIDTH - PADDING * 2.0) / GENE_BINS as f32;
+        for (index, distribution) in self.traits.iter().enumerate() {
+            let top = panel_y + PADDING + LABEL_HEIGHT + block_height * index as f32;
+            text(&distribution.label, top + LABEL_HEIGHT - 6.0, WHITE);
+
+            let baseline = top + LABEL_HEIGHT + SPARKLINE_HEIGHT;
+            let peak = distribution
+                .histogram
+                .iter()
+                .copied()
+                .max()
+                .unwrap_or(0)
+                .max(1) as f32;
+            for (bin, &count) in distribution.histogram.iter().enumerate() {
+                let height = SPARKLINE_HEIGHT * count as f32 / peak;
+                draw_rectangle(
+                    panel_x + PADDING + bar_width * bin as f32,
+                    baseline - height,
+                    bar_width - 1.0,
+                    height,
+                    distribution.colors[bin],
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::VisionMode;
+
+    #[test]
+    fn test_compute_buckets_alive_cells_only() {
+        let mut cells: Vec<Cell> = (0..4)
+            .map(|_| Cell::spawn(1000.0, 1000.0, 0, &None, VisionMode::NearestCells))
+            .collect();
+        cells[0].speed = 0.2; // Lowest bin
+        cells[0].color = RED;
+        cells[1].speed = 1.0; // Range max falls in the last bin
+        cells[1].color = Color::new(0.0, 0.0, 1.0, 1.0);
+        cells[2].speed = 1.0;
+        cells[2].color = Color::new(0.0, 0.0, 0.5, 1.0);
+        cells[3].speed = 0.6;
+        cells[3].state = CellState::Corpse; // Ignored
+
+        let stats = GeneStats::compute(&cells);
+        let speed = stats.distribution(Trait::Speed);
+        assert_eq!(speed.histogram[0], 1);
+        assert_eq!(speed.histogram[GENE_BINS - 1], 2);
+        for distribution in &stats.traits {
+            assert_eq!(distribution.histogram.iter().sum::<u32>(), 3);
+        }
+
+        // Bin colors average the cells in that bin
+        assert_eq!(speed.colors[0], RED);
+        assert!((speed.colors[GENE_BINS - 1].b - 0.75).abs() < 1e-5);
+        assert_eq!(speed.colors[5], GRAY);
+    }
+
+    #[test]
+    fn test_summaries_cover_extremes_and_turnover() {
+        let mut cells: Vec<Cell> = (0..3)
+            .map(|_| Cell::spawn(1000.0, 1000.0, 0, &None, VisionMode::NearestCells))
+            .collect();
+        for (cell, (mass, generation, age)) in
+            cells
+                .iter_mut()
+                .zip([(190.0, 2, 10.0), (200.0, 4, 20.0), (216.0, 9, 60.0)])
+        {
+            (cell.mass, cell.generation, cell.age) = (mass, generation, age);
+        }
+        let stats = GeneStats::compute(&cells);
+        assert_eq!(
+            stats.distribution(Trait::Mass).label,
+            "Mass  190.00 / 202.00 / 216.00"
+        );
+        assert_eq!(stats.summary, "3 alive | mean gen 5.0 | mean age 30");
+
+        // No alive cells: zeros instead of the accumulators' sentinels
+        let empty = GeneStats::compute(&[]);
+        assert_eq!(
+            empty.distribution(Trait::Speed).label,
+            "Speed  0.00 / 0.00 / 0.00"
+        );
+        assert_eq!(empty.summary, "0 alive | mean gen 0.0 | mean age 0");
+    }
+}
@@ -0,0 +1,132 @@
+use crate::camera::ViewBounds;
+use crate::cell::{Cell, CellState};
+use crate::spatial_grid::SpatialGrid;
+use macroquad::prelude::*;
+
+// Heatmap buckets are much coarser than the collision grid
+const HEATMAP_BUCKET_SIZE: f32 = 500.0;
+const SAMPLE_INTERVAL: f32 = 1.0; // Seconds between samples
+const MAX_ALPHA: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HeatmapMode {
+    CellCount,
+    Energy,
+}
+
+/// Coarse overlay showing where cells (or their energy) accumulate.
+/// Sampled once per SAMPLE_INTERVAL; rendering fades between the last two samples.
+pub struct Heatmap {
+    // Reused only for its bucket math (cells are never inserted)
+    grid: SpatialGrid,
+    previous: Vec<f32>,
+    current: Vec<f32>,
+    previous_max: f32,
+    current_max: f32,
+    time_since_sample: f32,
+    pub mode: HeatmapMode,
+    pub visible: bool,
+}
+
+impl Heatmap {
+    pub fn new(world_width: f32, world_height: f32) -> Self {
+        let grid = SpatialGrid::new(world_width, world_height, HEATMAP_BUCKET_SIZE);
+        let (w, h) = grid.dimensions();
+        Heatmap {
+            grid,
+            previous: vec![0.0; w * h],
+            current: vec![0.0; w * h],
+            previous_max: 0.0,
+            current_max: 0.0,
+            time_since_sample: SAMPLE_INTERVAL, // Sample on first update
+            mode: HeatmapMode::CellCount,
+            visible: false,
+        }
+    }
+
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            HeatmapMode::CellCount => HeatmapMode::Energy,
+            HeatmapMode::Energy => HeatmapMode::CellCount,
+        };
+        // Resample immediately so the new mode shows up without waiting
+        self.time_since_sample = SAMPLE_INTERVAL;
+    }
+
+    // Advance the sample timer and resample once per interval (only while visible)
+    pub fn update(&mut self, delta_time: f32, cells: &[Cell]) {
+        if !self.visible {
+            return;
+        }
+        self.time_since_sample += delta_time;
+        if self.time_since_sample >= SAMPLE_INTERVAL {
+            self.time_since_sample = 0.0;
+            self.sample(cells);
+        }
+    }
+
+    // Accumulate alive cell count or energy per bucket
+    fn sample(&mut self, cells: &[Cell]) {
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.previous_max = self.current_max;
+        self.current.iter_mut().for_each(|v| *v = 0.0);
+
+        for cell in cells {
+            if cell.state != CellState::Alive {
+                continue;
+            }
+            let value = match self.mode {
+                HeatmapMode::CellCount => 1.0,
+                HeatmapMode::Energy => cell.energy.max(0.0),
+            };
+            self.current[self.grid.get_bucket_index(cell.x, cell.y)] += value;
+        }
+
+        self.current_max = self.current.iter().cloned().fold(0.0, f32::max);
+    }
+
+    // Normalized (0..1) value of a bucket, blended between the previous and current sample
+    fn blended_value(&self, index: usize) -> f32 {
+        let t = (self.time_since_sample / SAMPLE_INTERVAL).clamp(0.0, 1.0);
+        let normalize = |value: f32, max: f32| if max > 0.0 { value / max } else { 0.0 };
+        let previous = normalize(self.previous[index], self.previous_max);
+        let current = normalize(self.current[index], self.current_max);
+        crate::math::lerp(previous, current, t)
+    }
+
+    pub fn render(&self, camera_x: f32, camera_y: f32, view: &ViewBounds) {
+        if !self.visible {
+            return;
+        }
+
+        let (grid_w, grid_h) = self.grid.dimensions();
+        let bucket_size = self.grid.bucket_size();
+
+        // Iterate visible buckets in screen space, wrapping indices around the world
+        let first_x = ((camera_x + view.min_x) / bucket_size).floor() as i32;
+        let first_y = ((camera_y + view.min_y) / bucket_size).floor() as i32;
+        let last_x = ((camera_x + view.max_x) / bucket_size).floor() as i32;
+        let last_y = ((camera_y + view.max_y) / bucket_size).floor() as i32;
+
+        for by in first_y..=last_y {
+            for bx in first_x..=last_x {
+                let wrapped_x = bx.rem_euclid(grid_w as i32) as usize;
+                let wrapped_y = by.rem_euclid(grid_h as i32) as usize;
+                let value = self.blended_value(wrapped_y * grid_w + wrapped_x);
+                if value <= 0.01 {
+                    continue;
+                }
+
+                // Cold (blue) to hot (red)
+                let color = Color::new(value, 0.2, 1.0 - value, value * MAX_ALPHA);
+                draw_rectangle(
+                    bx as f32 * bucket_size - camera_x,
+                    by as f32 * bucket_size - camera_y,
+                    bucket_size,
+                    bucket_size,
+                    color,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+// Cells whose hues are chained within this many degrees of each other share a species
+pub const SPECIES_HUE_TOLERANCE: f32 = 15.0;
+// Most species the hue circle can hold with gaps wider than the tolerance
+pub const MAX_HUE_SPECIES: usize = (360.0 / SPECIES_HUE_TOLERANCE) as usize;
+
+/// A group of alive cells with similar evolved hue
+#[derive(Debug, Clone)]
+pub struct HueSpecies {
+    pub id: u32,
+    pub mean_hue: f32, // Degrees, 0-360
+    pub alive_count: usize,
+}
+
+// Shortest angular distance between two hues (degrees)
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let diff = (a - b).rem_euclid(360.0);
+    diff.min(360.0 - diff)
+}
+
+// Circular mean of hues in degrees (plain averaging breaks across 0/360)
+fn mean_hue(hues: &[f32]) -> f32 {
+    let (sin, cos) = hues.iter().fold((0.0, 0.0), |(s, c), h| {
+        let r = h.to_radians();
+        (s + r.sin(), c + r.cos())
+    });
+    sin.atan2(cos).to_degrees().rem_euclid(360.0)
+}
+
+/// Single-pass sweep over sorted hues: a gap wider than `tolerance` starts a new
+/// cluster, and the first and last clusters merge if they touch across 0/360.
+/// Returns (mean hue, member count) per cluster. O(N log N) for the sort.
+pub fn cluster_hues(hues: &[f32], tolerance: f32) -> Vec<(f32, usize)> {
+    let mut sorted: Vec<f32> = hues.iter().map(|h| h.rem_euclid(360.0)).collect();
+    sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+
+    let mut clusters: Vec<Vec<f32>> = Vec::new();
+    for hue in sorted {
+        match clusters.last_mut() {
+            Some(cluster) if hue - cluster[cluster.len() - 1] <= tolerance => cluster.push(hue),
+            _ => clusters.push(vec![hue]),
+        }
+    }
+
+    if clusters.len() > 1 {
+        let first = clusters[0][0];
+        let last = *clusters[clusters.len() - 1].last().unwrap();
+        if first + 360.0 - last <= tolerance {
+            let wrapped = clusters.pop().unwrap();
+            clusters[0].extend(wrapped);
+        }
+    }
+
+    clusters
+        .iter()
+        .map(|cluster| (mean_hue(cluster), cluster.len()))
+        .collect()
+}
+
+/// Keeps species ids stable between updates: each new cluster inherits the id of the
+/// closest previous species within the tolerance, otherwise it gets a fresh id
+pub struct HueSpeciesTracker {
+    pub species: Vec<HueSpecies>, // Largest first
+    next_id: u32,
+}
+
+impl HueSpeciesTracker {
+    pub fn new() -> Self {
+        HueSpeciesTracker {
+            species: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    // Forget all species (world reset), without reporting them extinct
+    pub fn reset(&mut self) {
+        self.species.clear();
+    }
+
+    /// Recluster the alive hues. Returns the ids of species that died out.
+    pub fn update(&mut self, hues: &[f32]) -> Vec<u32> {
+        let mut clusters = cluster_hues(hues, SPECIES_HUE_TOLERANCE);
+        clusters.sort_unstable_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+        let mut previous = std::mem::take(&mut self.species);
+        for (hue, count) in clusters {
+            let closest = previous
+                .iter()
+                .enumerate()
+                .map(|(i, species)| (i, hue_distance(species.mean_hue, hue)))
+                .filter(|&(_, distance)| distance <= SPECIES_HUE_TOLERANCE)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i);
+            let id = match closest {
+                Some(i) => previous.swap_remove(i).id,
+                None => {
+                    self.next_id += 1;
+                    self.next_id
+                }
+            };
+            self.species.push(HueSpecies {
+                id,
+                mean_hue: hue,
+                alive_count: count,
+            });
+        }
+
+        previous.iter().map(|species| species.id).collect()
+    }
+
+    pub fn alive_counts(&self) -> HashMap<u32, usize> {
+        self.species
+            .iter()
+            .map(|species| (species.id, species.alive_count))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_hues_sweeps_and_wraps() {
+        let clusters = cluster_hues(&[10.0, 20.0, 100.0, 110.0, 355.0, 200.0], 15.0);
+        assert_eq!(clusters.len(), 3);
+
+        // 355, 10 and 20 chain across 0/360
+        let wrapped = clusters.iter().find(|c| c.1 == 3).unwrap();
+        assert!(hue_distance(wrapped.0, 8.3) < 0.5);
+        assert!(
+            clusters
+                .iter()
+                .any(|c| c.1 == 2 && hue_distance(c.0, 105.0) < 1e-3)
+        );
+        assert!(
+            clusters
+                .iter()
+                .any(|c| c.1 == 1 && hue_distance(c.0, 200.0) < 1e-3)
+        );
+
+        assert!(cluster_hues(&[], 15.0).is_empty());
+    }
+
+    #[test]
+    fn test_tracker_keeps_ids_and_reports_extinction() {
+        let mut tracker = HueSpeciesTracker::new();
+        assert!(tracker.update(&[10.0, 12.0, 100.0]).is_empty());
+        let counts = tracker.alive_counts();
+        assert_eq!(counts.len(), 2);
+        let red_id = tracker.species[0].id;
+
+        // Hues drift a little: same ids
+        assert!(tracker.update(&[14.0, 16.0, 104.0]).is_empty());
+        assert_eq!(tracker.alive_counts(), counts);
+
+        // The 100° species dies out
+        let extinct = tracker.update(&[15.0]);
+        assert_eq!(extinct.len(), 1);
+        assert_ne!(extinct[0], red_id);
+        assert_eq!(tracker.species[0].id, red_id);
+
+        // A species reappearing later is a new one
+        tracker.update(&[15.0, 100.0]);
+        assert!(tracker.species.iter().all(|s| s.id != extinct[0]));
+    }
+}
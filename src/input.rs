@@ -0,0 +1,49 @@
+/// Whether a UI component took the pointer this frame. World::pointer_capture asks
+/// each component in turn, and the camera only pans with pointer input that no
+/// component captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputCaptured {
+    No,
+    Yes,
+}
+
+impl InputCaptured {
+    pub fn from_hit(hit: bool) -> Self {
+        if hit {
+            InputCaptured::Yes
+        } else {
+            InputCaptured::No
+        }
+    }
+
+    // Ask the next component only while nothing has captured the pointer yet
+    pub fn or_else(self, next: impl FnOnce() -> InputCaptured) -> Self {
+        match self {
+            InputCaptured::Yes => InputCaptured::Yes,
+            InputCaptured::No => next(),
+        }
+    }
+
+    pub fn is_captured(self) -> bool {
+        self == InputCaptured::Yes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_capture_wins() {
+        let captured = InputCaptured::from_hit(true).or_else(|| panic!("asked after capture"));
+        assert!(captured.is_captured());
+
+        let free = InputCaptured::No.or_else(|| InputCaptured::from_hit(false));
+        assert_eq!(free, InputCaptured::No);
+        assert!(
+            InputCaptured::No
+                .or_else(|| InputCaptured::Yes)
+                .is_captured()
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use crate::camera::ViewBounds;
+use crate::config::IslandConfig;
+use macroquad::prelude::*;
+
+const BARRIER_COLOR: Color = Color::new(0.6, 0.6, 0.7, 0.8);
+const CORRIDOR_OPEN_COLOR: Color = Color::new(0.2, 0.9, 0.3, 0.6);
+const CORRIDOR_CLOSED_COLOR: Color = Color::new(0.9, 0.2, 0.2, 0.6);
+
+/// Island partition of the (wrapping) world: a columns x rows grid of regions split by
+/// barriers, each barrier side having one corridor gap centered on the island edge.
+/// The world edges are barriers too, since the world wraps around.
+#[derive(Debug, Clone, Copy)]
+pub struct Islands {
+    config: IslandConfig,
+    island_width: f32,
+    island_height: f32,
+}
+
+impl Islands {
+    /// None unless islands are enabled and the grid has more than one region
+    pub fn new(config: IslandConfig, world_width: f32, world_height: f32) -> Option<Self> {
+        if !config.enabled || config.columns * config.rows < 2 {
+            return None;
+        }
+        Some(Islands {
+            config,
+            island_width: world_width / config.columns.max(1) as f32,
+            island_height: world_height / config.rows.max(1) as f32,
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.config.columns * self.config.rows
+    }
+
+    fn column_of(&self, x: f32) -> usize {
+        ((x / self.island_width).floor().max(0.0) as usize).min(self.config.columns - 1)
+    }
+
+    fn row_of(&self, y: f32) -> usize {
+        ((y / self.island_height).floor().max(0.0) as usize).min(self.config.rows - 1)
+    }
+
+    /// Island index of a world position, row-major
+    pub fn island_of(&self, x: f32, y: f32) -> usize {
+        self.row_of(y) * self.config.columns + self.column_of(x)
+    }
+
+    /// Corridors open for the last `corridor_open_duration` seconds of every
+    /// `corridor_interval` seconds of simulation time
+    pub fn corridors_open(&self, elapsed: f32) -> bool {
+        let interval = self.config.corridor_interval.max(f32::EPSILON);
+        elapsed.rem_euclid(interval) >= interval - self.config.corridor_open_duration
+    }
+
+    // Whether `position` along a barrier falls in the corridor gap of its island edge
+    fn in_corridor(&self, position: f32, island_size: f32) -> bool {
+        let offset = position.rem_euclid(island_size) - island_size / 2.0;
+        offset.abs() <= self.config.corridor_width / 2.0
+    }
+
+    /// Whether a cell may move from `from` to `to` (positions after world wrapping).
+    /// Staying on one island is always allowed; crossing one barrier needs open
+    /// corridors and must happen inside the gap; cutting across a corner never is.
+    pub fn allows_move(&self, from: (f32, f32), to: (f32, f32), corridors_open: bool) -> bool {
+        let column_changed = self.column_of(from.0) != self.column_of(to.0);
+        let row_changed = self.row_of(from.1) != self.row_of(to.1);
+        match (column_changed, row_changed) {
+            (false, false) => true,
+            (true, false) => corridors_open && self.in_corridor(to.1, self.island_height),
+            (false, true) => corridors_open && self.in_corridor(to.0, self.island_width),
+            (true, true) => false,
+        }
+    }
+
+    /// Alive cells per island
+    pub fn population(&self, positions: impl Iterator<Item = (f32, f32)>) -> Vec<usize> {
+        let mut counts = vec![0; self.count()];
+        for (x, y) in positions {
+            counts[self.island_of(x, y)] += 1;
+        }
+        counts
+    }
+
+    // Barriers as wall segments with the corridor gaps colored by state,
+    // repeated across the wrapped world edges like the cells
+    pub fn render(
+        &self,
+        camera_x: f32,
+        camera_y: f32,
+        view: &ViewBounds,
+        world_width: f32,
+        world_height: f32,
+        corridors_open: bool,
+    ) {
+        let thickness = self.config.barrier_thickness;
+        let gap = self.config.corridor_width;
+        let corridor_color = if corridors_open {
+            CORRIDOR_OPEN_COLOR
+        } else {
+            CORRIDOR_CLOSED_COLOR
+        };
+
+        for offset_x in [-world_width, 0.0, world_width] {
+            for offset_y in [-world_height, 0.0, world_height] {
+                // Vertical barriers, split into one segment per row
+                for column in 0..self.config.columns {
+                    let x = column as f32 * self.island_width + offset_x - camera_x;
+                    if x + thickness < view.min_x || x - thickness > view.max_x {
+                        continue;
+                    }
+                    for row in 0..self.config.rows {
+                        let top = row as f32 * self.island_height + offset_y - camera_y;
+                        draw_barrier_segment(
+                            (x - thickness / 2.0, top),
+                            (thickness, self.island_height),
+                            gap,
+                            false,
+                            corridor_color,
+                            view,
+                        );
+                    }
+                }
+
+                // Horizontal barriers, split into one segment per column
+                for row in 0..self.config.rows {
+                    let y = row as f32 * self.island_height + offset_y - camera_y;
+                    if y + thickness < view.min_y || y - thickness > view.max_y {
+                        continue;
+                    }
+                    for column in 0..self.config.columns {
+                        let left = column as f32 * self.island_width + offset_x - camera_x;
+                        draw_barrier_segment(
+                            (left, y - thickness / 2.0),
+                            (self.island_width, thickness),
+                            gap,
+                            true,
+                            corridor_color,
+                            view,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+// One island edge: wall, centered corridor gap, wall (screen coordinates)
+fn draw_barrier_segment(
+    (x, y): (f32, f32),
+    (width, height): (f32, f32),
+    gap: f32,
+    horizontal: bool,
+    corridor_color: Color,
+    view: &ViewBounds,
+) {
+    let length = if horizontal { width } else { height };
+    let gap = gap.min(length);
+    let wall = (length - gap) / 2.0;
+    // (start along the segment, length, color)
+    let parts = [
+        (0.0, wall, BARRIER_COLOR),
+        (wall, gap, corridor_color),
+        (wall + gap, wall, BARRIER_COLOR),
+    ];
+    for (start, part_length, color) in parts {
+        let (px, py, pw, ph) = if horizontal {
+            (x + start, y, part_length, height)
+        } else {
+            (x, y + start, width, part_length)
+        };
+        if px + pw < view.min_x || px > view.max_x || py + ph < view.min_y || py > view.max_y {
+            continue;
+        }
+        draw_rectangle(px, py, pw, ph, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn islands() -> Islands {
+        let config = IslandConfig {
+            enabled: true,
+            columns: 2,
+            rows: 2,
+            corridor_width: 100.0,
+            corridor_interval: 60.0,
+            corridor_open_duration: 10.0,
+            ..IslandConfig::default()
+        };
+        Islands::new(config, 2000.0, 1000.0).unwrap()
+    }
+
+    #[test]
+    fn test_island_layout_and_schedule() {
+        let islands = islands();
+        assert_eq!(islands.count(), 4);
+        assert_eq!(islands.island_of(100.0, 100.0), 0);
+        assert_eq!(islands.island_of(1500.0, 100.0), 1);
+        assert_eq!(islands.island_of(100.0, 900.0), 2);
+
+        assert!(!islands.corridors_open(0.0));
+        assert!(!islands.corridors_open(49.0));
+        assert!(islands.corridors_open(55.0));
+        assert!(!islands.corridors_open(61.0));
+
+        // Disabled or a single region: no islands at all
+        assert!(Islands::new(IslandConfig::default(), 2000.0, 1000.0).is_none());
+    }
+
+    #[test]
+    fn test_barriers_block_except_through_open_corridors() {
+        let islands = islands();
+        // Within an island
+        assert!(islands.allows_move((100.0, 100.0), (150.0, 120.0), false));
+
+        // Crossing the x = 1000 barrier: row center (y = 250) is the corridor
+        let (from, to) = ((995.0, 250.0), (1005.0, 250.0));
+        assert!(!islands.allows_move(from, to, false));
+        assert!(islands.allows_move(from, to, true));
+        assert!(!islands.allows_move((995.0, 100.0), (1005.0, 100.0), true));
+
+        // Wrapping across the world edge crosses the x = 0 barrier
+        assert!(!islands.allows_move((1995.0, 100.0), (5.0, 100.0), true));
+        assert!(islands.allows_move((1995.0, 250.0), (5.0, 250.0), true));
+
+        // Corners are never passable
+        assert!(!islands.allows_move((995.0, 495.0), (1005.0, 505.0), true));
+
+        let counts = islands.population([(100.0, 100.0), (1500.0, 900.0)].into_iter());
+        assert_eq!(counts, vec![1, 0, 0, 1]);
+    }
+}
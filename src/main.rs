@@ -1,17 +1,45 @@
 mod background;
+mod brain_inspector;
 mod camera;
 mod cell;
+mod cell_sprites;
 mod config;
+mod config_watcher;
+mod director;
+mod eras;
+mod events;
+mod ffi;
+mod fitness;
+mod food;
+mod gene_stats;
+mod heatmap;
+mod hue_species;
+mod input;
+mod islands;
 mod math;
+mod minimap;
 mod neural_network;
+mod pheromones;
+mod replay;
 mod spatial_grid;
 mod stats;
 mod storage;
+mod succession;
+mod temperature;
+mod vision;
 mod world;
+mod zones;
 
+use config_watcher::ConfigWatcher;
 use macroquad::prelude::*;
+use replay::{RunEvent, RunRecording};
 use world::World;
 
+// Print a progress line every N ticks in headless mode
+const HEADLESS_REPORT_INTERVAL: usize = 1000;
+// Share of the initial population seeded by --import-genome (override with --import-share)
+const DEFAULT_IMPORT_SHARE_PERCENT: f32 = 25.0;
+
 fn window_conf() -> Conf {
     Conf {
         window_title: "Cells - Simple Scene".to_owned(),
@@ -20,30 +48,269 @@ fn window_conf() -> Conf {
     }
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
+// Read the value following a `--flag` command line argument
+fn arg_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+// --import-genome <file> [--import-share <percent>]
+struct GenomeImport {
+    path: String,
+    share: f32, // 0..1
+}
+
+fn genome_import_arg(args: &[String]) -> Option<GenomeImport> {
+    let path = arg_value(args, "--import-genome")?;
+    let percent = arg_value(args, "--import-share")
+        .and_then(|s| s.parse::<f32>().ok())
+        .unwrap_or(DEFAULT_IMPORT_SHARE_PERCENT);
+    Some(GenomeImport {
+        path,
+        share: percent / 100.0,
+    })
+}
+
+// Seed part of the initial population from a genome file; exits on an invalid genome
+fn apply_genome_import(world: &mut World, import: &Option<GenomeImport>) {
+    let Some(import) = import else {
+        return;
+    };
+    match storage::import_genome(&import.path, world.config()) {
+        Ok(genome) => {
+            let seeded = world.seed_from_genome(&genome, import.share);
+            println!(
+                "🧬 Seeded {} cells from {} (generation {})",
+                seeded, import.path, genome.generation
+            );
+        }
+        Err(e) => {
+            eprintln!("❌ Cannot import genome {}: {}", import.path, e);
+            std::process::exit(2);
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let genome_import = genome_import_arg(&args);
+
+    if let Some(name) = arg_value(&args, "--scenario")
+        && !config::set_scenario_by_name(&name)
+    {
+        eprintln!(
+            "Unknown scenario '{}', available: {}",
+            name,
+            config::SCENARIO_NAMES.join(", ")
+        );
+        std::process::exit(2);
+    }
+
+    let config_path = arg_value(&args, "--config");
+    let mut config_watcher = None;
+    if let Some(path) = config_path {
+        match config::SimulationConfig::from_file(&path) {
+            Ok(loaded) => {
+                config::set_config(loaded.clone());
+                config_watcher = Some(ConfigWatcher::new(path, loaded));
+            }
+            Err(error) => {
+                eprintln!("Failed to load config {}: {}", path, error);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    if let Some(path) = arg_value(&args, "--replay") {
+        run_replay(&path);
+        return;
+    }
+
+    if let Some(ticks) = arg_value(&args, "--headless") {
+        let Ok(ticks) = ticks.parse::<usize>() else {
+            eprintln!("--headless expects a tick count, got '{}'", ticks);
+            std::process::exit(2);
+        };
+        let seed = arg_value(&args, "--seed").and_then(|s| s.parse::<u64>().ok());
+        let reset_at = arg_value(&args, "--reset-at").and_then(|s| s.parse::<usize>().ok());
+        let record_path = arg_value(&args, "--record");
+        run_headless(ticks, seed, reset_at, record_path, genome_import);
+        return;
+    }
+
+    // Only native builds get a --config path, so the web build never watches a file
+    macroquad::Window::from_config(window_conf(), run_window(genome_import, config_watcher));
+}
+
+// Apply recorded manual events scheduled for this tick
+fn apply_run_events(world: &mut World, recording: &RunRecording, tick: usize) {
+    for event in recording.events_at(tick) {
+        match event {
+            RunEvent::Reset { .. } => world.respawn_from_best(),
+        }
+    }
+}
+
+fn print_progress(world: &World, tick: usize) {
+    let (best_score, best_generation) = world
+        .best_alive_cell()
+        .map(|cell| (world.fitness().score(cell), cell.generation))
+        .unwrap_or((0.0, 0));
+    println!(
+        "tick {:>8} | alive {:>5} | best fitness {:>10.1} | generation {:>4}",
+        tick,
+        world.alive_count(),
+        best_score,
+        best_generation
+    );
+}
+
+// Run the simulation without a window: no rendering, no input, one fixed tick per update.
+// Given the same seed (and saved brains) the run is deterministic.
+// With --record, saved brains are ignored so the run can be replayed from the seed alone.
+fn run_headless(
+    ticks: usize,
+    seed: Option<u64>,
+    reset_at: Option<usize>,
+    record_path: Option<String>,
+    genome_import: Option<GenomeImport>,
+) {
+    let mut config = config::get_config();
+    let seed = match (seed, &record_path) {
+        (None, Some(_)) => Some(0), // Recorded runs are always seeded
+        _ => seed,
+    };
+    if record_path.is_some() {
+        config.use_storage = false;
+    }
+    if let Some(seed) = seed {
+        rand::srand(seed);
+    }
+
+    let mut recording = RunRecording::new(seed.unwrap_or(0), config.clone());
+    if let Some(tick) = reset_at {
+        recording.events.push(RunEvent::Reset { tick });
+    }
+
+    let mut world = World::new(config);
+    // FPS is meaningless without rendering, keep the cap fixed
+    world.auto_cell_cap = false;
+    apply_genome_import(&mut world, &genome_import);
+
+    println!("Running headless for {} ticks (seed: {:?})", ticks, seed);
+    for tick in 1..=ticks {
+        apply_run_events(&mut world, &recording, tick);
+        world.update(world::FIXED_DT);
+        recording.record_checkpoint(tick, world.tick_hash());
+
+        if tick % HEADLESS_REPORT_INTERVAL == 0 || tick == ticks {
+            print_progress(&world, tick);
+        }
+    }
+
+    world.save_best_brain();
+
+    if let Some(path) = record_path {
+        recording.ticks = ticks;
+        match std::fs::write(&path, recording.to_json()) {
+            Ok(()) => println!(
+                "💾 Run recorded to {} ({} checkpoints)",
+                path,
+                recording.checkpoints.len()
+            ),
+            Err(e) => println!("⚠ Failed to write recording {}: {}", path, e),
+        }
+    }
+}
+
+// Re-run a recorded run from its seed and check the state hash at every checkpoint
+fn run_replay(path: &str) {
+    let Some(recording) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| RunRecording::from_json(&json))
+    else {
+        eprintln!("Could not read run recording '{}'", path);
+        std::process::exit(2);
+    };
+
+    rand::srand(recording.seed);
+    let mut world = World::new(recording.config.clone());
+    world.auto_cell_cap = false;
+
+    println!(
+        "Replaying {} ticks (seed: {}, {} checkpoints)",
+        recording.ticks,
+        recording.seed,
+        recording.checkpoints.len()
+    );
+    for tick in 1..=recording.ticks {
+        apply_run_events(&mut world, &recording, tick);
+        world.update(world::FIXED_DT);
+
+        let hash = world.tick_hash();
+        if let Some(expected) = recording.verify_checkpoint(tick, hash) {
+            eprintln!(
+                "❌ Replay diverged at tick {}: expected hash {:016x}, got {:016x}",
+                tick, expected.hash, hash
+            );
+            std::process::exit(1);
+        }
+        if tick % HEADLESS_REPORT_INTERVAL == 0 || tick == recording.ticks {
+            print_progress(&world, tick);
+        }
+    }
+
+    println!(
+        "✅ Replay matched all {} checkpoints",
+        recording.checkpoints.len()
+    );
+}
+
+async fn run_window(
+    genome_import: Option<GenomeImport>,
+    mut config_watcher: Option<ConfigWatcher>,
+) {
     // Load custom font
     let font_bytes = include_bytes!("../assets/Inter-Regular.ttf");
     let font = load_ttf_font_from_bytes(font_bytes).ok();
 
-    let mut world = World::spawn(font);
+    let mut world = World::spawn(font.clone());
+    apply_genome_import(&mut world, &genome_import);
 
     loop {
         let delta_time = get_frame_time();
 
+        // A scenario was selected at runtime (from JavaScript): restart with its config
+        if config::scenario_pending() {
+            world = World::spawn(font.clone());
+        }
+
+        // The --config file was edited: apply what can change without a restart
+        if let Some(watcher) = config_watcher.as_mut()
+            && let Some(reloaded) = watcher.poll(get_time())
+        {
+            world.apply_config_reload(watcher.path(), reloaded);
+        }
+
         clear_background(BLACK);
 
         // Handle stats box clicks first
         world.handle_stats_click();
+        world.handle_keyboard_input();
 
-        // Check if mouse is over stats box to skip camera input
+        // UI under the pointer keeps the camera from starting a drag
         let mouse_pos = mouse_position();
-        let skip_camera_mouse = world.is_mouse_over_stats(mouse_pos.0, mouse_pos.1);
+        let captured = world.pointer_capture(mouse_pos.0, mouse_pos.1);
 
-        world.camera.handle_input(delta_time, skip_camera_mouse);
+        world.camera.handle_input(delta_time, captured);
         world.camera.update();
         world.update(delta_time);
+        world.follow_selected_cell();
+        world.direct_camera(delta_time);
         world.render();
+        world.capture_screenshot_if_requested();
 
         next_frame().await
     }
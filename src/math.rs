@@ -1,3 +1,29 @@
 pub fn lerp(start: f32, end: f32, t: f32) -> f32 {
     start + (end - start) * t
 }
+
+// Hermite interpolation: 0 at or below edge0, 1 at or above edge1, smooth in between
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge0 == edge1 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothstep_pinned_at_start_middle_and_end() {
+        assert_eq!(smoothstep(0.0, 1.0, 0.0), 0.0);
+        assert_eq!(smoothstep(0.0, 1.0, 0.5), 0.5);
+        assert_eq!(smoothstep(0.0, 1.0, 1.0), 1.0);
+        assert_eq!(smoothstep(10.0, 20.0, 12.5), 0.15625);
+
+        // Clamped outside the range
+        assert_eq!(smoothstep(0.0, 1.0, -3.0), 0.0);
+        assert_eq!(smoothstep(5.0, 5.0, 5.0), 1.0);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::camera::ViewBounds;
+use crate::cell::{Cell, CellState};
+use crate::input::InputCaptured;
+use macroquad::prelude::*;
+
+// Minimap placement: bottom-left corner, fixed width, height follows the world aspect ratio
+const MINIMAP_WIDTH: f32 = 240.0;
+const MINIMAP_MARGIN: f32 = 20.0;
+const MAX_MINIMAP_POINTS: usize = 3000; // Cells beyond this are subsampled
+const POINT_SIZE: f32 = 2.0;
+
+/// Scaled overview of the whole world: one dot per cell plus the camera viewport
+pub struct Minimap {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    world_width: f32,
+    world_height: f32,
+}
+
+impl Minimap {
+    pub fn new(world_width: f32, world_height: f32, screen_h: f32) -> Self {
+        let height = MINIMAP_WIDTH * world_height / world_width;
+        Minimap {
+            x: MINIMAP_MARGIN,
+            y: screen_h - height - MINIMAP_MARGIN,
+            width: MINIMAP_WIDTH,
+            height,
+            world_width,
+            world_height,
+        }
+    }
+
+    /// World position to minimap pixel position (positions outside the world wrap)
+    pub fn world_to_minimap(&self, world_x: f32, world_y: f32) -> (f32, f32) {
+        (
+            self.x + world_x.rem_euclid(self.world_width) / self.world_width * self.width,
+            self.y + world_y.rem_euclid(self.world_height) / self.world_height * self.height,
+        )
+    }
+
+    /// Minimap pixel position to world position (clamped to the world)
+    pub fn minimap_to_world(&self, minimap_x: f32, minimap_y: f32) -> (f32, f32) {
+        let u = ((minimap_x - self.x) / self.width).clamp(0.0, 1.0);
+        let v = ((minimap_y - self.y) / self.height).clamp(0.0, 1.0);
+        (u * self.world_width, v * self.world_height)
+    }
+
+    pub fn contains(&self, screen_x: f32, screen_y: f32) -> bool {
+        screen_x >= self.x
+            && screen_x <= self.x + self.width
+            && screen_y >= self.y
+            && screen_y <= self.y + self.height
+    }
+
+    // Clicks on the minimap move the camera instead of panning it
+    pub fn capture(&self, screen_x: f32, screen_y: f32) -> InputCaptured {
+        InputCaptured::from_hit(self.contains(screen_x, screen_y))
+    }
+
+    // Draw the world rectangle, cells (subsampled) and the viewport outline.
+    // camera_x/y + view gives the visible world region (see Camera::view_bounds).
+    pub fn render(&self, cells: &[Cell], camera_x: f32, camera_y: f32, view: &ViewBounds) {
+        draw_rectangle(
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            Color::new(0.0, 0.0, 0.0, 0.75),
+        );
+
+        let step = cells.len().div_ceil(MAX_MINIMAP_POINTS).max(1);
+        for cell in cells.iter().step_by(step) {
+            let (px, py) = self.world_to_minimap(cell.x, cell.y);
+            let color = if cell.state == CellState::Alive {
+                cell.color
+            } else {
+                GRAY
+            };
+            draw_rectangle(px, py, POINT_SIZE, POINT_SIZE, color);
+        }
+
+        // Viewport may straddle the world edges: draw each wrapped piece clipped to the map
+        let min_x = (camera_x + view.min_x).rem_euclid(self.world_width);
+        let min_y = (camera_y + view.min_y).rem_euclid(self.world_height);
+        let view_w = (view.max_x - view.min_x).min(self.world_width);
+        let view_h = (view.max_y - view.min_y).min(self.world_height);
+        for offset_x in [0.0, -self.world_width] {
+            for offset_y in [0.0, -self.world_height] {
+                let left = (min_x + offset_x).max(0.0);
+                let top = (min_y + offset_y).max(0.0);
+                let right = (min_x + offset_x + view_w).min(self.world_width);
+                let bottom = (min_y + offset_y + view_h).min(self.world_height);
+                if right <= left || bottom <= top {
+                    continue;
+                }
+                draw_rectangle_lines(
+                    self.x + left / self.world_width * self.width,
+                    self.y + top / self.world_height * self.height,
+                    (right - left) / self.world_width * self.width,
+                    (bottom - top) / self.world_height * self.height,
+                    1.5,
+                    WHITE,
+                );
+            }
+        }
+
+        draw_rectangle_lines(self.x, self.y, self.width, self.height, 1.0, GRAY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_world_minimap_round_trip() {
+        let minimap = Minimap::new(8000.0, 7000.0, 1080.0);
+        assert!((minimap.height - 210.0).abs() < 1e-3);
+
+        let (mx, my) = minimap.world_to_minimap(2000.0, 3500.0);
+        assert!((mx - (minimap.x + 60.0)).abs() < 1e-3);
+        assert!((my - (minimap.y + 105.0)).abs() < 1e-3);
+
+        let (wx, wy) = minimap.minimap_to_world(mx, my);
+        assert!((wx - 2000.0).abs() < 0.1);
+        assert!((wy - 3500.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_minimap_wraps_and_clamps() {
+        let minimap = Minimap::new(8000.0, 7000.0, 1080.0);
+
+        // Positions past the world edge wrap like the world does
+        let wrapped = minimap.world_to_minimap(-2000.0, 7000.0 + 3500.0);
+        let inside = minimap.world_to_minimap(6000.0, 3500.0);
+        assert!((wrapped.0 - inside.0).abs() < 1e-3);
+        assert!((wrapped.1 - inside.1).abs() < 1e-3);
+
+        // Clicks on the border never map outside the world
+        let (wx, wy) = minimap.minimap_to_world(minimap.x - 5.0, minimap.y + minimap.height + 5.0);
+        assert_eq!((wx, wy), (0.0, 7000.0));
+        assert!(minimap.contains(minimap.x + 1.0, minimap.y + 1.0));
+        assert!(!minimap.contains(minimap.x - 1.0, minimap.y));
+    }
+}
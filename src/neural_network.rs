@@ -1,13 +1,35 @@
+use crate::config::FrozenLayers;
+use crate::vision::{MEMORY_INPUTS, PHEROMONE_INPUTS, TEMPERATURE_INPUTS};
 use macroquad::prelude::rand;
 use serde::{Deserialize, Serialize};
 
+// Bias of an output appended to a legacy brain, far below typical activations
+const SILENT_OUTPUT_BIAS: f32 = -5.0;
+
+/// Largest single weight change between two brains of the same shape
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightChange {
+    pub matrix: usize, // 0 = input to hidden, 1 = hidden to output
+    pub row: usize,
+    pub column: usize,
+    pub magnitude: f32, // Absolute change
+}
+
+impl WeightChange {
+    // "ih[3][12]" style position of the weight
+    pub fn label(&self) -> String {
+        let matrix = if self.matrix == 0 { "ih" } else { "ho" };
+        format!("{}[{}][{}]", matrix, self.row, self.column)
+    }
+}
+
 /// Neural network for cell decision-making
 ///
 /// Architecture:
 /// - Inputs: 5 sensors (distances to nearest cells)
 /// - Hidden layer: 2 * (inputs + outputs) nodes
-/// - Outputs: 4 actions (no-op, turn_left, turn_right, forward)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// - Outputs: 5 actions (no-op, turn_left, turn_right, forward, donate)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NeuralNetwork {
     // Input to hidden layer weights [hidden_size x input_size]
     weights_ih: Vec<Vec<f32>>,
@@ -21,6 +43,22 @@ pub struct NeuralNetwork {
     pub input_size: usize,
     pub hidden_size: usize,
     pub output_size: usize,
+
+    // Constant added to each movement action's output [no-op, left, right, forward]
+    // (donate gets none).
+    // Set from SimulationConfig::action_bias and saved with the brain, never mutated.
+    #[serde(default)]
+    pub action_bias: [f32; 4],
+
+    // Frozen parts are skipped by mutation, weight decay and gene transfer, e.g. to
+    // keep evolved input detectors (weights_ih) while only the policy evolves.
+    // Saved with the brain and inherited by children.
+    #[serde(default)]
+    pub ih_frozen: bool,
+    #[serde(default)]
+    pub bias_h_frozen: bool,
+    #[serde(default)]
+    pub ho_frozen: bool,
 }
 
 impl NeuralNetwork {
@@ -80,6 +118,56 @@ impl NeuralNetwork {
             input_size,
             hidden_size,
             output_size,
+            action_bias: [0.0; 4],
+            ih_frozen: false,
+            bias_h_frozen: false,
+            ho_frozen: false,
+        }
+    }
+
+    /// Lock the input-to-hidden weights (the feature detectors)
+    pub fn freeze_ih(&mut self) {
+        self.ih_frozen = true;
+    }
+
+    pub fn unfreeze_ih(&mut self) {
+        self.ih_frozen = false;
+    }
+
+    /// Lock the hidden biases
+    pub fn freeze_bias_h(&mut self) {
+        self.bias_h_frozen = true;
+    }
+
+    pub fn unfreeze_bias_h(&mut self) {
+        self.bias_h_frozen = false;
+    }
+
+    /// Lock the hidden-to-output weights (the policy)
+    pub fn freeze_ho(&mut self) {
+        self.ho_frozen = true;
+    }
+
+    pub fn unfreeze_ho(&mut self) {
+        self.ho_frozen = false;
+    }
+
+    /// Freeze exactly the parts `layers` selects and unfreeze the others
+    pub fn set_frozen_layers(&mut self, layers: FrozenLayers) {
+        if layers.weights_ih {
+            self.freeze_ih();
+        } else {
+            self.unfreeze_ih();
+        }
+        if layers.bias_h {
+            self.freeze_bias_h();
+        } else {
+            self.unfreeze_bias_h();
+        }
+        if layers.weights_ho {
+            self.freeze_ho();
+        } else {
+            self.unfreeze_ho();
         }
     }
 
@@ -89,8 +177,14 @@ impl NeuralNetwork {
     /// * `inputs` - Sensor values (should be normalized)
     ///
     /// # Returns
-    /// Vector of output activations (one per action)
+    /// Vector of output activations (one per action), including `action_bias`
     pub fn forward(&self, inputs: &[f32]) -> Vec<f32> {
+        self.forward_detailed(inputs).1
+    }
+
+    /// Forward pass that also returns the hidden layer: (hidden activations, outputs)
+    /// Used by the brain inspector to show how a decision was reached.
+    pub fn forward_detailed(&self, inputs: &[f32]) -> (Vec<f32>, Vec<f32>) {
         assert_eq!(inputs.len(), self.input_size, "Input size mismatch");
 
         // Compute hidden layer activations
@@ -110,10 +204,11 @@ impl NeuralNetwork {
             for (j, &hidden_val) in hidden.iter().enumerate().take(self.hidden_size) {
                 sum += self.weights_ho[i][j] * hidden_val;
             }
+            sum += self.action_bias.get(i).copied().unwrap_or(0.0);
             *output_val = sum; // No activation on output (will use softmax or argmax)
         }
 
-        outputs
+        (hidden, outputs)
     }
 
     /// ReLU activation function
@@ -126,34 +221,43 @@ impl NeuralNetwork {
     /// # Arguments
     /// * `rate` - Mutation rate (0.0 to 1.0), represents the probability that each weight will be mutated
     ///
+    /// * `weight_decay` - Passed to `apply_weight_decay` once mutation is done
+    ///
     /// When a weight is mutated, it's adjusted by a random value in the range [-0.1, 0.1]
-    pub fn mutate(&mut self, rate: f32) {
+    /// Frozen matrices (see freeze_ih) are left untouched.
+    pub fn mutate(&mut self, rate: f32, weight_decay: f32) {
         let rate = rate.clamp(0.0, 1.0);
 
         // Mutate input-to-hidden weights
-        for i in 0..self.hidden_size {
-            for j in 0..self.input_size {
-                if rand::gen_range(0.0, 1.0) < rate {
-                    let delta = rand::gen_range(-0.1, 0.1);
-                    self.weights_ih[i][j] = (self.weights_ih[i][j] + delta).clamp(-2.0, 2.0);
+        if !self.ih_frozen {
+            for i in 0..self.hidden_size {
+                for j in 0..self.input_size {
+                    if rand::gen_range(0.0, 1.0) < rate {
+                        let delta = rand::gen_range(-0.1, 0.1);
+                        self.weights_ih[i][j] = (self.weights_ih[i][j] + delta).clamp(-2.0, 2.0);
+                    }
                 }
             }
         }
 
         // Mutate hidden biases
-        for i in 0..self.hidden_size {
-            if rand::gen_range(0.0, 1.0) < rate {
-                let delta = rand::gen_range(-0.1, 0.1);
-                self.bias_h[i] = (self.bias_h[i] + delta).clamp(-2.0, 2.0);
+        if !self.bias_h_frozen {
+            for i in 0..self.hidden_size {
+                if rand::gen_range(0.0, 1.0) < rate {
+                    let delta = rand::gen_range(-0.1, 0.1);
+                    self.bias_h[i] = (self.bias_h[i] + delta).clamp(-2.0, 2.0);
+                }
             }
         }
 
         // Mutate hidden-to-output weights
-        for i in 0..self.output_size {
-            for j in 0..self.hidden_size {
-                if rand::gen_range(0.0, 1.0) < rate {
-                    let delta = rand::gen_range(-0.1, 0.1);
-                    self.weights_ho[i][j] = (self.weights_ho[i][j] + delta).clamp(-2.0, 2.0);
+        if !self.ho_frozen {
+            for i in 0..self.output_size {
+                for j in 0..self.hidden_size {
+                    if rand::gen_range(0.0, 1.0) < rate {
+                        let delta = rand::gen_range(-0.1, 0.1);
+                        self.weights_ho[i][j] = (self.weights_ho[i][j] + delta).clamp(-2.0, 2.0);
+                    }
                 }
             }
         }
@@ -165,6 +269,82 @@ impl NeuralNetwork {
                 self.bias_o[i] = (self.bias_o[i] + delta).clamp(-2.0, 2.0);
             }
         }
+
+        self.apply_weight_decay(weight_decay);
+    }
+
+    /// Mutate whole input-to-hidden rows, then individual weights
+    ///
+    /// * `row_rate` - Probability (0.0 to 1.0) that a row of `weights_ih` (one hidden
+    ///   neuron's input detector) is scaled as a whole by a random factor in 0.9..1.1,
+    ///   which keeps the ratios between its weights
+    /// * `weight_rate` - Per-weight mutation rate applied afterwards, as in `mutate`
+    ///
+    /// Weight decay is left to the caller (`apply_weight_decay`).
+    pub fn structured_mutate(&mut self, row_rate: f32, weight_rate: f32) {
+        let row_rate = if self.ih_frozen {
+            0.0
+        } else {
+            row_rate.clamp(0.0, 1.0)
+        };
+        for row in self.weights_ih.iter_mut() {
+            if rand::gen_range(0.0, 1.0) < row_rate {
+                let factor = rand::gen_range(0.9, 1.1);
+                for weight in row.iter_mut() {
+                    *weight = (*weight * factor).clamp(-2.0, 2.0);
+                }
+            }
+        }
+        self.mutate(weight_rate, 0.0);
+    }
+
+    /// Shrink every weight and bias by `(1.0 - decay)` (L2 regularization)
+    ///
+    /// Mutation deltas are a random walk, so without decay weights drift out until
+    /// they bunch up at the [-2.0, 2.0] clamp. A small decay pulls them back gently.
+    /// Frozen matrices keep their values.
+    pub fn apply_weight_decay(&mut self, decay: f32) {
+        let factor = 1.0 - decay.clamp(0.0, 1.0);
+        if factor == 1.0 {
+            return;
+        }
+        let scale = |values: &mut [f32]| values.iter_mut().for_each(|value| *value *= factor);
+        if !self.ih_frozen {
+            self.weights_ih.iter_mut().for_each(|row| scale(row));
+        }
+        if !self.bias_h_frozen {
+            scale(&mut self.bias_h);
+        }
+        if !self.ho_frozen {
+            self.weights_ho.iter_mut().for_each(|row| scale(row));
+        }
+        scale(&mut self.bias_o);
+    }
+
+    /// Lateral gene transfer: swap input-to-hidden weight rows start..start+len with
+    /// `other` (clamped to the hidden layer). Swapped weights are clamped to the
+    /// usual [-2.0, 2.0]. False, with nothing swapped, if the shapes differ or either
+    /// brain has frozen input-to-hidden weights.
+    pub fn swap_hidden_rows(
+        &mut self,
+        other: &mut NeuralNetwork,
+        start: usize,
+        len: usize,
+    ) -> bool {
+        if !self.same_shape(other) || self.ih_frozen || other.ih_frozen {
+            return false;
+        }
+        let end = (start + len).min(self.hidden_size);
+        for row in start.min(end)..end {
+            std::mem::swap(&mut self.weights_ih[row], &mut other.weights_ih[row]);
+            for weight in self.weights_ih[row]
+                .iter_mut()
+                .chain(other.weights_ih[row].iter_mut())
+            {
+                *weight = weight.clamp(-2.0, 2.0);
+            }
+        }
+        true
     }
 
     /// Number of multiplications performed in one forward pass
@@ -172,32 +352,269 @@ impl NeuralNetwork {
         self.hidden_size * (self.input_size + self.output_size)
     }
 
-    /// Get the action index with the highest activation
-    pub fn get_best_action(&self, inputs: &[f32]) -> usize {
-        let outputs = self.forward(inputs);
-        outputs
+    /// All weights and biases in a fixed order
+    fn parameters(&self) -> impl Iterator<Item = f32> + '_ {
+        self.weights_ih
             .iter()
-            .enumerate()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-            .map(|(idx, _)| idx)
-            .unwrap_or(0)
+            .flatten()
+            .chain(self.bias_h.iter())
+            .chain(self.weights_ho.iter().flatten())
+            .chain(self.bias_o.iter())
+            .copied()
+    }
+
+    fn same_shape(&self, other: &NeuralNetwork) -> bool {
+        self.input_size == other.input_size
+            && self.hidden_size == other.hidden_size
+            && self.output_size == other.output_size
+    }
+
+    pub fn parameter_count(&self) -> usize {
+        self.hidden_size * (self.input_size + 1) + self.output_size * (self.hidden_size + 1)
+    }
+
+    /// Whether the mean absolute difference over all weights and biases is <= threshold
+    /// Networks with different shapes are never within distance.
+    /// Stops early once the threshold is exceeded, and also rejects when the first
+    /// DISTANCE_PROBE_COUNT parameters already differ by far more than the threshold
+    /// (mutations are spread evenly, so related brains never look like that).
+    pub fn is_within_distance(&self, other: &NeuralNetwork, threshold: f32) -> bool {
+        const DISTANCE_PROBE_COUNT: usize = 64;
+        const PROBE_REJECT_FACTOR: f32 = 4.0;
+
+        if !self.same_shape(other) {
+            return false;
+        }
+        let max_total = threshold * self.parameter_count().max(1) as f32;
+        let mut total = 0.0;
+        for (i, (a, b)) in self.parameters().zip(other.parameters()).enumerate() {
+            total += (a - b).abs();
+            if total > max_total {
+                return false;
+            }
+            if i + 1 == DISTANCE_PROBE_COUNT
+                && total / DISTANCE_PROBE_COUNT as f32 > threshold * PROBE_REJECT_FACTOR
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Mean absolute difference over all weights and biases (0.0 for identical brains)
+    /// Networks with different shapes are infinitely far apart.
+    pub fn weight_diff(a: &NeuralNetwork, b: &NeuralNetwork) -> f32 {
+        if !a.same_shape(b) {
+            return f32::INFINITY;
+        }
+        let total: f32 = a
+            .parameters()
+            .zip(b.parameters())
+            .map(|(x, y)| (x - y).abs())
+            .sum();
+        total / a.parameter_count().max(1) as f32
+    }
+
+    /// Euclidean (L2) distance over all weights and biases (0.0 for identical brains)
+    /// Networks with different shapes are infinitely far apart.
+    pub fn distance(&self, other: &NeuralNetwork) -> f32 {
+        if !self.same_shape(other) {
+            return f32::INFINITY;
+        }
+        self.parameters()
+            .zip(other.parameters())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    /// Total absolute change of all weights and biases since `previous` (e.g. the
+    /// parent's brain), INFINITY for networks with different shapes
+    pub fn mutation_delta(&self, previous: &NeuralNetwork) -> f32 {
+        if !self.same_shape(previous) {
+            return f32::INFINITY;
+        }
+        self.parameters()
+            .zip(previous.parameters())
+            .map(|(a, b)| (a - b).abs())
+            .sum()
+    }
+
+    /// The weight (biases excluded) that changed the most since `previous`
+    /// None for networks with different shapes
+    pub fn most_changed_weight(&self, previous: &NeuralNetwork) -> Option<WeightChange> {
+        if !self.same_shape(previous) {
+            return None;
+        }
+        let matrices = [
+            (&self.weights_ih, &previous.weights_ih),
+            (&self.weights_ho, &previous.weights_ho),
+        ];
+        let mut largest: Option<WeightChange> = None;
+        for (matrix, (current, old)) in matrices.into_iter().enumerate() {
+            for (row, (current_row, old_row)) in current.iter().zip(old).enumerate() {
+                for (column, (a, b)) in current_row.iter().zip(old_row).enumerate() {
+                    let magnitude = (a - b).abs();
+                    if largest.is_none_or(|change| magnitude > change.magnitude) {
+                        largest = Some(WeightChange {
+                            matrix,
+                            row,
+                            column,
+                            magnitude,
+                        });
+                    }
+                }
+            }
+        }
+        largest
+    }
+
+    /// Cosine similarity of the flattened weights and biases, in -1..1
+    /// Networks with different shapes (or all-zero parameters) have similarity 0.
+    pub fn cosine_similarity(&self, other: &NeuralNetwork) -> f32 {
+        if !self.same_shape(other) {
+            return 0.0;
+        }
+        let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+        for (a, b) in self.parameters().zip(other.parameters()) {
+            dot += a * b;
+            norm_a += a * a;
+            norm_b += b * b;
+        }
+        let norms = (norm_a * norm_b).sqrt();
+        if norms > 0.0 { dot / norms } else { 0.0 }
+    }
+
+    /// Get the action index with the highest activation (see `argmax` for NaN and ties)
+    pub fn get_best_action(&self, inputs: &[f32]) -> usize {
+        argmax(&self.forward(inputs))
     }
 
     /// Serialize the neural network to JSON
-    /// Note: Currently unused - the codebase serializes SavedBrain structs directly.
+    /// Note: Currently unused outside tests - the codebase serializes SavedBrain structs directly.
     /// Kept for API symmetry with from_json() which is used for legacy format migration.
     #[allow(dead_code)]
     pub fn to_json(&self) -> String {
         serde_json::to_string(self).unwrap_or_default()
     }
 
-    /// Deserialize the neural network from JSON
+    /// Deserialize the neural network from JSON and check its shape (see validate)
     /// Used in storage.rs for backward compatibility with legacy save formats.
-    pub fn from_json(json: &str) -> Option<Self> {
-        serde_json::from_str(json).ok()
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let brain: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        brain.validate()?;
+        Ok(brain)
+    }
+
+    /// Brains saved before the memory inputs existed are MEMORY_INPUTS short, those
+    /// from before the temperature input also miss TEMPERATURE_INPUTS, and the oldest
+    /// ones PHEROMONE_INPUTS as well. All were appended at the end, so append zero
+    /// weights for them: the brain behaves exactly as before until mutation starts
+    /// using the new inputs. Returns whether it upgraded.
+    pub fn upgrade_legacy_inputs(&mut self, expected_input_size: usize) -> bool {
+        let missing = expected_input_size.saturating_sub(self.input_size);
+        let known_gaps = [
+            MEMORY_INPUTS,
+            TEMPERATURE_INPUTS + MEMORY_INPUTS,
+            PHEROMONE_INPUTS + TEMPERATURE_INPUTS + MEMORY_INPUTS,
+        ];
+        if !known_gaps.contains(&missing) {
+            return false;
+        }
+        for row in &mut self.weights_ih {
+            row.resize(expected_input_size, 0.0);
+        }
+        self.input_size = expected_input_size;
+        true
+    }
+
+    /// Brains saved before the donate action have one output less. Append it with zero
+    /// weights and a SILENT_OUTPUT_BIAS bias, so it loses to the older actions until
+    /// mutation raises it. Returns whether it upgraded.
+    pub fn upgrade_legacy_outputs(&mut self, expected_output_size: usize) -> bool {
+        if self.output_size + 1 != expected_output_size {
+            return false;
+        }
+        self.weights_ho.push(vec![0.0; self.hidden_size]);
+        self.bias_o.push(SILENT_OUTPUT_BIAS);
+        self.output_size = expected_output_size;
+        true
+    }
+
+    /// Check that every weight matrix and bias vector matches the declared sizes.
+    /// Deserialized brains (saved files, shared genomes) can be inconsistent, and
+    /// forward() would otherwise index out of bounds or silently skip weights.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.input_size == 0 || self.hidden_size == 0 || self.output_size == 0 {
+            return Err(format!(
+                "empty layer ({} inputs, {} hidden, {} outputs)",
+                self.input_size, self.hidden_size, self.output_size
+            ));
+        }
+        check_matrix(
+            "weights_ih",
+            &self.weights_ih,
+            self.hidden_size,
+            self.input_size,
+        )?;
+        check_len("bias_h", self.bias_h.len(), self.hidden_size)?;
+        check_matrix(
+            "weights_ho",
+            &self.weights_ho,
+            self.output_size,
+            self.hidden_size,
+        )?;
+        check_len("bias_o", self.bias_o.len(), self.output_size)
+    }
+}
+
+fn check_len(name: &str, len: usize, expected: usize) -> Result<(), String> {
+    if len == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} has {} entries, expected {}",
+            name, len, expected
+        ))
     }
 }
 
+fn check_matrix(
+    name: &str,
+    rows: &[Vec<f32>],
+    row_count: usize,
+    row_len: usize,
+) -> Result<(), String> {
+    check_len(name, rows.len(), row_count)?;
+    match rows.iter().position(|row| row.len() != row_len) {
+        Some(i) => Err(format!(
+            "{} row {} has {} entries, expected {}",
+            name,
+            i,
+            rows[i].len(),
+            row_len
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Index of the highest value, deterministic for any input:
+/// - NaN ranks below every number and is never selected (heavily mutated brains can emit it)
+/// - ties go to the lowest index
+/// - returns 0 when every value is NaN or the slice is empty
+pub fn argmax(values: &[f32]) -> usize {
+    let mut best: Option<(usize, f32)> = None;
+    for (idx, &value) in values.iter().enumerate() {
+        if value.is_nan() {
+            continue;
+        }
+        if best.is_none_or(|(_, best_value)| value > best_value) {
+            best = Some((idx, value));
+        }
+    }
+    best.map_or(0, |(idx, _)| idx)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,7 +639,7 @@ mod tests {
     fn test_mutate() {
         let mut nn = NeuralNetwork::new(5, 4);
         let original_weights = nn.weights_ih.clone();
-        nn.mutate(1.0); // 100% mutation rate
+        nn.mutate(1.0, 0.0); // 100% mutation rate
         // At least some weights should have changed
         let changed = nn
             .weights_ih
@@ -232,6 +649,177 @@ mod tests {
         assert!(changed);
     }
 
+    #[test]
+    fn test_frozen_layers_survive_mutation_and_decay() {
+        let mut nn = NeuralNetwork::new(5, 4);
+        nn.freeze_ih();
+        nn.freeze_bias_h();
+        let (weights_ih, bias_h) = (nn.weights_ih.clone(), nn.bias_h.clone());
+        let weights_ho = nn.weights_ho.clone();
+        nn.mutate(1.0, 0.1);
+        nn.structured_mutate(1.0, 1.0);
+        assert_eq!(nn.weights_ih, weights_ih);
+        assert_eq!(nn.bias_h, bias_h);
+        assert_ne!(nn.weights_ho, weights_ho);
+        let mut other = NeuralNetwork::new(5, 4);
+        assert!(!nn.swap_hidden_rows(&mut other, 0, 2));
+
+        // Freezing the policy instead: only the feature layer evolves
+        nn.unfreeze_ih();
+        nn.unfreeze_bias_h();
+        nn.freeze_ho();
+        let weights_ho = nn.weights_ho.clone();
+        nn.mutate(1.0, 0.1);
+        assert_eq!(nn.weights_ho, weights_ho);
+        assert_ne!(nn.weights_ih, weights_ih);
+        nn.unfreeze_ho();
+
+        // The flags are saved with the brain; brains saved before them load unfrozen
+        nn.freeze_ih();
+        let loaded = NeuralNetwork::from_json(&nn.to_json()).unwrap();
+        assert!(loaded.ih_frozen && !loaded.ho_frozen && !loaded.bias_h_frozen);
+        let mut legacy: serde_json::Value = serde_json::from_str(&nn.to_json()).unwrap();
+        legacy.as_object_mut().unwrap().remove("ih_frozen");
+        let legacy = NeuralNetwork::from_json(&legacy.to_string()).unwrap();
+        assert!(!legacy.ih_frozen);
+    }
+
+    #[test]
+    fn test_weight_decay_keeps_weights_from_drifting() {
+        let mean_abs = |nn: &NeuralNetwork| {
+            nn.parameters().map(f32::abs).sum::<f32>() / nn.parameter_count() as f32
+        };
+        let mut decayed = NeuralNetwork::new(5, 4);
+        let mut undecayed = decayed.clone();
+        for _ in 0..10_000 {
+            decayed.mutate(0.1, 0.001);
+            undecayed.mutate(0.1, 0.0);
+        }
+
+        // Equilibrium spread is about 0.4, while the undecayed walk piles up near the clamp
+        assert!(mean_abs(&decayed) < 0.6, "{}", mean_abs(&decayed));
+        assert!(mean_abs(&undecayed) > mean_abs(&decayed));
+
+        let mut nn = NeuralNetwork::new(5, 4);
+        let before: Vec<f32> = nn.parameters().collect();
+        nn.apply_weight_decay(0.5);
+        assert!(nn.parameters().zip(before).all(|(a, b)| a == b * 0.5));
+    }
+
+    #[test]
+    fn test_validate_rejects_inconsistent_shapes() {
+        let nn = NeuralNetwork::new(5, 4);
+        assert_eq!(nn.validate(), Ok(()));
+        assert!(NeuralNetwork::from_json(&nn.to_json()).is_ok());
+
+        let broken = |edit: fn(&mut NeuralNetwork)| {
+            let mut copy = nn.clone();
+            edit(&mut copy);
+            copy.validate().unwrap_err()
+        };
+        assert!(
+            broken(|b| b.hidden_size += 1).starts_with("weights_ih has 18 entries, expected 19")
+        );
+        assert!(broken(|b| b.input_size = 6).starts_with("weights_ih row 0 has 5 entries"));
+        assert!(
+            broken(|b| {
+                b.weights_ih[3].pop();
+            })
+            .starts_with("weights_ih row 3")
+        );
+        assert!(broken(|b| b.bias_h.truncate(2)).starts_with("bias_h has 2 entries"));
+        assert!(broken(|b| b.output_size = 3).starts_with("weights_ho has 4 entries"));
+        assert!(broken(|b| b.weights_ho[1].push(0.0)).starts_with("weights_ho row 1 has 19"));
+        assert!(broken(|b| b.bias_o.clear()).starts_with("bias_o has 0 entries"));
+        assert!(broken(|b| b.input_size = 0).starts_with("empty layer"));
+
+        // from_json reports shape errors instead of returning a brain that panics later
+        let mut mismatched = nn.clone();
+        mismatched.bias_o.push(1.0);
+        let error = NeuralNetwork::from_json(&mismatched.to_json()).unwrap_err();
+        assert!(error.contains("bias_o"), "{}", error);
+        assert!(NeuralNetwork::from_json("{").is_err());
+    }
+
+    #[test]
+    fn test_legacy_brain_gains_silent_pheromone_inputs() {
+        let legacy = NeuralNetwork::new(27, 4);
+        let inputs: Vec<f32> = (0..27).map(|i| (i as f32 * 0.37).sin()).collect();
+
+        let mut upgraded = legacy.clone();
+        assert!(upgraded.upgrade_legacy_inputs(34));
+        assert_eq!(upgraded.input_size, 34);
+        assert_eq!(upgraded.validate(), Ok(()));
+
+        // Zero weights: identical outputs whatever the pheromone, temperature and
+        // memory inputs read
+        let mut extended = inputs.clone();
+        extended.extend([0.8, -0.3, 0.5, 0.1, -0.2, 0.3, 1.0]);
+        assert_eq!(upgraded.forward(&extended), legacy.forward(&inputs));
+
+        // Only brains missing exactly the newer inputs are touched
+        assert!(!upgraded.upgrade_legacy_inputs(34));
+        assert!(!legacy.clone().upgrade_legacy_inputs(24));
+        assert!(!legacy.clone().upgrade_legacy_inputs(30));
+    }
+
+    #[test]
+    fn test_pheromone_era_brain_gains_silent_temperature_input() {
+        let legacy = NeuralNetwork::new(29, 4);
+        let inputs: Vec<f32> = (0..29).map(|i| (i as f32 * 0.37).cos()).collect();
+
+        let mut upgraded = legacy.clone();
+        assert!(upgraded.upgrade_legacy_inputs(34));
+        assert_eq!(upgraded.input_size, 34);
+
+        let mut extended = inputs.clone();
+        extended.extend([-0.9, 0.4, 0.2, -0.6, -1.0]);
+        assert_eq!(upgraded.forward(&extended), legacy.forward(&inputs));
+
+        // Temperature-era brains only miss the memory inputs
+        let mut recent = NeuralNetwork::new(30, 4);
+        assert!(recent.upgrade_legacy_inputs(34));
+        assert_eq!(recent.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_legacy_brain_gains_a_silent_donate_output() {
+        let legacy = NeuralNetwork::new(30, 4);
+        let inputs: Vec<f32> = (0..30).map(|i| (i as f32 * 0.21).sin()).collect();
+
+        let mut upgraded = legacy.clone();
+        assert!(upgraded.upgrade_legacy_outputs(5));
+        assert_eq!(upgraded.output_size, 5);
+        assert_eq!(upgraded.validate(), Ok(()));
+
+        // The old outputs are untouched, the new one sits at the silent bias
+        let outputs = upgraded.forward(&inputs);
+        assert_eq!(outputs[..4], legacy.forward(&inputs)[..]);
+        assert_eq!(outputs[4], SILENT_OUTPUT_BIAS);
+        assert!(!upgraded.upgrade_legacy_outputs(5));
+        assert!(!legacy.clone().upgrade_legacy_outputs(7));
+    }
+
+    #[test]
+    fn test_is_within_distance() {
+        let nn = NeuralNetwork::new(5, 4);
+        assert!(nn.is_within_distance(&nn.clone(), 0.0));
+
+        // Random networks differ by ~2/3 per parameter on average
+        let other = NeuralNetwork::new(5, 4);
+        assert!(!nn.is_within_distance(&other, 0.1));
+        assert!(nn.is_within_distance(&other, 4.0));
+
+        // A lightly mutated copy stays close
+        let mut mutated = nn.clone();
+        mutated.mutate(0.05, 0.0);
+        assert!(nn.is_within_distance(&mutated, 0.1));
+
+        // Different shapes are never close
+        let wider = NeuralNetwork::new_with_multiplier(5, 4, 2);
+        assert!(!nn.is_within_distance(&wider, 100.0));
+    }
+
     #[test]
     fn test_get_best_action() {
         let nn = NeuralNetwork::new(5, 4);
@@ -269,4 +857,182 @@ mod tests {
         let action = nn.get_best_action(&inputs);
         assert_eq!(action, 0); // Should not panic and return the only available action
     }
+
+    #[test]
+    fn test_negative_action_bias_breaks_no_op_tie() {
+        let mut nn = NeuralNetwork::new(2, 4);
+        // Zero network: every action outputs 0.0 and the tie goes to no-op
+        nn.apply_weight_decay(1.0);
+        let inputs = vec![0.3, -0.7];
+        assert_eq!(nn.get_best_action(&inputs), 0);
+
+        nn.action_bias = [-5.0, 0.0, 0.0, 0.0];
+        assert_eq!(nn.forward(&inputs), vec![-5.0, 0.0, 0.0, 0.0]);
+        assert_eq!(nn.get_best_action(&inputs), 1);
+
+        // Persisted with the brain; brains saved without it load with zero bias
+        let loaded = NeuralNetwork::from_json(&nn.to_json()).unwrap();
+        assert_eq!(loaded.action_bias, nn.action_bias);
+        let mut legacy: serde_json::Value = serde_json::from_str(&nn.to_json()).unwrap();
+        legacy.as_object_mut().unwrap().remove("action_bias");
+        let legacy = NeuralNetwork::from_json(&legacy.to_string()).unwrap();
+        assert_eq!(legacy.action_bias, [0.0; 4]);
+    }
+
+    #[test]
+    fn test_structured_mutation_keeps_row_ratios() {
+        let mut nn = NeuralNetwork::new(8, 4);
+        let original = nn.weights_ih.clone();
+        nn.structured_mutate(1.0, 0.0);
+        assert_ne!(nn.weights_ih, original);
+
+        // Every row was scaled as a whole by one factor in 0.9..1.1, so the ratios
+        // between its weights are unchanged
+        for (row, before) in nn.weights_ih.iter().zip(&original) {
+            let (largest, scaled) = before
+                .iter()
+                .zip(row)
+                .max_by(|a, b| a.0.abs().total_cmp(&b.0.abs()))
+                .unwrap();
+            let factor = scaled / largest;
+            assert!((0.9..=1.1).contains(&factor), "{}", factor);
+            for (b, a) in before.iter().zip(row) {
+                assert!((a - b * factor).abs() < 1e-5, "{} vs {}", a, b * factor);
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_hidden_rows_exchanges_a_block() {
+        let mut a = NeuralNetwork::new(6, 4);
+        let mut b = NeuralNetwork::new(6, 4);
+        // Out-of-range weights (e.g. from an old save) come out clamped
+        b.weights_ih[2][0] = 5.0;
+        let (before_a, before_b) = (a.weights_ih.clone(), b.weights_ih.clone());
+
+        assert!(a.swap_hidden_rows(&mut b, 1, 2));
+        assert_eq!(a.weights_ih[1], before_b[1]);
+        assert_eq!(b.weights_ih[1], before_a[1]);
+        assert_eq!(a.weights_ih[2][0], 2.0);
+        assert_eq!(a.weights_ih[2][1..], before_b[2][1..]);
+        assert_eq!(a.weights_ih[0], before_a[0]);
+        assert_eq!(a.weights_ih[3..], before_a[3..]);
+        assert_ne!(a.weights_ih, before_a);
+        let all = a.weights_ih.iter().chain(b.weights_ih.iter()).flatten();
+        assert!(all.copied().all(|w| (-2.0..=2.0).contains(&w)));
+
+        // A block running past the hidden layer stops at its end
+        assert!(a.swap_hidden_rows(&mut b, a.hidden_size - 1, 10));
+        let mut other_shape = NeuralNetwork::new(5, 4);
+        assert!(!a.swap_hidden_rows(&mut other_shape, 0, 1));
+    }
+
+    #[test]
+    fn test_argmax_skips_nan() {
+        assert_eq!(argmax(&[f32::NAN, 0.5, 2.0, f32::NAN]), 2);
+        assert_eq!(argmax(&[f32::NAN, -3.0]), 1);
+        assert_eq!(argmax(&[f32::NAN, f32::NAN]), 0);
+        assert_eq!(argmax(&[]), 0);
+    }
+
+    #[test]
+    fn test_argmax_tie_lowest_index_wins() {
+        assert_eq!(argmax(&[1.0, 3.0, 3.0, 3.0]), 1);
+        assert_eq!(argmax(&[0.0, 0.0, 0.0, 0.0]), 0);
+        assert_eq!(argmax(&[-1.0, f32::NAN, -1.0]), 0);
+    }
+
+    #[test]
+    fn test_forward_detailed_returns_hidden_and_output_layers() {
+        let nn = NeuralNetwork::new(5, 4);
+        let inputs = [0.5, -1.0, 0.0, 1.0, 0.25];
+        let (hidden, outputs) = nn.forward_detailed(&inputs);
+        assert_eq!(hidden.len(), nn.hidden_size);
+        assert_eq!(outputs.len(), nn.output_size);
+        // ReLU hidden layer, and the same outputs as a plain forward pass
+        assert!(hidden.iter().all(|&value| value >= 0.0));
+        assert_eq!(outputs, nn.forward(&inputs));
+    }
+
+    #[test]
+    fn test_distance_grows_with_mutation_rate() {
+        let nn = NeuralNetwork::new(5, 4);
+        assert_eq!(nn.distance(&nn.clone()), 0.0);
+
+        // Averaged over several mutants so the comparison doesn't hinge on one draw
+        let mean_distance = |rate: f32| {
+            (0..20)
+                .map(|_| {
+                    let mut mutated = nn.clone();
+                    mutated.mutate(rate, 0.0);
+                    nn.distance(&mutated)
+                })
+                .sum::<f32>()
+                / 20.0
+        };
+        let distances = [0.05, 0.2, 0.5, 1.0].map(mean_distance);
+        assert!(distances[0] > 0.0);
+        assert!(distances.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let wider = NeuralNetwork::new_with_multiplier(5, 4, 2);
+        assert_eq!(nn.distance(&wider), f32::INFINITY);
+    }
+
+    #[test]
+    fn test_mutation_delta_and_most_changed_weight() {
+        let parent = NeuralNetwork::new(5, 4);
+        assert_eq!(parent.mutation_delta(&parent.clone()), 0.0);
+
+        let mut child = parent.clone();
+        child.weights_ih[2][1] += 0.25;
+        child.weights_ho[3][0] -= 0.5;
+        child.bias_o[1] += 0.1; // Counts toward the delta, never as the most changed weight
+        assert!((child.mutation_delta(&parent) - 0.85).abs() < 1e-5);
+
+        let change = child.most_changed_weight(&parent).unwrap();
+        assert_eq!((change.matrix, change.row, change.column), (1, 3, 0));
+        assert!((change.magnitude - 0.5).abs() < 1e-5);
+        assert_eq!(change.label(), "ho[3][0]");
+
+        let wider = NeuralNetwork::new_with_multiplier(5, 4, 2);
+        assert_eq!(wider.mutation_delta(&parent), f32::INFINITY);
+        assert_eq!(wider.most_changed_weight(&parent), None);
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        let nn = NeuralNetwork::new(5, 4);
+        assert!((nn.cosine_similarity(&nn.clone()) - 1.0).abs() < 1e-5);
+
+        let mut mutated = nn.clone();
+        mutated.mutate(0.5, 0.0);
+        let similarity = nn.cosine_similarity(&mutated);
+        assert!(similarity > 0.5 && similarity < 1.0);
+
+        // Different shapes are unrelated
+        let wider = NeuralNetwork::new_with_multiplier(5, 4, 2);
+        assert_eq!(nn.cosine_similarity(&wider), 0.0);
+    }
+
+    #[test]
+    fn test_weight_diff_is_mean_absolute_difference() {
+        let nn = NeuralNetwork::new(5, 4);
+        assert_eq!(NeuralNetwork::weight_diff(&nn, &nn.clone()), 0.0);
+
+        let mut shifted = nn.clone();
+        for row in shifted.weights_ih.iter_mut() {
+            for weight in row.iter_mut() {
+                *weight += 0.5;
+            }
+        }
+        // Only the input-to-hidden weights moved
+        let moved = (shifted.hidden_size * shifted.input_size) as f32;
+        let expected = 0.5 * moved / nn.parameter_count() as f32;
+        let diff = NeuralNetwork::weight_diff(&nn, &shifted);
+        assert!((diff - expected).abs() < 1e-5);
+        assert_eq!(diff, NeuralNetwork::weight_diff(&shifted, &nn));
+
+        let wider = NeuralNetwork::new_with_multiplier(5, 4, 2);
+        assert_eq!(NeuralNetwork::weight_diff(&nn, &wider), f32::INFINITY);
+    }
 }
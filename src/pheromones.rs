@@ -0,0 +1,237 @@
+use crate::camera::ViewBounds;
+use crate::spatial_grid::SpatialGrid;
+use macroquad::prelude::*;
+use rayon::prelude::*;
+
+// Concentration at which the level sensor input reads 0.0 (halfway between -1 and 1)
+pub const PHEROMONE_HALF_SATURATION: f32 = 2.0;
+const OVERLAY_MAX_ALPHA: f32 = 0.5;
+
+/// Chemical trail field over the spatial grid buckets, wrapping with the world
+/// (or stopped by its walls, see set_wrapping). Alive cells deposit into their
+/// bucket; once per tick the whole field decays and diffuses to its 4 neighbors.
+pub struct PheromoneField {
+    values: Vec<f32>,
+    scratch: Vec<f32>, // Next tick's values, swapped in by update()
+    grid_width: usize,
+    grid_height: usize,
+    bucket_size: f32,
+    wrapping: bool,
+}
+
+impl PheromoneField {
+    /// Same buckets as the spatial grid
+    pub fn new(grid: &SpatialGrid) -> Self {
+        let (grid_width, grid_height) = grid.dimensions();
+        PheromoneField {
+            values: vec![0.0; grid_width * grid_height],
+            scratch: vec![0.0; grid_width * grid_height],
+            grid_width,
+            grid_height,
+            bucket_size: grid.bucket_size(),
+            wrapping: true,
+        }
+    }
+
+    // Follows MovementConfig::wrapping like the spatial grid: without wrapping the
+    // field neither diffuses nor reads gradients across the world edges
+    pub fn set_wrapping(&mut self, wrapping: bool) {
+        self.wrapping = wrapping;
+    }
+
+    // Bucket column and row of a world position (wrapped, or clamped to the edge
+    // buckets between walls)
+    fn bucket_of(&self, x: f32, y: f32) -> (usize, usize) {
+        let column = (x / self.bucket_size).floor() as i64;
+        let row = (y / self.bucket_size).floor() as i64;
+        let place = |index: i64, size: usize| {
+            if self.wrapping {
+                index.rem_euclid(size as i64) as usize
+            } else {
+                index.clamp(0, size as i64 - 1) as usize
+            }
+        };
+        (place(column, self.grid_width), place(row, self.grid_height))
+    }
+
+    fn value_at(&self, column: usize, row: usize) -> f32 {
+        self.values[row * self.grid_width + column]
+    }
+
+    pub fn deposit(&mut self, x: f32, y: f32, amount: f32) {
+        let (column, row) = self.bucket_of(x, y);
+        self.values[row * self.grid_width + column] += amount;
+    }
+
+    /// One tick of 4-neighbor diffusion (`diffusion` 0..1 of the way toward the
+    /// neighbor average, which conserves the total) followed by `decay` (0..1 lost)
+    pub fn update(&mut self, decay: f32, diffusion: f32) {
+        let (width, height) = (self.grid_width, self.grid_height);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let diffusion = diffusion.clamp(0.0, 1.0);
+        let keep = 1.0 - decay.clamp(0.0, 1.0);
+        let values = &self.values;
+        let wrapping = self.wrapping;
+
+        self.scratch
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(row, next)| {
+                let here = row * width;
+                let up = before(row, height, wrapping) * width;
+                let down = after(row, height, wrapping) * width;
+                for (column, out) in next.iter_mut().enumerate() {
+                    let left = before(column, width, wrapping);
+                    let right = after(column, width, wrapping);
+                    let value = values[here + column];
+                    let neighbors = (values[here + left]
+                        + values[here + right]
+                        + values[up + column]
+                        + values[down + column])
+                        * 0.25;
+                    *out = (value + diffusion * (neighbors - value)) * keep;
+                }
+            });
+        std::mem::swap(&mut self.values, &mut self.scratch);
+    }
+
+    /// Concentration in the bucket containing (x, y)
+    pub fn concentration(&self, x: f32, y: f32) -> f32 {
+        let (column, row) = self.bucket_of(x, y);
+        self.value_at(column, row)
+    }
+
+    /// Direction of increasing concentration at (x, y), from the difference between
+    /// opposite neighbor buckets; (0, 0) on a flat field
+    pub fn gradient(&self, x: f32, y: f32) -> (f32, f32) {
+        let (column, row) = self.bucket_of(x, y);
+        let (width, height, wrapping) = (self.grid_width, self.grid_height, self.wrapping);
+        let left = self.value_at(before(column, width, wrapping), row);
+        let right = self.value_at(after(column, width, wrapping), row);
+        let up = self.value_at(column, before(row, height, wrapping));
+        let down = self.value_at(column, after(row, height, wrapping));
+        ((right - left) * 0.5, (down - up) * 0.5)
+    }
+
+    pub fn total(&self) -> f32 {
+        self.values.iter().sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.fill(0.0);
+    }
+
+    // Translucent violet buckets, opacity saturating with concentration
+    pub fn render(&self, camera_x: f32, camera_y: f32, view: &ViewBounds) {
+        let first_x = ((camera_x + view.min_x) / self.bucket_size).floor() as i32;
+        let first_y = ((camera_y + view.min_y) / self.bucket_size).floor() as i32;
+        let last_x = ((camera_x + view.max_x) / self.bucket_size).floor() as i32;
+        let last_y = ((camera_y + view.max_y) / self.bucket_size).floor() as i32;
+
+        for by in first_y..=last_y {
+            for bx in first_x..=last_x {
+                let column = bx.rem_euclid(self.grid_width as i32) as usize;
+                let row = by.rem_euclid(self.grid_height as i32) as usize;
+                let level = self.value_at(column, row);
+                if level <= 0.01 {
+                    continue;
+                }
+                let alpha = level / (level + PHEROMONE_HALF_SATURATION) * OVERLAY_MAX_ALPHA;
+                draw_rectangle(
+                    bx as f32 * self.bucket_size - camera_x,
+                    by as f32 * self.bucket_size - camera_y,
+                    self.bucket_size,
+                    self.bucket_size,
+                    Color::new(0.7, 0.3, 1.0, alpha),
+                );
+            }
+        }
+    }
+}
+
+// Neighbor bucket index before `index` on an axis of `size` buckets: across the edge
+// when wrapping, the bucket itself at a wall (so nothing flows through it)
+fn before(index: usize, size: usize, wrapping: bool) -> usize {
+    if index > 0 {
+        index - 1
+    } else if wrapping {
+        size - 1
+    } else {
+        index
+    }
+}
+
+// Neighbor bucket index after `index`, see before()
+fn after(index: usize, size: usize, wrapping: bool) -> usize {
+    if index + 1 < size {
+        index + 1
+    } else if wrapping {
+        0
+    } else {
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field() -> PheromoneField {
+        PheromoneField::new(&SpatialGrid::new(1000.0, 500.0, 100.0))
+    }
+
+    #[test]
+    fn test_diffusion_conserves_and_decay_drains() {
+        let mut field = field();
+        field.deposit(450.0, 250.0, 10.0);
+        assert_eq!(field.concentration(420.0, 210.0), 10.0);
+
+        // Diffusion alone only spreads the total
+        field.update(0.0, 0.5);
+        assert!((field.total() - 10.0).abs() < 1e-4);
+        assert_eq!(field.concentration(450.0, 250.0), 5.0);
+        assert_eq!(field.concentration(550.0, 250.0), 1.25);
+
+        field.update(0.1, 0.5);
+        assert!((field.total() - 9.0).abs() < 1e-4);
+
+        field.clear();
+        assert_eq!(field.total(), 0.0);
+    }
+
+    #[test]
+    fn test_gradient_points_toward_deposit_across_edges() {
+        let mut field = field();
+        // Left neighbor of bucket column 0 is the last column (the world wraps)
+        field.deposit(950.0, 50.0, 4.0);
+        let (gx, gy) = field.gradient(50.0, 50.0);
+        assert_eq!((gx, gy), (-2.0, 0.0));
+
+        field.deposit(50.0, 150.0, 8.0);
+        let (gx, gy) = field.gradient(50.0, 50.0);
+        assert_eq!((gx, gy), (-2.0, 4.0));
+
+        assert_eq!(field.gradient(500.0, 350.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_walls_stop_diffusion_and_gradients_at_the_edges() {
+        let mut field = field();
+        field.set_wrapping(false);
+        field.deposit(950.0, 50.0, 4.0);
+        assert_eq!(field.gradient(50.0, 50.0), (0.0, 0.0));
+        // The wall side reads the bucket itself
+        assert_eq!(field.gradient(950.0, 250.0), (0.0, 0.0));
+        assert_eq!(field.gradient(950.0, 150.0), (0.0, -2.0));
+
+        // Nothing crosses to column 0, and the corner bucket keeps the total
+        field.update(0.0, 0.5);
+        assert!((field.total() - 4.0).abs() < 1e-4);
+        assert_eq!(field.concentration(50.0, 50.0), 0.0);
+        assert_eq!(field.concentration(950.0, 50.0), 3.0);
+        // Positions on the far wall land in the edge bucket
+        assert_eq!(field.concentration(1000.0, 0.0), 3.0);
+    }
+}
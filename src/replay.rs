@@ -0,0 +1,451 @@
+use crate::cell::{Cell, CellState};
+use crate::config::SimulationConfig;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Record one frame every RECORD_INTERVAL simulation ticks
+pub const RECORD_INTERVAL: usize = 10;
+const DEFAULT_MAX_FRAMES: usize = 1800;
+
+// Binary format header
+const REPLAY_MAGIC: &[u8; 8] = b"CELLREPL";
+const REPLAY_VERSION: u32 = 1;
+// Encoded sizes: tick (u64) + cell count (u32) per frame, 4 f32 + alive + RGB per cell
+const FRAME_HEADER_BYTES: usize = 12;
+const CELL_BYTES: usize = 20;
+
+/// Stripped-down snapshot of a single cell for replay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayCell {
+    pub x: f32,
+    pub y: f32,
+    pub energy: f32,
+    pub radius: f32,
+    pub is_alive: bool,
+    pub color: [u8; 3],
+}
+
+impl ReplayCell {
+    fn from_cell(cell: &Cell) -> Self {
+        ReplayCell {
+            x: cell.x,
+            y: cell.y,
+            energy: cell.energy,
+            radius: cell.get_current_radius(),
+            is_alive: cell.state == CellState::Alive,
+            color: [
+                (cell.color.r * 255.0) as u8,
+                (cell.color.g * 255.0) as u8,
+                (cell.color.b * 255.0) as u8,
+            ],
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        Color::from_rgba(self.color[0], self.color[1], self.color[2], 255)
+    }
+}
+
+/// Snapshot of all cells at a given simulation tick
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayFrame {
+    pub tick: usize,
+    pub cells: Vec<ReplayCell>,
+}
+
+impl ReplayFrame {
+    pub fn capture(tick: usize, cells: &[Cell]) -> Self {
+        ReplayFrame {
+            tick,
+            cells: cells.iter().map(ReplayCell::from_cell).collect(),
+        }
+    }
+}
+
+/// Records periodic snapshots of the world and plays them back
+pub struct ReplayRecorder {
+    pub frames: VecDeque<ReplayFrame>,
+    pub max_frames: usize,
+    recording: bool,
+    // Playback state: index of the frame being shown, None when not playing back
+    playback_index: Option<usize>,
+    playback_ticks: usize, // Ticks elapsed on the current playback frame
+}
+
+impl ReplayRecorder {
+    pub fn new() -> Self {
+        ReplayRecorder {
+            frames: VecDeque::new(),
+            max_frames: DEFAULT_MAX_FRAMES,
+            recording: false,
+            playback_index: None,
+            playback_ticks: 0,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playback_index.is_some()
+    }
+
+    // Start a fresh recording, discarding any previous frames
+    pub fn start_recording(&mut self) {
+        self.frames.clear();
+        self.recording = true;
+    }
+
+    pub fn stop_recording(&mut self) {
+        self.recording = false;
+    }
+
+    // Record a frame if recording and the tick falls on the record interval
+    // Oldest frames are dropped once max_frames is reached
+    pub fn record_tick(&mut self, tick: usize, cells: &[Cell]) {
+        if !self.recording || !tick.is_multiple_of(RECORD_INTERVAL) {
+            return;
+        }
+        if self.frames.len() >= self.max_frames {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(ReplayFrame::capture(tick, cells));
+    }
+
+    // Enter playback mode from the first frame (no-op if nothing recorded)
+    pub fn start_playback(&mut self) -> bool {
+        if self.frames.is_empty() {
+            return false;
+        }
+        self.recording = false;
+        self.playback_index = Some(0);
+        self.playback_ticks = 0;
+        true
+    }
+
+    pub fn stop_playback(&mut self) {
+        self.playback_index = None;
+    }
+
+    // Advance playback by one simulation tick, moving to the next frame every
+    // RECORD_INTERVAL ticks so playback runs at the original speed.
+    // Loops back to the start after the last frame.
+    pub fn advance_playback(&mut self) {
+        if let Some(index) = self.playback_index {
+            self.playback_ticks += 1;
+            if self.playback_ticks >= RECORD_INTERVAL {
+                self.playback_ticks = 0;
+                self.playback_index = Some((index + 1) % self.frames.len().max(1));
+            }
+        }
+    }
+
+    // Frame currently being played back, with its index
+    pub fn current_frame(&self) -> Option<(usize, &ReplayFrame)> {
+        self.playback_index
+            .and_then(|index| self.frames.get(index).map(|frame| (index, frame)))
+    }
+
+    /// Encode all frames to a compact little-endian binary format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let cell_total: usize = self.frames.iter().map(|f| f.cells.len()).sum();
+        let mut bytes = Vec::with_capacity(
+            16 + self.frames.len() * FRAME_HEADER_BYTES + cell_total * CELL_BYTES,
+        );
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+
+        for frame in &self.frames {
+            bytes.extend_from_slice(&(frame.tick as u64).to_le_bytes());
+            bytes.extend_from_slice(&(frame.cells.len() as u32).to_le_bytes());
+            for cell in &frame.cells {
+                bytes.extend_from_slice(&cell.x.to_le_bytes());
+                bytes.extend_from_slice(&cell.y.to_le_bytes());
+                bytes.extend_from_slice(&cell.energy.to_le_bytes());
+                bytes.extend_from_slice(&cell.radius.to_le_bytes());
+                bytes.push(cell.is_alive as u8);
+                bytes.extend_from_slice(&cell.color);
+            }
+        }
+
+        bytes
+    }
+
+    /// Decode frames from the binary format produced by to_bytes()
+    /// Returns None if the data is truncated or has the wrong header
+    pub fn frames_from_bytes(bytes: &[u8]) -> Option<VecDeque<ReplayFrame>> {
+        let mut reader = ByteReader { bytes, pos: 0 };
+
+        if reader.take(REPLAY_MAGIC.len())? != REPLAY_MAGIC {
+            return None;
+        }
+        if reader.u32()? != REPLAY_VERSION {
+            return None;
+        }
+
+        // Counts come from the file: reserve only what the remaining bytes can hold
+        let frame_count = reader.u32()? as usize;
+        let mut frames =
+            VecDeque::with_capacity(frame_count.min(reader.remaining() / FRAME_HEADER_BYTES));
+        for _ in 0..frame_count {
+            let tick = reader.u64()? as usize;
+            let cell_count = reader.u32()? as usize;
+            let mut cells = Vec::with_capacity(cell_count.min(reader.remaining() / CELL_BYTES));
+            for _ in 0..cell_count {
+                let x = reader.f32()?;
+                let y = reader.f32()?;
+                let energy = reader.f32()?;
+                let radius = reader.f32()?;
+                let is_alive = reader.take(1)?[0] != 0;
+                let color = reader.take(3)?;
+                cells.push(ReplayCell {
+                    x,
+                    y,
+                    energy,
+                    radius,
+                    is_alive,
+                    color: [color[0], color[1], color[2]],
+                });
+            }
+            frames.push_back(ReplayFrame { tick, cells });
+        }
+
+        Some(frames)
+    }
+}
+
+// Store a state hash every CHECKPOINT_INTERVAL ticks of a recorded run
+pub const CHECKPOINT_INTERVAL: usize = 100;
+
+/// Manual events injected into a recorded run, applied before the tick they name
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RunEvent {
+    Reset { tick: usize },
+}
+
+impl RunEvent {
+    pub fn tick(&self) -> usize {
+        match self {
+            RunEvent::Reset { tick } => *tick,
+        }
+    }
+}
+
+/// World::tick_hash() expected after a given tick
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub tick: usize,
+    pub hash: u64,
+}
+
+/// Everything needed to re-simulate a seeded run: the simulation is deterministic
+/// once seeded, so no per-tick state is stored, only hashes to verify against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecording {
+    pub seed: u64,
+    pub config: SimulationConfig,
+    pub ticks: usize,
+    pub events: Vec<RunEvent>,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl RunRecording {
+    pub fn new(seed: u64, config: SimulationConfig) -> Self {
+        RunRecording {
+            seed,
+            config,
+            ticks: 0,
+            events: Vec::new(),
+            checkpoints: Vec::new(),
+        }
+    }
+
+    // Events to apply before simulating the given tick
+    pub fn events_at(&self, tick: usize) -> impl Iterator<Item = &RunEvent> {
+        self.events.iter().filter(move |event| event.tick() == tick)
+    }
+
+    // Record a checkpoint if the tick falls on the checkpoint interval
+    pub fn record_checkpoint(&mut self, tick: usize, hash: u64) {
+        if tick.is_multiple_of(CHECKPOINT_INTERVAL) {
+            self.checkpoints.push(Checkpoint { tick, hash });
+        }
+    }
+
+    // Compare a replayed hash against the recording
+    // Returns the expected checkpoint when the replay diverged at this tick
+    pub fn verify_checkpoint(&self, tick: usize, hash: u64) -> Option<Checkpoint> {
+        self.checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.tick == tick)
+            .filter(|checkpoint| checkpoint.hash != hash)
+            .copied()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+}
+
+// Minimal cursor over a byte slice for decoding
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos + len)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        Some(f32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_cell(x: f32) -> ReplayCell {
+        ReplayCell {
+            x,
+            y: 2.0,
+            energy: 50.0,
+            radius: 8.0,
+            is_alive: true,
+            color: [255, 128, 0],
+        }
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut recorder = ReplayRecorder::new();
+        recorder.frames.push_back(ReplayFrame {
+            tick: 10,
+            cells: vec![sample_cell(1.0), sample_cell(3.0)],
+        });
+        recorder.frames.push_back(ReplayFrame {
+            tick: 20,
+            cells: vec![],
+        });
+
+        let bytes = recorder.to_bytes();
+        let frames = ReplayRecorder::frames_from_bytes(&bytes).unwrap();
+        assert_eq!(frames, recorder.frames);
+    }
+
+    #[test]
+    fn test_recording_drops_the_oldest_frame_when_full() {
+        let mut recorder = ReplayRecorder::new();
+        recorder.max_frames = 2;
+        recorder.start_recording();
+        for tick in [0, RECORD_INTERVAL, RECORD_INTERVAL * 2] {
+            recorder.record_tick(tick, &[]);
+        }
+        let ticks: Vec<usize> = recorder.frames.iter().map(|frame| frame.tick).collect();
+        assert_eq!(ticks, [RECORD_INTERVAL, RECORD_INTERVAL * 2]);
+    }
+
+    #[test]
+    fn test_truncated_bytes_rejected() {
+        let mut recorder = ReplayRecorder::new();
+        recorder.frames.push_back(ReplayFrame {
+            tick: 10,
+            cells: vec![sample_cell(1.0)],
+        });
+        let bytes = recorder.to_bytes();
+        assert!(ReplayRecorder::frames_from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        assert!(ReplayRecorder::frames_from_bytes(b"garbage").is_none());
+    }
+
+    #[test]
+    fn test_huge_counts_rejected_without_allocating() {
+        let mut header = Vec::new();
+        header.extend_from_slice(REPLAY_MAGIC);
+        header.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+
+        let mut frames = header.clone();
+        frames.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(ReplayRecorder::frames_from_bytes(&frames).is_none());
+
+        let mut cells = header;
+        cells.extend_from_slice(&1u32.to_le_bytes());
+        cells.extend_from_slice(&10u64.to_le_bytes());
+        cells.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(ReplayRecorder::frames_from_bytes(&cells).is_none());
+    }
+
+    #[test]
+    fn test_run_recording_round_trip_and_verify() {
+        let mut recording = RunRecording::new(42, SimulationConfig::default());
+        recording.events.push(RunEvent::Reset { tick: 150 });
+        for tick in 1..=250 {
+            recording.record_checkpoint(tick, tick as u64 * 7);
+        }
+        recording.ticks = 250;
+        assert_eq!(recording.checkpoints.len(), 2);
+
+        let loaded = RunRecording::from_json(&recording.to_json()).unwrap();
+        assert_eq!(loaded.seed, 42);
+        assert_eq!(loaded.events, recording.events);
+        assert_eq!(loaded.checkpoints, recording.checkpoints);
+        assert_eq!(loaded.events_at(150).count(), 1);
+        assert_eq!(loaded.events_at(151).count(), 0);
+
+        // Matching hashes and non-checkpoint ticks pass, a diverged hash is reported
+        assert!(loaded.verify_checkpoint(100, 700).is_none());
+        assert!(loaded.verify_checkpoint(150, 0).is_none());
+        assert_eq!(
+            loaded.verify_checkpoint(200, 0),
+            Some(Checkpoint {
+                tick: 200,
+                hash: 1400
+            })
+        );
+    }
+
+    #[test]
+    fn test_playback_advances_every_record_interval() {
+        let mut recorder = ReplayRecorder::new();
+        for tick in [10, 20] {
+            recorder.frames.push_back(ReplayFrame {
+                tick,
+                cells: vec![],
+            });
+        }
+        assert!(recorder.start_playback());
+        for _ in 0..RECORD_INTERVAL - 1 {
+            recorder.advance_playback();
+        }
+        assert_eq!(recorder.current_frame().unwrap().0, 0);
+        recorder.advance_playback();
+        assert_eq!(recorder.current_frame().unwrap().0, 1);
+        // Loops back to the first frame
+        for _ in 0..RECORD_INTERVAL {
+            recorder.advance_playback();
+        }
+        assert_eq!(recorder.current_frame().unwrap().0, 0);
+    }
+}
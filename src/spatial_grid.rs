@@ -2,9 +2,15 @@
 /// Divides the world into uniform buckets for O(1) spatial lookups
 pub struct SpatialGrid {
     buckets: Vec<Vec<usize>>,
+    // Insertion points, parallel to `buckets`, used for exact region filtering
+    positions: Vec<Vec<(f32, f32)>>,
     bucket_size: f32,
+    world_width: f32,
+    world_height: f32,
     grid_width: usize,
     grid_height: usize,
+    // False for a world with hard walls: queries stop at the edges
+    wrapping: bool,
 }
 
 impl SpatialGrid {
@@ -21,95 +27,260 @@ impl SpatialGrid {
 
         SpatialGrid {
             buckets: vec![Vec::new(); bucket_count],
+            positions: vec![Vec::new(); bucket_count],
             bucket_size,
+            world_width,
+            world_height,
             grid_width,
             grid_height,
+            wrapping: true,
         }
     }
 
+    /// Whether proximity queries reach across the world edges (the default)
+    pub fn set_wrapping(&mut self, wrapping: bool) {
+        self.wrapping = wrapping;
+    }
+
     /// Clears all buckets for a new frame
     pub fn clear(&mut self) {
         for bucket in &mut self.buckets {
             bucket.clear();
         }
+        for positions in &mut self.positions {
+            positions.clear();
+        }
     }
 
     /// Inserts a cell index at the given position
     pub fn insert(&mut self, x: f32, y: f32, cell_index: usize) {
         let bucket_index = self.get_bucket_index(x, y);
         self.buckets[bucket_index].push(cell_index);
+        self.positions[bucket_index].push((x, y));
     }
 
     /// Queries nearby cell indices within collision range
-    /// Returns indices of cells in the same bucket and neighboring buckets
+    /// Returns the sorted, deduplicated indices in the same bucket and neighboring buckets
+    /// The app uses for_each_within (exact distances) and count_nearby; tests check the
+    /// bucket coverage through this.
+    #[cfg(test)]
     pub fn query_nearby(&self, x: f32, y: f32, radius: f32) -> Vec<usize> {
-        // Pre-allocate based on typical nearby cell count to reduce reallocations
-        let bucket_range = (radius / self.bucket_size).ceil() as i32 + 1;
-        let buckets_to_check = ((2 * bucket_range + 1) * (2 * bucket_range + 1)) as usize;
-        let estimated_capacity = buckets_to_check * 5; // Estimate 5 cells per bucket
-        let mut nearby = Vec::with_capacity(estimated_capacity);
+        let mut nearby = Vec::new();
+        self.for_each_nearby_bucket(x, y, radius, |bucket_index| {
+            nearby.extend_from_slice(&self.buckets[bucket_index]);
+        });
+        nearby.sort_unstable();
+        nearby.dedup();
+        nearby
+    }
 
-        let grid_x = (x / self.bucket_size).floor() as i32;
-        let grid_y = (y / self.bucket_size).floor() as i32;
+    /// Calls `visit(index, dx, dy, distance_squared)` for every inserted index whose
+    /// position is within `radius` of (x, y), where (dx, dy) is the shortest offset
+    /// from (x, y) to it, across the world edges when wrapping. Positions come from
+    /// `position` rather than the insertion points, since cells move after the grid
+    /// is built. Each inserted entry is visited at most once.
+    pub fn for_each_within(
+        &self,
+        x: f32,
+        y: f32,
+        radius: f32,
+        position: impl Fn(usize) -> (f32, f32),
+        mut visit: impl FnMut(usize, f32, f32, f32),
+    ) {
+        let radius_squared = radius * radius;
+        self.for_each_nearby_bucket(x, y, radius, |bucket_index| {
+            for &index in &self.buckets[bucket_index] {
+                let (other_x, other_y) = position(index);
+                let (mut dx, mut dy) = (other_x - x, other_y - y);
+                if self.wrapping {
+                    dx = wrapped_delta(dx, self.world_width);
+                    dy = wrapped_delta(dy, self.world_height);
+                }
+                let distance_squared = dx * dx + dy * dy;
+                if distance_squared <= radius_squared {
+                    visit(index, dx, dy, distance_squared);
+                }
+            }
+        });
+    }
 
-        // Check how many buckets we need to search based on radius
+    // Calls `visit` with the index of every bucket within `radius` of (x, y) plus a
+    // one bucket margin, wrapping around the edges (or cut off at them without
+    // wrapping). A radius wider than the world visits each bucket once instead of
+    // lapping around.
+    fn for_each_nearby_bucket(&self, x: f32, y: f32, radius: f32, mut visit: impl FnMut(usize)) {
         let bucket_range = (radius / self.bucket_size).ceil() as i32 + 1;
+        let mut columns =
+            Self::nearby_bucket_range(x, bucket_range, self.bucket_size, self.grid_width);
+        let mut rows =
+            Self::nearby_bucket_range(y, bucket_range, self.bucket_size, self.grid_height);
+        if !self.wrapping {
+            columns = (columns.0.max(0), columns.1.min(self.grid_width as i32 - 1));
+            rows = (rows.0.max(0), rows.1.min(self.grid_height as i32 - 1));
+        }
 
-        // Query neighboring buckets (including wrapping)
-        for dy in -bucket_range..=bucket_range {
-            for dx in -bucket_range..=bucket_range {
-                let check_x = grid_x + dx;
-                let check_y = grid_y + dy;
-
-                // Handle wrapping boundaries
-                let wrapped_x = check_x.rem_euclid(self.grid_width as i32) as usize;
-                let wrapped_y = check_y.rem_euclid(self.grid_height as i32) as usize;
-
-                let bucket_index = wrapped_y * self.grid_width + wrapped_x;
-                nearby.extend_from_slice(&self.buckets[bucket_index]);
+        for by in rows.0..=rows.1 {
+            let wrapped_y = by.rem_euclid(self.grid_height as i32) as usize;
+            for bx in columns.0..=columns.1 {
+                let wrapped_x = bx.rem_euclid(self.grid_width as i32) as usize;
+                visit(wrapped_y * self.grid_width + wrapped_x);
             }
         }
-
-        nearby
     }
 
-    /// Gets the bucket index for a world position
-    fn get_bucket_index(&self, x: f32, y: f32) -> usize {
-        let grid_x = ((x / self.bucket_size).floor() as usize) % self.grid_width;
-        let grid_y = ((y / self.bucket_size).floor() as usize) % self.grid_height;
-        grid_y * self.grid_width + grid_x
+    // Unwrapped bucket range within `bucket_range` buckets of `coordinate` on one axis,
+    // capped to a single lap so no bucket is visited twice
+    fn nearby_bucket_range(
+        coordinate: f32,
+        bucket_range: i32,
+        bucket_size: f32,
+        bucket_count: usize,
+    ) -> (i32, i32) {
+        let center = (coordinate / bucket_size).floor() as i32;
+        let first = center - bucket_range;
+        (
+            first,
+            (center + bucket_range).min(first + bucket_count as i32 - 1),
+        )
     }
 
-    /// Count cells within a specified radius
-    /// Includes the cell itself in the count
-    pub fn count_nearby_in_bucket(&self, x: f32, y: f32, radius: f32) -> usize {
-        let grid_x = (x / self.bucket_size).floor() as i32;
-        let grid_y = (y / self.bucket_size).floor() as i32;
+    /// Queries cell indices whose insertion point lies inside an axis-aligned rectangle
+    /// The rectangle may extend past the world edges; it wraps around like the world does.
+    /// A rectangle larger than the world on an axis covers that whole axis.
+    /// The app only needs counts (count_in_rect); tests check the indices themselves.
+    #[cfg(test)]
+    pub fn query_rect(&self, min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.for_each_in_rect(min_x, min_y, max_x, max_y, |cell_index| {
+            found.push(cell_index)
+        });
+        found
+    }
 
+    /// Same as query_rect().len() without collecting the indices
+    pub fn count_in_rect(&self, min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> usize {
         let mut count = 0;
+        self.for_each_in_rect(min_x, min_y, max_x, max_y, |_| count += 1);
+        count
+    }
 
-        // Calculate how many buckets we need to check based on radius
-        let bucket_range = (radius / self.bucket_size).ceil() as i32 + 1;
+    // Calls `visit` with each cell index inside the rectangle (see query_rect)
+    fn for_each_in_rect(
+        &self,
+        min_x: f32,
+        min_y: f32,
+        max_x: f32,
+        max_y: f32,
+        mut visit: impl FnMut(usize),
+    ) {
+        if max_x < min_x || max_y < min_y {
+            return;
+        }
+
+        let columns = Self::rect_bucket_range(min_x, max_x, self.bucket_size, self.grid_width);
+        let rows = Self::rect_bucket_range(min_y, max_y, self.bucket_size, self.grid_height);
+        let covers_width = max_x - min_x >= self.world_width;
+        let covers_height = max_y - min_y >= self.world_height;
 
-        // Check neighboring buckets within the radius
-        for dy in -bucket_range..=bucket_range {
-            for dx in -bucket_range..=bucket_range {
-                let check_x = grid_x + dx;
-                let check_y = grid_y + dy;
+        for by in rows.0..=rows.1 {
+            let wrapped_y = by.rem_euclid(self.grid_height as i32) as usize;
+            // Shift stored points into the same world copy as the unwrapped bucket
+            let offset_y = by.div_euclid(self.grid_height as i32) as f32 * self.world_height;
 
-                // Handle wrapping boundaries
-                let wrapped_x = check_x.rem_euclid(self.grid_width as i32) as usize;
-                let wrapped_y = check_y.rem_euclid(self.grid_height as i32) as usize;
+            for bx in columns.0..=columns.1 {
+                let wrapped_x = bx.rem_euclid(self.grid_width as i32) as usize;
+                let offset_x = bx.div_euclid(self.grid_width as i32) as f32 * self.world_width;
 
                 let bucket_index = wrapped_y * self.grid_width + wrapped_x;
-                count += self.buckets[bucket_index].len();
+                let points = self.buckets[bucket_index]
+                    .iter()
+                    .zip(&self.positions[bucket_index]);
+                for (&cell_index, &(x, y)) in points {
+                    let x = x + offset_x;
+                    let y = y + offset_y;
+                    let inside_x = covers_width || (x >= min_x && x <= max_x);
+                    let inside_y = covers_height || (y >= min_y && y <= max_y);
+                    if inside_x && inside_y {
+                        visit(cell_index);
+                    }
+                }
             }
         }
+    }
 
+    // Unwrapped bucket range covering [min, max] on one axis,
+    // capped to a single lap so no bucket is visited twice
+    fn rect_bucket_range(min: f32, max: f32, bucket_size: f32, bucket_count: usize) -> (i32, i32) {
+        let first = (min / bucket_size).floor() as i32;
+        let last = (max / bucket_size).floor() as i32;
+        (first, last.min(first + bucket_count as i32 - 1))
+    }
+
+    /// Gets the bucket index for a world position
+    /// Positions outside the world (including negative ones) wrap around like the world
+    /// does, or land in the nearest edge bucket without wrapping
+    pub fn get_bucket_index(&self, x: f32, y: f32) -> usize {
+        let grid_x = (x / self.bucket_size).floor() as i32;
+        let grid_y = (y / self.bucket_size).floor() as i32;
+        let (grid_x, grid_y) = if self.wrapping {
+            (
+                grid_x.rem_euclid(self.grid_width as i32),
+                grid_y.rem_euclid(self.grid_height as i32),
+            )
+        } else {
+            (
+                grid_x.clamp(0, self.grid_width as i32 - 1),
+                grid_y.clamp(0, self.grid_height as i32 - 1),
+            )
+        };
+        grid_y as usize * self.grid_width + grid_x as usize
+    }
+
+    /// Grid dimensions in buckets (width, height)
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.grid_width, self.grid_height)
+    }
+
+    /// Cell indices per bucket, row-major (bucket index = y * grid_width + x)
+    pub fn buckets(&self) -> &[Vec<usize>] {
+        &self.buckets
+    }
+
+    /// Size of each bucket in world units
+    pub fn bucket_size(&self) -> f32 {
+        self.bucket_size
+    }
+
+    /// Same as query_nearby().len() without allocating: sums the bucket lengths
+    /// Includes the cell itself in the count
+    pub fn count_nearby(&self, x: f32, y: f32, radius: f32) -> usize {
+        let mut count = 0;
+        self.for_each_nearby_bucket(x, y, radius, |bucket_index| {
+            count += self.buckets[bucket_index].len();
+        });
         count
     }
 }
 
+/// Brings a coordinate back into 0..=size: wrapped around on a wrapping axis,
+/// clamped against the walls otherwise
+pub fn confine(value: f32, size: f32, wrapping: bool) -> f32 {
+    if wrapping {
+        value.rem_euclid(size)
+    } else {
+        value.clamp(0.0, size)
+    }
+}
+
+/// Shortest signed distance between two coordinates on a wrapping axis of length `size`
+pub fn wrapped_delta(delta: f32, size: f32) -> f32 {
+    if delta.abs() > size / 2.0 {
+        delta - delta.signum() * size
+    } else {
+        delta
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,4 +324,210 @@ mod tests {
         // This tests the wrapping logic works correctly
         assert!(!grid.buckets.is_empty());
     }
+
+    fn sorted(mut indices: Vec<usize>) -> Vec<usize> {
+        indices.sort_unstable();
+        indices
+    }
+
+    #[test]
+    fn test_query_rect_filters_by_position() {
+        let mut grid = SpatialGrid::new(1000.0, 1000.0, 100.0);
+        grid.insert(150.0, 150.0, 0);
+        grid.insert(190.0, 120.0, 1); // Same bucket as 0 but outside the rect
+        grid.insert(120.0, 400.0, 2);
+        grid.insert(400.0, 400.0, 3);
+
+        let found = grid.query_rect(100.0, 100.0, 180.0, 500.0);
+        assert_eq!(sorted(found), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_query_rect_edges_are_inclusive() {
+        let mut grid = SpatialGrid::new(1000.0, 1000.0, 100.0);
+        grid.insert(200.0, 200.0, 0);
+        grid.insert(300.0, 300.0, 1);
+
+        let found = grid.query_rect(200.0, 200.0, 300.0, 300.0);
+        assert_eq!(sorted(found), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_query_rect_wraps_across_boundary() {
+        let mut grid = SpatialGrid::new(1000.0, 1000.0, 100.0);
+        grid.insert(10.0, 10.0, 0);
+        grid.insert(990.0, 990.0, 1);
+        grid.insert(500.0, 500.0, 2);
+
+        // Rect spanning the corner, expressed past the far edge
+        let found = grid.query_rect(950.0, 950.0, 1050.0, 1050.0);
+        assert_eq!(sorted(found), vec![0, 1]);
+
+        // Same rect expressed with negative coordinates
+        let found = grid.query_rect(-50.0, -50.0, 50.0, 50.0);
+        assert_eq!(sorted(found), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_query_rect_larger_than_world() {
+        let mut grid = SpatialGrid::new(1000.0, 1000.0, 100.0);
+        for i in 0..10 {
+            grid.insert(i as f32 * 99.0, i as f32 * 99.0, i);
+        }
+
+        // Each cell is returned exactly once even though the rect covers the world several times
+        let found = grid.query_rect(-2500.0, -2500.0, 2500.0, 2500.0);
+        assert_eq!(sorted(found), (0..10).collect::<Vec<_>>());
+
+        // Larger than the world on one axis only
+        let found = grid.query_rect(-5000.0, 0.0, 5000.0, 250.0);
+        assert_eq!(sorted(found), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_query_rect_inverted_is_empty() {
+        let mut grid = SpatialGrid::new(1000.0, 1000.0, 100.0);
+        grid.insert(500.0, 500.0, 0);
+        assert!(grid.query_rect(600.0, 600.0, 400.0, 400.0).is_empty());
+    }
+
+    #[test]
+    fn test_counts_match_query_lengths() {
+        let mut grid = SpatialGrid::new(1000.0, 1000.0, 100.0);
+        let points = [
+            (10.0, 10.0),
+            (990.0, 990.0),
+            (150.0, 150.0),
+            (190.0, 120.0),
+            (500.0, 500.0),
+        ];
+        for (idx, &(x, y)) in points.iter().enumerate() {
+            grid.insert(x, y, idx);
+        }
+
+        for (x, y, radius) in [
+            (155.0, 155.0, 50.0),
+            (990.0, 10.0, 50.0),
+            (500.0, 500.0, 300.0),
+        ] {
+            assert_eq!(
+                grid.count_nearby(x, y, radius),
+                grid.query_nearby(x, y, radius).len()
+            );
+        }
+        let rects = [
+            (100.0, 100.0, 180.0, 500.0),
+            (-50.0, -50.0, 50.0, 50.0),
+            (-2500.0, -2500.0, 2500.0, 2500.0),
+            (600.0, 600.0, 400.0, 400.0),
+        ];
+        for (min_x, min_y, max_x, max_y) in rects {
+            assert_eq!(
+                grid.count_in_rect(min_x, min_y, max_x, max_y),
+                grid.query_rect(min_x, min_y, max_x, max_y).len()
+            );
+        }
+        assert_eq!(grid.count_in_rect(-50.0, -50.0, 50.0, 50.0), 2);
+    }
+
+    #[test]
+    fn test_negative_positions_wrap_to_the_far_buckets() {
+        let grid = SpatialGrid::new(1000.0, 1000.0, 100.0);
+        assert_eq!(grid.get_bucket_index(-0.5, 50.0), 9);
+        assert_eq!(grid.get_bucket_index(50.0, -0.5), 90);
+        assert_eq!(grid.get_bucket_index(1000.5, 1000.5), 0);
+    }
+
+    #[test]
+    fn test_walled_grid_does_not_reach_across_edges() {
+        let mut grid = SpatialGrid::new(1000.0, 1000.0, 100.0);
+        grid.set_wrapping(false);
+        assert_eq!(grid.get_bucket_index(-0.5, 50.0), 0);
+        assert_eq!(grid.get_bucket_index(1000.0, 1000.0), 99);
+
+        let points = [(990.0, 500.0), (10.0, 500.0), (950.0, 500.0)];
+        for (idx, &(x, y)) in points.iter().enumerate() {
+            grid.insert(x, y, idx);
+        }
+        let mut found = Vec::new();
+        grid.for_each_within(
+            995.0,
+            500.0,
+            60.0,
+            |i| points[i],
+            |i, dx, _, _| {
+                assert!(dx < 0.0);
+                found.push(i);
+            },
+        );
+        assert_eq!(sorted(found), [0, 2]);
+        assert_eq!(grid.count_nearby(5.0, 500.0, 20.0), 1);
+    }
+
+    #[test]
+    fn test_query_within_matches_brute_force() {
+        let (width, height) = (1000.0, 700.0);
+        let mut grid = SpatialGrid::new(width, height, 90.0);
+        // Fixed pseudo-random points (xorshift), some slightly outside the world
+        let mut seed = 0x2545_f491_u32;
+        let mut next = |scale: f32| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            (seed as f32 / u32::MAX as f32) * (scale + 2.0) - 1.0
+        };
+        let points: Vec<(f32, f32)> = (0..400).map(|_| (next(width), next(height))).collect();
+        for (idx, &(x, y)) in points.iter().enumerate() {
+            grid.insert(x, y, idx);
+        }
+
+        for query in 0..60 {
+            let (x, y) = (next(width), next(height));
+            let radius = [5.0, 40.0, 150.0, 420.0, 3000.0][query % 5];
+            let mut found = Vec::new();
+            grid.for_each_within(
+                x,
+                y,
+                radius,
+                |i| points[i],
+                |i, dx, dy, distance_squared| {
+                    assert!((dx * dx + dy * dy - distance_squared).abs() < 1e-2);
+                    found.push(i);
+                },
+            );
+
+            let expected: Vec<usize> = (0..points.len())
+                .filter(|&i| {
+                    let dx = wrapped_delta(points[i].0 - x, width);
+                    let dy = wrapped_delta(points[i].1 - y, height);
+                    dx * dx + dy * dy <= radius * radius
+                })
+                .collect();
+            assert_eq!(sorted(found), expected, "query {} radius {}", query, radius);
+        }
+    }
+
+    #[test]
+    fn test_nearby_queries_visit_each_bucket_once() {
+        let mut grid = SpatialGrid::new(300.0, 300.0, 100.0);
+        for i in 0..9 {
+            grid.insert(
+                (i % 3) as f32 * 100.0 + 50.0,
+                (i / 3) as f32 * 100.0 + 50.0,
+                i,
+            );
+        }
+        assert_eq!(
+            sorted(grid.query_nearby(10.0, 10.0, 500.0)),
+            (0..9).collect::<Vec<_>>()
+        );
+        assert_eq!(grid.count_nearby(10.0, 10.0, 500.0), 9);
+
+        // An index inserted twice is still reported once
+        grid.insert(60.0, 60.0, 0);
+        assert_eq!(
+            grid.query_nearby(10.0, 10.0, 500.0),
+            (0..9).collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,10 +1,516 @@
+use crate::events::SimEvent;
+use crate::input::InputCaptured;
+use crate::vision::SensorReadings;
 use macroquad::prelude::*;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+const EXPORT_BUTTON_LABEL: &str = "Export Genome";
+// Leaderboard rows at the bottom of the best-cell box
+const LEADERBOARD_FONT_SIZE: u16 = 18;
+const LEADERBOARD_ROW_HEIGHT: f32 = 22.0;
+const LEADERBOARD_GAP: f32 = 8.0;
+
+// Event log: entries kept in memory (dumped with Ctrl+L), entries drawn on screen,
+// seconds an entry stays visible, and the fade-out at the end of that time
+const EVENT_LOG_HISTORY: usize = 500;
+const EVENT_LOG_VISIBLE_ENTRIES: usize = 8;
+const EVENT_VISIBLE_SECS: f64 = 6.0;
+const EVENT_FADE_SECS: f64 = 2.0;
+
+// Event panel (L): events kept for scrolling back, and rows shown at once
+const EVENT_PANEL_HISTORY: usize = 200;
+const EVENT_PANEL_ROWS: usize = 20;
+const EVENT_PANEL_WIDTH: f32 = 460.0;
+
+// Shared by world.rs and storage.rs (storage has no access to the World)
+static EVENT_LOG: Mutex<EventLog> = Mutex::new(EventLog::new());
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventLevel {
+    Info,
+    Warn,
+}
+
+pub struct LogEntry {
+    pub time: f64, // Seconds since the first logged event
+    pub level: EventLevel,
+    pub message: String,
+}
+
+/// Recent notable events (saves, resets, cap changes...), newest last
+pub struct EventLog {
+    entries: VecDeque<LogEntry>,
+    start_time: Option<f64>,
+}
+
+impl EventLog {
+    pub const fn new() -> Self {
+        EventLog {
+            entries: VecDeque::new(),
+            start_time: None,
+        }
+    }
+
+    // `now` is an absolute clock in seconds; entries store it relative to the first event
+    pub fn log(&mut self, level: EventLevel, message: String, now: f64) {
+        let start = *self.start_time.get_or_insert(now);
+        self.entries.push_back(LogEntry {
+            time: now - start,
+            level,
+            message,
+        });
+        while self.entries.len() > EVENT_LOG_HISTORY {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn history(&self) -> &VecDeque<LogEntry> {
+        &self.entries
+    }
+
+    // Entries still on screen at `now` with their opacity, oldest first
+    pub fn visible(&self, now: f64) -> Vec<(&LogEntry, f32)> {
+        let elapsed = now - self.start_time.unwrap_or(now);
+        let mut visible: Vec<(&LogEntry, f32)> = self
+            .entries
+            .iter()
+            .rev()
+            .take(EVENT_LOG_VISIBLE_ENTRIES)
+            .filter_map(|entry| {
+                let remaining = EVENT_VISIBLE_SECS - (elapsed - entry.time);
+                (remaining > 0.0).then(|| (entry, (remaining / EVENT_FADE_SECS).min(1.0) as f32))
+            })
+            .collect();
+        visible.reverse();
+        visible
+    }
+}
+
+impl LogEntry {
+    // "[mm:ss] message"
+    fn formatted(&self) -> String {
+        let secs = self.time as u64;
+        format!("[{:02}:{:02}] {}", secs / 60, secs % 60, self.message)
+    }
+}
+
+// "HH:MM:SS" for a duration in seconds (hours keep growing past 99)
+pub fn format_hms(seconds: f64) -> String {
+    let secs = seconds.max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+// Wall clock in seconds (no graphics context needed, unlike get_time)
+fn event_clock() -> f64 {
+    macroquad::miniquad::date::now()
+}
+
+/// Print an event to stdout and add it to the on-screen event log
+pub fn log_event(level: EventLevel, message: String) {
+    println!("{}", message);
+    if let Ok(mut log) = EVENT_LOG.lock() {
+        log.log(level, message, event_clock());
+    }
+}
+
+// println!-style wrappers around log_event
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        $crate::stats::log_event($crate::stats::EventLevel::Info, format!($($arg)*))
+    };
+}
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        $crate::stats::log_event($crate::stats::EventLevel::Warn, format!($($arg)*))
+    };
+}
+pub(crate) use {log_info, log_warn};
+
+/// Print the whole in-memory event history to stdout
+pub fn dump_event_log() {
+    let Ok(log) = EVENT_LOG.lock() else {
+        return;
+    };
+    println!("===== Event log ({} entries) =====", log.history().len());
+    for entry in log.history() {
+        let level = match entry.level {
+            EventLevel::Info => "INFO",
+            EventLevel::Warn => "WARN",
+        };
+        println!("{} {}", level, entry.formatted());
+    }
+}
+
+/// Draw the last few events, bottom-aligned, with their right edge at `right_edge`
+/// Warnings are orange; entries fade out EVENT_VISIBLE_SECS after being logged.
+pub fn render_event_log(font: Option<&Font>, right_edge: f32) {
+    let Ok(log) = EVENT_LOG.lock() else {
+        return;
+    };
+    let font_size = 18;
+    let line_height = 24.0;
+    let padding = 20.0;
+
+    let visible = log.visible(event_clock());
+    let mut y = screen_height() - padding - line_height * visible.len() as f32;
+    for (entry, alpha) in visible {
+        let text = entry.formatted();
+        let width = measure_text(&text, font, font_size, 1.0).width;
+        let x = right_edge - padding - width;
+        let color = match entry.level {
+            EventLevel::Info => Color::new(1.0, 1.0, 1.0, alpha),
+            EventLevel::Warn => Color::new(1.0, 0.6, 0.1, alpha),
+        };
+        draw_rectangle(
+            x - 6.0,
+            y,
+            width + 12.0,
+            line_height - 2.0,
+            Color::new(0.0, 0.0, 0.0, 0.6 * alpha),
+        );
+        draw_text_ex(
+            &text,
+            x,
+            y + line_height - 8.0,
+            TextParams {
+                font,
+                font_size,
+                color,
+                ..Default::default()
+            },
+        );
+        y += line_height;
+    }
+}
+
+/// Scrollable list of recent simulation events (see events.rs), newest at the bottom
+pub struct EventPanel {
+    entries: VecDeque<(usize, SimEvent)>, // (tick, event)
+    scroll: usize,                        // Rows scrolled back from the newest
+}
+
+impl EventPanel {
+    pub fn new() -> Self {
+        EventPanel {
+            entries: VecDeque::new(),
+            scroll: 0,
+        }
+    }
+
+    pub fn record(&mut self, tick: usize, event: SimEvent) {
+        self.entries.push_back((tick, event));
+        while self.entries.len() > EVENT_PANEL_HISTORY {
+            self.entries.pop_front();
+        }
+        // Scrolled back: keep the same rows in view as new events arrive
+        if self.scroll > 0 {
+            self.scroll = (self.scroll + 1).min(self.max_scroll());
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.entries.len().saturating_sub(EVENT_PANEL_ROWS)
+    }
+
+    // Positive rows scroll back to older events, negative toward the newest
+    pub fn scroll_by(&mut self, rows: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(rows)
+            .min(self.max_scroll());
+    }
+
+    // Rows in view, oldest first
+    fn visible(&self) -> impl Iterator<Item = &(usize, SimEvent)> {
+        let end = self.entries.len() - self.scroll;
+        let start = end.saturating_sub(EVENT_PANEL_ROWS);
+        self.entries.range(start..end)
+    }
+
+    /// Draw the panel centered at the top of the screen
+    pub fn render(&self, font: Option<&Font>) {
+        let font_size = 16;
+        let line_height = 20.0;
+        let padding = 10.0;
+        let x = (screen_width() - EVENT_PANEL_WIDTH) / 2.0;
+        let y = 20.0;
+        let height = padding * 2.0 + line_height * (EVENT_PANEL_ROWS + 1) as f32;
+        draw_rectangle(
+            x,
+            y,
+            EVENT_PANEL_WIDTH,
+            height,
+            Color::new(0.0, 0.0, 0.0, 0.75),
+        );
+
+        let title = if self.scroll > 0 {
+            format!("Events ({} newer) [PgUp/PgDn]", self.scroll)
+        } else {
+            "Events [PgUp/PgDn]".to_string()
+        };
+        let mut lines = vec![(title, WHITE)];
+        lines.extend(self.visible().map(|(tick, event)| {
+            let color = if event.is_important() {
+                Color::new(1.0, 0.85, 0.3, 1.0)
+            } else {
+                LIGHTGRAY
+            };
+            (format!("[t{}] {}", tick, event.message()), color)
+        }));
+        for (row, (text, color)) in lines.iter().enumerate() {
+            draw_text_ex(
+                text,
+                x + padding,
+                y + padding + line_height * (row + 1) as f32 - 5.0,
+                TextParams {
+                    font,
+                    font_size,
+                    color: *color,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+/// Everything the detail panel shows about the selected cell
+pub struct CellDetail {
+    pub id: u64,
+    pub color: Color,
+    pub is_alive: bool,
+    pub energy: f32,
+    pub mass: f32, // Max energy capacity
+    pub age: f32,
+    pub age_energy_drain: f32, // Extra energy lost per tick to old age
+    pub generation: usize,
+    pub children_count: usize,
+    pub energy_donated: f32, // Given away to kin through the donate action
+    pub speed: f32,
+    pub turn_rate: f32,
+    pub energy_chunk_size: f32,
+    pub species_multiplier: f32,
+    pub sensors: SensorReadings,
+    pub last_action: Option<&'static str>,
+}
+
+impl CellDetail {
+    // Panel text, title first. Collapsed panels show only the title.
+    fn lines(&self, expanded: bool) -> Vec<String> {
+        let status = if self.is_alive { "" } else { " (DEAD)" };
+        if !expanded {
+            return vec![format!("Cell #{}{} [X: details]", self.id, status)];
+        }
+        let mut lines = vec![
+            format!("Cell #{}{} [X: collapse]", self.id, status),
+            format!("Energy: {:.1} / {:.0} mass", self.energy, self.mass),
+            format!(
+                "Age: {:.1} (age drain {:.2}/tick)",
+                self.age, self.age_energy_drain
+            ),
+            format!(
+                "Generation: {}  Children: {}  Donated: {:.0}",
+                self.generation, self.children_count, self.energy_donated
+            ),
+            format!("Speed: {:.2}  Turn rate: {:.3}", self.speed, self.turn_rate),
+            format!(
+                "Chunk: {:.1}  Multiplier: {:.2}",
+                self.energy_chunk_size, self.species_multiplier
+            ),
+            "Sensors:".to_string(),
+        ];
+        for slot in 0..crate::vision::SENSOR_COUNT {
+            lines.push(match self.sensors.get(slot) {
+                Some(reading) => format!(
+                    "  {}: {} {:+.0}° at {:.0}",
+                    slot + 1,
+                    if reading.is_alive { "alive" } else { "dead" },
+                    reading.angle.to_degrees(),
+                    reading.distance
+                ),
+                None => format!("  {}: -", slot + 1),
+            });
+        }
+        lines.push(format!(
+            "Last action: {}",
+            self.last_action.unwrap_or("none")
+        ));
+        lines
+    }
+}
+
+/// Panel about the selected cell at the left edge, starting at `top`. It is not
+/// drawn when it would reach `right_limit` (the best-cell box's left edge).
+pub fn render_detail(
+    detail: &CellDetail,
+    font: Option<&Font>,
+    expanded: bool,
+    top: f32,
+    right_limit: Option<f32>,
+) {
+    let font_size = 18;
+    let line_height = 22.0;
+    let padding = 20.0;
+    let bg_padding = 8.0;
+
+    let lines = detail.lines(expanded);
+    let text_width = lines
+        .iter()
+        .map(|line| measure_text(line, font, font_size, 1.0).width)
+        .fold(0.0_f32, f32::max);
+    let width = text_width + bg_padding * 2.0;
+    if right_limit.is_some_and(|limit| padding + width > limit - padding) {
+        return;
+    }
+
+    let height = line_height * lines.len() as f32 + bg_padding * 2.0;
+    draw_rectangle(padding, top, width, height, Color::new(0.0, 0.0, 0.0, 0.8));
+    draw_rectangle_lines(padding, top, width, height, 2.0, detail.color);
+    for (i, line) in lines.iter().enumerate() {
+        draw_text_ex(
+            line,
+            padding + bg_padding,
+            top + bg_padding + line_height * (i as f32 + 0.75),
+            TextParams {
+                font,
+                font_size,
+                color: WHITE,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// One side of the comparison panel
+pub struct ComparedCell {
+    pub id: u64,
+    pub color: Color,
+    pub is_alive: bool,
+    pub score: f32, // Under the configured fitness function
+    pub energy: f32,
+    pub age: f32,
+    pub generation: usize,
+    pub children_count: usize,
+    pub mutation_rate: f32, // Brain mutation rate its children get, relative to the base
+    pub speed: f32,
+    pub radius: f32,
+}
+
+/// Two selected cells side by side, shown instead of the best-cell box
+pub struct CellComparison {
+    pub cells: [ComparedCell; 2],
+    pub weight_diff: f32, // Mean absolute brain weight difference (infinite across tiers)
+    pub similarity: f32,  // Cosine similarity of the brain weights
+}
+
+impl CellComparison {
+    // Index of the cell with the higher fitness (the first one on ties)
+    pub fn fitter(&self) -> usize {
+        usize::from(self.cells[1].score > self.cells[0].score)
+    }
+
+    // Rows of (label, first cell, second cell), the title row first
+    fn rows(&self) -> Vec<(String, String, String)> {
+        let [a, b] = &self.cells;
+        let title = |cell: &ComparedCell| {
+            format!("#{}{}", cell.id, if cell.is_alive { "" } else { " (DEAD)" })
+        };
+        let row =
+            |label: &str, f: &dyn Fn(&ComparedCell) -> String| (label.to_string(), f(a), f(b));
+        let brain = if self.weight_diff.is_finite() {
+            format!(
+                "diff {:.3}  similarity {:.2}",
+                self.weight_diff, self.similarity
+            )
+        } else {
+            "different tiers".to_string()
+        };
+        vec![
+            ("Compare".to_string(), title(a), title(b)),
+            row("Score", &|c| format!("{:.1}", c.score)),
+            row("Energy", &|c| format!("{:.1}", c.energy)),
+            row("Age", &|c| format!("{:.1}", c.age)),
+            row("Generation", &|c| c.generation.to_string()),
+            row("Children", &|c| c.children_count.to_string()),
+            row("Mutation", &|c| format!("x{:.2}", c.mutation_rate)),
+            row("Speed", &|c| format!("{:.2}", c.speed)),
+            row("Radius", &|c| format!("{:.1}", c.radius)),
+            ("Brain".to_string(), brain, String::new()),
+        ]
+    }
+}
+
+// Comparison panel in the bottom-right corner (where the best-cell box goes):
+// a label column and one column per cell, the fitter cell's header in gold
+pub fn render_comparison(comparison: &CellComparison, font: Option<&Font>) {
+    let font_size = 20;
+    let line_height = 26.0;
+    let padding = 30.0;
+    let bg_padding = 10.0;
+    let column_gap = 24.0;
+
+    let rows = comparison.rows();
+    let column_width = |pick: fn(&(String, String, String)) -> &String| {
+        rows.iter()
+            .take(rows.len() - 1)
+            .map(|row| measure_text(pick(row), font, font_size, 1.0).width)
+            .fold(0.0_f32, f32::max)
+    };
+    let widths = [
+        column_width(|row| &row.0),
+        column_width(|row| &row.1),
+        column_width(|row| &row.2),
+    ];
+    let brain_row = rows.last().unwrap();
+    let brain_width = measure_text(&brain_row.1, font, font_size, 1.0).width;
+    let content_width =
+        (widths.iter().sum::<f32>() + column_gap * 2.0).max(widths[0] + column_gap + brain_width);
+
+    let width = content_width + bg_padding * 2.0;
+    let height = line_height * rows.len() as f32 + bg_padding * 2.0;
+    let x = screen_width() - width - padding;
+    let y = screen_height() - height - padding;
+    let fitter = comparison.fitter();
+
+    draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.8));
+    draw_rectangle_lines(x, y, width, height, 2.0, comparison.cells[fitter].color);
+
+    let columns = [
+        x + bg_padding,
+        x + bg_padding + widths[0] + column_gap,
+        x + bg_padding + widths[0] + widths[1] + column_gap * 2.0,
+    ];
+    let gold = Color::new(1.0, 0.84, 0.0, 1.0);
+    for (i, (label, first, second)) in rows.iter().enumerate() {
+        let text_y = y + bg_padding + line_height * (i as f32 + 0.75);
+        for (column, text) in [label, first, second].into_iter().enumerate() {
+            let color = match column {
+                0 => LIGHTGRAY,
+                _ if i == 0 && column - 1 == fitter => gold,
+                _ if i == 0 => comparison.cells[column - 1].color,
+                _ => WHITE,
+            };
+            draw_text_ex(
+                text,
+                columns[column],
+                text_y,
+                TextParams {
+                    font,
+                    font_size,
+                    color,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct BestCellStats {
-    pub energy_from_cells: f32,
+    pub score: f32, // Under the configured fitness function
     pub current_energy: f32,
     pub children_count: usize,
+    pub lateral_transfers: u32, // Weight blocks received by lateral gene transfer
     pub generation: usize,
     pub color: Color,
     pub age: f32,
@@ -15,11 +521,51 @@ pub struct BestCellStats {
     pub brain_operations: usize,
     pub cell_index: usize,
     pub prev_best_score: f32, // Previous best score for this tier
+    pub picked: bool,         // Clicked by the user rather than the best cell
+    pub tracking: Option<(usize, usize)>, // (rank, of) when picked by cycling the top cells
+}
+
+/// One row of the leaderboard: an alive cell ranked by fitness (rank 1 = fittest)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub id: u64, // Clicking the row selects this cell
+    pub color: Color,
+    pub fitness: f32,
+    pub generation: usize,
+    pub age: f32,
+}
+
+impl LeaderboardEntry {
+    fn line(&self) -> String {
+        format!(
+            "{}. #{}  {:.1}  gen {}  age {:.1}",
+            self.rank, self.id, self.fitness, self.generation, self.age
+        )
+    }
+}
+
+impl BestCellStats {
+    // Box title with status indicator
+    fn title(&self) -> String {
+        let title = match (self.picked, self.tracking) {
+            (false, _) => "Best Cell:".to_string(),
+            (true, Some((rank, of))) => format!("Tracking #{} of {}", rank, of),
+            (true, None) => "Selected Cell:".to_string(),
+        };
+        if self.is_alive {
+            title
+        } else {
+            format!("{} (DEAD)", title)
+        }
+    }
 }
 
 pub struct Stats {
     best_cell: Option<BestCellStats>,
     selected: bool,
+    leaderboard: Vec<LeaderboardEntry>,
+    pub event_panel: EventPanel,
 }
 
 impl Stats {
@@ -27,6 +573,8 @@ impl Stats {
         Stats {
             best_cell: None,
             selected: true, // Start selected by default
+            leaderboard: Vec::new(),
+            event_panel: EventPanel::new(),
         }
     }
 
@@ -40,18 +588,50 @@ impl Stats {
         self.best_cell = Some(stats);
     }
 
-    // Calculate comprehensive score based on children, energy from cells, and age
-    pub fn calculate_score(children: usize, energy_from_cells: f32, age: f32) -> f32 {
-        // Children count: 100 points per child (primary metric)
-        let children_score = children as f32 * 100.0;
+    // Replace the leaderboard rows shown under the best cell (fittest first)
+    pub fn set_leaderboard(&mut self, entries: Vec<LeaderboardEntry>) {
+        self.leaderboard = entries;
+    }
 
-        // Energy from cells: 1 point per energy (equally important as children)
-        let energy_score = energy_from_cells;
+    // Extra box height taken by the leaderboard rows
+    fn leaderboard_height(&self) -> f32 {
+        if self.leaderboard.is_empty() {
+            0.0
+        } else {
+            LEADERBOARD_GAP + LEADERBOARD_ROW_HEIGHT * self.leaderboard.len() as f32
+        }
+    }
 
-        // Age: 10 points per age unit (secondary metric - older cells have survived longer)
-        let age_score = age * 10.0;
+    // Top of the first leaderboard row for a box drawn at `bg_y` (see render)
+    fn leaderboard_top(bg_y: f32) -> f32 {
+        let line_height = 30.0;
+        let bg_padding = 10.0;
+        bg_y + bg_padding + line_height * 9.0 + LEADERBOARD_GAP
+    }
 
-        children_score + energy_score + age_score
+    // Id of the cell in the leaderboard row under the mouse, if any
+    pub fn leaderboard_row_at(
+        &self,
+        mouse_x: f32,
+        mouse_y: f32,
+        font: Option<&Font>,
+    ) -> Option<u64> {
+        let (x, _, width, _) = self.get_bounds(font)?;
+        if mouse_x < x || mouse_x > x + width {
+            return None;
+        }
+        // Same box position as render
+        let line_height = 30.0;
+        let bg_padding = 10.0;
+        let padding = 30.0;
+        let bg_height = line_height * 9.0 + bg_padding + self.leaderboard_height();
+        let bg_y = screen_height() - bg_height - padding;
+        let offset = mouse_y - Self::leaderboard_top(bg_y);
+        if offset < 0.0 {
+            return None;
+        }
+        let row = (offset / LEADERBOARD_ROW_HEIGHT) as usize;
+        self.leaderboard.get(row).map(|entry| entry.id)
     }
 
     // Get the bounds of the stats box for click detection
@@ -67,12 +647,13 @@ impl Stats {
         let title = "Best Cell:";
         let line1 = "Index: 99999"; // Max width estimate
         let line2 = "Energy: 999999.9";
-        let line3 = "Children: 99999";
+        let line3 = "Children: 99999  Transfers: 9999";
         let line4 = "Generation: 99999";
         let line5 = "Brain: m3 (99999 operations)";
         let line6 = "Age: 999.9";
         let line7 = "Score: 999999.9 + 999999.9"; // Account for diff display
         let line8 = "Pos: (9999.9, 9999.9)";
+        let leaderboard_row = "5. #999999  99999.9  gen 9999  age 999.9";
 
         let max_width = [
             measure_text(title, font, font_size as u16, 1.0).width,
@@ -84,6 +665,7 @@ impl Stats {
             measure_text(line6, font, font_size as u16, 1.0).width,
             measure_text(line7, font, font_size as u16, 1.0).width,
             measure_text(line8, font, font_size as u16, 1.0).width,
+            measure_text(leaderboard_row, font, LEADERBOARD_FONT_SIZE, 1.0).width,
         ]
         .iter()
         .cloned()
@@ -92,12 +674,39 @@ impl Stats {
         let bg_padding = 10.0;
         let bg_x = screen_w - max_width - padding - bg_padding * 2.0;
         let bg_width = max_width + bg_padding * 2.0;
-        let bg_height = line_height * 9.0 + bg_padding;
+        let bg_height = line_height * 9.0 + bg_padding + self.leaderboard_height();
         let bg_y = screen_h - bg_height - padding;
 
         Some((bg_x, bg_y, bg_width, bg_height))
     }
 
+    // Bounds of the "Export Genome" button, drawn just above the stats box
+    fn export_button_bounds(&self, font: Option<&Font>) -> Option<(f32, f32, f32, f32)> {
+        let (box_x, box_y, _, _) = self.get_bounds(font)?;
+        let width = measure_text(EXPORT_BUTTON_LABEL, font, 20, 1.0).width + 20.0;
+        let height = 32.0;
+        Some((box_x, box_y - height - 8.0, width, height))
+    }
+
+    // Check if mouse position is over the "Export Genome" button
+    pub fn is_mouse_over_export_button(
+        &self,
+        mouse_x: f32,
+        mouse_y: f32,
+        font: Option<&Font>,
+    ) -> bool {
+        if let Some((x, y, w, h)) = self.export_button_bounds(font) {
+            mouse_x >= x && mouse_x <= x + w && mouse_y >= y && mouse_y <= y + h
+        } else {
+            false
+        }
+    }
+
+    // Left edge of the stats box (and its export button), if shown
+    pub fn left_edge(&self, font: Option<&Font>) -> Option<f32> {
+        self.get_bounds(font).map(|(x, _, _, _)| x)
+    }
+
     // Check if mouse position is over the stats box
     pub fn is_mouse_over(&self, mouse_x: f32, mouse_y: f32, font: Option<&Font>) -> bool {
         if let Some((x, y, w, h)) = self.get_bounds(font) {
@@ -107,11 +716,24 @@ impl Stats {
         }
     }
 
+    // The stats box and its export button take the pointer
+    pub fn capture(&self, mouse_x: f32, mouse_y: f32, font: Option<&Font>) -> InputCaptured {
+        InputCaptured::from_hit(
+            self.is_mouse_over(mouse_x, mouse_y, font)
+                || self.is_mouse_over_export_button(mouse_x, mouse_y, font),
+        )
+    }
+
     // Toggle selection state
     pub fn toggle_selection(&mut self) {
         self.selected = !self.selected;
     }
 
+    // Follow (or stop following) the cell in the box with the camera
+    pub fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
+    }
+
     // Check if stats box is selected
     pub fn is_selected(&self) -> bool {
         self.selected
@@ -141,14 +763,16 @@ impl Stats {
             let line_height = 30.0;
             let padding = 30.0;
 
-            let score =
-                Self::calculate_score(best.children_count, best.energy_from_cells, best.age);
+            let score = best.score;
 
             // Calculate text widths (approximate)
-            let title = "Best Cell:";
+            let title = best.title();
             let line1 = format!("Index: {}", best.cell_index);
             let line2 = format!("Energy: {:.1}", best.current_energy);
-            let line3 = format!("Children: {}", best.children_count);
+            let line3 = format!(
+                "Children: {}  Transfers: {}",
+                best.children_count, best.lateral_transfers
+            );
             let line4 = format!("Generation: {}", best.generation);
             let line5 = format!(
                 "Brain: m{} ({} operations)",
@@ -171,10 +795,16 @@ impl Stats {
 
             let line8 = format!("Pos: ({:.1}, {:.1})", best.x, best.y);
 
+            let leaderboard_lines: Vec<String> = self
+                .leaderboard
+                .iter()
+                .map(LeaderboardEntry::line)
+                .collect();
+
             // Find the longest line for background width
             let line7_full = format!("{}{}", line7, line7_diff);
             let max_width = [
-                measure_text(title, font, font_size as u16, 1.0).width,
+                measure_text(&title, font, font_size as u16, 1.0).width,
                 measure_text(&line1, font, font_size as u16, 1.0).width,
                 measure_text(&line2, font, font_size as u16, 1.0).width,
                 measure_text(&line3, font, font_size as u16, 1.0).width,
@@ -184,15 +814,19 @@ impl Stats {
                 measure_text(&line7_full, font, font_size as u16, 1.0).width,
                 measure_text(&line8, font, font_size as u16, 1.0).width,
             ]
-            .iter()
-            .cloned()
+            .into_iter()
+            .chain(
+                leaderboard_lines
+                    .iter()
+                    .map(|line| measure_text(line, font, LEADERBOARD_FONT_SIZE, 1.0).width + 14.0),
+            )
             .fold(0.0_f32, f32::max);
 
             // Draw semi-transparent background
             let bg_padding = 10.0;
             let bg_x = screen_w - max_width - padding - bg_padding * 2.0;
             let bg_width = max_width + bg_padding * 2.0;
-            let bg_height = line_height * 9.0 + bg_padding;
+            let bg_height = line_height * 9.0 + bg_padding + self.leaderboard_height();
             let bg_y = screen_h - bg_height - padding;
 
             draw_rectangle(
@@ -218,11 +852,6 @@ impl Stats {
 
             // Draw title with status indicator
             let x = screen_w - max_width - padding;
-            let title_with_status = if best.is_alive {
-                "Best Cell:"
-            } else {
-                "Best Cell: (DEAD)"
-            };
 
             let text_params = TextParams {
                 font,
@@ -232,7 +861,7 @@ impl Stats {
             };
 
             draw_text_ex(
-                title_with_status,
+                &title,
                 x,
                 bg_y + bg_padding + font_size,
                 text_params.clone(),
@@ -301,6 +930,59 @@ impl Stats {
                 bg_y + bg_padding + font_size + line_height * 8.0,
                 text_params,
             );
+
+            // Leaderboard: a color swatch and one line per ranked cell, hovered row lit
+            let (mouse_x, mouse_y) = mouse_position();
+            let hovered = self.leaderboard_row_at(mouse_x, mouse_y, font);
+            let top = Self::leaderboard_top(bg_y);
+            for (i, (entry, line)) in self.leaderboard.iter().zip(&leaderboard_lines).enumerate() {
+                let row_y = top + LEADERBOARD_ROW_HEIGHT * i as f32;
+                if hovered == Some(entry.id) {
+                    draw_rectangle(
+                        bg_x,
+                        row_y,
+                        bg_width + bg_padding,
+                        LEADERBOARD_ROW_HEIGHT,
+                        Color::new(1.0, 1.0, 1.0, 0.1),
+                    );
+                }
+                draw_rectangle(x, row_y + 6.0, 8.0, 8.0, entry.color);
+                draw_text_ex(
+                    line,
+                    x + 14.0,
+                    row_y + LEADERBOARD_ROW_HEIGHT - 6.0,
+                    TextParams {
+                        font,
+                        font_size: LEADERBOARD_FONT_SIZE,
+                        color: WHITE,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
+        // "Export Genome" button above the stats box
+        if let Some((x, y, w, h)) = self.export_button_bounds(font) {
+            let (mouse_x, mouse_y) = mouse_position();
+            let hovered = self.is_mouse_over_export_button(mouse_x, mouse_y, font);
+            let background = if hovered {
+                Color::new(0.2, 0.2, 0.2, 0.9)
+            } else {
+                Color::new(0.0, 0.0, 0.0, 0.8)
+            };
+            draw_rectangle(x, y, w, h, background);
+            draw_rectangle_lines(x, y, w, h, 1.0, GRAY);
+            draw_text_ex(
+                EXPORT_BUTTON_LABEL,
+                x + 10.0,
+                y + 22.0,
+                TextParams {
+                    font,
+                    font_size: 20,
+                    color: WHITE,
+                    ..Default::default()
+                },
+            );
         }
     }
 }
@@ -310,39 +992,190 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_calculate_score_zeros() {
-        let score = Stats::calculate_score(0, 0.0, 0.0);
-        assert_eq!(score, 0.0);
+    fn test_format_hms() {
+        assert_eq!(format_hms(0.0), "00:00:00");
+        assert_eq!(format_hms(59.9), "00:00:59");
+        assert_eq!(format_hms(3723.0), "01:02:03");
+        assert_eq!(format_hms(360_000.0), "100:00:00");
     }
 
     #[test]
-    fn test_calculate_score_children() {
-        let score = Stats::calculate_score(2, 0.0, 0.0);
-        assert_eq!(score, 200.0);
+    fn test_detail_panel_collapses_to_its_title() {
+        let detail = CellDetail {
+            id: 7,
+            color: WHITE,
+            is_alive: false,
+            energy: 12.0,
+            mass: 200.0,
+            age: 40.0,
+            age_energy_drain: 0.05,
+            generation: 3,
+            children_count: 1,
+            energy_donated: 0.0,
+            speed: 0.5,
+            turn_rate: 0.1,
+            energy_chunk_size: 10.0,
+            species_multiplier: 1.0,
+            sensors: SensorReadings::default(),
+            last_action: None,
+        };
+        assert_eq!(detail.lines(false), vec!["Cell #7 (DEAD) [X: details]"]);
+
+        // Title, 6 stat lines, one line per sensor slot, last action
+        let lines = detail.lines(true);
+        assert_eq!(lines.len(), 7 + crate::vision::SENSOR_COUNT + 1);
+        assert_eq!(lines[7], "  1: -");
+        assert_eq!(lines.last().unwrap(), "Last action: none");
     }
 
     #[test]
-    fn test_calculate_score_energy() {
-        let score = Stats::calculate_score(0, 50.5, 0.0);
-        assert_eq!(score, 50.5);
+    fn test_comparison_rows_and_fitter() {
+        let cell = |id: u64, score: f32| ComparedCell {
+            id,
+            color: WHITE,
+            is_alive: id != 2,
+            score,
+            energy: 50.0,
+            age: 10.0,
+            generation: 4,
+            children_count: 2,
+            mutation_rate: 0.75,
+            speed: 1.5,
+            radius: 8.0,
+        };
+        let mut comparison = CellComparison {
+            cells: [cell(1, 3.0), cell(2, 7.5)],
+            weight_diff: 0.125,
+            similarity: 0.9,
+        };
+        assert_eq!(comparison.fitter(), 1);
+
+        let rows = comparison.rows();
+        assert_eq!(rows.len(), 10);
+        assert_eq!(rows[0].1, "#1");
+        assert_eq!(rows[0].2, "#2 (DEAD)");
+        assert_eq!(
+            rows[1],
+            ("Score".to_string(), "3.0".to_string(), "7.5".to_string())
+        );
+        assert_eq!(rows[9].1, "diff 0.125  similarity 0.90");
+
+        // Ties go to the first cell; brains of different tiers can't be compared
+        comparison.cells[1].score = 3.0;
+        comparison.weight_diff = f32::INFINITY;
+        assert_eq!(comparison.fitter(), 0);
+        assert_eq!(comparison.rows()[9].1, "different tiers");
     }
 
     #[test]
-    fn test_calculate_score_age() {
-        let score = Stats::calculate_score(0, 0.0, 10.0);
-        assert_eq!(score, 100.0);
+    fn test_best_cell_title_shows_tracking_rank() {
+        let mut best = BestCellStats {
+            score: 10.0,
+            current_energy: 50.0,
+            children_count: 1,
+            lateral_transfers: 0,
+            generation: 2,
+            color: WHITE,
+            age: 5.0,
+            x: 0.0,
+            y: 0.0,
+            is_alive: true,
+            brain_tier: 0,
+            brain_operations: 100,
+            cell_index: 0,
+            prev_best_score: 0.0,
+            picked: false,
+            tracking: None,
+        };
+        assert_eq!(best.title(), "Best Cell:");
+        best.picked = true;
+        assert_eq!(best.title(), "Selected Cell:");
+        best.tracking = Some((3, 10));
+        assert_eq!(best.title(), "Tracking #3 of 10");
+        best.is_alive = false;
+        assert_eq!(best.title(), "Tracking #3 of 10 (DEAD)");
     }
 
     #[test]
-    fn test_calculate_score_combination() {
-        let score = Stats::calculate_score(1, 20.0, 5.0);
-        assert_eq!(score, 170.0); // 100 + 20 + 50
+    fn test_leaderboard_rows_grow_the_stats_box() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.leaderboard_height(), 0.0);
+
+        let entries: Vec<LeaderboardEntry> = (1..=5)
+            .map(|rank| LeaderboardEntry {
+                rank,
+                id: 100 + rank as u64,
+                color: WHITE,
+                fitness: 60.0 - rank as f32 * 10.0,
+                generation: 3,
+                age: 12.25,
+            })
+            .collect();
+        assert_eq!(entries[0].line(), "1. #101  50.0  gen 3  age 12.2");
+        stats.set_leaderboard(entries);
+        assert_eq!(
+            stats.leaderboard_height(),
+            LEADERBOARD_GAP + LEADERBOARD_ROW_HEIGHT * 5.0
+        );
     }
 
     #[test]
-    fn test_calculate_score_large_values() {
-        let score = Stats::calculate_score(1000, 10000.0, 1000.0);
-        // 1000 * 100 + 10000 + 1000 * 10 = 100000 + 10000 + 10000 = 120000
-        assert_eq!(score, 120000.0);
+    fn test_event_log_caps_history() {
+        let mut log = EventLog::new();
+        for i in 0..EVENT_LOG_HISTORY + 10 {
+            log.log(EventLevel::Info, format!("event {}", i), 100.0 + i as f64);
+        }
+        assert_eq!(log.history().len(), EVENT_LOG_HISTORY);
+        assert_eq!(log.history()[0].message, "event 10");
+        assert_eq!(log.history()[0].time, 10.0);
+    }
+
+    #[test]
+    fn test_event_panel_scrolls_back_and_holds_its_place() {
+        let mut panel = EventPanel::new();
+        for generation in 0..30 {
+            panel.record(generation, SimEvent::GenerationMilestone { generation });
+        }
+        let ticks = |panel: &EventPanel| panel.visible().map(|(tick, _)| *tick).collect::<Vec<_>>();
+        assert_eq!(ticks(&panel), (10..30).collect::<Vec<_>>());
+
+        // Scrolling is clamped to the oldest page
+        panel.scroll_by(25);
+        assert_eq!(ticks(&panel), (0..20).collect::<Vec<_>>());
+
+        // New events don't move a scrolled-back view
+        panel.scroll_by(-5);
+        panel.record(30, SimEvent::ExtinctionNear { alive_count: 3 });
+        assert_eq!(ticks(&panel), (5..25).collect::<Vec<_>>());
+
+        panel.scroll_by(-100);
+        assert_eq!(ticks(&panel).last(), Some(&30));
+    }
+
+    #[test]
+    fn test_event_log_visible_entries_fade_out() {
+        let mut log = EventLog::new();
+        log.log(EventLevel::Info, "old".to_string(), 1000.0);
+        log.log(EventLevel::Warn, "new".to_string(), 1004.0);
+
+        // Old entry is fading (1s left), new one fully opaque
+        let visible = log.visible(1005.0);
+        assert_eq!(visible.len(), 2);
+        assert_eq!(visible[0].0.message, "old");
+        assert!((visible[0].1 - 0.5).abs() < 1e-5);
+        assert_eq!(visible[1].1, 1.0);
+
+        // Old entry gone once its time is up
+        let visible = log.visible(1007.0);
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].0.level, EventLevel::Warn);
+
+        // Only the newest entries are drawn
+        for i in 0..20 {
+            log.log(EventLevel::Info, format!("burst {}", i), 1008.0);
+        }
+        let visible = log.visible(1008.0);
+        assert_eq!(visible.len(), EVENT_LOG_VISIBLE_ENTRIES);
+        assert_eq!(visible.last().unwrap().0.message, "burst 19");
     }
 }
@@ -1,18 +1,51 @@
+use crate::cell::{ACTION_COUNT, Cell};
+use crate::config::SimulationConfig;
 use crate::neural_network::NeuralNetwork;
+use crate::stats::{log_info, log_warn};
+use crate::vision::VisionMode;
 use serde::{Deserialize, Serialize};
 
-// Expected neural network input size (must match cell sensor normalization)
-// 5 sensors × 4 values + 1 energy + 5 center-of-mass values + 1 density = 27
-const EXPECTED_INPUT_SIZE: usize = 27;
+// Each vision mode has its own input size (see VisionMode::input_count),
+// so brains are stored in separate slots per mode
+fn slot_prefix(vision_mode: VisionMode) -> &'static str {
+    match vision_mode {
+        VisionMode::NearestCells => "best_brain_m",
+        VisionMode::Raycast => "best_brain_ray_m",
+    }
+}
+
+// Vision mode a brain was trained for, inferred from its input size
+fn vision_mode_of(brain: &NeuralNetwork) -> VisionMode {
+    if brain.input_size == VisionMode::Raycast.input_count() {
+        VisionMode::Raycast
+    } else {
+        VisionMode::NearestCells
+    }
+}
+
+// Brain tiers saved per vision mode (see World::cached_best_brains)
+const SAVED_TIERS: usize = 4;
+
+// Name of a brain slot, shared by the localStorage key and the file name
+fn slot_name(tier: usize, vision_mode: VisionMode) -> String {
+    format!("{}{}", slot_prefix(vision_mode), tier)
+}
+
+// Every brain slot with the vision mode its brains are checked against
+fn all_slots() -> impl Iterator<Item = (String, VisionMode)> {
+    [VisionMode::NearestCells, VisionMode::Raycast]
+        .into_iter()
+        .flat_map(|mode| (0..SAVED_TIERS).map(move |tier| (slot_name(tier, mode), mode)))
+}
 
 #[cfg(target_arch = "wasm32")]
-fn key_for_tier(tier: usize) -> String {
-    format!("best_brain_m{}", tier)
+fn key_for_tier(tier: usize, vision_mode: VisionMode) -> String {
+    slot_name(tier, vision_mode)
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn file_for_tier(tier: usize) -> String {
-    format!("best_brain_m{}.json", tier)
+fn file_for_tier(tier: usize, vision_mode: VisionMode) -> String {
+    format!("{}.json", slot_name(tier, vision_mode))
 }
 
 // Note: The SavedState functionality has been disabled as Cell contains
@@ -20,7 +53,7 @@ fn file_for_tier(tier: usize) -> String {
 // Instead, we only save/load the neural network which is the key evolutionary data.
 
 /// Wrapper struct to save the neural network with score metrics
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct SavedBrain {
     // Score metrics for comparison
     score: f32,
@@ -30,6 +63,9 @@ struct SavedBrain {
     // The neural network itself
     brain: NeuralNetwork,
     generation: usize,
+    // Unix seconds when the brain was saved (0 for brains saved before this existed)
+    #[serde(default)]
+    saved_at: u64,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -43,6 +79,16 @@ unsafe extern "C" {
 
     /// Delete a key from localStorage (JavaScript implementation)
     fn storage_delete(key: *const u8, key_len: usize);
+
+    /// Copy a string to the system clipboard (JavaScript implementation)
+    fn clipboard_write(value: *const u8, value_len: usize);
+
+    /// Read the most recently pasted/copied clipboard text (JavaScript implementation)
+    /// Returns the length of the text, or 0 if nothing is available
+    fn clipboard_read(buffer: *mut u8, buffer_len: usize) -> usize;
+
+    /// Offer bytes as a browser download (JavaScript implementation)
+    fn download_file(name: *const u8, name_len: usize, bytes: *const u8, bytes_len: usize);
 }
 
 /// Save a neural network with score metrics to the tier-specific slot
@@ -62,34 +108,68 @@ pub fn save_best_neural_network(
         age,
         brain: brain.clone(),
         generation,
+        saved_at: unix_timestamp(),
     };
     let json = serde_json::to_string(&saved_brain).unwrap_or_default();
 
     #[cfg(target_arch = "wasm32")]
     unsafe {
-        let key = key_for_tier(tier);
+        let key = key_for_tier(tier, vision_mode_of(brain));
         storage_save(key.as_ptr(), key.len(), json.as_ptr(), json.len());
-        println!("💾 Best brain (tier {}) saved to localStorage", tier);
+        log_info!("💾 Best brain (tier {}) saved to localStorage", tier);
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     {
-        let path = file_for_tier(tier);
+        let path = file_for_tier(tier, vision_mode_of(brain));
         if let Err(e) = std::fs::write(&path, json.as_bytes()) {
-            println!("⚠ Failed to save brain (tier {}) to file: {}", tier, e);
+            log_warn!("⚠ Failed to save brain (tier {}) to file: {}", tier, e);
         } else {
-            println!("💾 Best brain (tier {}) saved to file", tier);
+            log_info!("💾 Best brain (tier {}) saved to file", tier);
         }
     }
 }
 
-/// Load a neural network for the given tier slot.
+// Why a loaded brain can't be used with the current architecture, if anything.
+// Brains saved before the pheromone or temperature inputs existed are upgraded in place.
+fn brain_incompatibility(brain: &mut NeuralNetwork, expected_input_size: usize) -> Option<String> {
+    if let Err(problem) = brain.validate() {
+        return Some(problem);
+    }
+    if brain.upgrade_legacy_inputs(expected_input_size) {
+        log_info!("🧠 Added newer sensor inputs to a brain saved before they existed");
+    }
+    if brain.upgrade_legacy_outputs(ACTION_COUNT) {
+        log_info!("🧠 Added the donate action to a brain saved before it existed");
+    }
+    if brain.input_size != expected_input_size {
+        return Some(format!(
+            "expected {} inputs, found {}",
+            expected_input_size, brain.input_size
+        ));
+    }
+    (brain.output_size != ACTION_COUNT).then(|| {
+        format!(
+            "expected {} outputs, found {}",
+            ACTION_COUNT, brain.output_size
+        )
+    })
+}
+
+/// Load a neural network for the given tier slot of a vision mode.
 /// Returns None if no saved brain exists.
 /// Returns (brain, generation, score)
-pub fn load_best_neural_network(tier: usize) -> Option<(NeuralNetwork, usize, f32)> {
+/// Reads and parses the whole slot: World loads each slot once into its
+/// cached_best_brains, and every spawn clones from that cache.
+pub fn load_best_neural_network(
+    tier: usize,
+    vision_mode: VisionMode,
+) -> Option<(NeuralNetwork, usize, f32)> {
+    let expected_input_size = vision_mode.input_count();
+
     #[cfg(target_arch = "wasm32")]
     unsafe {
-        let key = key_for_tier(tier);
+        let key = key_for_tier(tier, vision_mode);
         // Allocate a buffer for the result (max 1MB for neural network JSON)
         let mut buffer = vec![0u8; 1024 * 1024];
         let len = storage_load(key.as_ptr(), key.len(), buffer.as_mut_ptr(), buffer.len());
@@ -98,33 +178,39 @@ pub fn load_best_neural_network(tier: usize) -> Option<(NeuralNetwork, usize, f3
         if len > 0 {
             buffer.truncate(len);
             if let Ok(json) = String::from_utf8(buffer) {
-                if let Ok(saved_brain) = serde_json::from_str::<SavedBrain>(&json) {
-                    // Validate input size matches current architecture
-                    if saved_brain.brain.input_size != EXPECTED_INPUT_SIZE {
-                        println!(
-                            "⚠ Incompatible brain (tier {}): expected {} inputs, found {}. Deleting...",
-                            tier, EXPECTED_INPUT_SIZE, saved_brain.brain.input_size
+                if let Ok(mut saved_brain) = serde_json::from_str::<SavedBrain>(&json) {
+                    // Validate weight shapes and input size against the current architecture
+                    if let Some(problem) =
+                        brain_incompatibility(&mut saved_brain.brain, expected_input_size)
+                    {
+                        log_warn!(
+                            "⚠ Incompatible brain (tier {}): {}. Deleting...",
+                            tier,
+                            problem
                         );
                         storage_delete(key.as_ptr(), key.len());
                         return None;
                     }
-                    println!(
+                    log_info!(
                         "🧠 Loaded best brain (tier {}) from localStorage (gen {}, score {:.1})",
-                        tier, saved_brain.generation, saved_brain.score
+                        tier,
+                        saved_brain.generation,
+                        saved_brain.score
                     );
                     return Some((saved_brain.brain, saved_brain.generation, saved_brain.score));
                 }
-                if let Some(brain) = NeuralNetwork::from_json(&json) {
+                if let Ok(mut brain) = NeuralNetwork::from_json(&json) {
                     // Validate input size for legacy format
-                    if brain.input_size != EXPECTED_INPUT_SIZE {
-                        println!(
-                            "⚠ Incompatible legacy brain (tier {}): expected {} inputs, found {}. Deleting...",
-                            tier, EXPECTED_INPUT_SIZE, brain.input_size
+                    if let Some(problem) = brain_incompatibility(&mut brain, expected_input_size) {
+                        log_warn!(
+                            "⚠ Incompatible legacy brain (tier {}): {}. Deleting...",
+                            tier,
+                            problem
                         );
                         storage_delete(key.as_ptr(), key.len());
                         return None;
                     }
-                    println!(
+                    log_info!(
                         "🧠 Loaded best brain (tier {}) from localStorage (legacy format)",
                         tier
                     );
@@ -138,35 +224,43 @@ pub fn load_best_neural_network(tier: usize) -> Option<(NeuralNetwork, usize, f3
 
     #[cfg(not(target_arch = "wasm32"))]
     {
-        let path = file_for_tier(tier);
+        let path = file_for_tier(tier, vision_mode);
         if let Ok(json) = std::fs::read_to_string(&path) {
-            if let Ok(saved_brain) = serde_json::from_str::<SavedBrain>(&json) {
-                // Validate input size matches current architecture
-                if saved_brain.brain.input_size != EXPECTED_INPUT_SIZE {
-                    println!(
-                        "⚠ Incompatible brain (tier {}): expected {} inputs, found {}. Deleting {}...",
-                        tier, EXPECTED_INPUT_SIZE, saved_brain.brain.input_size, path
+            if let Ok(mut saved_brain) = serde_json::from_str::<SavedBrain>(&json) {
+                // Validate weight shapes and input size against the current architecture
+                if let Some(problem) =
+                    brain_incompatibility(&mut saved_brain.brain, expected_input_size)
+                {
+                    log_warn!(
+                        "⚠ Incompatible brain (tier {}): {}. Deleting {}...",
+                        tier,
+                        problem,
+                        path
                     );
                     let _ = std::fs::remove_file(&path);
                     return None;
                 }
-                println!(
+                log_info!(
                     "🧠 Loaded best brain (tier {}) from file (gen {}, score {:.1})",
-                    tier, saved_brain.generation, saved_brain.score
+                    tier,
+                    saved_brain.generation,
+                    saved_brain.score
                 );
                 return Some((saved_brain.brain, saved_brain.generation, saved_brain.score));
             }
-            if let Some(brain) = NeuralNetwork::from_json(&json) {
+            if let Ok(mut brain) = NeuralNetwork::from_json(&json) {
                 // Validate input size for legacy format
-                if brain.input_size != EXPECTED_INPUT_SIZE {
-                    println!(
-                        "⚠ Incompatible legacy brain (tier {}): expected {} inputs, found {}. Deleting {}...",
-                        tier, EXPECTED_INPUT_SIZE, brain.input_size, path
+                if let Some(problem) = brain_incompatibility(&mut brain, expected_input_size) {
+                    log_warn!(
+                        "⚠ Incompatible legacy brain (tier {}): {}. Deleting {}...",
+                        tier,
+                        problem,
+                        path
                     );
                     let _ = std::fs::remove_file(&path);
                     return None;
                 }
-                println!(
+                log_info!(
                     "🧠 Loaded best brain (tier {}) from file (legacy format)",
                     tier
                 );
@@ -176,3 +270,602 @@ pub fn load_best_neural_network(tier: usize) -> Option<(NeuralNetwork, usize, f3
         None
     }
 }
+
+/// Default file for the brain library on native (see export_all)
+pub const BRAIN_LIBRARY_FILE: &str = "cells_brain_library.json";
+
+/// Every saved brain slot in one document, for sharing evolved brains
+#[derive(Debug, Serialize, Deserialize)]
+struct BrainLibrary {
+    exported_at: u64, // Unix seconds
+    brains: Vec<LibraryEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LibraryEntry {
+    name: String, // Slot name, e.g. best_brain_m2
+    #[serde(flatten)]
+    saved: SavedBrain,
+}
+
+// Raw JSON stored in a brain slot, if any
+fn read_slot(name: &str) -> Option<String> {
+    #[cfg(target_arch = "wasm32")]
+    unsafe {
+        let mut buffer = vec![0u8; 1024 * 1024];
+        let len = storage_load(name.as_ptr(), name.len(), buffer.as_mut_ptr(), buffer.len());
+        buffer.truncate(len);
+        String::from_utf8(buffer)
+            .ok()
+            .filter(|json| !json.is_empty())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::read_to_string(format!("{}.json", name)).ok()
+    }
+}
+
+fn write_slot(name: &str, json: &str) -> Result<(), String> {
+    #[cfg(target_arch = "wasm32")]
+    unsafe {
+        storage_save(name.as_ptr(), name.len(), json.as_ptr(), json.len());
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let path = format!("{}.json", name);
+        std::fs::write(&path, json).map_err(|e| format!("cannot write {}: {}", path, e))
+    }
+}
+
+// Library document of every slot `read` returns a brain for. Legacy slots holding a
+// bare network are exported with zeroed metrics.
+fn library_json(read: impl Fn(&str) -> Option<String>) -> (String, usize) {
+    let brains: Vec<LibraryEntry> = all_slots()
+        .filter_map(|(name, _)| {
+            let json = read(&name)?;
+            let saved = serde_json::from_str::<SavedBrain>(&json).ok().or_else(|| {
+                let brain = NeuralNetwork::from_json(&json).ok()?;
+                Some(SavedBrain {
+                    score: 0.0,
+                    children_count: 0,
+                    energy_from_cells: 0.0,
+                    age: 0.0,
+                    brain,
+                    generation: 0,
+                    saved_at: 0,
+                })
+            })?;
+            Some(LibraryEntry { name, saved })
+        })
+        .collect();
+    let count = brains.len();
+    let library = BrainLibrary {
+        exported_at: unix_timestamp(),
+        brains,
+    };
+    (serde_json::to_string(&library).unwrap_or_default(), count)
+}
+
+// Write the brains of a library document into their slots. Unknown slot names and
+// brains that don't fit their slot's vision mode are skipped with a warning, as are
+// slots that already hold a brain unless `overwrite`. Returns the slots written.
+fn merge_library_json(
+    json: &str,
+    overwrite: bool,
+    read: impl Fn(&str) -> Option<String>,
+    mut write: impl FnMut(&str, &str) -> Result<(), String>,
+) -> Result<usize, String> {
+    let library: BrainLibrary =
+        serde_json::from_str(json).map_err(|e| format!("not a brain library: {}", e))?;
+    let mut written = 0;
+    for mut entry in library.brains {
+        let Some((_, vision_mode)) = all_slots().find(|(name, _)| *name == entry.name) else {
+            log_warn!("⚠ Skipping unknown brain slot {:?}", entry.name);
+            continue;
+        };
+        if let Some(problem) =
+            brain_incompatibility(&mut entry.saved.brain, vision_mode.input_count())
+        {
+            log_warn!("⚠ Skipping incompatible brain {}: {}", entry.name, problem);
+            continue;
+        }
+        if !overwrite && read(&entry.name).is_some() {
+            log_info!("Keeping the existing brain in {}", entry.name);
+            continue;
+        }
+        let saved = serde_json::to_string(&entry.saved).unwrap_or_default();
+        write(&entry.name, &saved)?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Export every saved brain slot (both vision modes, all tiers) with its metadata
+/// (generation, score, save time) as one JSON document.
+/// Native: written to `path`. Web: downloaded by the browser as `path`.
+pub fn export_all(path: &str) {
+    let (json, count) = library_json(read_slot);
+    if count == 0 {
+        log_warn!("⚠ No saved brains to export");
+        return;
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    unsafe {
+        download_file(path.as_ptr(), path.len(), json.as_ptr(), json.len());
+        log_info!("💾 Brain library ({} brains) downloaded as {}", count, path);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Err(e) = std::fs::write(path, json) {
+            log_warn!("⚠ Failed to export brain library to {}: {}", path, e);
+        } else {
+            log_info!("💾 Brain library ({} brains) exported to {}", count, path);
+        }
+    }
+}
+
+/// Merge a library written by export_all into the saved brain slots, replacing
+/// slots that already hold a brain only with `overwrite`. Returns the slots written.
+/// Native: read from `path`. Web: read from the clipboard.
+pub fn import_all(path: &str, overwrite: bool) -> Result<usize, String> {
+    #[cfg(target_arch = "wasm32")]
+    let json = unsafe {
+        let _ = path;
+        let mut buffer = vec![0u8; 4 * 1024 * 1024];
+        let len = clipboard_read(buffer.as_mut_ptr(), buffer.len());
+        if len == 0 {
+            return Err("clipboard is empty (paste the library into the page first)".to_owned());
+        }
+        buffer.truncate(len);
+        String::from_utf8(buffer).map_err(|e| format!("clipboard is not text: {}", e))?
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let json = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+
+    merge_library_json(&json, overwrite, read_slot, write_slot)
+}
+
+pub const STATS_LOG_FILE: &str = "cells_stats_log.csv";
+pub const STATS_LOG_HEADER: &str = "tick,alive_count,best_fitness,diversity";
+
+/// Append a row to the CSV stats log, writing the header when the log is new
+/// Native: appended to the file at `path`. Web: kept in localStorage under `path`.
+pub fn append_stats_log(path: &str, row: &str) {
+    #[cfg(target_arch = "wasm32")]
+    unsafe {
+        let mut buffer = vec![0u8; 1024 * 1024];
+        let len = storage_load(path.as_ptr(), path.len(), buffer.as_mut_ptr(), buffer.len());
+        buffer.truncate(len);
+        let mut log = String::from_utf8(buffer).unwrap_or_default();
+        if log.is_empty() {
+            log.push_str(STATS_LOG_HEADER);
+            log.push('\n');
+        }
+        log.push_str(row);
+        log.push('\n');
+        storage_save(path.as_ptr(), path.len(), log.as_ptr(), log.len());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::io::Write;
+
+        let result = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| {
+                if file.metadata()?.len() == 0 {
+                    writeln!(file, "{}", STATS_LOG_HEADER)?;
+                }
+                writeln!(file, "{}", row)
+            });
+        if let Err(e) = result {
+            log_warn!("⚠ Failed to append stats log {}: {}", path, e);
+        }
+    }
+}
+
+pub const ERA_LOG_FILE: &str = "eras.jsonl";
+
+/// Append one JSON line per finished era to ERA_LOG_FILE
+/// Native only: on the web the era history stays in memory.
+pub fn append_era_log(line: &str) {
+    #[cfg(target_arch = "wasm32")]
+    let _ = line;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::io::Write;
+
+        let result = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(ERA_LOG_FILE)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(e) = result {
+            log_warn!("⚠ Failed to append era log {}: {}", ERA_LOG_FILE, e);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const REPLAY_FILE: &str = "cells_replay.bin";
+
+/// Save an encoded replay recording
+/// Native: written to cells_replay.bin. Web: replays are kept in memory only
+/// (binary recordings are too large for localStorage).
+pub fn save_replay(bytes: &[u8]) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        log_warn!(
+            "⚠ Replay ({} bytes) kept in memory only on web builds",
+            bytes.len()
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Err(e) = std::fs::write(REPLAY_FILE, bytes) {
+            log_warn!("⚠ Failed to save replay to file: {}", e);
+        } else {
+            log_info!("💾 Replay saved to {} ({} bytes)", REPLAY_FILE, bytes.len());
+        }
+    }
+}
+
+/// Load an encoded replay recording, if one was saved
+pub fn load_replay() -> Option<Vec<u8>> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        None
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::read(REPLAY_FILE).ok()
+    }
+}
+
+/// Genome files are named genome_<unix seconds>.json, both when exported and when
+/// the import key looks for one
+const GENOME_FILE_PREFIX: &str = "genome_";
+
+// Seconds since the Unix epoch, for timestamped file names
+fn unix_timestamp() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        macroquad::miniquad::date::now() as u64
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// Timestamped file name for a new genome export: genome_<unix seconds>.json
+pub fn genome_export_path() -> String {
+    format!("{}{}.json", GENOME_FILE_PREFIX, unix_timestamp())
+}
+
+// Timestamp of a genome_<unix seconds>.json file name, None for any other name
+fn genome_file_timestamp(name: &str) -> Option<u64> {
+    name.strip_prefix(GENOME_FILE_PREFIX)?
+        .strip_suffix(".json")?
+        .parse()
+        .ok()
+}
+
+/// Export a cell's heritable genome (see Cell::to_genome_json) for sharing
+/// Native: written to `path`. Web: copied to the clipboard.
+pub fn export_genome(cell: &Cell, path: &str) {
+    let json = cell.to_genome_json();
+
+    #[cfg(target_arch = "wasm32")]
+    unsafe {
+        let _ = path;
+        clipboard_write(json.as_ptr(), json.len());
+        log_info!("📋 Genome copied to clipboard");
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Err(e) = std::fs::write(path, json) {
+            log_warn!("⚠ Failed to export genome to {}: {}", path, e);
+        } else {
+            log_info!("💾 Genome exported to {}", path);
+        }
+    }
+}
+
+/// Build a cell from a genome JSON and check it fits this world's sensor configuration
+pub fn genome_from_json(json: &str, config: &SimulationConfig) -> Result<Cell, String> {
+    let cell = Cell::from_genome_json(json, config.world_width, config.world_height)?;
+    if cell.vision_mode != config.vision_mode {
+        return Err(format!(
+            "genome was evolved with {:?} vision ({} inputs) but this world uses {:?} vision ({} inputs)",
+            cell.vision_mode,
+            cell.brain.input_size,
+            config.vision_mode,
+            config.vision_mode.input_count()
+        ));
+    }
+    Ok(cell)
+}
+
+/// Build a newborn cell from either an exported genome or a bare brain JSON.
+/// A bare brain gets a fresh random body, with the tier implied by its hidden width.
+pub fn cell_from_brain_json(json: &str, config: &SimulationConfig) -> Result<Cell, String> {
+    let genome_error = match genome_from_json(json, config) {
+        Ok(cell) => return Ok(cell),
+        Err(e) => e,
+    };
+    let Ok(mut brain) = serde_json::from_str::<NeuralNetwork>(json) else {
+        return Err(genome_error);
+    };
+    if let Some(problem) = brain_incompatibility(&mut brain, config.vision_mode.input_count()) {
+        return Err(format!("incompatible brain: {}", problem));
+    }
+    // Rounded: brains from before the donate action are slightly narrower per output
+    let width = brain.hidden_size as f32 / (2 * (brain.input_size + brain.output_size)) as f32;
+    let tier = (width.round() as usize).saturating_sub(1).min(3);
+    let mut cell = Cell::spawn(
+        config.world_width,
+        config.world_height,
+        tier,
+        &None,
+        config.vision_mode,
+    );
+    cell.brain = brain;
+    Ok(cell)
+}
+
+/// Import a shared genome as a newborn cell at a random position
+/// Native: read from `path`. Web: read from the clipboard.
+pub fn import_genome(path: &str, config: &SimulationConfig) -> Result<Cell, String> {
+    #[cfg(target_arch = "wasm32")]
+    let json = unsafe {
+        let _ = path;
+        let mut buffer = vec![0u8; 4 * 1024 * 1024];
+        let len = clipboard_read(buffer.as_mut_ptr(), buffer.len());
+        if len == 0 {
+            return Err("clipboard is empty (paste the genome into the page first)".to_owned());
+        }
+        buffer.truncate(len);
+        String::from_utf8(buffer).map_err(|e| format!("clipboard is not text: {}", e))?
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let json = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+
+    genome_from_json(&json, config)
+}
+
+/// Import the newest genome_<unix seconds>.json in the working directory (native), or
+/// the clipboard (web)
+pub fn import_latest_genome(config: &SimulationConfig) -> Result<Cell, String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        import_genome("", config)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let newest = std::fs::read_dir(".")
+            .map_err(|e| format!("cannot list the working directory: {}", e))?
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter_map(|name| Some((genome_file_timestamp(&name)?, name)))
+            .max();
+        match newest {
+            Some((_, path)) => import_genome(&path, config),
+            None => Err(format!(
+                "no {}<unix seconds>.json file in the working directory",
+                GENOME_FILE_PREFIX
+            )),
+        }
+    }
+}
+
+/// File name for a screenshot: cells_screenshot_<unix seconds>_tick<tick>.png
+pub fn screenshot_file_name(tick: usize) -> String {
+    format!("cells_screenshot_{}_tick{}.png", unix_timestamp(), tick)
+}
+
+/// Encode an RGBA8 screen capture as PNG.
+/// `rgba` rows are bottom-up, as returned by macroquad's get_screen_data.
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Option<Vec<u8>> {
+    use image::ImageEncoder;
+
+    let row_len = width as usize * 4;
+    if rgba.len() != row_len * height as usize {
+        return None;
+    }
+    let top_down: Vec<u8> = rgba
+        .chunks_exact(row_len)
+        .rev()
+        .flatten()
+        .copied()
+        .collect();
+
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png)
+        .write_image(&top_down, width, height, image::ColorType::Rgba8)
+        .ok()?;
+    Some(png)
+}
+
+/// Save a PNG screenshot
+/// Native: written to `file_name` in the working directory. Web: downloaded by the browser.
+pub fn save_screenshot(file_name: &str, png: &[u8]) {
+    #[cfg(target_arch = "wasm32")]
+    unsafe {
+        download_file(file_name.as_ptr(), file_name.len(), png.as_ptr(), png.len());
+        log_info!("📸 Screenshot downloaded as {}", file_name);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Err(e) = std::fs::write(file_name, png) {
+            log_warn!("⚠ Failed to save screenshot {}: {}", file_name, e);
+        } else {
+            log_info!("📸 Screenshot saved to {} ({} bytes)", file_name, png.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    fn saved(tier: usize, vision_mode: VisionMode, generation: usize) -> SavedBrain {
+        let cell = Cell::spawn(1000.0, 1000.0, tier, &None, vision_mode);
+        SavedBrain {
+            score: 10.0 * generation as f32,
+            children_count: generation,
+            energy_from_cells: 1.5,
+            age: 30.0,
+            brain: cell.brain,
+            generation,
+            saved_at: 1_700_000_000 + generation as u64,
+        }
+    }
+
+    #[test]
+    fn test_brain_library_round_trip_through_a_file() {
+        let slots: HashMap<String, String> = [
+            (
+                slot_name(0, VisionMode::NearestCells),
+                saved(0, VisionMode::NearestCells, 3),
+            ),
+            (
+                slot_name(2, VisionMode::NearestCells),
+                saved(2, VisionMode::NearestCells, 7),
+            ),
+            (
+                slot_name(1, VisionMode::Raycast),
+                saved(1, VisionMode::Raycast, 5),
+            ),
+        ]
+        .into_iter()
+        .map(|(name, brain)| (name, serde_json::to_string(&brain).unwrap()))
+        .collect();
+        let (json, count) = library_json(|name| slots.get(name).cloned());
+        assert_eq!(count, 3);
+
+        let path = std::env::temp_dir().join(format!("cells_library_{}.json", std::process::id()));
+        std::fs::write(&path, &json).unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        // Into an empty library: every brain lands in its slot unchanged
+        let imported = RefCell::new(HashMap::new());
+        let written = merge_library_json(
+            &json,
+            false,
+            |name| imported.borrow().get(name).cloned(),
+            |name, brain| {
+                imported
+                    .borrow_mut()
+                    .insert(name.to_owned(), brain.to_owned());
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(written, 3);
+        let parse = |json: &str| serde_json::from_str::<SavedBrain>(json).unwrap();
+        for (name, original) in &slots {
+            assert_eq!(parse(&imported.borrow()[name]), parse(original), "{}", name);
+        }
+
+        // Existing slots are kept unless overwriting
+        let write_count = |overwrite: bool| {
+            merge_library_json(
+                &json,
+                overwrite,
+                |name| slots.get(name).cloned(),
+                |_, _| Ok(()),
+            )
+            .unwrap()
+        };
+        assert_eq!(write_count(false), 0);
+        assert_eq!(write_count(true), 3);
+    }
+
+    #[test]
+    fn test_brain_library_import_rejects_unknown_and_mismatched_slots() {
+        let ray_brain = saved(0, VisionMode::Raycast, 1);
+        let library = BrainLibrary {
+            exported_at: 0,
+            brains: vec![
+                LibraryEntry {
+                    name: "../outside".to_owned(),
+                    saved: saved(0, VisionMode::NearestCells, 1),
+                },
+                // A raycast brain in a nearest-cells slot
+                LibraryEntry {
+                    name: slot_name(0, VisionMode::NearestCells),
+                    saved: ray_brain.clone(),
+                },
+                LibraryEntry {
+                    name: slot_name(0, VisionMode::Raycast),
+                    saved: ray_brain,
+                },
+            ],
+        };
+        let json = serde_json::to_string(&library).unwrap();
+        let mut names = Vec::new();
+        let written = merge_library_json(
+            &json,
+            true,
+            |_| None,
+            |name, _| {
+                names.push(name.to_owned());
+                Ok(())
+            },
+        );
+        assert_eq!(written, Ok(1));
+        assert_eq!(names, [slot_name(0, VisionMode::Raycast)]);
+        assert!(merge_library_json("{}", true, |_| None, |_, _| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_import_recognizes_the_export_file_names() {
+        let exported = genome_export_path();
+        assert!(genome_file_timestamp(&exported).is_some());
+        assert_eq!(
+            genome_file_timestamp("genome_1700000000.json"),
+            Some(1_700_000_000)
+        );
+        assert_eq!(genome_file_timestamp("genome_best.json"), None);
+        assert_eq!(genome_file_timestamp("cells_genome.json"), None);
+    }
+
+    #[test]
+    fn test_encode_png_flips_rows_top_down() {
+        // 2x2 capture, bottom row red, top row blue (bottom-up order)
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let rgba: Vec<u8> = [red, red, blue, blue].concat();
+
+        let png = encode_png(2, 2, &rgba).expect("valid capture");
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (2, 2));
+        assert_eq!(decoded.get_pixel(0, 0).0, blue);
+        assert_eq!(decoded.get_pixel(1, 1).0, red);
+
+        // Size mismatch is rejected
+        assert!(encode_png(3, 2, &rgba).is_none());
+    }
+}
@@ -0,0 +1,265 @@
+use crate::camera::ViewBounds;
+use crate::config::SuccessionConfig;
+use macroquad::prelude::*;
+
+// Seconds of simulation time between two succession steps
+pub const SUCCESSION_STEP_SECONDS: f32 = 1.0;
+const TINT_ALPHA: f32 = 0.07;
+
+/// Succession stage of a region, in the order regions move through them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionStage {
+    Barren,
+    Colonizing,
+    Mature,
+    Exhausted,
+}
+
+impl RegionStage {
+    const ALL: [RegionStage; 4] = [
+        RegionStage::Barren,
+        RegionStage::Colonizing,
+        RegionStage::Mature,
+        RegionStage::Exhausted,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            RegionStage::Barren => RegionStage::Colonizing,
+            RegionStage::Colonizing => RegionStage::Mature,
+            RegionStage::Mature => RegionStage::Exhausted,
+            RegionStage::Exhausted => RegionStage::Barren,
+        }
+    }
+
+    // Scales transition_chance by the region's density (0..1): life left alone grows
+    // back, while crowds exhaust a mature region. Exhausted land recovers regardless.
+    fn transition_factor(self, density: f32) -> f32 {
+        let density = density.clamp(0.0, 1.0);
+        match self {
+            RegionStage::Barren | RegionStage::Colonizing => 1.0 - density,
+            RegionStage::Mature => density,
+            RegionStage::Exhausted => 1.0,
+        }
+    }
+
+    fn tint(self) -> Color {
+        match self {
+            RegionStage::Barren => Color::new(0.7, 0.5, 0.3, TINT_ALPHA),
+            RegionStage::Colonizing => Color::new(0.6, 0.9, 0.4, TINT_ALPHA),
+            RegionStage::Mature => Color::new(0.1, 0.8, 0.3, TINT_ALPHA),
+            RegionStage::Exhausted => Color::new(0.5, 0.5, 0.5, TINT_ALPHA),
+        }
+    }
+}
+
+/// One region of the succession grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegionState {
+    pub stage: RegionStage,
+    // Food regeneration inside the region is scaled by this, metabolism by its inverse
+    pub energy_multiplier: f32,
+    pub age: f32, // Seconds spent in the current stage
+}
+
+/// The world split into a columns x rows grid of regions, each moving through the
+/// succession stages at a pace set by how crowded it is
+#[derive(Debug, Clone)]
+pub struct RegionMap {
+    config: SuccessionConfig,
+    region_width: f32,
+    region_height: f32,
+    pub regions: Vec<RegionState>,
+}
+
+impl RegionMap {
+    /// None unless succession is enabled. Regions start at random stages.
+    pub fn new(config: SuccessionConfig, world_width: f32, world_height: f32) -> Option<Self> {
+        if !config.enabled || config.columns == 0 || config.rows == 0 {
+            return None;
+        }
+        let regions = (0..config.columns * config.rows)
+            .map(|_| {
+                let stage = RegionStage::ALL[rand::gen_range(0, RegionStage::ALL.len())];
+                RegionState {
+                    stage,
+                    energy_multiplier: config.energy_multiplier(stage),
+                    age: 0.0,
+                }
+            })
+            .collect();
+        Some(RegionMap {
+            config,
+            region_width: world_width / config.columns as f32,
+            region_height: world_height / config.rows as f32,
+            regions,
+        })
+    }
+
+    /// Region index of a world position, row-major
+    pub fn region_of(&self, x: f32, y: f32) -> usize {
+        let column =
+            ((x / self.region_width).floor().max(0.0) as usize).min(self.config.columns - 1);
+        let row = ((y / self.region_height).floor().max(0.0) as usize).min(self.config.rows - 1);
+        row * self.config.columns + column
+    }
+
+    pub fn food_multiplier(&self, x: f32, y: f32) -> f32 {
+        self.regions[self.region_of(x, y)].energy_multiplier
+    }
+
+    pub fn metabolism_multiplier(&self, x: f32, y: f32) -> f32 {
+        1.0 / self.food_multiplier(x, y).max(f32::EPSILON)
+    }
+
+    /// Advance every region by one step of SUCCESSION_STEP_SECONDS. `density_map`
+    /// holds each region's crowding (0 empty, 1 at crowded_cells alive cells or
+    /// more), in region order; a region moves to its next stage with probability
+    /// transition_chance scaled by RegionStage::transition_factor.
+    pub fn step(&mut self, density_map: &[f32]) {
+        for (region, &density) in self.regions.iter_mut().zip(density_map) {
+            region.age += SUCCESSION_STEP_SECONDS;
+            let chance = self.config.transition_chance * region.stage.transition_factor(density);
+            if rand::gen_range(0.0, 1.0) < chance {
+                region.stage = region.stage.next();
+                region.energy_multiplier = self.config.energy_multiplier(region.stage);
+                region.age = 0.0;
+            }
+        }
+    }
+
+    /// Crowding of every region from the alive cell positions (see step)
+    pub fn density_map(&self, alive_positions: impl Iterator<Item = (f32, f32)>) -> Vec<f32> {
+        let mut counts = vec![0usize; self.regions.len()];
+        for (x, y) in alive_positions {
+            counts[self.region_of(x, y)] += 1;
+        }
+        let crowded = self.config.crowded_cells.max(1) as f32;
+        counts
+            .into_iter()
+            .map(|count| (count as f32 / crowded).min(1.0))
+            .collect()
+    }
+
+    // Subtle tint per region, repeated across the wrapped world edges
+    pub fn render(
+        &self,
+        camera_x: f32,
+        camera_y: f32,
+        view: &ViewBounds,
+        world_width: f32,
+        world_height: f32,
+    ) {
+        let (width, height) = (self.region_width, self.region_height);
+        let margin = width.max(height);
+        for (index, region) in self.regions.iter().enumerate() {
+            let column = index % self.config.columns;
+            let row = index / self.config.columns;
+            for offset_x in [-world_width, 0.0, world_width] {
+                for offset_y in [-world_height, 0.0, world_height] {
+                    let x = column as f32 * width + offset_x - camera_x;
+                    let y = row as f32 * height + offset_y - camera_y;
+                    if view.contains(x + width / 2.0, y + height / 2.0, margin) {
+                        draw_rectangle(x, y, width, height, region.stage.tint());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl SuccessionConfig {
+    pub fn energy_multiplier(&self, stage: RegionStage) -> f32 {
+        match stage {
+            RegionStage::Barren => self.barren_energy,
+            RegionStage::Colonizing => self.colonizing_energy,
+            RegionStage::Mature => self.mature_energy,
+            RegionStage::Exhausted => self.exhausted_energy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(transition_chance: f32) -> RegionMap {
+        let config = SuccessionConfig {
+            enabled: true,
+            columns: 2,
+            rows: 2,
+            transition_chance,
+            ..SuccessionConfig::default()
+        };
+        let mut map = RegionMap::new(config, 1000.0, 800.0).unwrap();
+        for region in &mut map.regions {
+            region.stage = RegionStage::Barren;
+            region.energy_multiplier = config.barren_energy;
+        }
+        map
+    }
+
+    #[test]
+    fn test_regions_cycle_through_the_stages() {
+        let mut stage = RegionStage::Barren;
+        for _ in 0..4 {
+            stage = stage.next();
+        }
+        assert_eq!(stage, RegionStage::Barren);
+        assert!(RegionMap::new(SuccessionConfig::default(), 1000.0, 800.0).is_none());
+
+        // Certain transitions: every empty region moves on each step
+        let mut map = map(1.0);
+        map.step(&[0.0; 4]);
+        assert!(
+            map.regions
+                .iter()
+                .all(|r| r.stage == RegionStage::Colonizing)
+        );
+        map.step(&[0.0; 4]);
+        let mature = map.config.mature_energy;
+        assert!(map.regions.iter().all(|r| r.energy_multiplier == mature));
+        assert_eq!(map.regions[0].age, 0.0);
+    }
+
+    #[test]
+    fn test_density_slows_recovery_and_drives_exhaustion() {
+        let mut map = map(1.0);
+        // A fully crowded barren region never recovers, the empty ones do
+        map.step(&[1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(map.regions[0].stage, RegionStage::Barren);
+        assert_eq!(map.regions[0].age, SUCCESSION_STEP_SECONDS);
+        assert_eq!(map.regions[1].stage, RegionStage::Colonizing);
+
+        // Mature regions only exhaust under grazing pressure
+        for region in &mut map.regions {
+            region.stage = RegionStage::Mature;
+        }
+        map.step(&[0.0, 1.0, 0.0, 1.0]);
+        let stages: Vec<RegionStage> = map.regions.iter().map(|r| r.stage).collect();
+        assert_eq!(
+            stages,
+            [
+                RegionStage::Mature,
+                RegionStage::Exhausted,
+                RegionStage::Mature,
+                RegionStage::Exhausted
+            ]
+        );
+    }
+
+    #[test]
+    fn test_density_map_and_multipliers_by_position() {
+        let mut map = map(0.0);
+        map.config.crowded_cells = 2;
+        // World 1000 x 800 in 2 x 2 regions of 500 x 400
+        let cells = [(10.0, 10.0), (20.0, 20.0), (30.0, 30.0), (900.0, 700.0)];
+        assert_eq!(map.density_map(cells.into_iter()), [1.0, 0.0, 0.0, 0.5]);
+        assert_eq!(map.region_of(999.9, 10.0), 1);
+        assert_eq!(map.region_of(10.0, 799.9), 2);
+
+        map.regions[3].energy_multiplier = 2.0;
+        assert_eq!(map.food_multiplier(900.0, 700.0), 2.0);
+        assert_eq!(map.metabolism_multiplier(900.0, 700.0), 0.5);
+    }
+}
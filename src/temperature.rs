@@ -0,0 +1,95 @@
+use crate::camera::ViewBounds;
+use crate::config::TemperatureConfig;
+use macroquad::prelude::*;
+use std::f32::consts::TAU;
+
+// Temperature read by cells while zones are disabled: the sensor input reads 0.0
+pub const NEUTRAL_TEMPERATURE: f32 = 0.5;
+// Width of the overlay's vertical bands, in world units
+const OVERLAY_BAND_WIDTH: f32 = 250.0;
+const OVERLAY_ALPHA: f32 = 0.12;
+
+/// Local temperature at world x, from 0.0 (cold, at the x = 0 edge) to 1.0 (hot,
+/// mid-world). A cosine so the field has no seam where the world wraps.
+pub fn temperature_at(x: f32, world_width: f32) -> f32 {
+    0.5 - 0.5 * (x / world_width * TAU).cos()
+}
+
+impl TemperatureConfig {
+    // Temperature a cell at world x senses (neutral when zones are off)
+    pub fn sample(&self, x: f32, world_width: f32) -> f32 {
+        if self.enabled {
+            temperature_at(x, world_width)
+        } else {
+            NEUTRAL_TEMPERATURE
+        }
+    }
+
+    // Interpolate a cold..hot factor, 1.0 when zones are off
+    fn factor(&self, cold: f32, hot: f32, temperature: f32) -> f32 {
+        if self.enabled {
+            cold + (hot - cold) * temperature.clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn metabolism_multiplier(&self, temperature: f32) -> f32 {
+        self.factor(self.cold_metabolism, self.hot_metabolism, temperature)
+    }
+
+    pub fn corpse_decay_multiplier(&self, temperature: f32) -> f32 {
+        self.factor(self.cold_corpse_decay, self.hot_corpse_decay, temperature)
+    }
+
+    pub fn feeding_multiplier(&self, temperature: f32) -> f32 {
+        self.factor(self.cold_feeding, self.hot_feeding, temperature)
+    }
+}
+
+// Translucent vertical bands, blue where cold through red where hot
+pub fn render(camera_x: f32, view: &ViewBounds, world_width: f32) {
+    let first = ((camera_x + view.min_x) / OVERLAY_BAND_WIDTH).floor() as i32;
+    let last = ((camera_x + view.max_x) / OVERLAY_BAND_WIDTH).floor() as i32;
+    for band in first..=last {
+        let x = band as f32 * OVERLAY_BAND_WIDTH;
+        let temperature = temperature_at(x + OVERLAY_BAND_WIDTH / 2.0, world_width);
+        draw_rectangle(
+            x - camera_x,
+            view.min_y,
+            OVERLAY_BAND_WIDTH,
+            view.max_y - view.min_y,
+            Color::new(temperature, 0.2, 1.0 - temperature, OVERLAY_ALPHA),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradient_is_cold_at_the_edge_hot_mid_world_and_seamless() {
+        assert!(temperature_at(0.0, 1000.0).abs() < 1e-6);
+        assert!((temperature_at(500.0, 1000.0) - 1.0).abs() < 1e-6);
+        assert!((temperature_at(250.0, 1000.0) - 0.5).abs() < 1e-6);
+        // Same temperature on both sides of the wrap
+        assert!((temperature_at(10.0, 1000.0) - temperature_at(990.0, 1000.0)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_factors_interpolate_only_when_enabled() {
+        let mut config = TemperatureConfig::default();
+        assert_eq!(config.sample(500.0, 1000.0), NEUTRAL_TEMPERATURE);
+        assert_eq!(config.metabolism_multiplier(1.0), 1.0);
+        assert_eq!(config.feeding_multiplier(0.0), 1.0);
+
+        config.enabled = true;
+        assert_eq!(config.metabolism_multiplier(0.0), config.cold_metabolism);
+        assert_eq!(config.metabolism_multiplier(1.0), config.hot_metabolism);
+        assert_eq!(config.corpse_decay_multiplier(1.0), config.hot_corpse_decay);
+        assert_eq!(config.feeding_multiplier(0.0), config.cold_feeding);
+        let mid = config.corpse_decay_multiplier(0.5);
+        assert!((mid - (config.cold_corpse_decay + config.hot_corpse_decay) / 2.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+// Raycast vision: RAY_COUNT rays spread evenly over a RAY_ARC frontal arc
+pub const RAY_COUNT: usize = 7;
+const RAY_ARC: f32 = PI; // 180°
+const VALUES_PER_RAY: usize = 2; // distance, kind
+
+// Pheromone inputs (concentration, gradient direction), after the density input
+pub const PHEROMONE_INPUTS: usize = 2;
+// Local temperature input, after the pheromone inputs
+pub const TEMPERATURE_INPUTS: usize = 1;
+// Memory of the previous tick's first sensor slot (angle, distance, energy, is_alive),
+// last in the input vector
+pub const MEMORY_INPUTS: usize = 4;
+// Inputs shared by both vision modes: 1 energy + 5 center of mass + 1 density
+// + pheromones + temperature + memory
+const COMMON_INPUTS: usize = 7 + PHEROMONE_INPUTS + TEMPERATURE_INPUTS + MEMORY_INPUTS;
+// Nearest-cells vision: the SENSOR_COUNT highest priority cells within sensor range
+pub const SENSOR_COUNT: usize = 5;
+const VALUES_PER_NEAREST_CELL: usize = 4; // angle, distance, energy, is_alive
+
+/// Which sensor model feeds the neural network
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VisionMode {
+    // 5 prioritized nearest cells (angle, distance, energy, is_alive each)
+    NearestCells,
+    // RAY_COUNT rays reporting distance and kind of the first hit
+    Raycast,
+}
+
+impl VisionMode {
+    /// Number of neural network inputs produced by this vision mode
+    /// NearestCells: 5 × 4 + 14 = 34, Raycast: 7 × 2 + 14 = 28
+    pub const fn input_count(self) -> usize {
+        match self {
+            VisionMode::NearestCells => SENSOR_COUNT * VALUES_PER_NEAREST_CELL + COMMON_INPUTS,
+            VisionMode::Raycast => RAY_COUNT * VALUES_PER_RAY + COMMON_INPUTS,
+        }
+    }
+}
+
+// Largest input_count over all vision modes, sizes the stack input buffer
+pub const MAX_INPUT_COUNT: usize = {
+    let nearest = VisionMode::NearestCells.input_count();
+    let raycast = VisionMode::Raycast.input_count();
+    if nearest > raycast { nearest } else { raycast }
+};
+
+/// One nearest-cells sensor slot: a target cell relative to the sensing cell
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SensorReading {
+    pub id: u64,    // Stable target id (resolve through World's id_to_index map)
+    pub angle: f32, // Angle from the sensing cell's front (-PI..PI)
+    pub distance: f32,
+    pub is_alive: bool,
+    pub energy: f32, // Target's current energy (what is left to eat of a corpse)
+}
+
+/// Up to SENSOR_COUNT readings stored inline, so refreshing them every tick
+/// never allocates. Derefs to the filled slots.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SensorReadings {
+    readings: [SensorReading; SENSOR_COUNT],
+    count: usize,
+}
+
+impl SensorReadings {
+    /// Keep the first SENSOR_COUNT readings, drop the rest
+    pub fn from_slice(readings: &[SensorReading]) -> Self {
+        let count = readings.len().min(SENSOR_COUNT);
+        let mut result = SensorReadings {
+            count,
+            ..SensorReadings::default()
+        };
+        result.readings[..count].copy_from_slice(&readings[..count]);
+        result
+    }
+}
+
+impl std::ops::Deref for SensorReadings {
+    type Target = [SensorReading];
+
+    fn deref(&self) -> &[SensorReading] {
+        &self.readings[..self.count]
+    }
+}
+
+/// Neural network inputs of one decision, built on the stack
+pub struct SensorInputs {
+    values: [f32; MAX_INPUT_COUNT],
+    len: usize,
+}
+
+impl Default for SensorInputs {
+    fn default() -> Self {
+        SensorInputs {
+            values: [0.0; MAX_INPUT_COUNT],
+            len: 0,
+        }
+    }
+}
+
+impl SensorInputs {
+    pub fn push(&mut self, value: f32) {
+        self.values[self.len] = value;
+        self.len += 1;
+    }
+
+    pub fn as_slice(&self) -> &[f32] {
+        &self.values[..self.len]
+    }
+}
+
+/// What a ray ran into (the world has no food or obstacles yet)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RayHitKind {
+    Nothing,
+    Alive,
+    Corpse,
+}
+
+impl RayHitKind {
+    // Nothing = 0, alive = 1, corpse = -1
+    pub fn as_input(self) -> f32 {
+        match self {
+            RayHitKind::Nothing => 0.0,
+            RayHitKind::Alive => 1.0,
+            RayHitKind::Corpse => -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub distance: f32, // Distance to the hit surface, max_range when nothing was hit
+    pub kind: RayHitKind,
+}
+
+/// A cell a ray may hit, relative to the viewing cell
+#[derive(Debug, Clone, Copy)]
+pub struct RayTarget {
+    pub angle: f32, // Angle from the viewer's front (-PI..PI)
+    pub distance: f32,
+    pub radius: f32,
+    pub is_alive: bool,
+}
+
+/// Angle of ray `i` relative to the cell's facing direction (left to right)
+pub fn ray_angle(i: usize) -> f32 {
+    -RAY_ARC / 2.0 + RAY_ARC * i as f32 / (RAY_COUNT - 1) as f32
+}
+
+/// Cast RAY_COUNT rays and report the closest target each one intersects within max_range
+pub fn cast_rays(targets: &[RayTarget], max_range: f32) -> Vec<RayHit> {
+    (0..RAY_COUNT)
+        .map(|i| {
+            let ray = ray_angle(i);
+            let mut closest = RayHit {
+                distance: max_range,
+                kind: RayHitKind::Nothing,
+            };
+
+            for target in targets {
+                // Target center projected on the ray and its offset from it
+                let relative_angle = target.angle - ray;
+                let along = target.distance * relative_angle.cos();
+                let offset = target.distance * relative_angle.sin().abs();
+                if along <= 0.0 || offset > target.radius {
+                    continue;
+                }
+
+                // Entry point of the ray into the target circle
+                let hit_distance =
+                    (along - (target.radius * target.radius - offset * offset).sqrt()).max(0.0);
+                if hit_distance < closest.distance {
+                    closest = RayHit {
+                        distance: hit_distance,
+                        kind: if target.is_alive {
+                            RayHitKind::Alive
+                        } else {
+                            RayHitKind::Corpse
+                        },
+                    };
+                }
+            }
+
+            closest
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(angle: f32, distance: f32, is_alive: bool) -> RayTarget {
+        RayTarget {
+            angle,
+            distance,
+            radius: 10.0,
+            is_alive,
+        }
+    }
+
+    #[test]
+    fn test_ray_angles_span_frontal_arc() {
+        assert!((ray_angle(0) + PI / 2.0).abs() < 1e-6);
+        assert!(ray_angle(RAY_COUNT / 2).abs() < 1e-6);
+        assert!((ray_angle(RAY_COUNT - 1) - PI / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_center_ray_hits_closest_target() {
+        let targets = [target(0.0, 200.0, true), target(0.0, 100.0, false)];
+        let hits = cast_rays(&targets, 400.0);
+        let center = hits[RAY_COUNT / 2];
+        assert_eq!(center.kind, RayHitKind::Corpse);
+        assert!((center.distance - 90.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_targets_behind_or_beside_are_missed() {
+        // Directly behind and far off to the side of every ray
+        let targets = [target(PI, 50.0, true), target(0.25, 300.0, true)];
+        let hits = cast_rays(&targets, 400.0);
+        assert_eq!(hits.len(), RAY_COUNT);
+        assert!(hits.iter().all(|hit| hit.kind == RayHitKind::Nothing));
+        assert!(hits.iter().all(|hit| hit.distance == 400.0));
+    }
+
+    #[test]
+    fn test_input_counts() {
+        assert_eq!(VisionMode::NearestCells.input_count(), 34);
+        assert_eq!(VisionMode::Raycast.input_count(), 28);
+        assert_eq!(MAX_INPUT_COUNT, 34);
+    }
+
+    #[test]
+    fn test_sensor_readings_keep_first_slots_inline() {
+        let reading = |id| SensorReading {
+            id,
+            ..SensorReading::default()
+        };
+        let all: Vec<SensorReading> = (0..8).map(reading).collect();
+        let readings = SensorReadings::from_slice(&all);
+        assert_eq!(readings.len(), SENSOR_COUNT);
+        assert_eq!(
+            readings.iter().map(|r| r.id).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4]
+        );
+
+        let few = SensorReadings::from_slice(&all[..2]);
+        assert_eq!(few.len(), 2);
+        assert!(few.get(2).is_none());
+        assert!(SensorReadings::default().is_empty());
+    }
+}
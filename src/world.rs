@@ -1,29 +1,391 @@
-use crate::camera::Camera;
-use crate::cell::{Cell, CellState};
+use crate::brain_inspector::{self, BrainInspection};
+use crate::camera::{Camera, ViewBounds};
+use crate::cell::{
+    Cell, CellState, CellType, ColorMode, DONATE_ACTION, DONATE_COST, DONATE_KIN_SIMILARITY,
+    DONATE_PULSE_TICKS, DONATE_RANGE, IMMIGRANT_MARKER_TICKS, PARASITE_DRAIN_RATE, PARASITE_JOLT,
+    PLAGUE_DURATION_TICKS, TRAIL_SAMPLE_INTERVAL, action_name,
+};
+use crate::cell_sprites::CellSprites;
 use crate::config::{SimulationConfig, get_config};
-use crate::spatial_grid::SpatialGrid;
-use crate::stats::Stats;
+use crate::director::AutoDirector;
+use crate::eras::EraTracker;
+use crate::events::{
+    EventBus, FOOD_BLOOM_MULTIPLIER, FOOD_BLOOM_PROB_PER_TICK, FoodBloomEvent, SimEvent,
+};
+use crate::ffi::{SimCommand, SimStats};
+use crate::fitness::{Fitness, FitnessPlugin};
+use crate::food::{FoodPellets, PELLET_RADIUS};
+use crate::gene_stats::{GeneStats, GeneStatsAccumulator};
+use crate::heatmap::Heatmap;
+use crate::hue_species::{HueSpeciesTracker, MAX_HUE_SPECIES};
+use crate::input::InputCaptured;
+use crate::islands::Islands;
+use crate::minimap::Minimap;
+use crate::neural_network::{NeuralNetwork, WeightChange};
+use crate::pheromones::PheromoneField;
+use crate::replay::ReplayRecorder;
+use crate::spatial_grid::{SpatialGrid, confine, wrapped_delta};
+use crate::stats::{
+    BestCellStats, CellComparison, CellDetail, ComparedCell, LeaderboardEntry, Stats, format_hms,
+    log_info, log_warn,
+};
+use crate::succession::{RegionMap, SUCCESSION_STEP_SECONDS};
+use crate::temperature;
+use crate::vision::{
+    RAY_COUNT, RayHitKind, RayTarget, SENSOR_COUNT, SensorReading, SensorReadings, VisionMode,
+    cast_rays, ray_angle,
+};
+use crate::zones::Zone;
 use macroquad::prelude::*;
 use rayon::prelude::*;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
-// FPS performance targets
-const TARGET_MIN_FPS: f32 = 30.0;
-const TARGET_GOOD_FPS: f32 = 60.0;
-const TARGET_MAX_FPS: f32 = 240.0;
+// FPS performance targets (grow/shrink band comes from SimulationConfig)
+const TARGET_MAX_FPS: f32 = 240.0; // Above this (and not vsync-locked) the cap grows quickly
 const FPS_SAMPLE_SIZE: usize = 60; // Track last 60 frames
 const ADJUSTMENT_INTERVAL: f32 = 2.0; // Adjust cap every 2 seconds
 const CELL_CAP_STEP: usize = 100; // Adjust cap by 100 cells at a time
 const CELL_CAP_SLOW_STEP: usize = 20; // Slow increase when FPS is good but not maxed
+const CELL_CAP_SUSTAIN_INTERVALS: usize = 2; // Intervals above the band before growing
+// Vsync detection: frame times within this relative jitter of a common refresh rate
+const VSYNC_JITTER: f32 = 0.05;
+const VSYNC_REFRESH_RATES: [f32; 7] = [30.0, 60.0, 75.0, 90.0, 120.0, 144.0, 165.0];
 
 // World simulation constants
-pub const SENSOR_RANGE: f32 = 400.0; // Public so cells can normalize sensor inputs
-const SENSOR_RANGE_SQUARED: f32 = SENSOR_RANGE * SENSOR_RANGE;
-const SENSOR_COUNT: usize = 5;
+pub const DEFAULT_SENSOR_RANGE: f32 = 400.0; // Until configured (see SimulationConfig::sensor_range)
 pub const REPRODUCTION_ENERGY_THRESHOLD: f32 = 100.0; // Public for energy normalization
-const CHILD_ENERGY_RATIO: f32 = 2.0 / 3.0;
-const PARENT_ENERGY_RATIO: f32 = 1.0 / 3.0;
 pub const DEPLETED_CELL_ENERGY: f32 = -100.0; // Public for energy normalization
+// Speciation: brains within this mean absolute weight difference share a species
+const SPECIES_DISTANCE_THRESHOLD: f32 = 0.1;
+const SPECIES_UPDATE_INTERVAL: usize = 600; // Ticks between species recounts
+const BOTTLENECK_SURVIVORS: usize = 10; // Fittest alive cells spared by a bottleneck event
+const LEADERBOARD_SIZE: usize = 5; // Fittest alive cells listed under the best cell
+const TRACKED_TOP_CELLS: usize = 10; // Fittest alive cells Tab cycles through
+const LEADERBOARD_INTERVAL: f64 = 1.0; // Seconds between leaderboard refreshes
+const GENOME_DIVERSITY_SAMPLE: usize = 32; // Alive cells compared pairwise for genome diversity
+// Plague: alive cells are grouped into hue bins of this width (degrees) to find the dominant cluster
+const PLAGUE_HUE_BIN_WIDTH: f32 = 30.0;
+// Elitism: ticks between checks that a pristine copy of the elite brain is alive
+const ELITE_TOP_UP_INTERVAL: usize = 600;
+const DENSITY_MAP_INTERVAL: usize = 10; // Ticks between density map refreshes
+const DENSITY_MAP_MAX_ALPHA: f32 = 0.6;
+// Kin selection: energy from a related corpse is scaled by 1 + KIN_BONUS * similarity
+const KIN_BONUS: f32 = 0.5;
+const KIN_CACHE_SLOTS: usize = 4096;
+// Lateral gene transfer between same-species neighbors (see lateral_gene_transfer)
+const LATERAL_TRANSFER_SIMILARITY: f32 = 0.8;
+const LATERAL_TRANSFER_CHANCE: f32 = 0.01; // Per alive cell per tick
+const SPAWN_TOOL_ENERGY: f32 = 100.0; // Energy of cells dropped with B+click
+const GENE_STATS_INTERVAL: usize = 60; // Ticks between trait distribution refreshes (1 s)
+// Population events: warn once when the alive count falls this low (re-armed above
+// twice as many), and announce every GENERATION_MILESTONE_INTERVAL generations
+const EXTINCTION_NEAR_ALIVE: usize = 10;
+const GENERATION_MILESTONE_INTERVAL: usize = 100;
+const EVENT_PANEL_SCROLL_ROWS: isize = 5; // Rows per PageUp/PageDown
+// Fixed simulation timestep
+pub const FIXED_DT: f32 = 1.0 / 60.0;
+const MAX_STEPS_PER_FRAME: usize = 16; // Caps catch-up work to avoid a spiral of death
+const STEP_EPSILON: f32 = 1e-4; // Absorbs float error so exactly one FIXED_DT runs one step
+const STEP_KEY_REPEAT_INTERVAL: f32 = 0.1; // Holding N while paused: 10 steps per second
+const MIN_SIMULATION_SPEED: f32 = 1.0;
+const MAX_SIMULATION_SPEED: f32 = 8.0;
+const CLICK_DRAG_TOLERANCE: f32 = 5.0; // Screen pixels a click may move before it counts as a drag
+const CLICK_PICK_RADIUS: f32 = 20.0; // Screen pixels around the click searched for a cell
+// Energy brush (U + left drag): energy given per tick to each alive cell under it,
+// and its radius range in world units (scroll with U held resizes it)
+const ENERGY_BRUSH_AMOUNT: f32 = 2.0;
+const ENERGY_BRUSH_DEFAULT_RADIUS: f32 = 60.0;
+const ENERGY_BRUSH_MIN_RADIUS: f32 = 10.0;
+const ENERGY_BRUSH_MAX_RADIUS: f32 = 500.0;
+const ENERGY_BRUSH_SCROLL_STEP: f32 = 0.1; // Radius change per scroll unit (fraction)
+const NIGHT_TINT_MAX_ALPHA: f32 = 0.35; // Background tint opacity at midnight
+const DETAIL_PANEL_GAP: f32 = 20.0; // Space between the stats column and the detail panel
+// Frame time sparkline next to the FPS line: one point per frame_times sample, on a
+// log scale from FRAME_GRAPH_FASTEST (bottom) to FRAME_GRAPH_SLOWEST (top)
+const FRAME_GRAPH_WIDTH: f32 = 120.0;
+const FRAME_GRAPH_HEIGHT: f32 = 24.0;
+const FRAME_GRAPH_FASTEST: f32 = 1.0 / 480.0;
+const FRAME_GRAPH_SLOWEST: f32 = 1.0 / 15.0;
+// Labelled stats sparklines (render_sparkline)
+const SPARKLINE_WIDTH: f32 = 120.0;
+const SPARKLINE_HEIGHT: f32 = 24.0;
+// Mutation sparkline next to the state line: the best lineage's last child deltas,
+// scaled to the largest one shown
+const MUTATION_DELTA_HISTORY: usize = 100;
+// Reproduction threshold sparkline after the cells line (use_dynamic_threshold only):
+// one sample every THRESHOLD_SAMPLE_INTERVAL ticks, from half the threshold (bottom)
+// to the full REPRODUCTION_ENERGY_THRESHOLD (top)
+const THRESHOLD_HISTORY: usize = 100;
+const THRESHOLD_SAMPLE_INTERVAL: usize = 30;
+// From this many cells on, render() draws sprites (Cell::render_batched) instead of shapes
+const BATCH_RENDER_MIN_CELLS: usize = 2000;
+const SENSOR_ARROWHEAD_SIZE: f32 = 5.0;
+const EMPTY_SLOT_STUB_LENGTH: f32 = 12.0; // Line length past the cell edge for empty slots
+const EMPTY_SLOT_FAN_STEP: f32 = std::f32::consts::TAU / SENSOR_COUNT as f32;
+
+// Darkness for a time of day in 0..1: 0.0 at noon (0.5), 1.0 at midnight (0.0)
+fn darkness_at(time_of_day: f32) -> f32 {
+    0.5 + 0.5 * (time_of_day * std::f32::consts::TAU).cos()
+}
+
+// Fixed timestep accumulator: adds delta_time * speed to the accumulator and returns
+// (steps to run, leftover time). If the cap is hit, leftover time is dropped rather
+// than carried forward, so a slow frame can't snowball into ever more steps.
+fn fixed_timestep_steps(accumulator: f32, delta_time: f32, speed: f32) -> (usize, f32) {
+    let accumulated = accumulator + delta_time.max(0.0) * speed;
+    let steps = ((accumulated + STEP_EPSILON) / FIXED_DT).floor() as usize;
+    if steps > MAX_STEPS_PER_FRAME {
+        return (MAX_STEPS_PER_FRAME, 0.0);
+    }
+    let leftover = (accumulated - steps as f32 * FIXED_DT).max(0.0);
+    (steps, leftover)
+}
+
+// Key repeat accumulator: adds delta_time to the time held and returns how many
+// whole `interval`s elapsed, keeping the remainder for the next frame
+fn held_key_repeats(held: &mut f32, delta_time: f32, interval: f32) -> usize {
+    *held += delta_time.max(0.0);
+    let repeats = (*held / interval).floor() as usize;
+    *held -= repeats as f32 * interval;
+    repeats
+}
+
+// Frame times cluster tightly around a common display refresh interval
+fn is_vsync_locked(frame_times: &VecDeque<f32>) -> bool {
+    if frame_times.len() < FPS_SAMPLE_SIZE / 2 {
+        return false;
+    }
+    let mean = frame_times.iter().sum::<f32>() / frame_times.len() as f32;
+    if mean <= 0.0 {
+        return false;
+    }
+    let jitter =
+        frame_times.iter().map(|t| (t - mean).abs()).sum::<f32>() / frame_times.len() as f32 / mean;
+    let fps = 1.0 / mean;
+    jitter < VSYNC_JITTER
+        && VSYNC_REFRESH_RATES
+            .iter()
+            .any(|rate| (fps - rate).abs() / rate < VSYNC_JITTER)
+}
+
+// Next auto cell cap and growth streak for one adjustment interval.
+// Shrinks as soon as FPS drops below the band, grows only after
+// CELL_CAP_SUSTAIN_INTERVALS in a row above it, holds inside it.
+// A vsync-locked frame rate hides headroom, so it only grows slowly.
+fn next_cell_cap(
+    cap: usize,
+    fps: f32,
+    vsync_locked: bool,
+    grow_streak: usize,
+    config: &SimulationConfig,
+) -> (usize, usize) {
+    let (cap, streak) = if fps < config.fps_shrink_threshold {
+        (cap.saturating_sub(CELL_CAP_STEP), 0)
+    } else if fps > config.fps_grow_threshold {
+        let streak = grow_streak + 1;
+        if streak < CELL_CAP_SUSTAIN_INTERVALS {
+            (cap, streak)
+        } else if fps > TARGET_MAX_FPS && !vsync_locked {
+            (cap + CELL_CAP_STEP, streak)
+        } else {
+            (cap + CELL_CAP_SLOW_STEP, streak)
+        }
+    } else {
+        (cap, 0)
+    };
+    (config.clamp_cell_cap(cap), streak)
+}
+
+// Line color of a sensor slot: red for an alive target, green for a corpse,
+// white for an empty slot
+fn sensor_line_color(target_is_alive: Option<bool>, alpha: f32) -> Color {
+    match target_is_alive {
+        Some(true) => Color::new(1.0, 0.3, 0.3, alpha),
+        Some(false) => Color::new(0.3, 1.0, 0.3, alpha),
+        None => Color::new(1.0, 1.0, 1.0, alpha),
+    }
+}
+
+// Heading-relative direction drawn for an empty sensor slot: the slots fan out
+// evenly around the heading, slot 2 straight ahead
+fn empty_slot_angle(slot: usize) -> f32 {
+    (slot as f32 - (SENSOR_COUNT - 1) as f32 / 2.0) * EMPTY_SLOT_FAN_STEP
+}
+
+// Back corners of an arrowhead pointing at `to`, None for a zero-length line
+fn arrowhead_points(from: Vec2, to: Vec2, size: f32) -> Option<(Vec2, Vec2)> {
+    let direction = (to - from).try_normalize()?;
+    let base = to - direction * size;
+    let side = direction.perp() * size * 0.5;
+    Some((base + side, base - side))
+}
+
+// Saved best brain (brain, generation) and its score for each tier of a vision mode
+type SavedBrains = ([Option<(NeuralNetwork, usize)>; 4], [f32; 4]);
+
+// One `load` call per tier slot (storage::load_best_neural_network outside tests)
+fn load_saved_brains(
+    vision_mode: VisionMode,
+    mut load: impl FnMut(usize, VisionMode) -> Option<(NeuralNetwork, usize, f32)>,
+) -> SavedBrains {
+    let mut saved: SavedBrains = Default::default();
+    for tier in 0..4 {
+        if let Some((brain, generation, score)) = load(tier, vision_mode) {
+            saved.0[tier] = Some((brain, generation));
+            saved.1[tier] = score;
+        }
+    }
+    saved
+}
+
+// Energy a cell needs to reproduce: REPRODUCTION_ENERGY_THRESHOLD scaled by how full the
+// population is (alive / cap), never below half of it, so a crashed population recovers faster
+fn dynamic_reproduction_threshold(alive_count: usize, max_cells: usize) -> f32 {
+    let fill = alive_count as f32 / max_cells.max(1) as f32;
+    REPRODUCTION_ENERGY_THRESHOLD * fill.clamp(0.5, 1.0)
+}
+
+// Sparkline of `values` (oldest left, `capacity` points across the full width) on a
+// translucent SPARKLINE_WIDTH x SPARKLINE_HEIGHT box at `top_left`, with `label` after
+// it. `level` maps a value to 0 (bottom) ..= 1 (top); results outside are clamped.
+fn render_sparkline(
+    (x, y): (f32, f32),
+    values: &VecDeque<f32>,
+    capacity: usize,
+    level: impl Fn(f32) -> f32,
+    color: Color,
+    label: &str,
+    font_size: f32,
+) {
+    let bottom = y + SPARKLINE_HEIGHT;
+    draw_rectangle(
+        x,
+        y,
+        SPARKLINE_WIDTH,
+        SPARKLINE_HEIGHT,
+        Color::new(0.0, 0.0, 0.0, 0.5),
+    );
+    let step = SPARKLINE_WIDTH / (capacity - 1) as f32;
+    let point = |i: usize, value: f32| {
+        (
+            x + i as f32 * step,
+            bottom - level(value).clamp(0.0, 1.0) * SPARKLINE_HEIGHT,
+        )
+    };
+    for (i, (&a, &b)) in values.iter().zip(values.iter().skip(1)).enumerate() {
+        let (x1, y1) = point(i, a);
+        let (x2, y2) = point(i + 1, b);
+        draw_line(x1, y1, x2, y2, 1.5, color);
+    }
+    draw_text(
+        label,
+        x + SPARKLINE_WIDTH + 8.0,
+        bottom - 4.0,
+        font_size * 0.75,
+        LIGHTGRAY,
+    );
+}
+
+// Type of a newly spawned (not born) cell: a parasite with chance parasite_fraction
+fn spawned_cell_type(config: &SimulationConfig) -> CellType {
+    if rand::gen_range(0.0, 1.0) < config.parasite_fraction {
+        CellType::Parasite
+    } else {
+        CellType::Normal
+    }
+}
+
+// The startup population: initial_cell_count cells cycling through the brain tiers
+// (from the cached brains when there are any), numbered from 1
+fn initial_population(
+    config: &SimulationConfig,
+    cached_best_brains: &[Option<(NeuralNetwork, usize)>; 4],
+) -> Vec<Cell> {
+    let mut cells = Vec::with_capacity(config.initial_cell_count);
+    for i in 0..config.initial_cell_count {
+        let tier = i % 4;
+        let mut cell = Cell::spawn(
+            config.world_width,
+            config.world_height,
+            tier,
+            &cached_best_brains[tier],
+            config.vision_mode,
+        );
+        cell.brain.action_bias = config.action_bias;
+        cell.brain.set_frozen_layers(config.freeze_layers);
+        cell.cell_type = spawned_cell_type(config);
+        cell.set_starting_energy(&config.energy);
+        // Half the population starts with low energy so they die quickly,
+        // seeding the world with corpses for others to eat.
+        if i % 2 == 1 {
+            cell.energy = rand::gen_range(0.0, REPRODUCTION_ENERGY_THRESHOLD * 0.5);
+        }
+        cell.id = i as u64 + 1;
+        cells.push(cell);
+    }
+    cells
+}
+
+// Metabolism multiplier for a time of day: 1.0 at noon, night_multiplier at midnight
+fn metabolism_multiplier_at(time_of_day: f32, night_multiplier: f32) -> f32 {
+    1.0 + (night_multiplier - 1.0) * darkness_at(time_of_day)
+}
+
+// Height of a frame time on the frame graph, 0 at FRAME_GRAPH_FASTEST (or faster) up
+// to graph_height at FRAME_GRAPH_SLOWEST (or slower). Log scale, so 240 and 45 FPS
+// are both readable.
+fn frame_time_height(frame_time: f32, graph_height: f32) -> f32 {
+    let range = (FRAME_GRAPH_SLOWEST / FRAME_GRAPH_FASTEST).ln();
+    let position = (frame_time.max(f32::MIN_POSITIVE) / FRAME_GRAPH_FASTEST).ln() / range;
+    position.clamp(0.0, 1.0) * graph_height
+}
+
+// Which cells the sensor overlay is drawn for (cycled with O)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorLineMode {
+    All,
+    SelectedOnly, // Just the selected cell; nothing while no cell is selected
+    Off,
+}
+
+impl SensorLineMode {
+    pub fn next(self) -> Self {
+        match self {
+            SensorLineMode::All => SensorLineMode::SelectedOnly,
+            SensorLineMode::SelectedOnly => SensorLineMode::Off,
+            SensorLineMode::Off => SensorLineMode::All,
+        }
+    }
+
+    // The mode in effect under the config's show_sensor_lines flag, which a reloaded
+    // or page-sent config can flip: turning the overlay on starts at SelectedOnly
+    pub fn with_overlay(self, show_sensor_lines: bool) -> Self {
+        match (self, show_sensor_lines) {
+            (_, false) => SensorLineMode::Off,
+            (SensorLineMode::Off, true) => SensorLineMode::SelectedOnly,
+            (mode, true) => mode,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SensorLineMode::All => "all cells",
+            SensorLineMode::SelectedOnly => "selected cell",
+            SensorLineMode::Off => "OFF",
+        }
+    }
+}
+
+// Read-only cell data for parallel sensor updates
+#[derive(Clone, Copy)]
+struct SensorTargetData {
+    id: u64,
+    x: f32,
+    y: f32,
+    energy: f32,
+    is_alive: bool,
+    radius: f32,
+}
 
 // Read-only cell data for parallel collision detection
 #[derive(Clone, Copy)]
@@ -33,31 +395,144 @@ struct CellCollisionData {
     radius: f32,
     energy_chunk_size: f32,
     species_multiplier: f32,
+    mass: f32,
     state: CellState,
+    infected: bool,
+    parasite: bool,
+}
+
+impl CellCollisionData {
+    // Alive cells with a body that pushes and hosts; parasites ride on their hosts
+    fn is_solid(&self) -> bool {
+        self.state == CellState::Alive && !self.parasite
+    }
+}
+
+// Largest body radius, so neighbor queries reach every cell that can touch
+fn max_collision_radius(collision_data: &[CellCollisionData]) -> f32 {
+    collision_data
+        .iter()
+        .map(|data| data.radius)
+        .fold(0.0, f32::max)
+}
+
+// Similarity cache for (feeder, corpse) pairs. Feeding on one corpse lasts many ticks,
+// so each pair is usually computed once. Fixed-size, direct-mapped by a hash of the ids:
+// like a bloom filter it never grows, but a slot stores its full key, so hits are exact
+// and a hash collision only evicts the older pair.
+struct KinCache {
+    slots: Vec<Option<(u64, u64, f32)>>,
+}
+
+impl KinCache {
+    fn new() -> Self {
+        KinCache {
+            slots: vec![None; KIN_CACHE_SLOTS],
+        }
+    }
+
+    fn get_or_insert_with(&mut self, a: u64, b: u64, compute: impl FnOnce() -> f32) -> f32 {
+        let hash = a.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ b.wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+        let slot = &mut self.slots[(hash >> 32) as usize % KIN_CACHE_SLOTS];
+        match *slot {
+            Some((key_a, key_b, similarity)) if key_a == a && key_b == b => similarity,
+            _ => {
+                let similarity = compute();
+                *slot = Some((a, b, similarity));
+                similarity
+            }
+        }
+    }
+
+    // Forget every pair, e.g. when a reset starts a new population
+    fn clear(&mut self) {
+        self.slots.fill(None);
+    }
 }
 
 pub struct World {
     pub cells: Vec<Cell>,
     pub camera: Camera,
     spatial_grid: SpatialGrid,
+    // Per-tick snapshots for update_sensors, kept between ticks to reuse their capacity
+    sensor_targets: Vec<SensorTargetData>,
+    density_counts: Vec<usize>,
+    id_to_index: HashMap<u64, usize>, // Rebuilt alongside the spatial grid
+    next_cell_id: u64,
     max_cells: usize,
+    cell_cap_grow_streak: usize, // Consecutive adjustment intervals above the FPS band
     frame_times: VecDeque<f32>,
     last_adjustment_time: f32,
     current_fps: f32,
     pub stats: Stats,
     best_cell_genome: Option<Cell>, // Store the complete best cell for respawning
-    last_best_cell_index: Option<usize>, // Track last best cell to avoid redundant clones
-    selected_cell_index: Option<usize>, // Currently selected cell for highlighting
+    elite_cell_id: Option<u64>,     // Cell carrying the unmutated elite brain (see top_up_elite)
+    cleared_by_user: bool, // World emptied on purpose: no extinction respawn or elite top-up until reset
+    // Followed/selected cells are tracked by id: indices go stale whenever cells are removed
+    last_best_cell_id: Option<u64>, // Track last best cell to avoid redundant clones
+    selected_cell_id: Option<u64>,  // Currently selected cell for highlighting
+    sensor_line_mode: SensorLineMode, // See sensor_lines(); show_sensor_lines mirrors it
+    picked_cell_id: Option<u64>,    // Clicked in the viewport, shown instead of the best cell
+    compared_cell_ids: Option<(u64, u64)>, // Selected cell and a shift-clicked one, compared side by side
+    click_press: Option<(f32, f32)>,       // Screen position of a left press on the viewport
+    right_click_press: Option<(f32, f32)>, // Screen position of a right press (drags pan instead)
+    energy_brush_radius: f32,
+    // World position the energy brush is painting at, applied on each simulation tick
+    energy_brush: Option<(f32, f32)>,
     followed_cell_death_time: Option<f64>, // Track when the followed cell died
+    clock: f64,                            // Accumulated frame time (seconds), never reset
+    leaderboard_updated_at: f64,           // Clock time of the last leaderboard refresh
+    // Ids of the TRACKED_TOP_CELLS fittest alive cells, fittest first, re-ranked with the
+    // leaderboard (once per second) so Tab cycling stays stable between refreshes
+    top_cell_ids: Vec<u64>,
+    tracked_rank: Option<usize>, // Index in top_cell_ids of the cell Tab selected
+    pub auto_cell_cap: bool,     // Adjust max_cells from FPS (disabled in headless mode)
     // Simulation controls
     pub paused: bool,
     pub simulation_speed: f32, // 1.0 = normal speed, 2.0 = double speed, etc. (min 1.0)
-    tick_accumulator: f32,     // Fractional tick accumulator for simulation speed
+    tick_accumulator: f32,     // Unsimulated time (seconds) carried over to the next frame
+    step_key_held: f32,        // Seconds N has been held while paused, toward the next repeat
     pub tick_count: usize,     // Cumulative ticks, resets on sim reset
     pub reset_count: usize,    // Cumulative resets
+    pub bottleneck_count: u32, // Bottleneck events triggered (see trigger_bottleneck)
+    pub eras: EraTracker,      // Summary of every reset-to-reset era (see finish_era)
     pub elapsed_time: f32,     // Elapsed simulation time in seconds, resets on sim reset
+    // Whole run, across resets: ticks simulated and their simulated seconds
+    // (f64 so hours of FIXED_DT steps don't lose precision)
+    pub total_ticks: u64,
+    pub sim_time: f64,
+    pub time_of_day: f32, // 0.0 = midnight, 0.5 = noon, wraps at 1.0
+    // Periodic auto-save
+    time_since_auto_save: f32,
+    pub stats_log_path: String, // CSV file (native) or localStorage key (web)
+    // Plague events
+    time_since_plague: f32,
+    pub infected_count: usize, // Cells (alive or corpse) currently carrying the plague
+    // Immigration: simulation seconds the hue diversity has been under the threshold
+    low_diversity_time: f32,
+    // Food blooms: random (or F key) bursts of food regeneration
+    food_bloom: Option<FoodBloomEvent>,
+    pub bloom_count: u32,
     // Diversity tracking
-    pub color_diversity: f32, // 0.0 = no diversity, 1.0 = maximum diversity
+    // Alive hue species (see hue_species.rs) / MAX_HUE_SPECIES, 0.0 to 1.0. It counts
+    // clusters of evolved hue, not the hue variance: one species reads 1 / 24 however
+    // spread out its hues are. Immigration thresholds (ImmigrationConfig) compare against it.
+    pub color_diversity: f32,
+    pub genome_diversity: f32, // Mean pairwise brain distance over sampled alive cells (see compute_genome_diversity)
+    // Hue species: alive cells clustered by evolved color (see hue_species.rs)
+    hue_species: HueSpeciesTracker,
+    pub species_alive_counts: HashMap<u32, usize>,
+    pub species_count: usize, // Distinct brain clusters among alive cells (see compute_species)
+    pub mean_brain_cost: f32, // Average per-tick brain metabolism of alive cells (see EnergyConfig::brain_cost)
+    // Best lineage: mutation_delta of each child the best cell had (latest
+    // MUTATION_DELTA_HISTORY, oldest first) and the largest single weight change of the last one
+    pub best_lineage_deltas: VecDeque<f32>,
+    // Energy needed to reproduce this tick (REPRODUCTION_ENERGY_THRESHOLD unless
+    // use_dynamic_threshold), and its samples for the stats graph, oldest first
+    pub dynamic_reproduction_threshold: f32,
+    threshold_history: VecDeque<f32>,
+    pub last_weight_change: Option<WeightChange>,
+    species_by_id: HashMap<u64, usize>, // Species per cell id from the last recount
     pub tier_cell_counts: [usize; 4],
     pub tier_diversities: [f32; 4],
     pub tier_current_best_scores: [f32; 4], // Current best score for alive cells in each tier
@@ -71,88 +546,202 @@ pub struct World {
     font: Option<Font>,
     // Parallax star-field background
     background: Option<crate::background::Background>,
+    // Cell sprite atlas for batched rendering (None without a window)
+    sprites: Option<CellSprites>,
+    // Replay recording and playback
+    replay: ReplayRecorder,
+    // Cell count / energy heatmap overlay
+    heatmap: Heatmap,
+    // Alive cells per spatial grid bucket, normalized to 0..1 (see compute_density_map)
+    density_map: Vec<f32>,
+    // What cell body colors represent
+    pub render_color_mode: ColorMode,
+    // F12 pressed: capture the screen once this frame's render is complete
+    screenshot_requested: bool,
+    // Trait distributions of the alive population (refreshed every GENE_STATS_INTERVAL ticks)
+    pub gene_stats: GeneStats,
+    kin_cache: KinCache,
+    // Islands mode geometry (None when disabled) and alive cells per island
+    islands: Option<Islands>,
+    pub island_counts: Vec<usize>,
+    // Fertile and barren regions (see ZoneConfig), placed when the world is created
+    pub zones: Vec<Zone>,
+    // Ecological succession regions (None unless enabled) and time toward their next step
+    pub regions: Option<RegionMap>,
+    time_since_succession: f32,
+    // Camera auto-director (Y) and the recent feeding / birth activity it looks at
+    pub director: AutoDirector,
+    // Nutrient pellets recycled from decayed corpses (see nutrient_recycle_fraction)
+    pub food: FoodPellets,
+    // Chemical trails deposited by alive cells (see PheromoneConfig)
+    pub pheromones: PheromoneField,
+    // Overrides config.fitness while set (see set_fitness_plugin)
+    fitness_plugin: Option<FitnessPlugin>,
+    // Simulation events of the current frame (see events.rs and dispatch_events)
+    events: EventBus,
+    extinction_warned: bool, // ExtinctionNear sent, until the population recovers
+    generation_milestone: usize, // Last generation milestone announced
 }
 
 impl World {
     pub fn spawn(font: Option<Font>) -> Self {
-        let config = get_config();
+        let mut world = Self::new(get_config());
+        world.font = font;
+        world.background = match crate::background::Background::new() {
+            Ok(bg) => Some(bg),
+            Err(e) => {
+                eprintln!("Background shader failed to load: {e:?}");
+                None
+            }
+        };
+        world.sprites = Some(CellSprites::new());
+        world
+    }
 
-        // Load best brain for each tier from storage
-        let mut cached_best_brains: [Option<(crate::neural_network::NeuralNetwork, usize)>; 4] =
-            [None, None, None, None];
-        let mut best_saved_scores = [0.0f32; 4];
+    // Create a world without any rendering resources (no font, no background shader).
+    // Safe to call without a macroquad window, e.g. in headless mode.
+    pub fn new(config: SimulationConfig) -> Self {
+        Self::with_brain_loader(config, crate::storage::load_best_neural_network)
+    }
 
-        for tier in 0..4 {
-            let loaded_data = crate::storage::load_best_neural_network(tier);
-            if let Some((brain, generation, score)) = loaded_data {
-                cached_best_brains[tier] = Some((brain, generation));
-                best_saved_scores[tier] = score;
-            }
-        }
+    // World::new with the saved brains read through `load`. Storage is only read
+    // here: every spawn afterwards clones from cached_best_brains.
+    fn with_brain_loader(
+        config: SimulationConfig,
+        load: impl FnMut(usize, VisionMode) -> Option<(NeuralNetwork, usize, f32)>,
+    ) -> Self {
+        // Load best brain for each tier from storage
+        let (cached_best_brains, best_saved_scores) = if config.use_storage {
+            load_saved_brains(config.vision_mode, load)
+        } else {
+            Default::default()
+        };
 
-        let mut cells = Vec::new();
-        for i in 0..config.initial_cell_count {
-            let tier = i % 4;
-            let mut cell = Cell::spawn(
-                config.world_width,
-                config.world_height,
-                tier,
-                &cached_best_brains[tier],
-            );
-            // Half the population starts with low energy so they die quickly,
-            // seeding the world with corpses for others to eat.
-            if i % 2 == 1 {
-                cell.energy = rand::gen_range(0.0, REPRODUCTION_ENERGY_THRESHOLD * 0.5);
-            }
-            cells.push(cell);
-        }
+        let cells = initial_population(&config, &cached_best_brains);
+        let next_cell_id = cells.len() as u64 + 1;
+        let zones = crate::zones::generate(&config.zones, config.world_width, config.world_height);
+        let spatial_grid = SpatialGrid::new(config.world_width, config.world_height, 100.0);
+        let pheromones = PheromoneField::new(&spatial_grid);
+        let mut camera = Camera::new();
+        camera.smoothstep_easing = config.camera_smoothstep;
 
         World {
             cells,
-            camera: Camera::new(),
-            spatial_grid: SpatialGrid::new(config.world_width, config.world_height, 100.0),
-            max_cells: config.initial_cell_count,
+            camera,
+            spatial_grid,
+            sensor_targets: Vec::new(),
+            density_counts: Vec::new(),
+            id_to_index: HashMap::new(),
+            next_cell_id,
+            max_cells: config.max_cells.unwrap_or(config.initial_cell_count),
+            cell_cap_grow_streak: 0,
             frame_times: VecDeque::with_capacity(FPS_SAMPLE_SIZE),
             last_adjustment_time: 0.0,
             current_fps: 60.0, // Initial estimate
             stats: Stats::new(),
             best_cell_genome: None,
-            last_best_cell_index: None,
-            selected_cell_index: None,
+            elite_cell_id: None,
+            cleared_by_user: false,
+            last_best_cell_id: None,
+            selected_cell_id: None,
+            sensor_line_mode: SensorLineMode::SelectedOnly,
+            picked_cell_id: None,
+            compared_cell_ids: None,
+            click_press: None,
+            right_click_press: None,
+            energy_brush_radius: ENERGY_BRUSH_DEFAULT_RADIUS,
+            energy_brush: None,
             followed_cell_death_time: None,
+            clock: 0.0,
+            leaderboard_updated_at: f64::NEG_INFINITY,
+            top_cell_ids: Vec::new(),
+            tracked_rank: None,
+            auto_cell_cap: true,
             paused: false,
             simulation_speed: 1.0,
             tick_accumulator: 0.0,
+            step_key_held: 0.0,
             tick_count: 0,
             reset_count: 0,
+            bottleneck_count: 0,
+            eras: EraTracker::new(),
             elapsed_time: 0.0,
+            total_ticks: 0,
+            sim_time: 0.0,
+            time_of_day: 0.5, // Start at noon
+            time_since_auto_save: 0.0,
+            stats_log_path: crate::storage::STATS_LOG_FILE.to_owned(),
+            time_since_plague: 0.0,
+            infected_count: 0,
+            low_diversity_time: 0.0,
+            food_bloom: None,
+            bloom_count: 0,
             color_diversity: 0.0,
+            genome_diversity: 0.0,
+            hue_species: HueSpeciesTracker::new(),
+            species_alive_counts: HashMap::new(),
+            species_count: 0,
+            mean_brain_cost: 0.0,
+            best_lineage_deltas: VecDeque::with_capacity(MUTATION_DELTA_HISTORY),
+            dynamic_reproduction_threshold: REPRODUCTION_ENERGY_THRESHOLD,
+            threshold_history: VecDeque::with_capacity(THRESHOLD_HISTORY),
+            last_weight_change: None,
+            species_by_id: HashMap::new(),
             tier_cell_counts: [0; 4],
             tier_diversities: [0.0; 4],
             tier_current_best_scores: [0.0; 4],
-            config,
             cached_best_brains,
             best_saved_scores,
-            font,
-            background: match crate::background::Background::new() {
-                Ok(bg) => Some(bg),
-                Err(e) => {
-                    eprintln!("Background shader failed to load: {e:?}");
-                    None
-                }
-            },
+            font: None,
+            background: None,
+            sprites: None,
+            replay: ReplayRecorder::new(),
+            heatmap: Heatmap::new(config.world_width, config.world_height),
+            density_map: Vec::new(),
+            render_color_mode: ColorMode::Inherited,
+            screenshot_requested: false,
+            gene_stats: GeneStats::default(),
+            kin_cache: KinCache::new(),
+            islands: Islands::new(config.islands, config.world_width, config.world_height),
+            island_counts: Vec::new(),
+            zones,
+            regions: RegionMap::new(config.succession, config.world_width, config.world_height),
+            time_since_succession: 0.0,
+            director: AutoDirector::new(config.director, config.world_width, config.world_height),
+            food: FoodPellets::new(config.world_width, config.world_height),
+            pheromones,
+            fitness_plugin: None,
+            events: EventBus::new(),
+            extinction_warned: false,
+            generation_milestone: 0,
+            config,
         }
     }
 
-    // Reset the world with spawns from the best cell's genome
-    pub fn respawn_from_best(&mut self) {
+    // Close the era and empty the world before a new population is spawned. Shared by
+    // respawn_from_best and restart_from_scratch so both resets clear the same state.
+    fn reset_world_state(&mut self) {
+        self.finish_era();
+
         // Clear current cells
         self.cells.clear();
+        self.food.clear();
+        self.pheromones.clear();
+        self.director.activity.clear();
+        self.kin_cache.clear();
+        self.cleared_by_user = false;
+        self.hue_species.reset();
+        self.species_alive_counts.clear();
 
         // Reset tick counter and elapsed time
         self.tick_count = 0;
         self.reset_count += 1;
         self.elapsed_time = 0.0;
+    }
+
+    // Reset the world with spawns from the best cell's genome
+    pub fn respawn_from_best(&mut self) {
+        self.reset_world_state();
 
         // Spawn count minimum is 100
         let spawn_count = self.max_cells.min(self.config.initial_cell_count).max(100);
@@ -188,22 +777,42 @@ impl World {
             let tier_count = (self.max_cells as f32 * ratio).round() as usize;
             let tier_count = tier_count.clamp(10, max_per_tier);
 
-            for _ in 0..tier_count {
+            for i in 0..tier_count {
                 let mut new_cell = Cell::spawn(
                     self.config.world_width,
                     self.config.world_height,
                     tier,
                     &self.cached_best_brains[tier],
+                    self.config.vision_mode,
                 );
 
+                // Elitism: the first cell of each tier keeps the saved brain unmutated
+                if i == 0
+                    && let Some((brain, _)) = &self.cached_best_brains[tier]
+                {
+                    new_cell.brain = brain.clone();
+                }
+                new_cell.brain.action_bias = self.config.action_bias;
+                new_cell.brain.set_frozen_layers(self.config.freeze_layers);
+                new_cell.cell_type = spawned_cell_type(&self.config);
+
                 // Give them starting energy
-                new_cell.energy = 100.0;
+                new_cell.set_starting_energy(&self.config.energy);
 
+                new_cell.id = self.next_id();
                 self.cells.push(new_cell);
             }
             total_spawned += tier_count;
         }
 
+        // The elite's pristine copy is the first cell of its tier
+        self.elite_cell_id = self.elite_tier().and_then(|elite_tier| {
+            self.cells
+                .iter()
+                .find(|cell| cell.brain_tier == elite_tier)
+                .map(|cell| cell.id)
+        });
+
         // Spawn dead bodies (20-30% of total spawn count) for food
         let corpse_count = (total_spawned as f32 * rand::gen_range(0.2, 0.3)) as usize;
         for _ in 0..corpse_count {
@@ -214,18 +823,26 @@ impl World {
                 self.config.world_height,
                 tier,
                 &self.cached_best_brains[tier],
+                self.config.vision_mode,
             );
 
-            // Make it a corpse with no energy
+            // Make it a corpse. With strict conservation an empty corpse is removed
+            // immediately, so seeded food corpses get the same spawn budget as the
+            // alive cells (EnergyConfig::starting_energy).
             corpse.state = CellState::Corpse;
-            corpse.energy = 0.0;
+            corpse.energy = if self.config.strict_energy_conservation {
+                self.config.energy.starting_energy(corpse.mass)
+            } else {
+                0.0
+            };
             // Randomize age to make it look more natural
             corpse.age = rand::gen_range(10.0, 100.0);
 
+            corpse.id = self.next_id();
             self.cells.push(corpse);
         }
 
-        println!(
+        log_info!(
             "World reset! Spawned {} alive cells + {} corpses (scores: [{:.1}, {:.1}, {:.1}, {:.1}], ratios: [{:.3}, {:.3}, {:.3}, {:.3}])",
             total_spawned,
             corpse_count,
@@ -240,61 +857,129 @@ impl World {
         );
     }
 
+    // Advance the simulation by one frame. Does not read input or touch the
+    // window, so it can run headless; see handle_keyboard_input and
+    // follow_selected_cell for the interactive parts of the frame.
     pub fn update(&mut self, delta_time: f32) {
-        // Handle keyboard controls
-        self.handle_keyboard_input();
+        self.advance_frame(delta_time);
+        self.dispatch_events();
+    }
+
+    // Hand the frame's simulation events to their consumers: every event goes to the
+    // event panel, important ones also to the event log (and the browser console)
+    fn dispatch_events(&mut self) {
+        for event in self.events.drain() {
+            if event.is_important() {
+                log_info!("{}", event.message());
+                #[cfg(target_arch = "wasm32")]
+                crate::events::log_to_console(&event);
+            }
+            self.stats.event_panel.record(self.tick_count, event);
+        }
+    }
+
+    fn advance_frame(&mut self, delta_time: f32) {
+        self.clock += delta_time as f64;
+
+        // Requests from the hosting page queued since the last frame (see ffi.rs)
+        for command in crate::ffi::take_commands() {
+            self.apply_command(command);
+        }
+        crate::ffi::publish_stats(self.sim_stats());
 
         // Update FPS tracking
         self.update_fps(delta_time);
 
+        // Sample the heatmap (once per second, independent of rendering)
+        self.heatmap.update(delta_time, &self.cells);
+
         // Skip simulation if paused
         if self.paused {
             return;
         }
 
-        // Adjust max_cells cap based on FPS (once per frame)
-        self.adjust_cell_cap();
+        // Accumulate scaled real time and run fixed-size ticks, so 2.0x speed
+        // really runs twice the ticks and behavior doesn't depend on frame rate
+        let (steps, leftover) =
+            fixed_timestep_steps(self.tick_accumulator, delta_time, self.simulation_speed);
+        self.tick_accumulator = leftover;
+
+        // In playback mode, step through recorded frames instead of simulating
+        if self.replay.is_playing() {
+            for _ in 0..steps {
+                self.step();
+            }
+            return;
+        }
+
+        // Adjust max_cells cap based on FPS (once per frame) unless pinned
+        if self.auto_cell_cap && self.config.max_cells.is_none() {
+            self.adjust_cell_cap();
+        }
+
+        for _ in 0..steps {
+            self.step();
+        }
+
+        self.update_auto_save(delta_time);
+    }
+
+    // Advance exactly one fixed tick: a full simulation tick (cell updates, collisions,
+    // reproduction, sensors, stats), or one recorded frame during replay playback.
+    // Ignores `paused`, so the step key can call it while paused.
+    pub fn step(&mut self) {
+        if self.replay.is_playing() {
+            self.replay.advance_playback();
+        } else {
+            self.run_simulation_tick(FIXED_DT);
+        }
+    }
 
-        // Add simulation speed to accumulator and run multiple ticks if needed
-        self.tick_accumulator += self.simulation_speed;
+    // Step key: one tick while paused (no-op while running), logged with the tick number
+    fn step_paused(&mut self) {
+        if !self.paused {
+            return;
+        }
+        self.step();
+        log_info!("⏭ Stepped to tick {}", self.tick_count);
+    }
 
-        // Run simulation ticks based on accumulated time
-        while self.tick_accumulator >= 1.0 {
-            self.run_simulation_tick(delta_time);
-            self.tick_accumulator -= 1.0;
+    // Periodically persist the best brain and append a stats row,
+    // so unattended runs never lose more than auto_save_interval seconds
+    fn update_auto_save(&mut self, delta_time: f32) {
+        self.time_since_auto_save += delta_time;
+        if self.time_since_auto_save < self.config.auto_save_interval {
+            return;
+        }
+        self.time_since_auto_save = 0.0;
+        if !self.config.use_storage {
+            return;
         }
 
-        // Update camera to follow selected cell if stats box is selected (once per frame)
+        self.save_best_brain();
+
+        let best_fitness = self
+            .best_alive_cell()
+            .map_or(0.0, |cell| self.fitness().score(cell));
+        let row = format!(
+            "{},{},{:.1},{:.4}",
+            self.tick_count,
+            self.alive_count(),
+            best_fitness,
+            self.diversity()
+        );
+        crate::storage::append_stats_log(&self.stats_log_path, &row);
+    }
+
+    // Update camera to follow selected cell if stats box is selected (once per frame)
+    pub fn follow_selected_cell(&mut self) {
         if let Some((x, y)) = self.stats.get_selected_position() {
             // Center the camera on the selected cell using 10% of the delta
             let screen_w = screen_width();
             let screen_h = screen_height();
             let target_camera_x = x - screen_w / 2.0;
             let target_camera_y = y - screen_h / 2.0;
-
-            // Calculate delta (distance to target)
-            let mut delta_x = target_camera_x - self.camera.x;
-            let mut delta_y = target_camera_y - self.camera.y;
-
-            // Account for world wrapping - take shortest path around toroidal world
-            let world_width = self.config.world_width;
-            let world_height = self.config.world_height;
-
-            if delta_x.abs() > world_width / 2.0 {
-                if delta_x > 0.0 {
-                    delta_x -= world_width;
-                } else {
-                    delta_x += world_width;
-                }
-            }
-
-            if delta_y.abs() > world_height / 2.0 {
-                if delta_y > 0.0 {
-                    delta_y -= world_height;
-                } else {
-                    delta_y += world_height;
-                }
-            }
+            let (delta_x, delta_y) = self.camera_delta_to(target_camera_x, target_camera_y);
 
             // Move camera towards target using configured tracking speed
             self.camera.target_x = self.camera.x + delta_x * self.config.camera_tracking_speed;
@@ -302,35 +987,168 @@ impl World {
         }
     }
 
+    // Auto-director (Y): any manual camera input hands control back, otherwise cut to
+    // the busiest region each time the current shot has been held long enough
+    pub fn direct_camera(&mut self, delta_time: f32) {
+        if !self.director.is_active() {
+            return;
+        }
+        if self.camera.user_moved() {
+            self.director.stop();
+            log_info!("🎬 Auto-director off (manual camera input)");
+            return;
+        }
+        if let Some((x, y)) = self.director.next_shot(delta_time) {
+            self.center_camera_on(x, y);
+            self.camera.target_zoom = self.config.director.zoom;
+        }
+    }
+
     // Run a single simulation tick
     fn run_simulation_tick(&mut self, delta_time: f32) {
         // Increment tick counter and elapsed time
         self.tick_count += 1;
         self.elapsed_time += delta_time;
+        self.total_ticks += 1;
+        self.sim_time += delta_time as f64;
+
+        // Advance the day/night cycle
+        if self.config.day_length > 0.0 {
+            self.time_of_day = (self.time_of_day + delta_time / self.config.day_length).fract();
+        }
+
+        // Periodically strike the dominant hue cluster with a plague
+        if self.config.plague_enabled {
+            self.time_since_plague += delta_time;
+            if self.time_since_plague >= self.config.plague_interval {
+                self.time_since_plague = 0.0;
+                self.try_start_plague();
+            }
+        }
+
+        // A population stuck in too few hue species gets a batch of random immigrants
+        let immigration = self.config.immigration;
+        if immigration.enabled
+            && !self.species_alive_counts.is_empty()
+            && self.color_diversity < immigration.diversity_threshold
+        {
+            self.low_diversity_time += delta_time;
+            if self.low_diversity_time >= immigration.stagnation_seconds {
+                self.low_diversity_time = 0.0;
+                self.inject_immigrants();
+            }
+        } else {
+            self.low_diversity_time = 0.0;
+        }
+
+        self.apply_energy_brush();
+        self.director.activity.decay();
+
+        // Food blooms count down in simulation time; a new one can start at random
+        self.count_down_food_bloom(delta_time);
+        if self.food_bloom.is_none() && rand::gen_range(0.0, 1.0) < FOOD_BLOOM_PROB_PER_TICK {
+            self.start_food_bloom();
+        }
+
+        // Succession regions move on once per SUCCESSION_STEP_SECONDS of simulation time
+        if let Some(regions) = &mut self.regions {
+            self.time_since_succession += delta_time;
+            if self.time_since_succession >= SUCCESSION_STEP_SECONDS {
+                self.time_since_succession -= SUCCESSION_STEP_SECONDS;
+                let alive = self.cells.iter().filter(|c| c.state == CellState::Alive);
+                let density = regions.density_map(alive.map(|c| (c.x, c.y)));
+                regions.step(&density);
+            }
+        }
 
         // Auto-reset after 10 minutes of elapsed simulation time
         const AUTO_RESET_TIME: f32 = 600.0; // 10 minutes in seconds
         if self.elapsed_time >= AUTO_RESET_TIME && self.best_cell_genome.is_some() {
-            println!("Auto-reset triggered after 10 minutes of simulation time");
+            log_info!("Auto-reset triggered after 10 minutes of simulation time");
             self.respawn_from_best();
         }
 
         // Capture world dimensions for parallel context
         let world_width = self.config.world_width;
         let world_height = self.config.world_height;
+        let metabolism_multiplier = self.metabolism_multiplier();
+        let energy = self.config.energy;
+        let movement = self.config.movement;
+        let temperature = self.config.temperature;
+
+        // Positions before moving, to undo moves through closed island barriers
+        let previous_positions: Vec<(f32, f32)> = match self.islands {
+            Some(_) => self.cells.iter().map(|cell| (cell.x, cell.y)).collect(),
+            None => Vec::new(),
+        };
 
-        // Parallel cell updates
+        // Parallel cell updates, metabolism scaled by the zones and succession region
+        // each cell is in
+        let zones = &self.zones;
+        let regions = self.regions.as_ref();
         self.cells.par_iter_mut().for_each(|cell| {
-            cell.update(world_width, world_height);
+            let zone_multiplier = crate::zones::metabolism_multiplier(
+                zones,
+                cell.x,
+                cell.y,
+                (world_width, world_height),
+            ) * regions
+                .map_or(1.0, |r| r.metabolism_multiplier(cell.x, cell.y));
+            cell.update(
+                world_width,
+                world_height,
+                metabolism_multiplier * zone_multiplier,
+                &energy,
+                &movement,
+                &temperature,
+            );
         });
 
+        // Strict conservation removes empty corpses, and a starved cell dies at zero
+        // energy. Its body is still food: the corpse keeps the energy the cell died
+        // with plus what it put into growing (body_energy), never new energy.
+        if self.config.strict_energy_conservation {
+            for cell in self.cells.iter_mut().filter(|cell| cell.just_died()) {
+                cell.energy += std::mem::take(&mut cell.body_energy);
+            }
+        }
+
+        // Islands: barriers stop cells, except inside a corridor while corridors are open
+        if let Some(islands) = self.islands {
+            let open = islands.corridors_open(self.elapsed_time);
+            self.cells
+                .par_iter_mut()
+                .zip(previous_positions.par_iter())
+                .for_each(|(cell, &(x, y))| {
+                    if !islands.allows_move((x, y), (cell.x, cell.y), open) {
+                        cell.x = x;
+                        cell.y = y;
+                        cell.velocity_x = 0.0;
+                        cell.velocity_y = 0.0;
+                    }
+                });
+        }
+
+        // Sample movement trails (no per-cell buffers at all while trails are off)
+        if self.config.show_trails && self.tick_count.is_multiple_of(TRAIL_SAMPLE_INTERVAL) {
+            self.cells
+                .par_iter_mut()
+                .for_each(|cell| cell.record_trail());
+        }
+
+        // Report this tick's deaths
+        for cell in self.cells.iter().filter(|cell| cell.just_died()) {
+            self.events.push(SimEvent::CellDied {
+                id: cell.id,
+                fitness: self.fitness().score(cell),
+            });
+        }
+
         // Save best cell's brain if it just died and score improved
-        if let Some(best_idx) = self.last_best_cell_index
-            && best_idx < self.cells.len()
-        {
+        if let Some(best_idx) = self.last_best_cell_id.and_then(|id| self.index_of(id)) {
             let best_cell = &self.cells[best_idx];
             if best_cell.state == CellState::Corpse {
-                let score = best_cell.score();
+                let score = self.fitness().score(best_cell);
                 let tier = best_cell.brain_tier;
                 // Only save if score is positive and better than previous best for this tier
                 if score > self.best_saved_scores[tier] {
@@ -339,1372 +1157,6693 @@ impl World {
                     let children = best_cell.children_count;
                     let energy = best_cell.energy_from_cells;
                     let age = best_cell.age;
-                    crate::storage::save_best_neural_network(
-                        tier,
-                        &brain_clone,
-                        generation,
-                        score,
-                        children,
-                        energy,
-                        age,
-                    );
+                    if self.config.use_storage {
+                        crate::storage::save_best_neural_network(
+                            tier,
+                            &brain_clone,
+                            generation,
+                            score,
+                            children,
+                            energy,
+                            age,
+                        );
+                    }
                     // Update cache and best score
                     self.cached_best_brains[tier] = Some((brain_clone, generation));
                     self.best_saved_scores[tier] = score;
-                    println!("📈 New high score (tier {}): {:.1}", tier, score);
+                    self.events.push(SimEvent::NewRecord {
+                        tier,
+                        fitness: score,
+                    });
                 }
             }
         }
 
         // Build spatial grid for collision detection
         self.rebuild_spatial_grid();
+        if self.tick_count.is_multiple_of(ELITE_TOP_UP_INTERVAL) && !self.cleared_by_user {
+            self.top_up_elite();
+            self.rebuild_spatial_grid();
+        }
+        if self.config.cell_collisions {
+            self.separate_alive_cells();
+        }
         self.check_collisions();
+        self.eat_food_pellets();
+        self.infected_count = self.cells.iter().filter(|c| c.infected.is_some()).count();
 
-        self.handle_reproduction();
+        self.update_reproduction_threshold();
+        self.handle_reproduction(self.dynamic_reproduction_threshold);
 
         // Rebuild spatial grid after collisions/reproduction changed cell array
         self.rebuild_spatial_grid();
+        self.update_pheromones();
         self.update_sensors();
+        self.lateral_gene_transfer();
 
-        self.update_stats();
-
-        // Check for extinction and respawn if needed (after stats to ensure best_cell_genome is set)
-        // Count alive cells
-        let alive_count = self
-            .cells
-            .iter()
-            .filter(|c| c.state == CellState::Alive)
-            .count();
-        if alive_count == 0 && self.best_cell_genome.is_some() {
-            self.respawn_from_best();
+        // Refresh the cached density overlay while it is shown
+        if self.config.show_ui
+            && self.config.show_density_map
+            && self.tick_count.is_multiple_of(DENSITY_MAP_INTERVAL)
+        {
+            self.density_map = self.compute_density_map();
         }
-    }
 
-    // Check if mouse is over stats box
-    pub fn is_mouse_over_stats(&self, mouse_x: f32, mouse_y: f32) -> bool {
-        self.stats
-            .is_mouse_over(mouse_x, mouse_y, self.font.as_ref())
-    }
-
-    // Handle mouse clicks on the stats box
-    pub fn handle_stats_click(&mut self) {
-        if is_mouse_button_pressed(MouseButton::Left) {
-            let mouse_pos = mouse_position();
-            if self
-                .stats
-                .is_mouse_over(mouse_pos.0, mouse_pos.1, self.font.as_ref())
-            {
-                self.stats.toggle_selection();
+        // Recount species periodically (clustering brains is expensive)
+        if self.tick_count.is_multiple_of(SPECIES_UPDATE_INTERVAL) {
+            self.update_species();
+            if self.config.genome_diversity {
+                self.genome_diversity = self.compute_genome_diversity();
             }
         }
-    }
 
-    // Handle keyboard input for simulation controls
-    fn handle_keyboard_input(&mut self) {
-        // Space: Toggle pause
-        if is_key_pressed(KeyCode::Space) {
-            self.paused = !self.paused;
-            println!(
-                "Simulation {}",
-                if self.paused { "PAUSED" } else { "RESUMED" }
-            );
-        }
+        self.update_stats();
 
-        // R: Manual reset with best genome
-        if is_key_pressed(KeyCode::R) {
+        // Record a replay frame (no-op unless recording)
+        self.replay.record_tick(self.tick_count, &self.cells);
+
+        // Check for extinction and respawn if needed (after stats to ensure best_cell_genome is set)
+        if self.alive_count() == 0 && !self.cleared_by_user {
             if self.best_cell_genome.is_some() {
                 self.respawn_from_best();
-                println!("Manual reset triggered");
-            } else {
-                println!("No best genome available for reset");
+            } else if self.config.auto_restart_on_extinction && self.config.initial_cell_count > 0 {
+                self.restart_from_scratch();
             }
         }
+    }
 
-        // + or =: Increase speed
-        if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
-            self.simulation_speed = (self.simulation_speed * 1.5).min(8.0);
-            println!("Simulation speed: {:.1}x", self.simulation_speed);
+    // Summarize the era a reset is about to end: log it and, with storage on,
+    // append it to the era log file
+    fn finish_era(&mut self) {
+        let summary = self.eras.finish(self.tick_count);
+        log_info!("📜 {}", summary.message());
+        if self.config.use_storage {
+            match serde_json::to_string(summary) {
+                Ok(line) => crate::storage::append_era_log(&line),
+                Err(e) => log_warn!("⚠ Failed to serialize era summary: {}", e),
+            }
         }
+    }
 
-        // - or _: Decrease speed
-        if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
-            self.simulation_speed = (self.simulation_speed / 1.5).max(1.0);
-            println!("Simulation speed: {:.1}x", self.simulation_speed);
+    // Print every finished era to stdout and the event log (Ctrl+Y)
+    pub fn dump_era_history(&self) {
+        println!("===== Eras ({} finished) =====", self.eras.history.len());
+        for summary in &self.eras.history {
+            println!("{}", summary.message());
+            log_info!("📜 {}", summary.message());
+        }
+        if self.eras.history.is_empty() {
+            log_info!(
+                "📜 No finished era yet (era {} running)",
+                self.eras.current_era()
+            );
         }
+    }
 
-        // 1: Reset to normal speed
-        if is_key_pressed(KeyCode::Key1) {
-            self.simulation_speed = 1.0;
-            println!("Simulation speed: 1.0x (normal)");
+    // Extinction before any genome was saved: start over with a fresh startup population
+    fn restart_from_scratch(&mut self) {
+        self.reset_world_state();
+        self.cells = initial_population(&self.config, &self.cached_best_brains);
+        for cell in &mut self.cells {
+            cell.id = self.next_cell_id;
+            self.next_cell_id += 1;
         }
+        self.refresh_after_population_change();
+        log_warn!(
+            "☠ Extinct before any genome was saved: restarted with {} fresh cells",
+            self.cells.len()
+        );
     }
 
-    // Rebuild spatial grid with all current cell positions
-    fn rebuild_spatial_grid(&mut self) {
-        self.spatial_grid.clear();
-        for (idx, cell) in self.cells.iter().enumerate() {
-            self.spatial_grid.insert(cell.x, cell.y, idx);
-        }
+    // Remove every cell, leaving an empty world for manual spawning. The best genome
+    // is kept, and extinction respawn stays off until the next reset (R).
+    pub fn clear_all(&mut self) -> usize {
+        let removed = self.cells.len();
+        self.cells.clear();
+        self.food.clear();
+        self.pheromones.clear();
+        self.director.activity.clear();
+        self.cleared_by_user = true;
+        self.refresh_after_population_change();
+        log_info!("🧹 Cleared all {} cells", removed);
+        removed
     }
 
-    fn update_fps(&mut self, delta_time: f32) {
-        // Add current frame time
-        self.frame_times.push_back(delta_time);
-
-        // Keep only the last FPS_SAMPLE_SIZE frames
-        while self.frame_times.len() > FPS_SAMPLE_SIZE {
-            self.frame_times.pop_front();
+    // Remove the lowest scoring `fraction` of the alive cells in one step (corpses
+    // are left alone). best_cell_genome is untouched, so respawning still works.
+    // Returns the number of cells removed.
+    pub fn cull_weakest(&mut self, fraction: f32) -> usize {
+        let mut scores: Vec<(f32, u64)> = self
+            .cells
+            .iter()
+            .filter(|c| c.state == CellState::Alive)
+            .map(|c| (self.fitness().score(c), c.id))
+            .collect();
+        let count =
+            ((scores.len() as f32 * fraction.clamp(0.0, 1.0)).round() as usize).min(scores.len());
+        if count == 0 {
+            return 0;
         }
 
-        // Calculate average FPS
-        if !self.frame_times.is_empty() {
-            let avg_frame_time: f32 =
-                self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32;
-            self.current_fps = if avg_frame_time > 0.0 {
-                1.0 / avg_frame_time
-            } else {
-                60.0 // Fallback
-            };
+        // Partial sort: only the culled set needs to be found, not ordered
+        scores.select_nth_unstable_by(count - 1, |a, b| a.0.total_cmp(&b.0));
+        let culled: std::collections::HashSet<u64> =
+            scores[..count].iter().map(|&(_, id)| id).collect();
+        self.cells.retain(|c| !culled.contains(&c.id));
+        self.refresh_after_population_change();
+        log_info!(
+            "✂ Culled the weakest {} cells ({:.0}%)",
+            count,
+            fraction * 100.0
+        );
+        count
+    }
+
+    // Genetic bottleneck: every alive cell but the BOTTLENECK_SURVIVORS fittest dies at
+    // once (corpses are left alone). The survivors keep their full state, and the world
+    // refills to the respawn population with mutated children of the survivors taken
+    // round-robin, scattered at random positions across the world so the recovery
+    // doesn't start from a few clumps. Returns the number of cells removed.
+    pub fn trigger_bottleneck(&mut self) -> usize {
+        let mut top = self.compute_top_cells(BOTTLENECK_SURVIVORS);
+        if top.is_empty() {
+            return 0;
+        }
+        top.sort_unstable_by_key(|entry| entry.id);
+        let survivor_ids: Vec<u64> = top.into_iter().map(|entry| entry.id).collect();
+        let survivors: Vec<Cell> = survivor_ids
+            .iter()
+            .filter_map(|&id| self.index_of(id).map(|idx| self.cells[idx].clone()))
+            .collect();
+        let before = self.cells.len();
+        self.cells.retain(|cell| {
+            cell.state != CellState::Alive || survivor_ids.binary_search(&cell.id).is_ok()
+        });
+        let removed = before - self.cells.len();
+        let spawn_count = self.max_cells.min(self.config.initial_cell_count).max(100);
+        let offspring = spawn_count.saturating_sub(survivors.len());
+        for i in 0..offspring {
+            let mut child = survivors[i % survivors.len()].spawn_child(
+                self.config.mutation_rate_multiplier,
+                self.config.weight_decay,
+                self.config.structured_mutation,
+            );
+            child.x = rand::gen_range(0.0, self.config.world_width);
+            child.y = rand::gen_range(0.0, self.config.world_height);
+            child.set_starting_energy(&self.config.energy);
+            child.id = self.next_id();
+            self.cells.push(child);
         }
+
+        self.bottleneck_count += 1;
+        self.events.push(SimEvent::Bottleneck {
+            survivors: survivors.len(),
+            offspring,
+        });
+        self.refresh_after_population_change();
+        removed
     }
 
-    fn adjust_cell_cap(&mut self) {
-        // Only adjust every ADJUSTMENT_INTERVAL seconds
-        self.last_adjustment_time += self.frame_times.back().unwrap_or(&0.016);
+    // Start a food bloom, or restart the countdown of the running one
+    pub fn start_food_bloom(&mut self) {
+        let bloom = FoodBloomEvent::new();
+        self.events.push(SimEvent::FoodBloom {
+            duration: bloom.duration_remaining,
+        });
+        self.food_bloom = Some(bloom);
+        self.bloom_count += 1;
+    }
 
-        if self.last_adjustment_time < ADJUSTMENT_INTERVAL {
-            return;
+    fn count_down_food_bloom(&mut self, delta_time: f32) {
+        if let Some(bloom) = &mut self.food_bloom
+            && !bloom.tick(delta_time)
+        {
+            self.food_bloom = None;
+            log_info!("Food bloom ended");
         }
+    }
 
-        self.last_adjustment_time = 0.0;
-
-        // Adjust cap based on FPS
-        if self.current_fps < TARGET_MIN_FPS {
-            // FPS too low, reduce cap
-            self.max_cells = self.max_cells.saturating_sub(CELL_CAP_STEP);
-        } else if self.current_fps > TARGET_MAX_FPS {
-            // FPS very high, increase cap quickly
-            self.max_cells += CELL_CAP_STEP;
-        } else if self.current_fps > TARGET_GOOD_FPS {
-            // FPS comfortably above 60, slowly grow the cap
-            self.max_cells += CELL_CAP_SLOW_STEP;
+    // Food regeneration rate relative to normal: FOOD_BLOOM_MULTIPLIER during a bloom.
+    // Strict conservation never creates energy, so there a bloom recycles as usual.
+    pub fn food_regen_multiplier(&self) -> f32 {
+        if self.food_bloom.is_some() && !self.config.strict_energy_conservation {
+            FOOD_BLOOM_MULTIPLIER
+        } else {
+            1.0
         }
-        self.max_cells = self.max_cells.clamp(10, 7_000);
-        // If FPS is between 30-60, don't change the cap
     }
 
-    fn handle_reproduction(&mut self) {
-        let mut new_cells = Vec::new();
-        let current_cell_count = self.cells.len();
-        let best_cell_idx = self.last_best_cell_index;
+    // Index-based state and stats are stale after cells were removed outside of update
+    fn refresh_after_population_change(&mut self) {
+        self.rebuild_spatial_grid();
+        self.update_stats();
+    }
 
-        for (idx, cell) in self.cells.iter_mut().enumerate() {
-            if cell.energy > REPRODUCTION_ENERGY_THRESHOLD
-                && cell.age < 15.0
-                && cell.ticks_since_last_child >= 2.0
-            {
-                // Check if we're at or over the max_cells cap
-                let projected_count = current_cell_count + new_cells.len();
-                if projected_count >= self.max_cells {
-                    // Cap reached: cell keeps its energy and cannot reproduce
-                    continue;
-                }
+    /// Spawn `immigration.fraction` of the cell cap as fresh random cells (at most as
+    /// many as the cap still has room for): new brains (never the saved ones), random
+    /// traits and a shared hue opposite the dominant species, each highlighted for
+    /// IMMIGRANT_MARKER_TICKS. Returns how many arrived.
+    pub fn inject_immigrants(&mut self) -> usize {
+        let room = self.max_cells.saturating_sub(self.alive_count());
+        let count = ((self.max_cells as f32 * self.config.immigration.fraction)
+            .round()
+            .max(1.0) as usize)
+            .min(room);
+        if count == 0 {
+            return 0;
+        }
+        let hue = self
+            .hue_species
+            .species
+            .iter()
+            .filter(|species| species.alive_count > 0)
+            .max_by_key(|species| species.alive_count)
+            .map_or_else(
+                || rand::gen_range(0.0, 360.0),
+                |species| (species.mean_hue + 180.0).rem_euclid(360.0),
+            );
 
-                // Calculate energy distribution
-                let total_energy = cell.energy;
-                let child_energy = total_energy * CHILD_ENERGY_RATIO;
-                let parent_energy = total_energy * PARENT_ENERGY_RATIO;
+        for _ in 0..count {
+            let mut immigrant = Cell::spawn(
+                self.config.world_width,
+                self.config.world_height,
+                rand::gen_range(0, 4),
+                &None,
+                self.config.vision_mode,
+            );
+            let cell_hue = (hue + rand::gen_range(-15.0, 15.0)).rem_euclid(360.0);
+            immigrant.color = Cell::hsv_to_rgb(cell_hue, 0.8, 0.9);
+            immigrant.brain.action_bias = self.config.action_bias;
+            immigrant.brain.set_frozen_layers(self.config.freeze_layers);
+            immigrant.cell_type = spawned_cell_type(&self.config);
+            immigrant.set_starting_energy(&self.config.energy);
+            immigrant.immigrant_marker = IMMIGRANT_MARKER_TICKS;
+            immigrant.id = self.next_id();
+            self.cells.push(immigrant);
+        }
 
-                // Create child cell
-                let mut child = cell.spawn_child();
-                child.energy = child_energy;
-                new_cells.push(child);
+        log_info!(
+            "🧳 Diversity at {:.0}%: {} immigrants arrived around hue {:.0}°",
+            self.color_diversity * 100.0,
+            count,
+            hue
+        );
+        count
+    }
 
-                // Update parent energy, increment children count, reset spawn cooldown
-                cell.energy = parent_energy;
-                cell.children_count += 1;
-                cell.ticks_since_last_child = 0.0;
+    // Infect a random fraction of the dominant hue cluster if it holds more than
+    // plague_dominance_threshold of the alive population.
+    // Returns the number of newly infected cells.
+    pub fn try_start_plague(&mut self) -> usize {
+        let bin_count = (360.0 / PLAGUE_HUE_BIN_WIDTH).ceil() as usize;
+        let hue_bin = |cell: &Cell| {
+            let (h, _, _) = Cell::rgb_to_hsv_public(cell.color);
+            ((h / PLAGUE_HUE_BIN_WIDTH) as usize).min(bin_count - 1)
+        };
 
-                // Save neural network if this is the best cell reproducing AND score improved
-                if Some(idx) == best_cell_idx {
-                    let score = cell.score();
-                    let tier = cell.brain_tier;
-                    // Only save if score is better than previous best for this tier
-                    if score > self.best_saved_scores[tier] {
-                        let prev_score = self.best_saved_scores[tier];
-                        let brain_clone = cell.brain.clone();
-                        let generation = cell.generation;
-                        let children = cell.children_count;
-                        let energy = cell.energy_from_cells;
-                        let age = cell.age;
-                        crate::storage::save_best_neural_network(
-                            tier,
-                            &brain_clone,
-                            generation,
-                            score,
-                            children,
-                            energy,
-                            age,
-                        );
-                        // Update cache and best score
-                        self.cached_best_brains[tier] = Some((brain_clone, generation));
-                        self.best_saved_scores[tier] = score;
-                        println!(
-                            "📈 New high score (tier {}): {:.1} (previous: {:.1})",
-                            tier, score, prev_score
-                        );
-                    }
-                }
+        let mut bin_counts = vec![0usize; bin_count];
+        for cell in self.cells.iter().filter(|c| c.state == CellState::Alive) {
+            bin_counts[hue_bin(cell)] += 1;
+        }
+        let alive: usize = bin_counts.iter().sum();
+        let Some((dominant_bin, &dominant_count)) = bin_counts
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, &count)| count)
+        else {
+            return 0;
+        };
+        if alive == 0
+            || (dominant_count as f32 / alive as f32) <= self.config.plague_dominance_threshold
+        {
+            return 0;
+        }
+
+        let fraction = self.config.plague_infection_fraction;
+        let mut newly_infected = 0;
+        for cell in self.cells.iter_mut() {
+            if cell.state == CellState::Alive
+                && cell.infected.is_none()
+                && hue_bin(cell) == dominant_bin
+                && rand::gen_range(0.0, 1.0) < fraction
+            {
+                cell.infected = Some(PLAGUE_DURATION_TICKS);
+                newly_infected += 1;
             }
         }
 
-        // Add new cells to the world
-        self.cells.extend(new_cells);
+        log_info!(
+            "🦠 Plague! {} of {} cells in hue {:.0}°-{:.0}° infected",
+            newly_infected,
+            dominant_count,
+            dominant_bin as f32 * PLAGUE_HUE_BIN_WIDTH,
+            (dominant_bin + 1) as f32 * PLAGUE_HUE_BIN_WIDTH
+        );
+        newly_infected
     }
 
-    fn update_sensors(&mut self) {
-        // Spatial grid already built in update(), reuse it
-        // Extract cell data for sensor calculations
-        let cell_data: Vec<(f32, f32, f32, f32, f32)> = self
-            .cells
-            .iter()
-            .map(|c| {
-                let is_alive = if c.state == CellState::Alive {
-                    1.0
-                } else {
-                    0.0
-                };
-                (c.x, c.y, c.energy, c.mass, is_alive)
+    // Tier whose saved brain has the best score: its unmutated brain is the elite genome
+    fn elite_tier(&self) -> Option<usize> {
+        (0..4)
+            .filter(|&tier| self.cached_best_brains[tier].is_some())
+            .max_by(|&a, &b| {
+                self.best_saved_scores[a]
+                    .partial_cmp(&self.best_saved_scores[b])
+                    .unwrap_or(std::cmp::Ordering::Equal)
             })
-            .collect();
+    }
 
-        // Capture world dimensions for parallel context
-        let world_width = self.config.world_width;
-        let world_height = self.config.world_height;
+    // Make sure a cell with the unmutated elite brain is alive, spawning one if the
+    // previous carrier died (and there is room under the cap). Returns the new cell's id.
+    pub fn top_up_elite(&mut self) -> Option<u64> {
+        let carrier_alive = self
+            .elite_cell_id
+            .and_then(|id| self.index_of(id))
+            .and_then(|idx| self.cells.get(idx))
+            .is_some_and(|cell| cell.state == CellState::Alive);
+        if carrier_alive || self.cells.len() >= self.max_cells {
+            return None;
+        }
 
-        // Calculate local density for each cell (must be done before parallel update)
-        let density_counts: Vec<usize> = self
-            .cells
+        let tier = self.elite_tier()?;
+        let (brain, generation) = self.cached_best_brains[tier].clone()?;
+        let mut elite = Cell::spawn(
+            self.config.world_width,
+            self.config.world_height,
+            tier,
+            &None,
+            self.config.vision_mode,
+        );
+        elite.brain = brain;
+        elite.brain.action_bias = self.config.action_bias;
+        elite.brain.set_frozen_layers(self.config.freeze_layers);
+        elite.generation = generation;
+        elite.set_starting_energy(&self.config.energy);
+        elite.id = self.next_id();
+        let id = elite.id;
+        self.cells.push(elite);
+        self.elite_cell_id = Some(id);
+        Some(id)
+    }
+
+    // Alive cells mark their bucket, then the field decays and diffuses (once per tick)
+    fn update_pheromones(&mut self) {
+        let settings = self.config.pheromones;
+        if !settings.enabled {
+            return;
+        }
+        for cell in &self.cells {
+            if cell.state == CellState::Alive {
+                self.pheromones
+                    .deposit(cell.x, cell.y, settings.deposit_amount);
+            }
+        }
+        self.pheromones
+            .update(settings.decay_rate, settings.diffusion_rate);
+    }
+
+    // Alive cells per spatial grid bucket, normalized so the densest bucket is 1.0
+    pub fn compute_density_map(&self) -> Vec<f32> {
+        let counts: Vec<usize> = self
+            .spatial_grid
+            .buckets()
             .iter()
-            .map(|cell| {
-                let count = self
-                    .spatial_grid
-                    .count_nearby_in_bucket(cell.x, cell.y, SENSOR_RANGE);
-                count.max(1) // Ensure count is at least 1 to avoid division by zero
+            .map(|bucket| {
+                bucket
+                    .iter()
+                    .filter(|&&idx| {
+                        self.cells
+                            .get(idx)
+                            .is_some_and(|cell| cell.state == CellState::Alive)
+                    })
+                    .count()
             })
             .collect();
 
-        // Capture max_cells for density penalty calculation
-        let max_cells = self.max_cells;
-
-        // Update sensors for each cell in parallel
-        self.cells.par_iter_mut().enumerate().for_each(|(i, cell)| {
-            // Update local density from pre-calculated counts
-            cell.local_density = density_counts[i];
+        let max = counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+        counts.iter().map(|&count| count as f32 / max).collect()
+    }
 
-            // Calculate density penalty if cluster > 50% of population cap
-            if cell.local_density > max_cells / 2 {
-                // Penalty: (1 - (1 / nb_cells))
-                // This value is subtracted from score, so higher density = higher penalty
-                cell.density_penalty = 1.0 - (1.0 / cell.local_density as f32);
-            } else {
-                // No penalty when not overcrowded
-                cell.density_penalty = 0.0;
-            }
-            // Query nearby cells using spatial grid
-            let nearby_indices = self.spatial_grid.query_nearby(cell.x, cell.y, SENSOR_RANGE);
+    // ExtinctionNear once per decline (an empty world respawns instead), and a
+    // GenerationMilestone whenever the newest generation passes the next one
+    fn push_population_events(&mut self, alive_count: usize, max_generation: usize) {
+        if alive_count > EXTINCTION_NEAR_ALIVE * 2 {
+            self.extinction_warned = false;
+        } else if alive_count > 0 && alive_count <= EXTINCTION_NEAR_ALIVE && !self.extinction_warned
+        {
+            self.extinction_warned = true;
+            self.events.push(SimEvent::ExtinctionNear { alive_count });
+        }
 
-            // Calculate distances and angles to all nearby cells
-            let mut sensor_data: Vec<(usize, f32, f32, f32, f32, f32)> = nearby_indices
-                .iter()
-                .filter_map(|&j| {
-                    if i == j {
-                        return None; // Skip self
-                    }
+        let milestone =
+            max_generation / GENERATION_MILESTONE_INTERVAL * GENERATION_MILESTONE_INTERVAL;
+        if milestone > self.generation_milestone {
+            self.generation_milestone = milestone;
+            self.events.push(SimEvent::GenerationMilestone {
+                generation: milestone,
+            });
+        }
+    }
 
-                    // Bounds check for safety
-                    if j >= cell_data.len() {
-                        return None;
-                    }
+    // Number of alive cells
+    pub fn alive_count(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|c| c.state == CellState::Alive)
+            .count()
+    }
 
-                    let (x2, y2, energy, mass, is_alive) = cell_data[j];
+    // What every score in the world is computed with: the plugin if one is set,
+    // otherwise the configured built-in
+    pub fn fitness(&self) -> Fitness {
+        match self.fitness_plugin {
+            Some(plugin) => Fitness::Plugin(plugin),
+            None => Fitness::Builtin(self.config.fitness),
+        }
+    }
 
-                    // Handle wrapping distance calculation
-                    let mut dx = x2 - cell.x;
-                    let mut dy = y2 - cell.y;
+    // Extension point for experimenting with fitness definitions in code: call it
+    // from main.rs after World::new. None returns to config.fitness. Only tests
+    // call it in this tree, hence the allow.
+    #[allow(dead_code)]
+    pub fn set_fitness_plugin(&mut self, plugin: Option<FitnessPlugin>) {
+        self.fitness_plugin = plugin;
+        self.forget_saved_scores();
+        log_info!("🏆 Fitness function: {}", self.fitness().name());
+        self.update_stats();
+    }
 
-                    // Adjust for world wrapping
-                    if dx.abs() > world_width / 2.0 {
-                        dx = dx - dx.signum() * world_width;
-                    }
-                    if dy.abs() > world_height / 2.0 {
-                        dy = dy - dy.signum() * world_height;
-                    }
+    // Switch to the next fitness preset (dropping any plugin) and re-pick the best
+    // cell right away, so the highlight follows the new definition even while paused
+    pub fn cycle_fitness(&mut self) {
+        self.fitness_plugin = None;
+        self.config.fitness = self.config.fitness.next_preset();
+        self.forget_saved_scores();
+        log_info!("🏆 Fitness function: {}", self.fitness().name());
+        self.update_stats();
+    }
 
-                    let distance_squared = dx * dx + dy * dy;
+    // Saved scores are in the units of the fitness that produced them, so after a
+    // switch the next best cell under the new definition takes over each slot
+    fn forget_saved_scores(&mut self) {
+        self.best_saved_scores = [0.0; 4];
+    }
 
-                    // Filter out cells that are too far using squared distance to avoid sqrt()
-                    if distance_squared > SENSOR_RANGE_SQUARED {
-                        return None;
-                    }
+    // Highest scoring alive cell, if any
+    pub fn best_alive_cell(&self) -> Option<&Cell> {
+        let fitness = self.fitness();
+        self.cells
+            .iter()
+            .filter(|c| c.state == CellState::Alive)
+            .max_by(|a, b| {
+                fitness
+                    .score(a)
+                    .partial_cmp(&fitness.score(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
 
-                    let distance = distance_squared.sqrt();
+    // Save the best alive cell's brain if it beats the saved score for its tier
+    pub fn save_best_brain(&mut self) {
+        let Some(best_cell) = self.best_alive_cell() else {
+            return;
+        };
+        let score = self.fitness().score(best_cell);
+        let tier = best_cell.brain_tier;
+        if score <= self.best_saved_scores[tier] {
+            return;
+        }
 
-                    // Calculate angle to target relative to cell's facing direction
-                    let angle_to_target = dy.atan2(dx);
-                    let mut angle_from_front = angle_to_target - cell.angle;
+        let brain_clone = best_cell.brain.clone();
+        let generation = best_cell.generation;
+        if self.config.use_storage {
+            crate::storage::save_best_neural_network(
+                tier,
+                &brain_clone,
+                generation,
+                score,
+                best_cell.children_count,
+                best_cell.energy_from_cells,
+                best_cell.age,
+            );
+        }
+        self.cached_best_brains[tier] = Some((brain_clone, generation));
+        self.best_saved_scores[tier] = score;
+        self.events.push(SimEvent::NewRecord {
+            tier,
+            fitness: score,
+        });
+    }
 
-                    // Normalize angle to -PI..PI range
-                    while angle_from_front > std::f32::consts::PI {
-                        angle_from_front -= std::f32::consts::TAU;
-                    }
-                    while angle_from_front < -std::f32::consts::PI {
-                        angle_from_front += std::f32::consts::TAU;
+    /// Cluster cells into species by neural network weight distance.
+    /// Greedy leader clustering: each cell joins the first species whose founding
+    /// brain is within SPECIES_DISTANCE_THRESHOLD, otherwise it founds a new one.
+    /// Brains of different tiers have different shapes and never share a species,
+    /// so tiers are clustered independently (in parallel).
+    /// Returns a species id per cell, in `cells` order.
+    pub fn compute_species(&self) -> Vec<usize> {
+        let tier_clusters: Vec<Vec<(usize, usize)>> = (0..4)
+            .into_par_iter()
+            .map(|tier| {
+                let mut founders: Vec<usize> = Vec::new();
+                let mut assignments = Vec::new();
+                for (i, cell) in self.cells.iter().enumerate() {
+                    if cell.brain_tier.min(3) != tier {
+                        continue;
                     }
+                    let species = founders
+                        .iter()
+                        .position(|&founder| {
+                            self.cells[founder]
+                                .brain
+                                .is_within_distance(&cell.brain, SPECIES_DISTANCE_THRESHOLD)
+                        })
+                        .unwrap_or_else(|| {
+                            founders.push(i);
+                            founders.len() - 1
+                        });
+                    assignments.push((i, species));
+                }
+                assignments
+            })
+            .collect();
 
-                    // Return (index, angle_from_front, distance, mass, is_alive, energy)
-                    Some((j, angle_from_front, distance, mass, is_alive, energy))
-                })
-                .collect();
-
-            // Use partial sort to get top SENSOR_COUNT without sorting the entire vec
-            // Priority: dead > alive, then high energy > low energy, then close > far
-            if sensor_data.len() > SENSOR_COUNT {
-                // Use select_nth_unstable to partition around the (SENSOR_COUNT-1)th element
-                // This partitions so elements [0..SENSOR_COUNT] are the smallest/best
-                sensor_data.select_nth_unstable_by(SENSOR_COUNT - 1, |a, b| {
-                    let is_alive_a = a.4; // 1.0 if alive, 0.0 if dead
-                    let is_alive_b = b.4;
-                    let energy_a = a.5; // Energy is now at position 5
-                    let energy_b = b.5;
-
-                    // Sort by alive status ascending (0.0 before 1.0, so dead before alive)
-                    // Then by energy descending, then by distance ascending
-                    is_alive_a
-                        .partial_cmp(&is_alive_b)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                        .then(
-                            energy_b
-                                .partial_cmp(&energy_a)
-                                .unwrap_or(std::cmp::Ordering::Equal),
-                        )
-                        .then(a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal))
-                });
-                // Keep only the top SENSOR_COUNT
-                sensor_data.truncate(SENSOR_COUNT);
+        // Offset each tier's local ids so species ids are unique across tiers
+        let mut species = vec![0; self.cells.len()];
+        let mut offset = 0;
+        for assignments in tier_clusters {
+            let tier_species = assignments.iter().map(|&(_, s)| s + 1).max().unwrap_or(0);
+            for (i, s) in assignments {
+                species[i] = offset + s;
             }
+            offset += tier_species;
+        }
+        species
+    }
 
-            // Calculate center of mass for dead and alive cells separately
-            let (dead_cells, alive_cells): (Vec<_>, Vec<_>) = sensor_data
-                .iter()
-                .partition(|&&(_, _, _, _, is_alive, _)| is_alive == 0.0);
-
-            let dead_count = dead_cells.len() as f32;
-            let alive_count = alive_cells.len() as f32;
-            let total_count = dead_count + alive_count;
+    // Number of species with at least one alive member
+    fn count_alive_species(&self, species: &[usize]) -> usize {
+        let mut alive_species: Vec<usize> = self
+            .cells
+            .iter()
+            .zip(species)
+            .filter(|(cell, _)| cell.state == CellState::Alive)
+            .map(|(_, &s)| s)
+            .collect();
+        alive_species.sort_unstable();
+        alive_species.dedup();
+        alive_species.len()
+    }
 
-            // Calculate dead/alive ratio: -1.0 = all alive, 1.0 = all dead, 0.0 = balanced
-            cell.dead_alive_ratio = if total_count > 0.0 {
-                (dead_count - alive_count) / total_count
-            } else {
-                0.0
-            };
+    // Recluster species, updating the alive species count and the per-id cache used for coloring
+    fn update_species(&mut self) {
+        let species = self.compute_species();
+        self.species_count = self.count_alive_species(&species);
+        self.species_by_id = self
+            .cells
+            .iter()
+            .zip(&species)
+            .map(|(cell, &s)| (cell.id, s))
+            .collect();
+    }
 
-            // Calculate center of mass for dead cells
-            if !dead_cells.is_empty() {
-                let mut sum_x = 0.0f32;
-                let mut sum_y = 0.0f32;
+    // Mean NeuralNetwork::distance over all pairs of up to GENOME_DIVERSITY_SAMPLE alive
+    // cells, taken at an even stride so no random numbers are drawn. Pairs from
+    // different tiers (infinitely far apart) are left out; 0.0 without any pair.
+    pub fn compute_genome_diversity(&self) -> f32 {
+        let alive: Vec<&Cell> = self
+            .cells
+            .iter()
+            .filter(|cell| cell.state == CellState::Alive)
+            .collect();
+        let stride = alive.len().div_ceil(GENOME_DIVERSITY_SAMPLE).max(1);
+        let sample: Vec<&Cell> = alive.into_iter().step_by(stride).collect();
 
-                for &&(_j, angle_from_front, distance, _, _, _) in &dead_cells {
-                    // Convert to cell's local coordinate frame (angle_from_front is already relative to facing direction)
-                    sum_x += angle_from_front.cos() * distance;
-                    sum_y += angle_from_front.sin() * distance;
-                }
+        let (total, pairs) = (0..sample.len())
+            .into_par_iter()
+            .map(|i| {
+                sample[i + 1..]
+                    .iter()
+                    .map(|other| sample[i].brain.distance(&other.brain))
+                    .filter(|distance| distance.is_finite())
+                    .fold((0.0, 0), |(total, pairs), distance| {
+                        (total + distance, pairs + 1)
+                    })
+            })
+            .reduce(|| (0.0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+        if pairs > 0 { total / pairs as f32 } else { 0.0 }
+    }
 
-                let avg_x = sum_x / dead_count;
-                let avg_y = sum_y / dead_count;
+    // Diversity reported in the stats log and sim_get_stats (see SimulationConfig::genome_diversity)
+    pub fn diversity(&self) -> f32 {
+        if self.config.genome_diversity {
+            self.genome_diversity
+        } else {
+            self.color_diversity
+        }
+    }
 
-                // Calculate angle and distance to center of mass (in cell's local frame)
-                cell.dead_center_distance = (avg_x * avg_x + avg_y * avg_y).sqrt();
-                cell.dead_center_angle = avg_y.atan2(avg_x); // Relative to cell's facing direction
-            } else {
-                // No dead cells detected
-                cell.dead_center_angle = 0.0;
-                cell.dead_center_distance = SENSOR_RANGE; // Max range indicates nothing detected
+    // Cheap FNV-1a hash of every cell's id, position and energy (bit-exact)
+    // Two runs from the same seed and config produce the same hash at every tick
+    pub fn tick_hash(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let mut hash = FNV_OFFSET;
+        let mut mix = |value: u64| {
+            for byte in value.to_le_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
             }
+        };
+
+        mix(self.tick_count as u64);
+        for cell in &self.cells {
+            mix(cell.id);
+            mix(cell.x.to_bits() as u64);
+            mix(cell.y.to_bits() as u64);
+            mix(cell.energy.to_bits() as u64);
+        }
+        for pellet in &self.food.pellets {
+            mix(pellet.id);
+            mix(pellet.energy.to_bits() as u64);
+        }
+        mix(self.pheromones.total().to_bits() as u64);
+        hash
+    }
 
-            // Calculate center of mass for alive cells
-            if !alive_cells.is_empty() {
-                let mut sum_x = 0.0f32;
-                let mut sum_y = 0.0f32;
+    // Metabolism multiplier for the current time of day
+    // 1.0 at noon, night_metabolism_multiplier at midnight
+    pub fn metabolism_multiplier(&self) -> f32 {
+        metabolism_multiplier_at(self.time_of_day, self.config.night_metabolism_multiplier)
+    }
 
-                for &&(_j, angle_from_front, distance, _, _, _) in &alive_cells {
-                    // Convert to cell's local coordinate frame (angle_from_front is already relative to facing direction)
-                    sum_x += angle_from_front.cos() * distance;
-                    sum_y += angle_from_front.sin() * distance;
+    // Whether the UI takes the pointer at a screen position this frame, keeping the
+    // camera from starting a drag: the spawn tool, the minimap, the stats box and its
+    // export button, and a finger landing on a cell (touch drags pan from empty space)
+    pub fn pointer_capture(&self, mouse_x: f32, mouse_y: f32) -> InputCaptured {
+        InputCaptured::from_hit(self.is_spawn_tool_active() || self.is_energy_brush_active())
+            .or_else(|| {
+                if self.config.show_ui {
+                    self.minimap().capture(mouse_x, mouse_y)
+                } else {
+                    InputCaptured::No
+                }
+            })
+            .or_else(|| {
+                if self.compared_cell_ids.is_none() {
+                    self.stats.capture(mouse_x, mouse_y, self.font.as_ref())
+                } else {
+                    InputCaptured::No
                 }
+            })
+            .or_else(|| {
+                InputCaptured::from_hit(
+                    touches().len() == 1 && self.cell_under_pointer(mouse_x, mouse_y).is_some(),
+                )
+            })
+    }
 
-                let avg_x = sum_x / alive_count;
-                let avg_y = sum_y / alive_count;
+    // Index of the cell a click at a screen position would pick
+    fn cell_under_pointer(&self, screen_x: f32, screen_y: f32) -> Option<usize> {
+        let (x, y) =
+            self.camera
+                .screen_to_world(screen_x, screen_y, screen_width(), screen_height());
+        self.find_cell_at(x, y, CLICK_PICK_RADIUS / self.camera.zoom)
+    }
 
-                // Calculate angle and distance to center of mass (in cell's local frame)
-                cell.alive_center_distance = (avg_x * avg_x + avg_y * avg_y).sqrt();
-                cell.alive_center_angle = avg_y.atan2(avg_x); // Relative to cell's facing direction
-            } else {
-                // No alive cells detected
-                cell.alive_center_angle = 0.0;
-                cell.alive_center_distance = SENSOR_RANGE; // Max range indicates nothing detected
-            }
+    // B held: left clicks spawn cells instead of selecting them
+    pub fn is_spawn_tool_active(&self) -> bool {
+        is_key_down(KeyCode::B)
+    }
 
-            cell.nearest_cells = sensor_data;
-        });
+    // U held: left drags paint energy into the cells under the brush, scroll resizes it
+    pub fn is_energy_brush_active(&self) -> bool {
+        is_key_down(KeyCode::U)
     }
 
-    fn update_stats(&mut self) {
-        // First, find the cell with the highest score (only alive cells)
-        let mut best_score = f32::MIN;
-        let mut best_cell_index = None;
-        let mut alive_cells = Vec::new();
-        let mut tier_best_scores = [f32::MIN; 4];
+    // Aim the energy brush at the mouse while U and the left button are down. The
+    // energy itself is given in run_simulation_tick, so a paused world only gets it
+    // when single-stepped.
+    fn update_energy_brush(&mut self) {
+        let active = self.is_energy_brush_active();
+        self.camera.scroll_locked = active;
+        self.energy_brush = None;
+        if !active {
+            return;
+        }
+        let scroll = mouse_wheel().1;
+        if scroll != 0.0 {
+            self.energy_brush_radius = (self.energy_brush_radius
+                * (1.0 + scroll.signum() * ENERGY_BRUSH_SCROLL_STEP))
+                .clamp(ENERGY_BRUSH_MIN_RADIUS, ENERGY_BRUSH_MAX_RADIUS);
+        }
+        if is_mouse_button_down(MouseButton::Left) {
+            let (mouse_x, mouse_y) = mouse_position();
+            self.energy_brush = Some(self.camera.screen_to_world(
+                mouse_x,
+                mouse_y,
+                screen_width(),
+                screen_height(),
+            ));
+        }
+    }
 
-        for (i, cell) in self.cells.iter().enumerate() {
-            // Only consider alive cells
-            if cell.state == CellState::Alive {
-                alive_cells.push(cell);
-
-                // Use new comprehensive score method
-                let score = cell.score();
-                if score > best_score {
-                    best_score = score;
-                    best_cell_index = Some(i);
-                }
-
-                // Track best score per tier
-                let tier = cell.brain_tier.min(3);
-                if score > tier_best_scores[tier] {
-                    tier_best_scores[tier] = score;
+    // Indices of the alive cells whose center is within `radius` of a world position
+    // (distances wrap around the world edges)
+    pub fn alive_cells_within(&self, world_x: f32, world_y: f32, radius: f32) -> Vec<usize> {
+        let x = confine(
+            world_x,
+            self.config.world_width,
+            self.config.movement.wrapping,
+        );
+        let y = confine(
+            world_y,
+            self.config.world_height,
+            self.config.movement.wrapping,
+        );
+        let position = |idx: usize| {
+            self.cells
+                .get(idx)
+                .map_or((f32::NAN, f32::NAN), |cell| (cell.x, cell.y))
+        };
+        let mut found = Vec::new();
+        self.spatial_grid
+            .for_each_within(x, y, radius, position, |idx, _, _, _| {
+                if self.cells[idx].state == CellState::Alive {
+                    found.push(idx);
                 }
-            }
-        }
-
-        // Update tier current best scores (convert f32::MIN to 0.0 for empty tiers)
-        for (tier, &score) in tier_best_scores.iter().enumerate() {
-            self.tier_current_best_scores[tier] = if score == f32::MIN { 0.0 } else { score };
-        }
-
-        // Calculate color diversity (hue variance)
-        if alive_cells.len() > 1 {
-            let hues: Vec<f32> = alive_cells
-                .iter()
-                .map(|cell| {
-                    let (h, _, _) = Cell::rgb_to_hsv_public(cell.color);
-                    h
-                })
-                .collect();
-
-            // Calculate variance of hues
-            let mean_hue: f32 = hues.iter().sum::<f32>() / hues.len() as f32;
-            let variance: f32 = hues
-                .iter()
-                .map(|h| {
-                    let diff = (h - mean_hue).abs();
-                    // Handle wraparound (hue is 0-360)
-                    let diff = if diff > 180.0 { 360.0 - diff } else { diff };
-                    diff * diff
-                })
-                .sum::<f32>()
-                / hues.len() as f32;
+            });
+        found
+    }
 
-            // Normalize diversity to 0-1 (max variance is ~180^2)
-            self.color_diversity = (variance / (180.0 * 180.0)).min(1.0);
-        } else {
-            self.color_diversity = 0.0;
+    // One tick of the energy brush: every alive cell under it gains ENERGY_BRUSH_AMOUNT
+    fn apply_energy_brush(&mut self) {
+        let Some((x, y)) = self.energy_brush else {
+            return;
+        };
+        for idx in self.alive_cells_within(x, y, self.energy_brush_radius) {
+            self.cells[idx].gain_energy(ENERGY_BRUSH_AMOUNT);
         }
+    }
 
-        // Calculate per-tier cell counts and intra-tier hue diversity
-        let mut tier_counts = [0usize; 4];
-        let mut tier_hues: [Vec<f32>; 4] = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
-        for cell in &alive_cells {
-            let tier = cell.brain_tier.min(3);
-            tier_counts[tier] += 1;
-            let (h, _, _) = Cell::rgb_to_hsv_public(cell.color);
-            tier_hues[tier].push(h);
-        }
-        self.tier_cell_counts = tier_counts;
-        for (tier, hues) in tier_hues.iter().enumerate() {
-            self.tier_diversities[tier] = if hues.len() > 1 {
-                let mean: f32 = hues.iter().sum::<f32>() / hues.len() as f32;
-                let variance: f32 = hues
-                    .iter()
-                    .map(|h| {
-                        let diff = (h - mean).abs();
-                        let diff = if diff > 180.0 { 360.0 - diff } else { diff };
-                        diff * diff
-                    })
-                    .sum::<f32>()
-                    / hues.len() as f32;
-                (variance / (180.0 * 180.0)).min(1.0)
+    // Handle mouse clicks on the stats box and its "Export Genome" button,
+    // B+click cell spawning and the U energy brush
+    pub fn handle_stats_click(&mut self) {
+        self.update_energy_brush();
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let mouse_pos = mouse_position();
+            // The comparison panel covers the stats box and its export button
+            let stats_box_shown = self.compared_cell_ids.is_none();
+            if self.is_energy_brush_active() {
+                // Painting, see update_energy_brush
+            } else if self.is_spawn_tool_active() {
+                let (x, y) = self.camera.screen_to_world(
+                    mouse_pos.0,
+                    mouse_pos.1,
+                    screen_width(),
+                    screen_height(),
+                );
+                self.spawn_at(x, y);
+            } else if stats_box_shown
+                && self.stats.is_mouse_over_export_button(
+                    mouse_pos.0,
+                    mouse_pos.1,
+                    self.font.as_ref(),
+                )
+            {
+                self.export_best_genome();
+            } else if self.config.show_ui && self.minimap().contains(mouse_pos.0, mouse_pos.1) {
+                let (x, y) = self.minimap().minimap_to_world(mouse_pos.0, mouse_pos.1);
+                self.center_camera_on(x, y);
+            } else if stats_box_shown
+                && let Some(id) =
+                    self.stats
+                        .leaderboard_row_at(mouse_pos.0, mouse_pos.1, self.font.as_ref())
+            {
+                self.compared_cell_ids = None;
+                self.select_cell(id);
+            } else if stats_box_shown
+                && self
+                    .stats
+                    .is_mouse_over(mouse_pos.0, mouse_pos.1, self.font.as_ref())
+            {
+                self.stats.toggle_selection();
             } else {
-                0.0
-            };
+                self.click_press = Some(mouse_pos);
+            }
         }
 
-        // Update stats and genome with the best cell only, or clear if no alive cells
-        let current_time = get_time();
-
-        // Check if the currently followed cell has died
-        let should_switch_target = if let Some(last_index) = self.last_best_cell_index {
-            if last_index < self.cells.len() && self.cells[last_index].state == CellState::Alive {
-                // Currently followed cell is still alive, reset death timer
-                self.followed_cell_death_time = None;
-                true // Can switch immediately to a better cell
-            } else {
-                // Currently followed cell is dead
-                if self.followed_cell_death_time.is_none() {
-                    // Record death time
-                    self.followed_cell_death_time = Some(current_time);
-                }
-
-                // Check if 3 seconds have passed since death
-                if let Some(death_time) = self.followed_cell_death_time {
-                    current_time - death_time >= 3.0
+        // Pressing and releasing in place (not a camera drag) picks the cell under the
+        // mouse, or compares it with the selected cell when Shift is held
+        if is_mouse_button_released(MouseButton::Left)
+            && let Some((press_x, press_y)) = self.click_press.take()
+        {
+            let (mouse_x, mouse_y) = mouse_position();
+            if (mouse_x - press_x).hypot(mouse_y - press_y) <= CLICK_DRAG_TOLERANCE {
+                let (x, y) =
+                    self.camera
+                        .screen_to_world(mouse_x, mouse_y, screen_width(), screen_height());
+                let radius = CLICK_PICK_RADIUS / self.camera.zoom;
+                if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+                    self.compare_cell_at(x, y, radius);
                 } else {
-                    false
+                    self.compared_cell_ids = None;
+                    self.pick_cell_at(x, y, radius);
                 }
             }
-        } else {
-            // No cell being followed, can switch immediately
-            true
-        };
-
-        if let Some(best_index) = best_cell_index {
-            let best_cell = &self.cells[best_index];
-
-            // Only update to new best cell if we should switch targets
-            if should_switch_target {
-                // Only clone the best cell's genome if it changed (avoid expensive clone every frame)
-                if self.last_best_cell_index != Some(best_index) {
-                    self.best_cell_genome = Some(best_cell.clone());
-                    self.last_best_cell_index = Some(best_index);
-                    self.followed_cell_death_time = None; // Reset death timer for new target
-                }
-
-                // Set stats to show the current best alive cell
-                self.stats.set(crate::stats::BestCellStats {
-                    energy_from_cells: best_cell.energy_from_cells,
-                    current_energy: best_cell.energy,
-                    children_count: best_cell.children_count,
-                    generation: best_cell.generation,
-                    color: best_cell.color,
-                    age: best_cell.age,
-                    x: best_cell.x,
-                    y: best_cell.y,
-                    is_alive: true, // is_alive is guaranteed true since we filtered for it
-                    brain_tier: best_cell.brain_tier,
-                    brain_operations: best_cell.brain.operation_count(),
-                    cell_index: best_index,
-                    prev_best_score: self.best_saved_scores[best_cell.brain_tier],
-                });
+        }
 
-                // Update selected cell index if stats are selected
-                if self.stats.is_selected() {
-                    self.selected_cell_index = Some(best_index);
-                } else {
-                    self.selected_cell_index = None;
-                }
-            } else if let Some(last_index) = self.last_best_cell_index {
-                // Keep showing the dead cell until 3 seconds pass
-                if last_index < self.cells.len() {
-                    let dead_cell = &self.cells[last_index];
-                    self.stats.set(crate::stats::BestCellStats {
-                        energy_from_cells: dead_cell.energy_from_cells,
-                        current_energy: dead_cell.energy,
-                        children_count: dead_cell.children_count,
-                        generation: dead_cell.generation,
-                        color: dead_cell.color,
-                        age: dead_cell.age,
-                        x: dead_cell.x,
-                        y: dead_cell.y,
-                        is_alive: false,
-                        brain_tier: dead_cell.brain_tier,
-                        brain_operations: dead_cell.brain.operation_count(),
-                        cell_index: last_index,
-                        prev_best_score: self.best_saved_scores[dead_cell.brain_tier],
-                    });
-                }
+        // Right click (not a camera drag): back to the best cell
+        if is_mouse_button_pressed(MouseButton::Right) {
+            self.right_click_press = Some(mouse_position());
+        }
+        if is_mouse_button_released(MouseButton::Right)
+            && let Some((press_x, press_y)) = self.right_click_press.take()
+        {
+            let (mouse_x, mouse_y) = mouse_position();
+            if (mouse_x - press_x).hypot(mouse_y - press_y) <= CLICK_DRAG_TOLERANCE {
+                self.compared_cell_ids = None;
+                self.clear_picked_cell();
             }
-        } else if should_switch_target {
-            // No alive cells found and cooldown has passed, clear the stats
-            self.stats.clear();
-            self.last_best_cell_index = None;
-            self.selected_cell_index = None;
-            self.followed_cell_death_time = None;
         }
     }
 
-    pub fn check_collisions(&mut self) {
-        // Spatial grid already built in update(), reuse it
-        // Extract read-only collision data for parallel processing
-        let collision_data: Vec<CellCollisionData> = self
+    // Index of the cell nearest to a world position whose body is within `radius`
+    // of it (the position is confined like a cell's, and distances wrap around the
+    // world edges only when movement wraps)
+    pub fn find_cell_at(&self, world_x: f32, world_y: f32, radius: f32) -> Option<usize> {
+        let wrapping = self.config.movement.wrapping;
+        let x = confine(world_x, self.config.world_width, wrapping);
+        let y = confine(world_y, self.config.world_height, wrapping);
+
+        // A body can reach `radius` from at most its own radius away
+        let max_radius = self
             .cells
             .iter()
-            .map(|cell| CellCollisionData {
-                x: cell.x,
-                y: cell.y,
-                radius: cell.get_current_radius(), // Use age-based radius
-                energy_chunk_size: cell.energy_chunk_size,
-                species_multiplier: cell.species_multiplier,
-                state: cell.state,
-            })
-            .collect();
-
-        // Capture world dimensions for parallel context
-        let world_width = self.config.world_width;
-        let world_height = self.config.world_height;
+            .map(|cell| cell.get_current_radius())
+            .fold(0.0, f32::max);
+        let position = |idx: usize| {
+            self.cells
+                .get(idx)
+                .map_or((f32::NAN, f32::NAN), |cell| (cell.x, cell.y))
+        };
 
-        // Parallel collision detection using spatial grid
-        // Returns (alive_cell_index, corpse_cell_index, chunk_size, multiplier)
-        let collisions: Vec<(usize, usize, f32, f32)> = (0..collision_data.len())
-            .into_par_iter()
-            .filter_map(|i| {
-                // Skip corpse cells as energy donors
-                if collision_data[i].state == CellState::Corpse {
-                    return None;
+        let mut nearest: Option<(usize, f32)> = None;
+        self.spatial_grid.for_each_within(
+            x,
+            y,
+            radius + max_radius,
+            position,
+            |idx, _, _, distance_squared| {
+                let distance = distance_squared.sqrt();
+                let reach = radius + self.cells[idx].get_current_radius();
+                if distance <= reach && nearest.is_none_or(|(_, best)| distance < best) {
+                    nearest = Some((idx, distance));
                 }
+            },
+        );
+        nearest.map(|(idx, _)| idx)
+    }
 
-                let cell_i = &collision_data[i];
-
-                // Query nearby cells using spatial grid instead of checking all cells
-                let nearby_indices = self.spatial_grid.query_nearby(
-                    cell_i.x,
-                    cell_i.y,
-                    cell_i.radius * 3.0, // Query radius (conservative estimate)
-                );
-
-                // Check for collision with nearby corpse cells only
-                for &j in &nearby_indices {
-                    if i == j || collision_data[j].state == CellState::Alive {
-                        continue; // Skip self and alive cells
-                    }
-
-                    let cell_j = &collision_data[j];
-
-                    // Handle wrapping distance calculation
-                    let mut dx = cell_i.x - cell_j.x;
-                    let mut dy = cell_i.y - cell_j.y;
-
-                    // Adjust for world wrapping
-                    if dx.abs() > world_width / 2.0 {
-                        dx = dx - dx.signum() * world_width;
-                    }
-                    if dy.abs() > world_height / 2.0 {
-                        dy = dy - dy.signum() * world_height;
-                    }
+    // Show the cell at a world position in the stats box (and highlight it) instead
+    // of the best cell. Returns its id, or None (nothing changes) if no cell is there.
+    pub fn pick_cell_at(&mut self, world_x: f32, world_y: f32, radius: f32) -> Option<u64> {
+        let idx = self.find_cell_at(world_x, world_y, radius)?;
+        let id = self.cells[idx].id;
+        self.select_cell(id);
+        Some(id)
+    }
 
-                    let distance_squared = dx * dx + dy * dy;
-                    let collision_distance = cell_i.radius + cell_j.radius;
-                    let collision_distance_squared = collision_distance * collision_distance;
+    // Show a cell in the stats box instead of the best cell (false if it is gone)
+    pub fn select_cell(&mut self, id: u64) -> bool {
+        let Some(idx) = self.index_of(id) else {
+            return false;
+        };
+        self.picked_cell_id = Some(id);
+        self.selected_cell_id = Some(id);
+        self.tracked_rank = None;
+        self.stats.set(self.cell_stats(idx, true));
+        log_info!("🔍 Selected cell #{}", id);
+        true
+    }
 
-                    if distance_squared < collision_distance_squared {
-                        // Return (alive_idx, corpse_idx, chunk_size, species_multiplier)
-                        return Some((i, j, cell_i.energy_chunk_size, cell_i.species_multiplier));
-                    }
-                }
+    // Select and follow the next (or previous) of the top cells, wrapping around and
+    // skipping cells that died since the last re-rank. Starts from the fittest.
+    pub fn cycle_tracked_cell(&mut self, forward: bool) -> Option<u64> {
+        let count = self.top_cell_ids.len();
+        let mut rank = self.tracked_rank;
+        for _ in 0..count {
+            let next = match (rank, forward) {
+                (None, true) => 0,
+                (None, false) => count - 1,
+                (Some(rank), true) => (rank + 1) % count,
+                (Some(rank), false) => (rank + count - 1) % count,
+            };
+            rank = Some(next);
+            let id = self.top_cell_ids[next];
+            if self
+                .index_of(id)
+                .is_some_and(|idx| self.cells[idx].state == CellState::Alive)
+            {
+                self.compared_cell_ids = None;
+                self.select_cell(id);
+                self.tracked_rank = Some(next);
+                self.stats.set_selected(true);
+                self.update_stats();
+                return Some(id);
+            }
+        }
+        None
+    }
 
-                None
-            })
-            .collect();
+    // Home: back to following the global best cell
+    pub fn track_best_cell(&mut self) {
+        self.tracked_rank = None;
+        self.compared_cell_ids = None;
+        self.clear_picked_cell();
+        self.stats.set_selected(true);
+        self.update_stats();
+    }
 
-        // Apply energy transfers to alive cells and reduce energy from corpse cells
-        for (alive_idx, corpse_idx, chunk_size, multiplier) in &collisions {
-            // Alive cell gains energy through gain_energy() (handles growth mechanic)
-            self.cells[*alive_idx].gain_energy(chunk_size * multiplier);
-            // Corpse cell loses base chunk_size
-            self.cells[*corpse_idx].energy -= chunk_size;
-        }
+    // Escape: stop moving the camera with any cell
+    pub fn stop_following(&mut self) {
+        self.tracked_rank = None;
+        self.stats.set_selected(false);
+        self.update_stats();
+    }
 
-        // Collect cells with energy below threshold to remove
-        let mut indices_to_remove: Vec<usize> = self
+    // The `n` fittest alive cells, fittest first (rank 1)
+    pub fn compute_top_cells(&self, n: usize) -> Vec<LeaderboardEntry> {
+        let fitness = self.fitness();
+        let mut scored: Vec<(f32, &Cell)> = self
             .cells
             .iter()
+            .filter(|cell| cell.state == CellState::Alive)
+            .map(|cell| (fitness.score(cell), cell))
+            .collect();
+        scored.sort_unstable_by(|a, b| b.0.total_cmp(&a.0).then(a.1.id.cmp(&b.1.id)));
+        scored
+            .into_iter()
+            .take(n)
             .enumerate()
-            .filter_map(|(idx, cell)| {
-                if cell.energy < DEPLETED_CELL_ENERGY {
-                    Some(idx)
-                } else {
-                    None
-                }
+            .map(|(i, (score, cell))| LeaderboardEntry {
+                rank: i + 1,
+                id: cell.id,
+                color: cell.color,
+                fitness: score,
+                generation: cell.generation,
+                age: cell.age,
             })
-            .collect();
+            .collect()
+    }
 
-        // Remove depleted cells in reverse order to maintain index validity
-        // Using unstable sort for performance we don't need to order them, we just mutate directly
-        // to clean up
-        indices_to_remove.sort_unstable();
-        for &idx in indices_to_remove.iter().rev() {
-            self.cells.swap_remove(idx);
+    // Compare the cell at a world position with the selected cell. Without a
+    // selection (or clicking the selected cell) this is a plain pick, and empty
+    // space closes the comparison.
+    pub fn compare_cell_at(&mut self, world_x: f32, world_y: f32, radius: f32) -> Option<u64> {
+        let Some(idx) = self.find_cell_at(world_x, world_y, radius) else {
+            self.compared_cell_ids = None;
+            return None;
+        };
+        let id = self.cells[idx].id;
+        match self.selected_cell_id {
+            Some(selected) if selected != id => {
+                self.compared_cell_ids = Some((selected, id));
+                log_info!("🔍 Comparing cell #{} with cell #{}", selected, id);
+                Some(id)
+            }
+            _ => {
+                self.compared_cell_ids = None;
+                self.pick_cell_at(world_x, world_y, radius)
+            }
         }
+    }
 
-        // Boundary wrapping now handled inline in cell.update()
+    // Back to showing the best cell
+    pub fn clear_picked_cell(&mut self) {
+        if self.picked_cell_id.take().is_some() {
+            self.selected_cell_id = None;
+            self.update_stats();
+        }
+    }
+
+    // Minimap layout for the current screen size
+    fn minimap(&self) -> Minimap {
+        Minimap::new(
+            self.config.world_width,
+            self.config.world_height,
+            screen_height(),
+        )
     }
 
-    fn render_sensor_lines(&self) {
+    // Camera offset to a target camera position: the shortest way around a wrapping
+    // world, straight across a walled one (movement.wrapping off)
+    fn camera_delta_to(&self, target_x: f32, target_y: f32) -> (f32, f32) {
+        let delta_x = target_x - self.camera.x;
+        let delta_y = target_y - self.camera.y;
+        if !self.config.movement.wrapping {
+            return (delta_x, delta_y);
+        }
         let world_width = self.config.world_width;
         let world_height = self.config.world_height;
-        let screen_w = screen_width();
-        let screen_h = screen_height();
+        (
+            wrapped_delta(delta_x.rem_euclid(world_width), world_width),
+            wrapped_delta(delta_y.rem_euclid(world_height), world_height),
+        )
+    }
 
-        // Define all possible wraparound positions (same as cell rendering)
-        let wraparound_offsets = [
-            (0.0, 0.0),                    // Original position
-            (-world_width, 0.0),           // Wrapped left
-            (world_width, 0.0),            // Wrapped right
-            (0.0, -world_height),          // Wrapped top
-            (0.0, world_height),           // Wrapped bottom
-            (-world_width, -world_height), // Top-left corner
-            (world_width, -world_height),  // Top-right corner
-            (-world_width, world_height),  // Bottom-left corner
-            (world_width, world_height),   // Bottom-right corner
-        ];
+    // Glide the camera to a world position (see camera_delta_to) and stop following
+    // the best cell so the new position sticks
+    fn center_camera_on(&mut self, x: f32, y: f32) {
+        let (delta_x, delta_y) =
+            self.camera_delta_to(x - screen_width() / 2.0, y - screen_height() / 2.0);
+        self.camera.target_x = self.camera.x + delta_x;
+        self.camera.target_y = self.camera.y + delta_y;
+        if self.stats.is_selected() {
+            self.stats.toggle_selection();
+        }
+    }
 
-        for cell in &self.cells {
-            // Only draw sensors for alive cells
-            if cell.state != CellState::Alive {
-                continue;
+    // Export the selected cell's genome, or the best alive cell's if none is selected
+    // (clipboard on web, genome_<timestamp>.json on native)
+    pub fn export_best_genome(&self) {
+        let selected = self
+            .selected_cell_id
+            .and_then(|id| self.index_of(id))
+            .map(|idx| &self.cells[idx])
+            .filter(|cell| cell.state == CellState::Alive);
+        match selected.or_else(|| self.best_alive_cell()) {
+            Some(cell) => {
+                crate::storage::export_genome(cell, &crate::storage::genome_export_path())
             }
+            None => log_warn!("⚠ No alive cell to export"),
+        }
+    }
 
-            // Render sensor lines for each wraparound position
-            for (offset_x, offset_y) in &wraparound_offsets {
-                // Adjust camera position to create wraparound effect
-                let adjusted_camera_x = self.camera.x - offset_x;
-                let adjusted_camera_y = self.camera.y - offset_y;
+    // Merge a shared brain library into the saved slots (clipboard on web,
+    // cells_brain_library.json on native), then reload the tier cache from them
+    pub fn import_brain_library(&mut self, overwrite: bool) {
+        match crate::storage::import_all(crate::storage::BRAIN_LIBRARY_FILE, overwrite) {
+            Ok(written) => {
+                log_info!("🧠 Imported {} brains from the library", written);
+                if self.config.use_storage && written > 0 {
+                    (self.cached_best_brains, self.best_saved_scores) = load_saved_brains(
+                        self.config.vision_mode,
+                        crate::storage::load_best_neural_network,
+                    );
+                }
+            }
+            Err(e) => log_warn!("⚠ Brain library import failed: {}", e),
+        }
+    }
 
-                // Check if cell is visible at this wraparound position
-                let cell_screen_x = cell.x - adjusted_camera_x;
-                let cell_screen_y = cell.y - adjusted_camera_y;
-                let margin = SENSOR_RANGE; // Use sensor range as margin
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
 
-                if cell_screen_x < -margin
-                    || cell_screen_x > screen_w + margin
-                    || cell_screen_y < -margin
-                    || cell_screen_y > screen_h + margin
-                {
-                    continue; // Skip if cell not visible at this wraparound position
+    // Apply an edited config file to the running world (see config::HOT_RELOAD_FIELDS),
+    // given the file's previous and new contents. A file that failed to parse leaves
+    // the current config in place.
+    pub fn apply_config_reload(
+        &mut self,
+        path: &str,
+        reloaded: Result<(SimulationConfig, SimulationConfig), String>,
+    ) {
+        let reload = reloaded.and_then(|(previous, reloaded)| {
+            let mut config = self.config.clone();
+            let reload = config.hot_reload(&previous, &reloaded)?;
+            self.config = config;
+            Ok(reload)
+        });
+        match reload {
+            Ok(reload) => {
+                if !reload.applied.is_empty() {
+                    log_info!("🔄 Reloaded {}: {}", path, reload.applied.join(", "));
+                }
+                if !reload.needs_restart.is_empty() {
+                    log_warn!(
+                        "⚠ {} changed fields that need a restart: {}",
+                        path,
+                        reload.needs_restart.join(", ")
+                    );
                 }
+            }
+            Err(error) => log_warn!(
+                "⚠ Config reload failed, keeping the current config: {}",
+                error
+            ),
+        }
+    }
 
-                for &(target_idx, _angle, _distance, _mass, _is_alive, _energy) in
-                    &cell.nearest_cells
-                {
-                    // Safety check: ensure target index is valid
-                    if target_idx >= self.cells.len() {
-                        continue;
+    // Apply one request from the hosting page (see ffi.rs)
+    pub fn apply_command(&mut self, command: SimCommand) {
+        match command {
+            SimCommand::Pause(paused) => {
+                self.paused = paused;
+                log_info!("{}", if paused { "⏸ Paused" } else { "▶ Resumed" });
+            }
+            SimCommand::SetSpeed(speed) if speed.is_finite() => {
+                self.simulation_speed = speed.clamp(MIN_SIMULATION_SPEED, MAX_SIMULATION_SPEED);
+                log_info!("Simulation speed: {:.1}x", self.simulation_speed);
+            }
+            SimCommand::SetSpeed(speed) => log_warn!("⚠ Ignored simulation speed {}", speed),
+            SimCommand::Reset => self.respawn_from_best(),
+            SimCommand::SetConfigJson(json) => match self.config.with_overrides(&json) {
+                Ok(config) => self.apply_config(config),
+                Err(e) => log_warn!("⚠ Config update rejected: {}", e),
+            },
+            SimCommand::InjectBrainJson(json) => {
+                match crate::storage::cell_from_brain_json(&json, &self.config) {
+                    Ok(cell) => {
+                        self.import_genome(cell);
                     }
+                    Err(e) => log_warn!("⚠ Brain injection failed: {}", e),
+                }
+            }
+        }
+    }
 
-                    let target = &self.cells[target_idx];
-
-                    // Calculate vector from cell to target
-                    let mut dx = target.x - cell.x;
-                    let mut dy = target.y - cell.y;
-
-                    // Handle world wrapping for line drawing
-                    if dx.abs() > world_width / 2.0 {
-                        dx = dx - dx.signum() * world_width;
-                    }
-                    if dy.abs() > world_height / 2.0 {
-                        dy = dy - dy.signum() * world_height;
-                    }
+    // Swap in an updated config. Changes the world was built around (size, vision,
+    // storage) restart it with the new config; the rest applies from the next tick.
+    fn apply_config(&mut self, config: SimulationConfig) {
+        let rebuild = config.world_width != self.config.world_width
+            || config.world_height != self.config.world_height
+            || config.vision_mode != self.config.vision_mode
+            || config.use_storage != self.config.use_storage;
+        if rebuild {
+            log_info!("🔄 Restarting the world for the new config");
+            let font = self.font.take();
+            let background = self.background.take();
+            let sprites = self.sprites.take();
+            *self = World::new(config);
+            self.font = font;
+            self.background = background;
+            self.sprites = sprites;
+            return;
+        }
 
-                    // Calculate target position accounting for wrapping
-                    let target_x = cell.x + dx;
-                    let target_y = cell.y + dy;
+        let cap_changed = config.max_cells != self.config.max_cells;
+        self.islands = Islands::new(config.islands, config.world_width, config.world_height);
+        if config.zones != self.config.zones {
+            self.zones =
+                crate::zones::generate(&config.zones, config.world_width, config.world_height);
+        }
+        if config.succession != self.config.succession {
+            self.regions =
+                RegionMap::new(config.succession, config.world_width, config.world_height);
+        }
+        self.camera.smoothstep_easing = config.camera_smoothstep;
+        let fitness_changed = config.fitness != self.config.fitness;
+        self.config = config;
+        if fitness_changed && self.fitness_plugin.is_none() {
+            self.forget_saved_scores();
+        }
+        if cap_changed {
+            match self.config.max_cells {
+                Some(cap) => self.pin_cell_cap(cap),
+                None => self.cell_cap_grow_streak = 0,
+            }
+        }
+        log_info!("⚙ Config updated");
+    }
 
-                    // Calculate angle to target
-                    let angle_to_target = dy.atan2(dx);
+    // Numbers reported to the hosting page by sim_get_stats
+    pub fn sim_stats(&self) -> SimStats {
+        SimStats {
+            tick: self.tick_count,
+            population: self.alive_count(),
+            fps: self.current_fps,
+            diversity: self.diversity(),
+            best_fitness: self
+                .best_alive_cell()
+                .map_or(0.0, |cell| self.fitness().score(cell)),
+            paused: self.paused,
+            speed: self.simulation_speed,
+        }
+    }
 
-                    // Normalize angles to -PI to PI range
-                    let mut angle_diff = angle_to_target - cell.angle;
-                    while angle_diff > std::f32::consts::PI {
-                        angle_diff -= std::f32::consts::TAU;
-                    }
-                    while angle_diff < -std::f32::consts::PI {
-                        angle_diff += std::f32::consts::TAU;
-                    }
+    // Inject a cell built from a shared genome (see storage::import_genome)
+    // Returns the new cell's id
+    pub fn import_genome(&mut self, mut cell: Cell) -> u64 {
+        cell.brain.action_bias = self.config.action_bias;
+        cell.brain.set_frozen_layers(self.config.freeze_layers);
+        cell.id = self.next_id();
+        let id = cell.id;
+        log_info!(
+            "🧬 Imported genome (tier {}, generation {}) at ({:.0}, {:.0})",
+            cell.brain_tier,
+            cell.generation,
+            cell.x,
+            cell.y
+        );
+        self.cells.push(cell);
+        id
+    }
 
-                    // Calculate opacity based on how much the target is in front
-                    // 0 degrees (directly in front) = full opacity
-                    // ±180 degrees (directly behind) = no opacity
-                    let normalized_angle = angle_diff.abs() / std::f32::consts::PI; // 0.0 to 1.0
-                    let opacity = (1.0 - normalized_angle).max(0.0);
+    // Debug tool: drop a cell at a world position (confined to the world like any
+    // cell: wrapped, or clamped against the walls without wrapping) with
+    // SPAWN_TOOL_ENERGY, a mutated child of the best genome when there is one.
+    // Deliberately ignores the cell cap so cells can be injected into a full world.
+    pub fn spawn_at(&mut self, x: f32, y: f32) -> u64 {
+        let mut cell = match &self.best_cell_genome {
+            Some(best) => best.spawn_child(
+                self.config.mutation_rate_multiplier,
+                self.config.weight_decay,
+                self.config.structured_mutation,
+            ),
+            None => Cell::spawn(
+                self.config.world_width,
+                self.config.world_height,
+                0,
+                &None,
+                self.config.vision_mode,
+            ),
+        };
+        let wrapping = self.config.movement.wrapping;
+        cell.x = confine(x, self.config.world_width, wrapping);
+        cell.y = confine(y, self.config.world_height, wrapping);
+        cell.energy = SPAWN_TOOL_ENERGY;
+        cell.brain.action_bias = self.config.action_bias;
+        cell.brain.set_frozen_layers(self.config.freeze_layers);
+        cell.id = self.next_id();
+        let id = cell.id;
+        log_info!(
+            "🐣 Spawned cell #{} (tier {}, generation {}) at ({:.0}, {:.0})",
+            id,
+            cell.brain_tier,
+            cell.generation,
+            cell.x,
+            cell.y
+        );
+        self.cells.push(cell);
+        id
+    }
 
-                    // Convert to screen coordinates using adjusted camera
-                    let x1 = cell.x - adjusted_camera_x;
-                    let y1 = cell.y - adjusted_camera_y;
-                    let x2 = target_x - adjusted_camera_x;
-                    let y2 = target_y - adjusted_camera_y;
+    // Replace `fraction` of the population (evenly spread) with copies of a genome
+    // at random positions. Replaced cells keep their ids, and copies get the configured
+    // action bias, frozen layers and starting energy like any spawn. Returns the
+    // number seeded.
+    pub fn seed_from_genome(&mut self, genome: &Cell, fraction: f32) -> usize {
+        let count = ((self.cells.len() as f32 * fraction.clamp(0.0, 1.0)).round() as usize)
+            .min(self.cells.len());
+        for i in 0..count {
+            let idx = i * self.cells.len() / count;
+            let mut cell = genome.clone();
+            cell.id = self.cells[idx].id;
+            cell.x = rand::gen_range(0.0, self.config.world_width);
+            cell.y = rand::gen_range(0.0, self.config.world_height);
+            cell.angle = rand::gen_range(0.0, std::f32::consts::TAU);
+            cell.brain.action_bias = self.config.action_bias;
+            cell.brain.set_frozen_layers(self.config.freeze_layers);
+            cell.set_starting_energy(&self.config.energy);
+            self.cells[idx] = cell;
+        }
+        count
+    }
 
-                    // Create color with opacity (use cell's color)
-                    let line_color = Color::new(
-                        cell.color.r,
-                        cell.color.g,
-                        cell.color.b,
-                        opacity * 0.3, // Scale down opacity for subtlety
-                    );
+    // Handle keyboard input for simulation controls
+    pub fn handle_keyboard_input(&mut self) {
+        // Space: Toggle pause
+        if is_key_pressed(KeyCode::Space) {
+            self.paused = !self.paused;
+            log_info!(
+                "Simulation {}",
+                if self.paused { "PAUSED" } else { "RESUMED" }
+            );
+        }
 
-                    // Draw line
-                    draw_line(x1, y1, x2, y2, 1.0, line_color);
+        // N: Advance one tick while paused; holding it repeats at a slow fixed rate
+        if self.paused {
+            if is_key_pressed(KeyCode::N) {
+                self.step_key_held = 0.0;
+                self.step_paused();
+            } else if is_key_down(KeyCode::N) {
+                let repeats = held_key_repeats(
+                    &mut self.step_key_held,
+                    get_frame_time(),
+                    STEP_KEY_REPEAT_INTERVAL,
+                );
+                for _ in 0..repeats {
+                    self.step_paused();
                 }
             }
         }
-    }
 
-    pub fn render(&self) {
-        // Render parallax star-field background
-        if let Some(bg) = &self.background {
-            bg.render(self.camera.x, self.camera.y);
+        let ctrl_down = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+
+        // Ctrl+R: Start/stop replay recording (saved to disk when stopped)
+        if ctrl_down && is_key_pressed(KeyCode::R) {
+            if self.replay.is_recording() {
+                self.replay.stop_recording();
+                log_info!(
+                    "Replay recording stopped ({} frames)",
+                    self.replay.frames.len()
+                );
+                crate::storage::save_replay(&self.replay.to_bytes());
+            } else {
+                self.replay.start_recording();
+                log_info!("Replay recording started");
+            }
         }
 
-        // Render boundary lines (only if UI enabled)
-        if self.config.show_ui {
-            self.render_grid();
-            self.render_boundaries();
+        // Ctrl+B: Bottleneck event (plain B is the spawn tool)
+        if ctrl_down && is_key_pressed(KeyCode::B) {
+            self.trigger_bottleneck();
         }
 
-        // Render sensor lines first (so they appear behind cells)
-        if self.config.show_sensor_lines {
-            self.render_sensor_lines();
+        // F: Food bloom event
+        if is_key_pressed(KeyCode::F) {
+            self.start_food_bloom();
         }
 
-        // Count stats
-        let mut cells_in_viewport = 0;
-        let screen_w = screen_width();
-        let screen_h = screen_height();
+        // Y: Toggle the camera auto-director (Ctrl+Y dumps the era history)
+        if !ctrl_down && is_key_pressed(KeyCode::Y) {
+            if self.director.is_active() {
+                self.director.stop();
+                log_info!("🎬 Auto-director off");
+            } else {
+                self.director.start();
+                log_info!("🎬 Auto-director on");
+            }
+        }
 
-        // Get world dimensions for wraparound rendering
-        let world_width = self.config.world_width;
-        let world_height = self.config.world_height;
+        // Ctrl+P: Enter/exit replay playback (loads the saved replay if none in memory)
+        if ctrl_down && is_key_pressed(KeyCode::P) {
+            if self.replay.is_playing() {
+                self.replay.stop_playback();
+                log_info!("Replay playback stopped");
+            } else {
+                if self.replay.frames.is_empty()
+                    && let Some(frames) = crate::storage::load_replay()
+                        .and_then(|bytes| ReplayRecorder::frames_from_bytes(&bytes))
+                {
+                    self.replay.frames = frames;
+                }
+                if self.replay.start_playback() {
+                    log_info!(
+                        "Replay playback started ({} frames)",
+                        self.replay.frames.len()
+                    );
+                } else {
+                    log_info!("No replay recorded");
+                }
+            }
+        }
 
-        // Define all possible wraparound positions
-        // Cells are rendered at multiple positions to show toroidal topology
-        let wraparound_offsets = [
-            (0.0, 0.0),                    // Original position
-            (-world_width, 0.0),           // Wrapped left
-            (world_width, 0.0),            // Wrapped right
-            (0.0, -world_height),          // Wrapped top
-            (0.0, world_height),           // Wrapped bottom
-            (-world_width, -world_height), // Top-left corner
-            (world_width, -world_height),  // Top-right corner
-            (-world_width, world_height),  // Bottom-left corner
-            (world_width, world_height),   // Bottom-right corner
-        ];
+        // Ctrl+Y: Dump the era history to stdout and the event log
+        if ctrl_down && is_key_pressed(KeyCode::Y) {
+            self.dump_era_history();
+        }
 
-        // Render cells and count viewport cells
-        for (idx, cell) in self.cells.iter().enumerate() {
-            // Count cells in viewport (only for original position)
-            let screen_x = cell.x - self.camera.x;
-            let screen_y = cell.y - self.camera.y;
-            let margin = cell.get_current_radius() * 1.5;
+        // Ctrl+L: Dump the full event history to stdout
+        if ctrl_down && is_key_pressed(KeyCode::L) {
+            crate::stats::dump_event_log();
+        }
 
-            if !(screen_x < -margin
-                || screen_x > screen_w + margin
-                || screen_y < -margin
-                || screen_y > screen_h + margin)
-            {
-                cells_in_viewport += 1;
+        // Ctrl+E: Export the selected (or best) cell's genome (plain E rotates the camera)
+        // Ctrl+Shift+E: Export every saved brain slot as one library document
+        let shift_down = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if ctrl_down && is_key_pressed(KeyCode::E) {
+            if shift_down {
+                crate::storage::export_all(crate::storage::BRAIN_LIBRARY_FILE);
+            } else {
+                self.export_best_genome();
             }
+        }
 
-            // Render cell at all wraparound positions
-            for (dx, dy) in &wraparound_offsets {
-                // Adjust camera position to create wraparound effect
-                let adjusted_camera_x = self.camera.x - dx;
-                let adjusted_camera_y = self.camera.y - dy;
+        // Ctrl+I: Import a brain library, keeping brains already saved
+        // (Ctrl+Shift+I: replacing them)
+        if ctrl_down && is_key_pressed(KeyCode::I) {
+            self.import_brain_library(shift_down);
+        }
 
-                // cell.render() has built-in viewport culling, will skip if off-screen
-                cell.render(adjusted_camera_x, adjusted_camera_y);
+        // I: Import a shared genome (clipboard on web, the newest genome_<timestamp>.json on native)
+        if !ctrl_down && is_key_pressed(KeyCode::I) {
+            match crate::storage::import_latest_genome(&self.config) {
+                Ok(cell) => {
+                    self.import_genome(cell);
+                }
+                Err(e) => log_warn!("⚠ Genome import failed: {}", e),
+            }
+        }
 
-                // Draw selection highlight if this is the selected cell
-                if self.selected_cell_index == Some(idx) {
-                    let screen_x = cell.x - adjusted_camera_x;
-                    let screen_y = cell.y - adjusted_camera_y;
-                    let current_radius = cell.get_current_radius();
+        // V: Cycle what cell colors show (inherited hue, generation, energy, species)
+        if is_key_pressed(KeyCode::V) {
+            self.render_color_mode = self.render_color_mode.next();
+            if self.render_color_mode == ColorMode::Species {
+                // Classify current cells right away instead of waiting for the next recount
+                self.update_species();
+            }
+            log_info!("Color mode: {:?}", self.render_color_mode);
+        }
 
-                    // Only draw if on screen
-                    let margin = current_radius * 1.5;
-                    if !(screen_x < -margin
-                        || screen_x > screen_w + margin
-                        || screen_y < -margin
-                        || screen_y > screen_h + margin)
-                    {
-                        let gold = Color::new(1.0, 0.84, 0.0, 1.0);
-                        let gold_transparent = Color::new(1.0, 0.84, 0.0, 0.5);
-                        draw_circle_lines(
-                            screen_x,
-                            screen_y,
-                            current_radius + 9.0, // Slightly larger than cell
-                            4.0,                  // Thickness
-                            gold_transparent,
-                        );
-                        draw_circle_lines(
-                            screen_x,
-                            screen_y,
-                            current_radius + 12.0, // Slightly larger than cell
-                            1.0,                   // Thickness
-                            gold,
-                        );
+        // [ / ]: Pin the cell cap lower / higher, \: back to FPS-based auto cap
+        if is_key_pressed(KeyCode::LeftBracket) {
+            self.pin_cell_cap(self.max_cells.saturating_sub(CELL_CAP_STEP));
+        }
+        if is_key_pressed(KeyCode::RightBracket) {
+            self.pin_cell_cap(self.max_cells + CELL_CAP_STEP);
+        }
+        if is_key_pressed(KeyCode::Backslash) && self.config.max_cells.is_some() {
+            self.config.max_cells = None;
+            self.cell_cap_grow_streak = 0;
+            log_info!("Cell cap back to auto");
+        }
 
-                        // Draw target line if cell has a target
-                        if let Some((target_x, target_y)) = cell.current_target_pos {
-                            // Calculate nose position (front of the cell)
-                            let nose_x = screen_x + cell.angle.cos() * current_radius;
-                            let nose_y = screen_y + cell.angle.sin() * current_radius;
+        // F12: Screenshot (taken at the end of the frame, see capture_screenshot_if_requested)
+        if is_key_pressed(KeyCode::F12) {
+            self.screenshot_requested = true;
+        }
 
-                            // Calculate target position accounting for world wrapping
-                            let mut target_dx = target_x - cell.x;
-                            let mut target_dy = target_y - cell.y;
+        // O: Cycle the sensor overlay between all cells, the selected cell and off
+        // (drawn even when the rest of the UI is hidden)
+        if is_key_pressed(KeyCode::O) {
+            self.sensor_line_mode = self.sensor_lines().next();
+            self.config.show_sensor_lines = self.sensor_line_mode != SensorLineMode::Off;
+            log_info!("Sensor overlay: {}", self.sensor_line_mode.label());
+        }
 
-                            // Adjust for world wrapping
-                            if target_dx.abs() > world_width / 2.0 {
-                                target_dx = target_dx - target_dx.signum() * world_width;
-                            }
-                            if target_dy.abs() > world_height / 2.0 {
-                                target_dy = target_dy - target_dy.signum() * world_height;
-                            }
+        // T: Toggle movement trails (buffers are freed when turned off)
+        if is_key_pressed(KeyCode::T) {
+            self.config.show_trails = !self.config.show_trails;
+            if !self.config.show_trails {
+                for cell in &mut self.cells {
+                    cell.trail = Default::default();
+                }
+            }
+            log_info!(
+                "Trails {}",
+                if self.config.show_trails { "ON" } else { "OFF" }
+            );
+        }
 
-                            let target_screen_x = screen_x + target_dx;
-                            let target_screen_y = screen_y + target_dy;
-
-                            // Calculate line color based on current_alignment_score
-                            // Score ranges from 1.0 (0° diff, perfect) to -1.0 (180° diff, opposite)
-                            // Color mapping:
-                            //   1.0 (0° diff) = White (perfectly aligned)
-                            //   0.0 (90° diff) = Yellow (perpendicular)
-                            //   -1.0 (180° diff) = Red (facing away)
-                            let alignment = cell.current_alignment_score.clamp(-1.0, 1.0);
-
-                            let line_color = if alignment >= 0.0 {
-                                // 0° to 90°: interpolate from white (1.0) to yellow (0.0)
-                                // alignment = 1.0: white (1,1,1)
-                                // alignment = 0.0: yellow (1,1,0)
-                                Color::new(1.0, 1.0, alignment, 0.9)
-                            } else {
-                                // 90° to 180°: interpolate from yellow (0.0) to red (-1.0)
-                                // alignment = 0.0: yellow (1,1,0)
-                                // alignment = -1.0: red (1,0,0)
-                                let t = -alignment; // 0.0 to 1.0
-                                Color::new(1.0, 1.0 - t, 0.0, 0.9)
-                            };
-
-                            // Draw line from nose to target
-                            draw_line(
-                                nose_x,
-                                nose_y,
-                                target_screen_x,
-                                target_screen_y,
-                                3.0,
-                                line_color,
-                            );
+        // G: Toggle the spatial grid density overlay (D is taken by WASD panning)
+        if is_key_pressed(KeyCode::G) {
+            self.config.show_density_map = !self.config.show_density_map;
+            if self.config.show_density_map {
+                self.density_map = self.compute_density_map();
+            }
+        }
 
-                            // Draw small circle at target position
-                            draw_circle(target_screen_x, target_screen_y, 5.0, line_color);
-                        }
+        // M: Cycle the fitness function preset
+        if is_key_pressed(KeyCode::M) {
+            self.cycle_fitness();
+        }
 
-                        // Draw center of mass indicators for dead and alive cells
-                        // Light gray for dead cells, yellow for alive cells
-                        const CENTER_OF_MASS_RADIUS: f32 = 8.0;
-
-                        // Dead cells center of mass (light gray)
-                        if cell.dead_center_distance < SENSOR_RANGE {
-                            // Calculate screen position from cell's local frame
-                            let dead_center_world_angle = cell.angle + cell.dead_center_angle;
-                            let dead_center_world_x =
-                                cell.x + dead_center_world_angle.cos() * cell.dead_center_distance;
-                            let dead_center_world_y =
-                                cell.y + dead_center_world_angle.sin() * cell.dead_center_distance;
-
-                            let dead_center_screen_x = dead_center_world_x - adjusted_camera_x;
-                            let dead_center_screen_y = dead_center_world_y - adjusted_camera_y;
-
-                            let light_gray = Color::new(0.7, 0.7, 0.7, 0.8);
-                            draw_circle(
-                                dead_center_screen_x,
-                                dead_center_screen_y,
-                                CENTER_OF_MASS_RADIUS,
-                                light_gray,
-                            );
-                            draw_circle_lines(
-                                dead_center_screen_x,
-                                dead_center_screen_y,
-                                CENTER_OF_MASS_RADIUS,
-                                2.0,
-                                Color::new(0.5, 0.5, 0.5, 1.0),
-                            );
-                        }
+        // P: Toggle the pheromone overlay (Ctrl+P is replay playback)
+        if !ctrl_down && is_key_pressed(KeyCode::P) {
+            self.config.show_pheromones = !self.config.show_pheromones;
+            log_info!(
+                "Pheromone overlay {}",
+                if self.config.show_pheromones {
+                    "ON"
+                } else {
+                    "OFF"
+                }
+            );
+        }
 
-                        // Alive cells center of mass (yellow)
-                        if cell.alive_center_distance < SENSOR_RANGE {
-                            // Calculate screen position from cell's local frame
-                            let alive_center_world_angle = cell.angle + cell.alive_center_angle;
-                            let alive_center_world_x = cell.x
-                                + alive_center_world_angle.cos() * cell.alive_center_distance;
-                            let alive_center_world_y = cell.y
-                                + alive_center_world_angle.sin() * cell.alive_center_distance;
-
-                            let alive_center_screen_x = alive_center_world_x - adjusted_camera_x;
-                            let alive_center_screen_y = alive_center_world_y - adjusted_camera_y;
-
-                            let yellow = Color::new(1.0, 1.0, 0.0, 0.8);
-                            draw_circle(
-                                alive_center_screen_x,
-                                alive_center_screen_y,
-                                CENTER_OF_MASS_RADIUS,
-                                yellow,
-                            );
-                            draw_circle_lines(
-                                alive_center_screen_x,
-                                alive_center_screen_y,
-                                CENTER_OF_MASS_RADIUS,
-                                2.0,
-                                Color::new(0.8, 0.8, 0.0, 1.0),
-                            );
-                        }
-                    }
+        // L: Toggle the simulation event panel (Ctrl+L dumps the event log),
+        // PageUp/PageDown scroll it
+        if !ctrl_down && is_key_pressed(KeyCode::L) {
+            self.config.show_event_panel = !self.config.show_event_panel;
+        }
+        if is_key_pressed(KeyCode::PageUp) {
+            self.stats.event_panel.scroll_by(EVENT_PANEL_SCROLL_ROWS);
+        }
+        if is_key_pressed(KeyCode::PageDown) {
+            self.stats.event_panel.scroll_by(-EVENT_PANEL_SCROLL_ROWS);
+        }
+
+        // Z: Toggle the temperature zone overlay
+        if is_key_pressed(KeyCode::Z) {
+            self.config.show_temperature = !self.config.show_temperature;
+            log_info!(
+                "Temperature overlay {}",
+                if self.config.show_temperature {
+                    "ON"
+                } else {
+                    "OFF"
                 }
-            }
+            );
         }
 
-        // Render stats (only if UI enabled)
-        if self.config.show_ui {
-            self.render_stats(cells_in_viewport);
+        // Tab / Shift+Tab: follow the next / previous of the top 10 cells,
+        // Home: back to the best cell, Escape: stop following
+        if is_key_pressed(KeyCode::Tab) {
+            let forward = !(is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift));
+            self.cycle_tracked_cell(forward);
+        }
+        if is_key_pressed(KeyCode::Home) {
+            self.track_best_cell();
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            self.stop_following();
+        }
 
-            // Render best cell stats (bottom-right corner)
-            self.stats.render(self.font.as_ref());
+        // X: Expand or collapse the selected cell's detail panel
+        if is_key_pressed(KeyCode::X) {
+            self.config.show_cell_detail = !self.config.show_cell_detail;
         }
-    }
 
-    fn render_grid(&self) {
-        let grid_spacing = 250.0;
-        let dot_radius = 2.0;
-        let dot_color = Color::new(1.0, 1.0, 1.0, 0.3); // White with low opacity
+        // J: Toggle the selected cell's brain inspector
+        if is_key_pressed(KeyCode::J) {
+            self.config.show_brain_inspector = !self.config.show_brain_inspector;
+        }
 
-        let screen_w = screen_width();
-        let screen_h = screen_height();
+        // K: Toggle the gene frequency panel (T is taken by trails)
+        if is_key_pressed(KeyCode::K) {
+            self.config.show_gene_stats = !self.config.show_gene_stats;
+            if self.config.show_gene_stats {
+                self.gene_stats = GeneStats::compute(&self.cells);
+            }
+        }
 
-        // Get world dimensions for wraparound rendering
-        let world_width = self.config.world_width;
-        let world_height = self.config.world_height;
+        // H: Toggle heatmap overlay, Shift+H: switch between cell count and energy
+        if is_key_pressed(KeyCode::H) {
+            if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+                self.heatmap.toggle_mode();
+                log_info!("Heatmap mode: {:?}", self.heatmap.mode);
+            } else {
+                self.heatmap.visible = !self.heatmap.visible;
+            }
+        }
 
-        // Define all possible wraparound positions (same as cell rendering)
-        let wraparound_offsets = [
-            (0.0, 0.0),                    // Original position
-            (-world_width, 0.0),           // Wrapped left
-            (world_width, 0.0),            // Wrapped right
-            (0.0, -world_height),          // Wrapped top
-            (0.0, world_height),           // Wrapped bottom
-            (-world_width, -world_height), // Top-left corner
-            (world_width, -world_height),  // Top-right corner
-            (-world_width, world_height),  // Bottom-left corner
-            (world_width, world_height),   // Bottom-right corner
-        ];
+        // Delete: Clear all cells (empty world, e.g. before spawning with B+click)
+        if is_key_pressed(KeyCode::Delete) {
+            self.clear_all();
+        }
 
-        // Calculate which grid points are visible on screen
-        // Start from the first grid point that could be visible
-        let start_x = ((self.camera.x / grid_spacing).floor() * grid_spacing).max(0.0);
-        let start_y = ((self.camera.y / grid_spacing).floor() * grid_spacing).max(0.0);
+        // C: Cull the weakest cull_fraction of the alive cells
+        if is_key_pressed(KeyCode::C) {
+            self.cull_weakest(self.config.cull_fraction);
+        }
 
-        // Draw grid points within world boundaries at all wraparound positions
-        let mut y = start_y;
-        while y <= world_height {
-            let mut x = start_x;
-            while x <= world_width {
-                // Render dot at all wraparound positions
-                for (dx, dy) in &wraparound_offsets {
-                    // Adjust camera position to create wraparound effect
-                    let adjusted_camera_x = self.camera.x - dx;
-                    let adjusted_camera_y = self.camera.y - dy;
+        // R: Manual reset with best genome
+        if !ctrl_down && is_key_pressed(KeyCode::R) {
+            if self.best_cell_genome.is_some() {
+                self.respawn_from_best();
+                log_info!("Manual reset triggered");
+            } else {
+                log_info!("No best genome available for reset");
+            }
+        }
 
-                    // Convert world coordinates to screen coordinates
-                    let screen_x = x - adjusted_camera_x;
-                    let screen_y = y - adjusted_camera_y;
+        // + or =: Increase speed
+        if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+            self.simulation_speed = (self.simulation_speed * 1.5).min(MAX_SIMULATION_SPEED);
+            log_info!("Simulation speed: {:.1}x", self.simulation_speed);
+        }
 
-                    // Only draw if on screen
-                    if screen_x >= -dot_radius
-                        && screen_x <= screen_w + dot_radius
-                        && screen_y >= -dot_radius
-                        && screen_y <= screen_h + dot_radius
-                    {
-                        draw_circle(screen_x, screen_y, dot_radius, dot_color);
-                    }
-                }
+        // - or _: Decrease speed
+        if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+            self.simulation_speed = (self.simulation_speed / 1.5).max(MIN_SIMULATION_SPEED);
+            log_info!("Simulation speed: {:.1}x", self.simulation_speed);
+        }
 
-                x += grid_spacing;
-            }
-            y += grid_spacing;
+        // 1: Reset to normal speed
+        if is_key_pressed(KeyCode::Key1) {
+            self.simulation_speed = 1.0;
+            log_info!("Simulation speed: 1.0x (normal)");
         }
     }
 
-    fn render_boundaries(&self) {
-        let boundary_color = Color::new(0.3, 0.3, 0.3, 1.0);
-        let line_thickness = 2.0;
+    // Rebuild spatial grid with all current cell positions
+    // Also rebuilds the id -> index map, since both go stale on the same structural changes
+    fn rebuild_spatial_grid(&mut self) {
+        // Synced here so a hot-reloaded movement.wrapping applies on the next tick
+        self.spatial_grid
+            .set_wrapping(self.config.movement.wrapping);
+        self.food.set_wrapping(self.config.movement.wrapping);
+        self.pheromones.set_wrapping(self.config.movement.wrapping);
+        self.spatial_grid.clear();
+        for (idx, cell) in self.cells.iter().enumerate() {
+            self.spatial_grid.insert(cell.x, cell.y, idx);
+        }
+        self.rebuild_id_map();
+    }
 
-        // Left boundary (x = 0)
-        let left_screen_x = 0.0 - self.camera.x;
-        draw_line(
-            left_screen_x,
-            0.0 - self.camera.y,
-            left_screen_x,
-            self.config.world_height - self.camera.y,
-            line_thickness,
-            boundary_color,
-        );
+    // Rebuild only the id -> index map (after removals, when the grid is rebuilt later anyway)
+    fn rebuild_id_map(&mut self) {
+        self.id_to_index.clear();
+        for (idx, cell) in self.cells.iter().enumerate() {
+            self.id_to_index.insert(cell.id, idx);
+        }
+    }
 
-        // Right boundary (x = world_width)
-        let right_screen_x = self.config.world_width - self.camera.x;
-        draw_line(
-            right_screen_x,
-            0.0 - self.camera.y,
-            right_screen_x,
-            self.config.world_height - self.camera.y,
-            line_thickness,
-            boundary_color,
-        );
+    // Allocate a new unique cell id (ids are never reused)
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_cell_id;
+        self.next_cell_id += 1;
+        id
+    }
 
-        // Top boundary (y = 0)
-        let top_screen_y = 0.0 - self.camera.y;
-        draw_line(
-            0.0 - self.camera.x,
-            top_screen_y,
-            self.config.world_width - self.camera.x,
+    // Resolve a cell id to its current index in `cells`
+    // The map can lag behind removals (swap_remove, resets) until the next rebuild, so the
+    // entry is checked against the cell actually stored there: a stale id resolves to None,
+    // never to whichever cell was swapped into its slot
+    pub fn index_of(&self, id: u64) -> Option<usize> {
+        self.id_to_index
+            .get(&id)
+            .copied()
+            .filter(|&idx| self.cells.get(idx).is_some_and(|cell| cell.id == id))
+    }
+
+    fn update_fps(&mut self, delta_time: f32) {
+        // Add current frame time
+        self.frame_times.push_back(delta_time);
+
+        // Keep only the last FPS_SAMPLE_SIZE frames
+        while self.frame_times.len() > FPS_SAMPLE_SIZE {
+            self.frame_times.pop_front();
+        }
+
+        // Calculate average FPS
+        if !self.frame_times.is_empty() {
+            let avg_frame_time: f32 =
+                self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32;
+            self.current_fps = if avg_frame_time > 0.0 {
+                1.0 / avg_frame_time
+            } else {
+                60.0 // Fallback
+            };
+        }
+    }
+
+    fn adjust_cell_cap(&mut self) {
+        // Only adjust every ADJUSTMENT_INTERVAL seconds
+        self.last_adjustment_time += self.frame_times.back().unwrap_or(&0.016);
+
+        if self.last_adjustment_time < ADJUSTMENT_INTERVAL {
+            return;
+        }
+
+        self.last_adjustment_time = 0.0;
+
+        let (cap, streak) = next_cell_cap(
+            self.max_cells,
+            self.current_fps,
+            is_vsync_locked(&self.frame_times),
+            self.cell_cap_grow_streak,
+            &self.config,
+        );
+        self.max_cells = cap;
+        self.cell_cap_grow_streak = streak;
+    }
+
+    // Pin the cell cap to a manual value, disabling FPS-based adjustment
+    fn pin_cell_cap(&mut self, cap: usize) {
+        self.max_cells = self.config.clamp_cell_cap(cap);
+        self.config.max_cells = Some(self.max_cells);
+        log_info!("Cell cap pinned to {} (\\ for auto)", self.max_cells);
+    }
+
+    // Refresh dynamic_reproduction_threshold, sampling it for the stats graph
+    fn update_reproduction_threshold(&mut self) {
+        if !self.config.use_dynamic_threshold {
+            self.dynamic_reproduction_threshold = REPRODUCTION_ENERGY_THRESHOLD;
+            self.threshold_history.clear();
+            return;
+        }
+        let alive = self
+            .cells
+            .iter()
+            .filter(|c| c.state == CellState::Alive)
+            .count();
+        self.dynamic_reproduction_threshold = dynamic_reproduction_threshold(alive, self.max_cells);
+        if self.tick_count.is_multiple_of(THRESHOLD_SAMPLE_INTERVAL) {
+            if self.threshold_history.len() == THRESHOLD_HISTORY {
+                self.threshold_history.pop_front();
+            }
+            self.threshold_history
+                .push_back(self.dynamic_reproduction_threshold);
+        }
+    }
+
+    // Cells above `energy_threshold` energy (young, off cooldown) split off a child
+    fn handle_reproduction(&mut self, energy_threshold: f32) {
+        let mut new_cells = Vec::new();
+        let current_cell_count = self.cells.len();
+        let best_cell_idx = self.last_best_cell_id.and_then(|id| self.index_of(id));
+        let cooldown = self.config.reproduction_cooldown_ticks;
+        let mutation_multiplier = self.config.mutation_rate_multiplier;
+        let weight_decay = self.config.weight_decay;
+        let structured_mutation = self.config.structured_mutation;
+        let (child_ratio, parent_ratio) = (
+            self.config.child_energy_ratio,
+            self.config.parent_energy_ratio,
+        );
+        let fitness = self.fitness();
+        let islands = self.islands;
+        let corridors_open =
+            islands.is_some_and(|islands| islands.corridors_open(self.elapsed_time));
+        let (world_width, world_height) = (self.config.world_width, self.config.world_height);
+        let wrapping = self.config.movement.wrapping;
+
+        for (idx, cell) in self.cells.iter_mut().enumerate() {
+            if cell.energy > energy_threshold
+                && cell.age < 15.0
+                && cell.reproduction_cooldown <= 0.0
+            {
+                // Check if we're at or over the max_cells cap
+                let projected_count = current_cell_count + new_cells.len();
+                if projected_count >= self.max_cells {
+                    // Cap reached: cell keeps its energy and cannot reproduce
+                    continue;
+                }
+
+                // Calculate energy distribution
+                let total_energy = cell.energy;
+                let child_energy = total_energy * child_ratio;
+                let parent_energy = total_energy * parent_ratio;
+
+                // Create child cell
+                let mut child =
+                    cell.spawn_child(mutation_multiplier, weight_decay, structured_mutation);
+                child.energy = child_energy;
+                child.reproduction_cooldown = cooldown;
+
+                // Children never appear across an island barrier: they start on the
+                // parent instead, the same rule that stops moves
+                let birthplace = (
+                    confine(child.x, world_width, wrapping),
+                    confine(child.y, world_height, wrapping),
+                );
+                if let Some(islands) = islands
+                    && !islands.allows_move((cell.x, cell.y), birthplace, corridors_open)
+                {
+                    (child.x, child.y) = (cell.x, cell.y);
+                }
+
+                // How much the best lineage's brain is still changing
+                if Some(idx) == best_cell_idx {
+                    let delta = child.brain.mutation_delta(&cell.brain);
+                    if delta.is_finite() {
+                        if self.best_lineage_deltas.len() == MUTATION_DELTA_HISTORY {
+                            self.best_lineage_deltas.pop_front();
+                        }
+                        self.best_lineage_deltas.push_back(delta);
+                        self.last_weight_change = child.brain.most_changed_weight(&cell.brain);
+                    }
+                }
+                new_cells.push(child);
+
+                // Update parent energy, increment children count, restart the cooldown
+                cell.energy = parent_energy;
+                cell.children_count += 1;
+                cell.reproduction_cooldown = cooldown;
+
+                // Save neural network if this is the best cell reproducing AND score improved
+                if Some(idx) == best_cell_idx {
+                    let score = fitness.score(cell);
+                    let tier = cell.brain_tier;
+                    // Only save if score is better than previous best for this tier
+                    if score > self.best_saved_scores[tier] {
+                        let prev_score = self.best_saved_scores[tier];
+                        let brain_clone = cell.brain.clone();
+                        let generation = cell.generation;
+                        let children = cell.children_count;
+                        let energy = cell.energy_from_cells;
+                        let age = cell.age;
+                        if self.config.use_storage {
+                            crate::storage::save_best_neural_network(
+                                tier,
+                                &brain_clone,
+                                generation,
+                                score,
+                                children,
+                                energy,
+                                age,
+                            );
+                        }
+                        // Update cache and best score
+                        self.cached_best_brains[tier] = Some((brain_clone, generation));
+                        self.best_saved_scores[tier] = score;
+                        log_info!(
+                            "📈 New high score (tier {}): {:.1} (previous: {:.1})",
+                            tier,
+                            score,
+                            prev_score
+                        );
+                    }
+                }
+            }
+        }
+
+        // Add new cells to the world
+        self.eras.record_reproductions(new_cells.len());
+        for mut child in new_cells {
+            child.id = self.next_id();
+            self.director.activity.record(child.x, child.y);
+            self.cells.push(child);
+        }
+    }
+
+    fn update_sensors(&mut self) {
+        // Spatial grid already built in update(), reuse it
+        // Extract cell data for sensor calculations (into last tick's buffer)
+        let mut cell_data = std::mem::take(&mut self.sensor_targets);
+        cell_data.clear();
+        cell_data.extend(self.cells.iter().map(|c| SensorTargetData {
+            id: c.id,
+            x: c.x,
+            y: c.y,
+            energy: c.energy,
+            is_alive: c.state == CellState::Alive,
+            radius: c.get_current_radius(),
+        }));
+        let raycast = self.config.vision_mode == VisionMode::Raycast;
+
+        // Capture world width and sensor range for parallel context
+        let world_width = self.config.world_width;
+        let sensor_range = self.config.sensor_range;
+
+        // Calculate local density for each cell (must be done before parallel update)
+        let mut density_counts = std::mem::take(&mut self.density_counts);
+        density_counts.clear();
+        density_counts.extend(self.cells.iter().map(|cell| {
+            let count = self.spatial_grid.count_nearby(cell.x, cell.y, sensor_range);
+            count.max(1) // Ensure count is at least 1 to avoid division by zero
+        }));
+
+        // Capture max_cells for density penalty calculation
+        let max_cells = self.max_cells;
+
+        // Pheromone field, sampled read-only (None leaves the inputs at zero)
+        let pheromones = self.config.pheromones.enabled.then_some(&self.pheromones);
+        let temperature = self.config.temperature;
+
+        // Food pellets are sensed like corpses: food that is not alive
+        let food = &self.food;
+        let pellet_target = |p: usize| {
+            let pellet = &food.pellets[p];
+            SensorTargetData {
+                id: pellet.id,
+                x: pellet.x,
+                y: pellet.y,
+                energy: pellet.energy,
+                is_alive: false,
+                radius: PELLET_RADIUS,
+            }
+        };
+
+        // Update sensors for each cell in parallel. Each rayon worker reuses its own
+        // scratch buffers (candidate readings, ray targets) across cells.
+        self.cells.par_iter_mut().enumerate().for_each_init(
+            || (Vec::<SensorReading>::new(), Vec::<RayTarget>::new()),
+            |(sensor_data, ray_targets), (i, cell)| {
+                // Update local density from pre-calculated counts
+                cell.local_density = density_counts[i];
+
+                // Calculate density penalty if cluster > 50% of population cap
+                if cell.local_density > max_cells / 2 {
+                    // Penalty: (1 - (1 / nb_cells))
+                    // This value is subtracted from score, so higher density = higher penalty
+                    cell.density_penalty = 1.0 - (1.0 / cell.local_density as f32);
+                } else {
+                    // No penalty when not overcrowded
+                    cell.density_penalty = 0.0;
+                }
+                // Cells normalize their inputs against the range they were sensed with
+                cell.sensor_range = sensor_range;
+
+                // Pheromone concentration here, and which way it increases
+                if let Some(field) = pheromones {
+                    cell.pheromone_level = field.concentration(cell.x, cell.y);
+                    let (gradient_x, gradient_y) = field.gradient(cell.x, cell.y);
+                    cell.pheromone_angle = if gradient_x == 0.0 && gradient_y == 0.0 {
+                        0.0
+                    } else {
+                        let relative = gradient_y.atan2(gradient_x) - cell.angle;
+                        (relative + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+                            - std::f32::consts::PI
+                    };
+                } else {
+                    cell.pheromone_level = 0.0;
+                    cell.pheromone_angle = 0.0;
+                }
+
+                // Local temperature, read by the sensor input and by Cell::update
+                cell.temperature = temperature.sample(cell.x, world_width);
+
+                // Every cell within range is a potential ray hit (raycast vision only)
+                ray_targets.clear();
+
+                // Calculate distances and angles to every cell and pellet within range
+                // (the grids do the wrapped distance filtering)
+                sensor_data.clear();
+                let (cell_x, cell_y, cell_angle) = (cell.x, cell.y, cell.angle);
+                let mut sense =
+                    |target: SensorTargetData, dx: f32, dy: f32, distance_squared: f32| {
+                        let distance = distance_squared.sqrt();
+
+                        // Calculate angle to target relative to cell's facing direction
+                        let angle_to_target = dy.atan2(dx);
+                        let mut angle_from_front = angle_to_target - cell_angle;
+
+                        // Normalize angle to -PI..PI range
+                        while angle_from_front > std::f32::consts::PI {
+                            angle_from_front -= std::f32::consts::TAU;
+                        }
+                        while angle_from_front < -std::f32::consts::PI {
+                            angle_from_front += std::f32::consts::TAU;
+                        }
+
+                        if raycast {
+                            ray_targets.push(RayTarget {
+                                angle: angle_from_front,
+                                distance,
+                                radius: target.radius,
+                                is_alive: target.is_alive,
+                            });
+                        }
+
+                        sensor_data.push(SensorReading {
+                            id: target.id,
+                            angle: angle_from_front,
+                            distance,
+                            is_alive: target.is_alive,
+                            energy: target.energy,
+                        });
+                    };
+                // Out of range indices (NaN position) never match
+                let target_position = |j: usize| {
+                    cell_data
+                        .get(j)
+                        .map_or((f32::NAN, f32::NAN), |target| (target.x, target.y))
+                };
+                self.spatial_grid.for_each_within(
+                    cell_x,
+                    cell_y,
+                    sensor_range,
+                    target_position,
+                    |j, dx, dy, distance_squared| {
+                        if j != i {
+                            sense(cell_data[j], dx, dy, distance_squared);
+                        }
+                    },
+                );
+                food.for_each_within(
+                    cell_x,
+                    cell_y,
+                    sensor_range,
+                    |p, dx, dy, distance_squared| {
+                        sense(pellet_target(p), dx, dy, distance_squared);
+                    },
+                );
+
+                if raycast {
+                    cell.ray_hits = cast_rays(ray_targets, sensor_range);
+                }
+
+                // Use partial sort to get top SENSOR_COUNT without sorting the entire vec
+                // Priority: dead > alive, then high energy > low energy, then close > far
+                if sensor_data.len() > SENSOR_COUNT {
+                    // Use select_nth_unstable to partition around the (SENSOR_COUNT-1)th element
+                    // This partitions so elements [0..SENSOR_COUNT] are the smallest/best
+                    sensor_data.select_nth_unstable_by(SENSOR_COUNT - 1, |a, b| {
+                        // Sort by alive status ascending (false before true, so dead before alive)
+                        // Then by energy descending, then by distance ascending
+                        a.is_alive
+                            .cmp(&b.is_alive)
+                            .then(
+                                b.energy
+                                    .partial_cmp(&a.energy)
+                                    .unwrap_or(std::cmp::Ordering::Equal),
+                            )
+                            .then(
+                                a.distance
+                                    .partial_cmp(&b.distance)
+                                    .unwrap_or(std::cmp::Ordering::Equal),
+                            )
+                    });
+                }
+                // Keep only the top SENSOR_COUNT
+                cell.nearest_cells = SensorReadings::from_slice(sensor_data);
+
+                // Center of mass for dead and alive cells separately, summed in the
+                // cell's local frame (angle is already relative to the facing direction)
+                let mut dead = (0.0f32, 0.0f32, 0usize);
+                let mut alive = (0.0f32, 0.0f32, 0usize);
+                for reading in cell.nearest_cells.iter() {
+                    let sums = if reading.is_alive {
+                        &mut alive
+                    } else {
+                        &mut dead
+                    };
+                    sums.0 += reading.angle.cos() * reading.distance;
+                    sums.1 += reading.angle.sin() * reading.distance;
+                    sums.2 += 1;
+                }
+
+                let dead_count = dead.2 as f32;
+                let alive_count = alive.2 as f32;
+                let total_count = dead_count + alive_count;
+
+                // Calculate dead/alive ratio: -1.0 = all alive, 1.0 = all dead, 0.0 = balanced
+                cell.dead_alive_ratio = if total_count > 0.0 {
+                    (dead_count - alive_count) / total_count
+                } else {
+                    0.0
+                };
+
+                // Calculate center of mass for dead cells
+                if dead.2 > 0 {
+                    let avg_x = dead.0 / dead_count;
+                    let avg_y = dead.1 / dead_count;
+
+                    // Calculate angle and distance to center of mass (in cell's local frame)
+                    cell.dead_center_distance = (avg_x * avg_x + avg_y * avg_y).sqrt();
+                    cell.dead_center_angle = avg_y.atan2(avg_x); // Relative to cell's facing direction
+                } else {
+                    // No dead cells detected
+                    cell.dead_center_angle = 0.0;
+                    cell.dead_center_distance = sensor_range; // Max range indicates nothing detected
+                }
+
+                // Calculate center of mass for alive cells
+                if alive.2 > 0 {
+                    let avg_x = alive.0 / alive_count;
+                    let avg_y = alive.1 / alive_count;
+
+                    // Calculate angle and distance to center of mass (in cell's local frame)
+                    cell.alive_center_distance = (avg_x * avg_x + avg_y * avg_y).sqrt();
+                    cell.alive_center_angle = avg_y.atan2(avg_x); // Relative to cell's facing direction
+                } else {
+                    // No alive cells detected
+                    cell.alive_center_angle = 0.0;
+                    cell.alive_center_distance = sensor_range; // Max range indicates nothing detected
+                }
+            },
+        );
+
+        self.sensor_targets = cell_data;
+        self.density_counts = density_counts;
+    }
+
+    fn update_stats(&mut self) {
+        // First, find the cell with the highest score (only alive cells)
+        let mut best_score = f32::MIN;
+        let mut best_cell_index = None;
+        let mut alive_cells = Vec::new();
+        let mut tier_best_scores = [f32::MIN; 4];
+        // Trait distributions are cheap but only worth refreshing while shown
+        let mut genes = (self.config.show_gene_stats
+            && self.tick_count.is_multiple_of(GENE_STATS_INTERVAL))
+        .then(GeneStatsAccumulator::new);
+
+        for (i, cell) in self.cells.iter().enumerate() {
+            // Only consider alive cells
+            if cell.state == CellState::Alive {
+                alive_cells.push(cell);
+                if let Some(genes) = &mut genes {
+                    genes.add(cell);
+                }
+
+                let score = self.fitness().score(cell);
+                if score > best_score {
+                    best_score = score;
+                    best_cell_index = Some(i);
+                }
+
+                // Track best score per tier
+                let tier = cell.brain_tier.min(3);
+                if score > tier_best_scores[tier] {
+                    tier_best_scores[tier] = score;
+                }
+            }
+        }
+
+        if let Some(genes) = genes {
+            self.gene_stats = genes.finish();
+        }
+
+        // Update tier current best scores (convert f32::MIN to 0.0 for empty tiers)
+        for (tier, &score) in tier_best_scores.iter().enumerate() {
+            self.tier_current_best_scores[tier] = if score == f32::MIN { 0.0 } else { score };
+        }
+
+        // Cluster alive cells into hue species and report the ones that died out
+        // (a total extinction is handled by respawn_from_best instead)
+        let hues: Vec<f32> = alive_cells
+            .iter()
+            .map(|cell| Cell::rgb_to_hsv_public(cell.color).0)
+            .collect();
+        let extinct = self.hue_species.update(&hues);
+        if !hues.is_empty() {
+            for id in extinct {
+                log_info!("💀 Hue species #{} went extinct", id);
+            }
+        }
+        self.species_alive_counts = self.hue_species.alive_counts();
+
+        if let Some(islands) = self.islands {
+            self.island_counts = islands.population(alive_cells.iter().map(|c| (c.x, c.y)));
+        }
+
+        // Color diversity: share of the hue circle's possible species that are alive
+        self.color_diversity =
+            (self.species_alive_counts.len() as f32 / MAX_HUE_SPECIES as f32).min(1.0);
+
+        // Calculate per-tier cell counts and intra-tier hue diversity
+        let mut tier_counts = [0usize; 4];
+        let mut tier_hues: [Vec<f32>; 4] = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        for cell in &alive_cells {
+            let tier = cell.brain_tier.min(3);
+            tier_counts[tier] += 1;
+            let (h, _, _) = Cell::rgb_to_hsv_public(cell.color);
+            tier_hues[tier].push(h);
+        }
+        self.tier_cell_counts = tier_counts;
+        for (tier, hues) in tier_hues.iter().enumerate() {
+            self.tier_diversities[tier] = if hues.len() > 1 {
+                let mean: f32 = hues.iter().sum::<f32>() / hues.len() as f32;
+                let variance: f32 = hues
+                    .iter()
+                    .map(|h| {
+                        let diff = (h - mean).abs();
+                        let diff = if diff > 180.0 { 360.0 - diff } else { diff };
+                        diff * diff
+                    })
+                    .sum::<f32>()
+                    / hues.len() as f32;
+                (variance / (180.0 * 180.0)).min(1.0)
+            } else {
+                0.0
+            };
+        }
+
+        // Leaderboard, rate limited: sorting every alive cell each tick isn't worth it
+        if self.clock - self.leaderboard_updated_at >= LEADERBOARD_INTERVAL {
+            self.leaderboard_updated_at = self.clock;
+            let mut top_cells = self.compute_top_cells(TRACKED_TOP_CELLS);
+            self.top_cell_ids = top_cells.iter().map(|entry| entry.id).collect();
+            top_cells.truncate(LEADERBOARD_SIZE);
+            self.stats.set_leaderboard(top_cells);
+            // The tracked cell keeps its selection; its rank follows the new order
+            self.tracked_rank = self
+                .tracked_rank
+                .and(self.picked_cell_id)
+                .and_then(|id| self.top_cell_ids.iter().position(|&top| top == id));
+        }
+
+        let alive_count = alive_cells.len();
+        self.mean_brain_cost = if alive_count > 0 {
+            alive_cells
+                .iter()
+                .map(|cell| self.config.energy.brain_cost(cell.brain.parameter_count()))
+                .sum::<f32>()
+                / alive_count as f32
+        } else {
+            0.0
+        };
+        let max_generation = alive_cells.iter().map(|c| c.generation).max().unwrap_or(0);
+        let mean_generation = alive_cells.iter().map(|c| c.generation as f32).sum::<f32>()
+            / alive_count.max(1) as f32;
+        self.push_population_events(alive_count, max_generation);
+        let diversity = self.diversity();
+        self.eras
+            .observe(alive_count, best_score, mean_generation, diversity);
+
+        // Update stats and genome with the best cell only, or clear if no alive cells
+        let current_time = self.clock;
+
+        // Check if the currently followed cell has died
+        let last_index = self.last_best_cell_id.and_then(|id| self.index_of(id));
+        let should_switch_target = if self.last_best_cell_id.is_some() {
+            if last_index.is_some_and(|idx| self.cells[idx].state == CellState::Alive) {
+                // Currently followed cell is still alive, reset death timer
+                self.followed_cell_death_time = None;
+                true // Can switch immediately to a better cell
+            } else {
+                // Currently followed cell is dead
+                if self.followed_cell_death_time.is_none() {
+                    // Record death time
+                    self.followed_cell_death_time = Some(current_time);
+                }
+
+                // Check if 3 seconds have passed since death
+                if let Some(death_time) = self.followed_cell_death_time {
+                    current_time - death_time >= 3.0
+                } else {
+                    false
+                }
+            }
+        } else {
+            // No cell being followed, can switch immediately
+            true
+        };
+
+        if let Some(best_index) = best_cell_index {
+            let best_cell = &self.cells[best_index];
+
+            // Only update to new best cell if we should switch targets
+            if should_switch_target {
+                // Only clone the best cell's genome if it changed (avoid expensive clone every frame)
+                if self.last_best_cell_id != Some(best_cell.id) {
+                    self.best_cell_genome = Some(best_cell.clone());
+                    self.last_best_cell_id = Some(best_cell.id);
+                    self.followed_cell_death_time = None; // Reset death timer for new target
+                }
+
+                // Set stats to show the current best alive cell
+                self.stats.set(self.cell_stats(best_index, false));
+
+                // Update selected cell index if stats are selected
+                if self.stats.is_selected() {
+                    self.selected_cell_id = Some(best_cell.id);
+                } else {
+                    self.selected_cell_id = None;
+                }
+            } else {
+                // Keep showing the dead cell until 3 seconds pass (until it is removed)
+                if let Some(last_index) = last_index {
+                    self.stats.set(self.cell_stats(last_index, false));
+                }
+            }
+        } else if should_switch_target {
+            // No alive cells found and cooldown has passed, clear the stats
+            self.stats.clear();
+            self.last_best_cell_id = None;
+            self.selected_cell_id = None;
+            self.followed_cell_death_time = None;
+        }
+
+        // The comparison closes once either cell is removed from the world
+        if let Some((first, second)) = self.compared_cell_ids
+            && (self.index_of(first).is_none() || self.index_of(second).is_none())
+        {
+            self.compared_cell_ids = None;
+        }
+
+        // A cell clicked in the viewport replaces the best cell in the stats box
+        // until it is removed from the world
+        if let Some(id) = self.picked_cell_id {
+            match self.index_of(id) {
+                Some(idx) => {
+                    self.stats.set(self.cell_stats(idx, true));
+                    self.selected_cell_id = Some(id);
+                }
+                None => {
+                    self.picked_cell_id = None;
+                    self.tracked_rank = None;
+                    self.selected_cell_id = None;
+                }
+            }
+        }
+    }
+
+    // Stats box contents for the cell at `idx`
+    fn cell_stats(&self, idx: usize, picked: bool) -> BestCellStats {
+        let cell = &self.cells[idx];
+        BestCellStats {
+            score: self.fitness().score(cell),
+            current_energy: cell.energy,
+            children_count: cell.children_count,
+            lateral_transfers: cell.lateral_transfers_received,
+            generation: cell.generation,
+            color: cell.color,
+            age: cell.age,
+            x: cell.x,
+            y: cell.y,
+            is_alive: cell.state == CellState::Alive,
+            brain_tier: cell.brain_tier,
+            brain_operations: cell.brain.operation_count(),
+            cell_index: idx,
+            prev_best_score: self.best_saved_scores[cell.brain_tier],
+            picked,
+            tracking: self
+                .tracked_rank
+                .filter(|&rank| picked && self.top_cell_ids.get(rank) == Some(&cell.id))
+                .map(|rank| (rank + 1, self.top_cell_ids.len())),
+        }
+    }
+
+    // Lateral gene transfer, a weak form of sexual reproduction: an alive cell whose
+    // nearest alive neighbor is within half the sensor range and of the same species
+    // (genome similarity above LATERAL_TRANSFER_SIMILARITY) sometimes swaps a random
+    // block of input-to-hidden weight rows with it. Costs no energy; each cell
+    // trades at most once per tick. Uses the readings from update_sensors.
+    fn lateral_gene_transfer(&mut self) {
+        let reach = self.config.sensor_range / 2.0;
+        let mut traded = vec![false; self.cells.len()];
+        for i in 0..self.cells.len() {
+            if traded[i] || self.cells[i].state != CellState::Alive {
+                continue;
+            }
+            let Some(nearest) = self.cells[i]
+                .nearest_cells
+                .iter()
+                .filter(|reading| reading.is_alive)
+                .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            else {
+                continue;
+            };
+            if nearest.distance > reach || rand::gen_range(0.0, 1.0) >= LATERAL_TRANSFER_CHANCE {
+                continue;
+            }
+            let Some(j) = self.index_of(nearest.id) else {
+                continue;
+            };
+            if j == i
+                || traded[j]
+                || self.cells[j].state != CellState::Alive
+                || self.cells[i].genome_similarity(&self.cells[j]) <= LATERAL_TRANSFER_SIMILARITY
+            {
+                continue;
+            }
+
+            let hidden_size = self.cells[i].brain.hidden_size.max(1);
+            let len = rand::gen_range(1, hidden_size / 2 + 1);
+            let start = rand::gen_range(0, hidden_size - len + 1);
+            let (low, high) = self.cells.split_at_mut(i.max(j));
+            let (a, b) = (&mut low[i.min(j)], &mut high[0]);
+            if a.brain.swap_hidden_rows(&mut b.brain, start, len) {
+                a.lateral_transfers_received += 1;
+                b.lateral_transfers_received += 1;
+                traded[i] = true;
+                traded[j] = true;
+            }
+        }
+    }
+
+    // Both sides of the comparison panel, None when not comparing
+    pub fn cell_comparison(&self) -> Option<CellComparison> {
+        let (first, second) = self.compared_cell_ids?;
+        let a = &self.cells[self.index_of(first)?];
+        let b = &self.cells[self.index_of(second)?];
+        let compared = |cell: &Cell| ComparedCell {
+            id: cell.id,
+            color: cell.color,
+            is_alive: cell.state == CellState::Alive,
+            score: self.fitness().score(cell),
+            energy: cell.energy,
+            age: cell.age,
+            generation: cell.generation,
+            children_count: cell.children_count,
+            mutation_rate: cell.mutation_rate_factor() * self.config.mutation_rate_multiplier,
+            speed: cell.speed,
+            radius: cell.radius,
+        };
+        Some(CellComparison {
+            cells: [compared(a), compared(b)],
+            weight_diff: crate::neural_network::NeuralNetwork::weight_diff(&a.brain, &b.brain),
+            similarity: a.genome_similarity(b),
+        })
+    }
+
+    // What the detail panel shows about the selected cell, None without a selection
+    pub fn selected_cell_detail(&self) -> Option<CellDetail> {
+        let cell = &self.cells[self.index_of(self.selected_cell_id?)?];
+        Some(CellDetail {
+            id: cell.id,
+            color: cell.color,
+            is_alive: cell.state == CellState::Alive,
+            energy: cell.energy,
+            mass: cell.mass,
+            age: cell.age,
+            age_energy_drain: cell.age_energy_drain(),
+            generation: cell.generation,
+            children_count: cell.children_count,
+            energy_donated: cell.energy_donated,
+            speed: cell.speed,
+            turn_rate: cell.turn_rate,
+            energy_chunk_size: cell.energy_chunk_size,
+            species_multiplier: cell.species_multiplier,
+            sensors: cell.nearest_cells,
+            last_action: cell.last_action.map(action_name),
+        })
+    }
+
+    // The selected cell's brain on its current sensors, None without a selection
+    pub fn selected_cell_brain(&self) -> Option<BrainInspection> {
+        let cell = &self.cells[self.index_of(self.selected_cell_id?)?];
+        Some(BrainInspection::of(cell))
+    }
+
+    // Energy gain multiplier for an alive cell feeding on a corpse: 1 + KIN_BONUS * similarity
+    // for relatives within genetic_distance_threshold, 1.0 otherwise (never a penalty)
+    fn kin_multiplier(&mut self, alive_idx: usize, corpse_idx: usize) -> f32 {
+        let (alive, corpse) = (&self.cells[alive_idx], &self.cells[corpse_idx]);
+        let similarity = self
+            .kin_cache
+            .get_or_insert_with(alive.id, corpse.id, || alive.genome_similarity(corpse))
+            .max(0.0);
+        if 1.0 - similarity <= self.config.genetic_distance_threshold {
+            1.0 + KIN_BONUS * similarity
+        } else {
+            1.0
+        }
+    }
+
+    // Soft-body collisions: every alive cell overlapping another alive cell gains
+    // velocity away from it, overlap_vector * push_force / mass, where the overlap
+    // vector points away from the other cell with a length of the penetration depth
+    // over the sum of both radii. Both cells of a pair are pushed (each computes its
+    // own share), so crowds spread out without overriding the brains' steering.
+    fn push_apart_alive_cells(&mut self, collision_data: &[CellCollisionData]) {
+        let push_force = self.config.movement.push_force;
+        if push_force <= 0.0 {
+            return;
+        }
+        let max_radius = max_collision_radius(collision_data);
+
+        let pushes: Vec<(f32, f32)> = (0..collision_data.len())
+            .into_par_iter()
+            .map(|i| {
+                let cell_i = &collision_data[i];
+                if !cell_i.is_solid() {
+                    return (0.0, 0.0);
+                }
+                let (mut push_x, mut push_y) = (0.0, 0.0);
+                self.spatial_grid.for_each_within(
+                    cell_i.x,
+                    cell_i.y,
+                    cell_i.radius + max_radius,
+                    |j| (collision_data[j].x, collision_data[j].y),
+                    |j, dx, dy, distance_squared| {
+                        let cell_j = &collision_data[j];
+                        if i == j || !cell_j.is_solid() {
+                            return;
+                        }
+                        let distance = distance_squared.sqrt();
+                        let reach = cell_i.radius + cell_j.radius;
+                        // Exactly stacked cells have no direction to push in
+                        if distance >= reach || distance <= 0.0 {
+                            return;
+                        }
+                        // Away from the neighbor, against its offset
+                        let overlap = (reach - distance) / reach;
+                        push_x -= dx / distance * overlap;
+                        push_y -= dy / distance * overlap;
+                    },
+                );
+                let scale = push_force / cell_i.mass.max(1.0);
+                (push_x * scale, push_y * scale)
+            })
+            .collect();
+
+        for (cell, (push_x, push_y)) in self.cells.iter_mut().zip(pushes) {
+            cell.velocity_x += push_x;
+            cell.velocity_y += push_y;
+        }
+    }
+
+    // Hard collisions (cell_collisions): overlapping alive cells are moved apart along
+    // the line between them, each by the overlap times the other's share of their
+    // combined mass, so a pair separates fully and the heavier cell moves less.
+    // Moves are computed in parallel from one snapshot, capped at max_collision_push
+    // so dense clumps relax over several ticks instead of jittering, then applied.
+    fn separate_alive_cells(&mut self) {
+        let collision_data = self.collision_snapshot();
+        let max_radius = max_collision_radius(&collision_data);
+        let max_push = self.config.movement.max_collision_push;
+
+        let moves: Vec<(f32, f32)> = (0..collision_data.len())
+            .into_par_iter()
+            .map(|i| {
+                let cell_i = &collision_data[i];
+                if !cell_i.is_solid() {
+                    return (0.0, 0.0);
+                }
+                let (mut move_x, mut move_y) = (0.0, 0.0);
+                self.spatial_grid.for_each_within(
+                    cell_i.x,
+                    cell_i.y,
+                    cell_i.radius + max_radius,
+                    |j| (collision_data[j].x, collision_data[j].y),
+                    |j, dx, dy, distance_squared| {
+                        let cell_j = &collision_data[j];
+                        if i == j || !cell_j.is_solid() {
+                            return;
+                        }
+                        let distance = distance_squared.sqrt();
+                        let overlap = cell_i.radius + cell_j.radius - distance;
+                        // Exactly stacked cells have no direction to separate in
+                        if overlap <= 0.0 || distance <= 0.0 {
+                            return;
+                        }
+                        let share = cell_j.mass / (cell_i.mass + cell_j.mass).max(f32::EPSILON);
+                        move_x -= dx / distance * overlap * share;
+                        move_y -= dy / distance * overlap * share;
+                    },
+                );
+                let length = move_x.hypot(move_y);
+                if length > max_push {
+                    (move_x * max_push / length, move_y * max_push / length)
+                } else {
+                    (move_x, move_y)
+                }
+            })
+            .collect();
+
+        let (world_width, world_height) = (self.config.world_width, self.config.world_height);
+        let wrapping = self.config.movement.wrapping;
+        let open = self
+            .islands
+            .is_some_and(|islands| islands.corridors_open(self.elapsed_time));
+        for (cell, (move_x, move_y)) in self.cells.iter_mut().zip(moves) {
+            if move_x == 0.0 && move_y == 0.0 {
+                continue;
+            }
+            let to = (
+                confine(cell.x + move_x, world_width, wrapping),
+                confine(cell.y + move_y, world_height, wrapping),
+            );
+            // Collisions never push a cell through an island barrier
+            if let Some(islands) = self.islands
+                && !islands.allows_move((cell.x, cell.y), to, open)
+            {
+                continue;
+            }
+            (cell.x, cell.y) = to;
+        }
+    }
+
+    // Read-only per-cell data for the parallel collision passes
+    fn collision_snapshot(&self) -> Vec<CellCollisionData> {
+        self.cells
+            .iter()
+            .map(|cell| CellCollisionData {
+                x: cell.x,
+                y: cell.y,
+                radius: cell.get_current_radius(), // Use age-based radius
+                energy_chunk_size: cell.energy_chunk_size,
+                species_multiplier: cell.species_multiplier,
+                mass: cell.mass,
+                state: cell.state,
+                infected: cell.infected.is_some(),
+                parasite: cell.is_parasite(),
+            })
+            .collect()
+    }
+
+    pub fn check_collisions(&mut self) {
+        // Spatial grid already built in update(), reuse it
+        // Extract read-only collision data for parallel processing
+        let collision_data = self.collision_snapshot();
+
+        self.push_apart_alive_cells(&collision_data);
+
+        // Farthest apart two touching cells can be is one radius plus the largest one
+        let max_radius = max_collision_radius(&collision_data);
+        let position = |j: usize| (collision_data[j].x, collision_data[j].y);
+        // Whether cells i and j overlap, given their squared distance
+        let touching = |i: usize, j: usize, distance_squared: f32| {
+            let collision_distance = collision_data[i].radius + collision_data[j].radius;
+            distance_squared < collision_distance * collision_distance
+        };
+
+        // Parallel collision detection using spatial grid
+        // Returns (alive_cell_index, corpse_cell_index, chunk_size, multiplier)
+        let collisions: Vec<(usize, usize, f32, f32)> = (0..collision_data.len())
+            .into_par_iter()
+            .filter_map(|i| {
+                // Skip corpse cells as energy donors, and parasites (see feed_parasites)
+                if collision_data[i].state == CellState::Corpse || collision_data[i].parasite {
+                    return None;
+                }
+
+                let cell_i = &collision_data[i];
+
+                // First nearby corpse the cell touches (the grid handles wrapping)
+                let mut corpse = None;
+                self.spatial_grid.for_each_within(
+                    cell_i.x,
+                    cell_i.y,
+                    cell_i.radius + max_radius,
+                    position,
+                    |j, _, _, distance_squared| {
+                        let is_corpse = collision_data[j].state != CellState::Alive;
+                        if corpse.is_none() && is_corpse && touching(i, j, distance_squared) {
+                            corpse = Some(j);
+                        }
+                    },
+                );
+
+                // Return (alive_idx, corpse_idx, chunk_size, species_multiplier)
+                corpse.map(|j| (i, j, cell_i.energy_chunk_size, cell_i.species_multiplier))
+            })
+            .collect();
+
+        // Apply energy transfers to alive cells and reduce energy from corpse cells
+        // Cold corpses give up their energy in smaller chunks
+        let strict = self.config.strict_energy_conservation;
+        let temperature = self.config.temperature;
+        for (alive_idx, corpse_idx, chunk_size, multiplier) in &collisions {
+            let multiplier = multiplier * self.kin_multiplier(*alive_idx, *corpse_idx);
+            let chunk_size =
+                chunk_size * temperature.feeding_multiplier(self.cells[*corpse_idx].temperature);
+            if strict {
+                // Multiplier is extraction efficiency, but a corpse can never give
+                // out more than it holds. Processed sequentially so several feeders
+                // on the same corpse share what is left.
+                let available = self.cells[*corpse_idx].energy.max(0.0);
+                let transfer = (chunk_size * multiplier).min(available);
+                if transfer <= 0.0 {
+                    continue;
+                }
+                self.cells[*corpse_idx].energy -= transfer;
+                // Alive cell gains energy through gain_energy() (handles growth mechanic)
+                self.cells[*alive_idx].gain_energy(transfer);
+            } else {
+                // Alive cell gains energy through gain_energy() (handles growth mechanic)
+                self.cells[*alive_idx].gain_energy(chunk_size * multiplier);
+                // Corpse cell loses base chunk_size
+                self.cells[*corpse_idx].energy -= chunk_size;
+            }
+            let feeder = &collision_data[*alive_idx];
+            self.director.activity.record(feeder.x, feeder.y);
+        }
+
+        self.feed_parasites(&collision_data);
+        self.share_donations(&collision_data);
+
+        // Plague transmission: healthy alive cells touching a carrier (alive or corpse) catch it
+        if collision_data.iter().any(|data| data.infected) {
+            let newly_infected: Vec<usize> = (0..collision_data.len())
+                .into_par_iter()
+                .filter(|&i| {
+                    let cell_i = &collision_data[i];
+                    if cell_i.state != CellState::Alive || cell_i.infected {
+                        return false;
+                    }
+
+                    let mut exposed = false;
+                    self.spatial_grid.for_each_within(
+                        cell_i.x,
+                        cell_i.y,
+                        cell_i.radius + max_radius,
+                        position,
+                        |j, _, _, distance_squared| {
+                            exposed |= i != j
+                                && collision_data[j].infected
+                                && touching(i, j, distance_squared);
+                        },
+                    );
+                    exposed
+                })
+                .collect();
+
+            for idx in newly_infected {
+                self.cells[idx].infected = Some(PLAGUE_DURATION_TICKS);
+            }
+        }
+
+        // Collect cells with energy below threshold to remove
+        // Strict mode: corpses are removed as soon as they are empty
+        // Corpses past corpse_max_age go too, whatever energy they still hold
+        let corpse_max_age = self.config.corpse_max_age;
+        let mut indices_to_remove: Vec<usize> = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, cell)| {
+                let is_corpse = cell.state == CellState::Corpse;
+                let empty_corpse = strict && is_corpse && cell.energy <= 0.0;
+                let expired_corpse =
+                    is_corpse && corpse_max_age > 0.0 && cell.corpse_age >= corpse_max_age;
+                if empty_corpse || expired_corpse || cell.energy < DEPLETED_CELL_ENERGY {
+                    Some(idx)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // Nutrient recycling: removed corpses return part of their decayed energy as pellets
+        let recycle_fraction = self.config.nutrient_recycle_fraction.clamp(0.0, 1.0);
+        if recycle_fraction > 0.0 {
+            let bloom_food = self.food_regen_multiplier();
+            let world_size = (self.config.world_width, self.config.world_height);
+            let next_cell_id = &mut self.next_cell_id;
+            for &idx in &indices_to_remove {
+                let corpse = &self.cells[idx];
+                if corpse.state != CellState::Corpse {
+                    continue;
+                }
+                let zone_food =
+                    crate::zones::food_multiplier(&self.zones, corpse.x, corpse.y, world_size)
+                        * self
+                            .regions
+                            .as_ref()
+                            .map_or(1.0, |r| r.food_multiplier(corpse.x, corpse.y));
+                self.food.spawn_from_corpse(
+                    (corpse.x, corpse.y),
+                    corpse.get_current_radius(),
+                    corpse.decayed_energy * recycle_fraction * zone_food * bloom_food,
+                    world_size,
+                    || {
+                        let id = *next_cell_id;
+                        *next_cell_id += 1;
+                        id
+                    },
+                );
+            }
+        }
+
+        // Remove depleted cells in reverse order to maintain index validity
+        // Using unstable sort for performance we don't need to order them, we just mutate directly
+        // to clean up
+        indices_to_remove.sort_unstable();
+        for &idx in indices_to_remove.iter().rev() {
+            self.cells.swap_remove(idx);
+        }
+        // Keep id lookups exact for reproduction and stats later this tick
+        if !indices_to_remove.is_empty() {
+            self.rebuild_id_map();
+        }
+
+        // Boundary wrapping now handled inline in cell.update()
+    }
+
+    // Parasites never eat corpses or pellets. One without a live host attaches to the
+    // first alive non-parasite it touches; while attached it rides on the host's
+    // center, drains PARASITE_DRAIN_RATE per tick (keeping half, the rest is lost as
+    // heat) and jolts the host's velocity by up to PARASITE_JOLT. A host that dies or
+    // is removed leaves its parasites to find a new one or starve.
+    fn feed_parasites(&mut self, collision_data: &[CellCollisionData]) {
+        if !collision_data
+            .iter()
+            .any(|data| data.parasite && data.state == CellState::Alive)
+        {
+            return;
+        }
+        let max_radius = max_collision_radius(collision_data);
+
+        let attachments: Vec<(usize, Option<usize>)> = (0..collision_data.len())
+            .into_par_iter()
+            .filter_map(|i| {
+                let parasite = &collision_data[i];
+                if !parasite.parasite || parasite.state != CellState::Alive {
+                    return None;
+                }
+                let current = self.cells[i]
+                    .host_id
+                    .and_then(|id| self.index_of(id))
+                    .filter(|&host| collision_data[host].is_solid());
+                if current.is_some() {
+                    return Some((i, current));
+                }
+                let mut host = None;
+                self.spatial_grid.for_each_within(
+                    parasite.x,
+                    parasite.y,
+                    parasite.radius + max_radius,
+                    |j| (collision_data[j].x, collision_data[j].y),
+                    |j, _, _, distance_squared| {
+                        let reach = parasite.radius + collision_data[j].radius;
+                        if host.is_none()
+                            && collision_data[j].is_solid()
+                            && distance_squared < reach * reach
+                        {
+                            host = Some(j);
+                        }
+                    },
+                );
+                Some((i, host))
+            })
+            .collect();
+
+        for (parasite_idx, host_idx) in attachments {
+            self.cells[parasite_idx].host_id = host_idx.map(|idx| self.cells[idx].id);
+            let Some(host_idx) = host_idx else {
+                continue;
+            };
+            let host = &mut self.cells[host_idx];
+            let drained = PARASITE_DRAIN_RATE.min(host.energy.max(0.0));
+            host.energy -= drained;
+            host.velocity_x += rand::gen_range(-PARASITE_JOLT, PARASITE_JOLT);
+            host.velocity_y += rand::gen_range(-PARASITE_JOLT, PARASITE_JOLT);
+            let host_position = (host.x, host.y);
+
+            let parasite = &mut self.cells[parasite_idx];
+            parasite.gain_energy(drained * 0.5);
+            (parasite.x, parasite.y) = host_position;
+        }
+    }
+
+    // Alive cells whose last action was donate (and that can afford it) pay DONATE_COST,
+    // split evenly through gain_energy among the alive kin within DONATE_RANGE
+    // (genome similarity above DONATE_KIN_SIMILARITY). Without kin in range nothing is paid.
+    fn share_donations(&mut self, collision_data: &[CellCollisionData]) {
+        let donates = |cell: &Cell| {
+            cell.state == CellState::Alive
+                && cell.last_action == Some(DONATE_ACTION)
+                && cell.energy > DONATE_COST
+        };
+        if !self.cells.iter().any(donates) {
+            return;
+        }
+
+        let donations: Vec<(usize, Vec<usize>)> = (0..self.cells.len())
+            .into_par_iter()
+            .filter(|&i| donates(&self.cells[i]))
+            .map(|i| {
+                let donor = &self.cells[i];
+                let mut kin = Vec::new();
+                self.spatial_grid.for_each_within(
+                    donor.x,
+                    donor.y,
+                    DONATE_RANGE,
+                    |j| (collision_data[j].x, collision_data[j].y),
+                    |j, _, _, _| {
+                        if j != i
+                            && collision_data[j].state == CellState::Alive
+                            && donor.genome_similarity(&self.cells[j]) > DONATE_KIN_SIMILARITY
+                        {
+                            kin.push(j);
+                        }
+                    },
+                );
+                (i, kin)
+            })
+            .filter(|(_, kin)| !kin.is_empty())
+            .collect();
+
+        for (donor_idx, kin) in donations {
+            let donor = &mut self.cells[donor_idx];
+            donor.energy -= DONATE_COST;
+            donor.energy_donated += DONATE_COST;
+            donor.donate_pulse = DONATE_PULSE_TICKS;
+            let share = DONATE_COST / kin.len() as f32;
+            for recipient in kin {
+                self.cells[recipient].gain_energy(share);
+            }
+        }
+    }
+
+    // Alive cells eat every food pellet they touch. Contacts are found in parallel,
+    // then applied in order so each pellet feeds only one cell.
+    fn eat_food_pellets(&mut self) {
+        if self.food.pellets.is_empty() {
+            return;
+        }
+        self.food.rebuild_grid();
+
+        let food = &self.food;
+        let contacts: Vec<(usize, Vec<usize>)> = self
+            .cells
+            .par_iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.state == CellState::Alive && !cell.is_parasite())
+            .map(|(i, cell)| {
+                let reach = cell.get_current_radius() + PELLET_RADIUS;
+                let mut touching = Vec::new();
+                food.for_each_within(cell.x, cell.y, reach, |p, _, _, distance_squared| {
+                    if distance_squared < reach * reach {
+                        touching.push(p);
+                    }
+                });
+                (i, touching)
+            })
+            .filter(|(_, touching): &(usize, Vec<usize>)| !touching.is_empty())
+            .collect();
+        if contacts.is_empty() {
+            return;
+        }
+
+        let mut eaten = vec![false; self.food.pellets.len()];
+        for (cell_idx, touching) in contacts {
+            for pellet_idx in touching {
+                if !eaten[pellet_idx] {
+                    eaten[pellet_idx] = true;
+                    self.cells[cell_idx].gain_energy(self.food.pellets[pellet_idx].energy);
+                }
+            }
+        }
+        let mut eaten = eaten.into_iter();
+        self.food.pellets.retain(|_| !eaten.next().unwrap_or(false));
+        self.food.rebuild_grid();
+    }
+
+    // Sensor overlay mode in effect (see SensorLineMode::with_overlay)
+    fn sensor_lines(&self) -> SensorLineMode {
+        self.sensor_line_mode
+            .with_overlay(self.config.show_sensor_lines)
+    }
+
+    fn render_sensor_lines(&self, view: &ViewBounds) {
+        let selected_only = self.sensor_lines() == SensorLineMode::SelectedOnly;
+        let world_width = self.config.world_width;
+        let world_height = self.config.world_height;
+
+        // Define all possible wraparound positions (same as cell rendering)
+        let wraparound_offsets = [
+            (0.0, 0.0),                    // Original position
+            (-world_width, 0.0),           // Wrapped left
+            (world_width, 0.0),            // Wrapped right
+            (0.0, -world_height),          // Wrapped top
+            (0.0, world_height),           // Wrapped bottom
+            (-world_width, -world_height), // Top-left corner
+            (world_width, -world_height),  // Top-right corner
+            (-world_width, world_height),  // Bottom-left corner
+            (world_width, world_height),   // Bottom-right corner
+        ];
+
+        for cell in &self.cells {
+            // Only draw sensors for alive cells
+            if cell.state != CellState::Alive
+                || (selected_only && self.selected_cell_id != Some(cell.id))
+            {
+                continue;
+            }
+
+            // Render sensor lines for each wraparound position
+            for (offset_x, offset_y) in &wraparound_offsets {
+                // Adjust camera position to create wraparound effect
+                let adjusted_camera_x = self.camera.x - offset_x;
+                let adjusted_camera_y = self.camera.y - offset_y;
+
+                // Check if cell is visible at this wraparound position
+                let cell_screen_x = cell.x - adjusted_camera_x;
+                let cell_screen_y = cell.y - adjusted_camera_y;
+                let margin = self.config.sensor_range; // Use sensor range as margin
+
+                if !view.contains(cell_screen_x, cell_screen_y, margin) {
+                    continue; // Skip if cell not visible at this wraparound position
+                }
+
+                if self.config.vision_mode == VisionMode::Raycast {
+                    Self::render_rays(cell, cell_screen_x, cell_screen_y);
+                    continue;
+                }
+
+                for slot in 0..SENSOR_COUNT {
+                    let target = cell.nearest_cells.get(slot).and_then(|reading| {
+                        // Skip targets that no longer exist
+                        let target = &self.cells[self.index_of(reading.id)?];
+                        Some((target, reading.is_alive))
+                    });
+
+                    // Direction of the slot: towards its target, or a fixed fan
+                    // around the heading for empty slots
+                    let (target_x, target_y, angle_to_target) = match target {
+                        Some((target, _)) => {
+                            // Calculate vector from cell to target
+                            let mut dx = target.x - cell.x;
+                            let mut dy = target.y - cell.y;
+
+                            // Handle world wrapping for line drawing
+                            if dx.abs() > world_width / 2.0 {
+                                dx = dx - dx.signum() * world_width;
+                            }
+                            if dy.abs() > world_height / 2.0 {
+                                dy = dy - dy.signum() * world_height;
+                            }
+
+                            // Calculate target position accounting for wrapping
+                            (cell.x + dx, cell.y + dy, dy.atan2(dx))
+                        }
+                        None => {
+                            let angle = cell.angle + empty_slot_angle(slot);
+                            let length = cell.radius + EMPTY_SLOT_STUB_LENGTH;
+                            (
+                                cell.x + angle.cos() * length,
+                                cell.y + angle.sin() * length,
+                                angle,
+                            )
+                        }
+                    };
+
+                    // Normalize angles to -PI to PI range
+                    let mut angle_diff = angle_to_target - cell.angle;
+                    while angle_diff > std::f32::consts::PI {
+                        angle_diff -= std::f32::consts::TAU;
+                    }
+                    while angle_diff < -std::f32::consts::PI {
+                        angle_diff += std::f32::consts::TAU;
+                    }
+
+                    // Calculate opacity based on how much the target is in front
+                    // 0 degrees (directly in front) = full opacity
+                    // ±180 degrees (directly behind) = no opacity
+                    let normalized_angle = angle_diff.abs() / std::f32::consts::PI; // 0.0 to 1.0
+                    let opacity = (1.0 - normalized_angle).max(0.0);
+
+                    // Convert to screen coordinates using adjusted camera
+                    let x1 = cell.x - adjusted_camera_x;
+                    let y1 = cell.y - adjusted_camera_y;
+                    let x2 = target_x - adjusted_camera_x;
+                    let y2 = target_y - adjusted_camera_y;
+
+                    let line_color = sensor_line_color(
+                        target.map(|(_, is_alive)| is_alive),
+                        opacity * 0.3, // Scale down opacity for subtlety
+                    );
+                    draw_line(x1, y1, x2, y2, 1.0, line_color);
+
+                    // Direction dot on the cell edge, drawn for every slot
+                    let dot_x = x1 + angle_to_target.cos() * cell.radius;
+                    let dot_y = y1 + angle_to_target.sin() * cell.radius;
+                    draw_circle(dot_x, dot_y, 1.5, line_color);
+
+                    if target.is_some()
+                        && let Some((left, right)) =
+                            arrowhead_points(vec2(x1, y1), vec2(x2, y2), SENSOR_ARROWHEAD_SIZE)
+                    {
+                        draw_triangle(vec2(x2, y2), left, right, line_color);
+                    }
+                }
+            }
+        }
+    }
+
+    // Draw what a cell sees in raycast vision mode: each ray stops at its first hit
+    fn render_rays(cell: &Cell, screen_x: f32, screen_y: f32) {
+        for (i, hit) in cell.ray_hits.iter().enumerate().take(RAY_COUNT) {
+            let angle = cell.angle + ray_angle(i);
+            let end_x = screen_x + angle.cos() * hit.distance;
+            let end_y = screen_y + angle.sin() * hit.distance;
+
+            let line_color = Color::new(cell.color.r, cell.color.g, cell.color.b, 0.2);
+            draw_line(screen_x, screen_y, end_x, end_y, 1.0, line_color);
+
+            // Mark hits: green for alive cells, red for corpses
+            let hit_color = match hit.kind {
+                RayHitKind::Alive => Color::new(0.3, 1.0, 0.3, 0.8),
+                RayHitKind::Corpse => Color::new(1.0, 0.3, 0.3, 0.8),
+                RayHitKind::Nothing => continue,
+            };
+            draw_circle(end_x, end_y, 2.5, hit_color);
+        }
+    }
+
+    pub fn render(&self) {
+        // Render parallax star-field background
+        if let Some(bg) = &self.background {
+            bg.render(self.camera.x, self.camera.y);
+        }
+
+        // Tint the background darker at night
+        let night_alpha = darkness_at(self.time_of_day) * NIGHT_TINT_MAX_ALPHA;
+        if night_alpha > 0.0 {
+            draw_rectangle(
+                0.0,
+                0.0,
+                screen_width(),
+                screen_height(),
+                Color::new(0.0, 0.02, 0.1, night_alpha),
+            );
+        }
+
+        // Everything in world space rotates with the camera from here on;
+        // the background and night tint above stay fixed to the screen
+        let view = self.camera.view_bounds(screen_width(), screen_height());
+        self.camera.begin_world_render();
+
+        // Render boundary lines (only if UI enabled)
+        if self.config.show_ui {
+            self.render_grid(&view);
+            self.render_boundaries();
+        }
+
+        // Succession regions, zones and island barriers are part of the world, drawn even
+        // with the UI hidden
+        if let Some(regions) = &self.regions {
+            regions.render(
+                self.camera.x,
+                self.camera.y,
+                &view,
+                self.config.world_width,
+                self.config.world_height,
+            );
+        }
+        crate::zones::render(
+            &self.zones,
+            self.camera.x,
+            self.camera.y,
+            &view,
+            self.config.world_width,
+            self.config.world_height,
+        );
+        if let Some(islands) = &self.islands {
+            islands.render(
+                self.camera.x,
+                self.camera.y,
+                &view,
+                self.config.world_width,
+                self.config.world_height,
+                islands.corridors_open(self.elapsed_time),
+            );
+        }
+
+        self.food.render(
+            self.camera.x,
+            self.camera.y,
+            &view,
+            self.config.world_width,
+            self.config.world_height,
+        );
+
+        // Heatmap overlay (behind sensor lines and cells)
+        self.heatmap.render(self.camera.x, self.camera.y, &view);
+        if self.config.show_ui && self.config.show_density_map {
+            self.render_density_map(&view);
+        }
+        if self.config.show_ui && self.config.show_pheromones {
+            self.pheromones.render(self.camera.x, self.camera.y, &view);
+        }
+        if self.config.show_ui && self.config.show_temperature {
+            temperature::render(self.camera.x, &view, self.config.world_width);
+        }
+
+        // In playback mode, draw the recorded frame instead of live cells
+        if self.replay.is_playing() {
+            self.render_replay_frame(&view);
+            self.camera.end_world_render();
+            self.render_replay_label();
+            return;
+        }
+
+        // Render sensor lines first (so they appear behind cells)
+        if self.sensor_lines() != SensorLineMode::Off {
+            self.render_sensor_lines(&view);
+        }
+
+        // Count cells in viewport from the spatial grid (handles world wrapping)
+        let cells_in_viewport = self.spatial_grid.count_in_rect(
+            self.camera.x + view.min_x,
+            self.camera.y + view.min_y,
+            self.camera.x + view.max_x,
+            self.camera.y + view.max_y,
+        );
+
+        // Get world dimensions for wraparound rendering
+        let world_width = self.config.world_width;
+        let world_height = self.config.world_height;
+
+        // Define all possible wraparound positions
+        // Cells are rendered at multiple positions to show toroidal topology
+        let wraparound_offsets = [
+            (0.0, 0.0),                    // Original position
+            (-world_width, 0.0),           // Wrapped left
+            (world_width, 0.0),            // Wrapped right
+            (0.0, -world_height),          // Wrapped top
+            (0.0, world_height),           // Wrapped bottom
+            (-world_width, -world_height), // Top-left corner
+            (world_width, -world_height),  // Top-right corner
+            (-world_width, world_height),  // Bottom-left corner
+            (world_width, world_height),   // Bottom-right corner
+        ];
+
+        // Render cells
+        let max_generation = match self.render_color_mode {
+            ColorMode::Generation => self.cells.iter().map(|c| c.generation).max().unwrap_or(0),
+            _ => 0,
+        };
+        // Large populations are drawn from the sprite atlas in one batch
+        let batched = self
+            .sprites
+            .as_ref()
+            .filter(|_| self.cells.len() >= BATCH_RENDER_MIN_CELLS);
+        // Body radius of the host an attached parasite is drawn around
+        let host_radius = |cell: &Cell| {
+            cell.host_id
+                .and_then(|id| self.index_of(id))
+                .map(|idx| self.cells[idx].get_current_radius())
+        };
+        let color_of = |cell: &Cell| {
+            cell.display_color(
+                self.render_color_mode,
+                max_generation,
+                self.species_by_id.get(&cell.id).copied(),
+            )
+        };
+        // Camera positions that render each cell at all its wraparound copies
+        let wrapped_cameras =
+            wraparound_offsets.map(|(dx, dy)| (self.camera.x - dx, self.camera.y - dy));
+        let is_drawn_parasite = |cell: &Cell| cell.is_parasite() && cell.state == CellState::Alive;
+
+        // Drawn in passes, so shapes never land between two sprites and break the
+        // atlas batch: trails under everything, then the bodies, then the overlays.
+        // All render paths have built-in viewport culling, and skip off-screen copies.
+        if self.config.show_trails {
+            for cell in &self.cells {
+                let color = color_of(cell);
+                for &(camera_x, camera_y) in &wrapped_cameras {
+                    cell.render_trail(camera_x, camera_y, &view, color, world_width, world_height);
+                }
+            }
+        }
+
+        for cell in self.cells.iter().filter(|cell| !is_drawn_parasite(cell)) {
+            let color = color_of(cell);
+            for &(camera_x, camera_y) in &wrapped_cameras {
+                match batched {
+                    Some(sprites) => cell.render_batched(
+                        sprites,
+                        camera_x,
+                        camera_y,
+                        &view,
+                        color,
+                        self.camera.zoom,
+                    ),
+                    None => cell.render(camera_x, camera_y, &view, color),
+                }
+            }
+        }
+
+        // Overlays: attached parasites, and the selection highlight of the selected
+        // cell (or the compared ones)
+        for cell in &self.cells {
+            let parasite = is_drawn_parasite(cell);
+            let highlighted = self.selected_cell_id == Some(cell.id)
+                || self
+                    .compared_cell_ids
+                    .is_some_and(|(first, second)| cell.id == first || cell.id == second);
+            if !parasite && !highlighted {
+                continue;
+            }
+            for &(camera_x, camera_y) in &wrapped_cameras {
+                if parasite {
+                    cell.render_parasite(
+                        camera_x,
+                        camera_y,
+                        &view,
+                        color_of(cell),
+                        host_radius(cell),
+                    );
+                }
+                if highlighted {
+                    self.render_selection_highlight(cell, camera_x, camera_y, &view);
+                }
+            }
+        }
+
+        // Energy brush outline under the mouse while U is held
+        if self.is_energy_brush_active() {
+            let (mouse_x, mouse_y) = mouse_position();
+            let (x, y) =
+                self.camera
+                    .screen_to_world(mouse_x, mouse_y, screen_width(), screen_height());
+            draw_circle_lines(
+                x - self.camera.x,
+                y - self.camera.y,
+                self.energy_brush_radius,
+                2.0 / self.camera.zoom,
+                Color::new(1.0, 0.9, 0.3, 0.8),
+            );
+        }
+
+        self.camera.end_world_render();
+
+        // Plague indicator (top center)
+        if self.infected_count > 0 {
+            let label = format!("PLAGUE: {} infected", self.infected_count);
+            let font_size = 24.0;
+            let dims = measure_text(&label, self.font.as_ref(), font_size as u16, 1.0);
+            draw_text_ex(
+                &label,
+                (screen_width() - dims.width) / 2.0,
+                40.0,
+                TextParams {
+                    font: self.font.as_ref(),
+                    font_size: font_size as u16,
+                    color: Color::new(0.4, 1.0, 0.2, 1.0),
+                    ..Default::default()
+                },
+            );
+        }
+
+        // Food bloom indicator (top center, below the plague one)
+        if let Some(bloom) = &self.food_bloom {
+            let label = format!("FOOD BLOOM ACTIVE {:.0}s", bloom.duration_remaining.ceil());
+            let font_size = 24.0;
+            let dims = measure_text(&label, self.font.as_ref(), font_size as u16, 1.0);
+            draw_text_ex(
+                &label,
+                (screen_width() - dims.width) / 2.0,
+                70.0,
+                TextParams {
+                    font: self.font.as_ref(),
+                    font_size: font_size as u16,
+                    color: YELLOW,
+                    ..Default::default()
+                },
+            );
+        }
+
+        // Render stats (only if UI enabled)
+        if self.config.show_ui {
+            let stats_bottom = self.render_stats(cells_in_viewport);
+            self.minimap()
+                .render(&self.cells, self.camera.x, self.camera.y, &view);
+
+            // Render best cell stats (bottom-right corner), or the comparison in its place
+            match self.cell_comparison() {
+                Some(comparison) => {
+                    crate::stats::render_comparison(&comparison, self.font.as_ref())
+                }
+                None => self.stats.render(self.font.as_ref()),
+            }
+
+            // Selected cell details (left side, under the stats column)
+            if let Some(detail) = self.selected_cell_detail() {
+                crate::stats::render_detail(
+                    &detail,
+                    self.font.as_ref(),
+                    self.config.show_cell_detail,
+                    stats_bottom + DETAIL_PANEL_GAP,
+                    self.stats.left_edge(self.font.as_ref()),
+                );
+            }
+
+            if self.config.show_brain_inspector
+                && let Some(inspection) = self.selected_cell_brain()
+            {
+                brain_inspector::render(&inspection, self.font.as_ref());
+            }
+
+            if self.config.show_gene_stats {
+                self.gene_stats.render(self.font.as_ref());
+            }
+
+            if self.config.show_event_panel {
+                self.stats.event_panel.render(self.font.as_ref());
+            }
+        }
+
+        // Recent events, left of the stats box (shown even with the rest of the UI hidden)
+        if self.config.show_event_log {
+            let right_edge = if self.config.show_ui {
+                self.stats.left_edge(self.font.as_ref())
+            } else {
+                None
+            };
+            crate::stats::render_event_log(
+                self.font.as_ref(),
+                right_edge.unwrap_or(screen_width()),
+            );
+        }
+    }
+
+    // Save a PNG of the finished frame if F12 was pressed. Call after render(),
+    // before next_frame(), so the capture includes everything drawn this frame.
+    pub fn capture_screenshot_if_requested(&mut self) {
+        if !self.screenshot_requested {
+            return;
+        }
+        self.screenshot_requested = false;
+
+        let screen = get_screen_data();
+        let file_name = crate::storage::screenshot_file_name(self.tick_count);
+        match crate::storage::encode_png(screen.width as u32, screen.height as u32, &screen.bytes) {
+            Some(png) => crate::storage::save_screenshot(&file_name, &png),
+            None => log_warn!("⚠ Failed to encode screenshot"),
+        }
+    }
+
+    // Gold rings around a selected (or compared) cell, its target line and its
+    // sensed centers of mass, for one wraparound copy
+    fn render_selection_highlight(
+        &self,
+        cell: &Cell,
+        camera_x: f32,
+        camera_y: f32,
+        view: &ViewBounds,
+    ) {
+        let world_width = self.config.world_width;
+        let world_height = self.config.world_height;
+        let screen_x = cell.x - camera_x;
+        let screen_y = cell.y - camera_y;
+        let current_radius = cell.get_current_radius();
+
+        // Only draw if on screen
+        let margin = current_radius * 1.5;
+        if view.contains(screen_x, screen_y, margin) {
+            let gold = Color::new(1.0, 0.84, 0.0, 1.0);
+            let gold_transparent = Color::new(1.0, 0.84, 0.0, 0.5);
+            draw_circle_lines(
+                screen_x,
+                screen_y,
+                current_radius + 9.0, // Slightly larger than cell
+                4.0,                  // Thickness
+                gold_transparent,
+            );
+            draw_circle_lines(
+                screen_x,
+                screen_y,
+                current_radius + 12.0, // Slightly larger than cell
+                1.0,                   // Thickness
+                gold,
+            );
+
+            // Draw target line if cell has a target
+            if let Some((target_x, target_y)) = cell.current_target_pos {
+                // Calculate nose position (front of the cell)
+                let nose_x = screen_x + cell.angle.cos() * current_radius;
+                let nose_y = screen_y + cell.angle.sin() * current_radius;
+
+                // Calculate target position accounting for world wrapping
+                let mut target_dx = target_x - cell.x;
+                let mut target_dy = target_y - cell.y;
+
+                // Adjust for world wrapping
+                if target_dx.abs() > world_width / 2.0 {
+                    target_dx = target_dx - target_dx.signum() * world_width;
+                }
+                if target_dy.abs() > world_height / 2.0 {
+                    target_dy = target_dy - target_dy.signum() * world_height;
+                }
+
+                let target_screen_x = screen_x + target_dx;
+                let target_screen_y = screen_y + target_dy;
+
+                // Calculate line color based on current_alignment_score
+                // Score ranges from 1.0 (0° diff, perfect) to -1.0 (180° diff, opposite)
+                // Color mapping:
+                //   1.0 (0° diff) = White (perfectly aligned)
+                //   0.0 (90° diff) = Yellow (perpendicular)
+                //   -1.0 (180° diff) = Red (facing away)
+                let alignment = cell.current_alignment_score.clamp(-1.0, 1.0);
+
+                let line_color = if alignment >= 0.0 {
+                    // 0° to 90°: interpolate from white (1.0) to yellow (0.0)
+                    // alignment = 1.0: white (1,1,1)
+                    // alignment = 0.0: yellow (1,1,0)
+                    Color::new(1.0, 1.0, alignment, 0.9)
+                } else {
+                    // 90° to 180°: interpolate from yellow (0.0) to red (-1.0)
+                    // alignment = 0.0: yellow (1,1,0)
+                    // alignment = -1.0: red (1,0,0)
+                    let t = -alignment; // 0.0 to 1.0
+                    Color::new(1.0, 1.0 - t, 0.0, 0.9)
+                };
+
+                // Draw line from nose to target
+                draw_line(
+                    nose_x,
+                    nose_y,
+                    target_screen_x,
+                    target_screen_y,
+                    3.0,
+                    line_color,
+                );
+
+                // Draw small circle at target position
+                draw_circle(target_screen_x, target_screen_y, 5.0, line_color);
+            }
+
+            // Draw center of mass indicators for dead and alive cells
+            // Light gray for dead cells, yellow for alive cells
+            const CENTER_OF_MASS_RADIUS: f32 = 8.0;
+
+            // Dead cells center of mass (light gray)
+            if cell.dead_center_distance < cell.sensor_range {
+                // Calculate screen position from cell's local frame
+                let dead_center_world_angle = cell.angle + cell.dead_center_angle;
+                let dead_center_world_x =
+                    cell.x + dead_center_world_angle.cos() * cell.dead_center_distance;
+                let dead_center_world_y =
+                    cell.y + dead_center_world_angle.sin() * cell.dead_center_distance;
+
+                let dead_center_screen_x = dead_center_world_x - camera_x;
+                let dead_center_screen_y = dead_center_world_y - camera_y;
+
+                let light_gray = Color::new(0.7, 0.7, 0.7, 0.8);
+                draw_circle(
+                    dead_center_screen_x,
+                    dead_center_screen_y,
+                    CENTER_OF_MASS_RADIUS,
+                    light_gray,
+                );
+                draw_circle_lines(
+                    dead_center_screen_x,
+                    dead_center_screen_y,
+                    CENTER_OF_MASS_RADIUS,
+                    2.0,
+                    Color::new(0.5, 0.5, 0.5, 1.0),
+                );
+            }
+
+            // Alive cells center of mass (yellow)
+            if cell.alive_center_distance < cell.sensor_range {
+                // Calculate screen position from cell's local frame
+                let alive_center_world_angle = cell.angle + cell.alive_center_angle;
+                let alive_center_world_x =
+                    cell.x + alive_center_world_angle.cos() * cell.alive_center_distance;
+                let alive_center_world_y =
+                    cell.y + alive_center_world_angle.sin() * cell.alive_center_distance;
+
+                let alive_center_screen_x = alive_center_world_x - camera_x;
+                let alive_center_screen_y = alive_center_world_y - camera_y;
+
+                let yellow = Color::new(1.0, 1.0, 0.0, 0.8);
+                draw_circle(
+                    alive_center_screen_x,
+                    alive_center_screen_y,
+                    CENTER_OF_MASS_RADIUS,
+                    yellow,
+                );
+                draw_circle_lines(
+                    alive_center_screen_x,
+                    alive_center_screen_y,
+                    CENTER_OF_MASS_RADIUS,
+                    2.0,
+                    Color::new(0.8, 0.8, 0.0, 1.0),
+                );
+            }
+        }
+    }
+
+    fn render_replay_frame(&self, view: &ViewBounds) {
+        let Some((_, frame)) = self.replay.current_frame() else {
+            return;
+        };
+
+        for cell in &frame.cells {
+            let screen_x = cell.x - self.camera.x;
+            let screen_y = cell.y - self.camera.y;
+            if !view.contains(screen_x, screen_y, cell.radius) {
+                continue;
+            }
+
+            let color = cell.color();
+            if cell.is_alive {
+                draw_circle(screen_x, screen_y, cell.radius, color);
+            } else {
+                let gray = Color::new(color.r * 0.3, color.g * 0.3, color.b * 0.3, 1.0);
+                draw_circle_lines(screen_x, screen_y, cell.radius, 2.0, gray);
+            }
+        }
+    }
+
+    // Playback status, drawn in screen space
+    fn render_replay_label(&self) {
+        let Some((index, frame)) = self.replay.current_frame() else {
+            return;
+        };
+        let label = format!(
+            "REPLAY frame {}/{} (tick {}) - Ctrl+P to exit",
+            index + 1,
+            self.replay.frames.len(),
+            frame.tick
+        );
+        draw_text(&label, 20.0, 44.0, 24.0, YELLOW);
+    }
+
+    // Translucent rectangle per spatial grid bucket, transparent (empty) to bright orange (dense)
+    fn render_density_map(&self, view: &ViewBounds) {
+        let (grid_w, grid_h) = self.spatial_grid.dimensions();
+        if self.density_map.len() != grid_w * grid_h {
+            return;
+        }
+        let bucket_size = self.spatial_grid.bucket_size();
+
+        // Visible buckets, wrapping indices around the world
+        let first_x = ((self.camera.x + view.min_x) / bucket_size).floor() as i32;
+        let first_y = ((self.camera.y + view.min_y) / bucket_size).floor() as i32;
+        let last_x = ((self.camera.x + view.max_x) / bucket_size).floor() as i32;
+        let last_y = ((self.camera.y + view.max_y) / bucket_size).floor() as i32;
+
+        for by in first_y..=last_y {
+            for bx in first_x..=last_x {
+                let wrapped_x = bx.rem_euclid(grid_w as i32) as usize;
+                let wrapped_y = by.rem_euclid(grid_h as i32) as usize;
+                let density = self.density_map[wrapped_y * grid_w + wrapped_x];
+                if density <= 0.0 {
+                    continue;
+                }
+
+                draw_rectangle(
+                    bx as f32 * bucket_size - self.camera.x,
+                    by as f32 * bucket_size - self.camera.y,
+                    bucket_size,
+                    bucket_size,
+                    Color::new(1.0, 0.55, 0.0, density * DENSITY_MAP_MAX_ALPHA),
+                );
+            }
+        }
+    }
+
+    fn render_grid(&self, view: &ViewBounds) {
+        let grid_spacing = 250.0;
+        let dot_radius = 2.0;
+        let dot_color = Color::new(1.0, 1.0, 1.0, 0.3); // White with low opacity
+
+        // Get world dimensions for wraparound rendering
+        let world_width = self.config.world_width;
+        let world_height = self.config.world_height;
+
+        // Define all possible wraparound positions (same as cell rendering)
+        let wraparound_offsets = [
+            (0.0, 0.0),                    // Original position
+            (-world_width, 0.0),           // Wrapped left
+            (world_width, 0.0),            // Wrapped right
+            (0.0, -world_height),          // Wrapped top
+            (0.0, world_height),           // Wrapped bottom
+            (-world_width, -world_height), // Top-left corner
+            (world_width, -world_height),  // Top-right corner
+            (-world_width, world_height),  // Bottom-left corner
+            (world_width, world_height),   // Bottom-right corner
+        ];
+
+        // Calculate which grid points are visible on screen
+        // Start from the first grid point that could be visible
+        let start_x =
+            (((self.camera.x + view.min_x) / grid_spacing).floor() * grid_spacing).max(0.0);
+        let start_y =
+            (((self.camera.y + view.min_y) / grid_spacing).floor() * grid_spacing).max(0.0);
+
+        // Draw grid points within world boundaries at all wraparound positions
+        let mut y = start_y;
+        while y <= world_height {
+            let mut x = start_x;
+            while x <= world_width {
+                // Render dot at all wraparound positions
+                for (dx, dy) in &wraparound_offsets {
+                    // Adjust camera position to create wraparound effect
+                    let adjusted_camera_x = self.camera.x - dx;
+                    let adjusted_camera_y = self.camera.y - dy;
+
+                    // Convert world coordinates to screen coordinates
+                    let screen_x = x - adjusted_camera_x;
+                    let screen_y = y - adjusted_camera_y;
+
+                    // Only draw if on screen
+                    if view.contains(screen_x, screen_y, dot_radius) {
+                        draw_circle(screen_x, screen_y, dot_radius, dot_color);
+                    }
+                }
+
+                x += grid_spacing;
+            }
+            y += grid_spacing;
+        }
+    }
+
+    fn render_boundaries(&self) {
+        let boundary_color = Color::new(0.3, 0.3, 0.3, 1.0);
+        let line_thickness = 2.0;
+
+        // Left boundary (x = 0)
+        let left_screen_x = 0.0 - self.camera.x;
+        draw_line(
+            left_screen_x,
+            0.0 - self.camera.y,
+            left_screen_x,
+            self.config.world_height - self.camera.y,
+            line_thickness,
+            boundary_color,
+        );
+
+        // Right boundary (x = world_width)
+        let right_screen_x = self.config.world_width - self.camera.x;
+        draw_line(
+            right_screen_x,
+            0.0 - self.camera.y,
+            right_screen_x,
+            self.config.world_height - self.camera.y,
+            line_thickness,
+            boundary_color,
+        );
+
+        // Top boundary (y = 0)
+        let top_screen_y = 0.0 - self.camera.y;
+        draw_line(
+            0.0 - self.camera.x,
+            top_screen_y,
+            self.config.world_width - self.camera.x,
             top_screen_y,
             line_thickness,
             boundary_color,
         );
 
-        // Bottom boundary (y = world_height)
-        let bottom_screen_y = self.config.world_height - self.camera.y;
-        draw_line(
-            0.0 - self.camera.x,
-            bottom_screen_y,
-            self.config.world_width - self.camera.x,
-            bottom_screen_y,
-            line_thickness,
-            boundary_color,
+        // Bottom boundary (y = world_height)
+        let bottom_screen_y = self.config.world_height - self.camera.y;
+        draw_line(
+            0.0 - self.camera.x,
+            bottom_screen_y,
+            self.config.world_width - self.camera.x,
+            bottom_screen_y,
+            line_thickness,
+            boundary_color,
+        );
+    }
+
+    // Sparkline of frame_times (oldest left), with the FPS levels adjust_cell_cap
+    // reacts to: shrink threshold (red), grow threshold (yellow), TARGET_MAX_FPS (green).
+    // Draws straight from the VecDeque, nothing is allocated.
+    fn render_frame_time_graph(&self, x: f32, y: f32) {
+        let bottom = y + FRAME_GRAPH_HEIGHT;
+        draw_rectangle(
+            x,
+            y,
+            FRAME_GRAPH_WIDTH,
+            FRAME_GRAPH_HEIGHT,
+            Color::new(0.0, 0.0, 0.0, 0.5),
+        );
+        let targets = [
+            (
+                self.config.fps_shrink_threshold,
+                Color::new(1.0, 0.3, 0.3, 0.6),
+            ),
+            (
+                self.config.fps_grow_threshold,
+                Color::new(1.0, 0.9, 0.3, 0.6),
+            ),
+            (TARGET_MAX_FPS, Color::new(0.3, 1.0, 0.3, 0.6)),
+        ];
+        for (fps, color) in targets {
+            let line_y = bottom - frame_time_height(1.0 / fps, FRAME_GRAPH_HEIGHT);
+            draw_line(x, line_y, x + FRAME_GRAPH_WIDTH, line_y, 1.0, color);
+        }
+
+        let step = FRAME_GRAPH_WIDTH / (FPS_SAMPLE_SIZE - 1) as f32;
+        let point = |i: usize, frame_time: f32| {
+            (
+                x + i as f32 * step,
+                bottom - frame_time_height(frame_time, FRAME_GRAPH_HEIGHT),
+            )
+        };
+        for (i, (&a, &b)) in self
+            .frame_times
+            .iter()
+            .zip(self.frame_times.iter().skip(1))
+            .enumerate()
+        {
+            let (x1, y1) = point(i, a);
+            let (x2, y2) = point(i + 1, b);
+            draw_line(x1, y1, x2, y2, 1.5, WHITE);
+        }
+    }
+
+    // Sparkline of threshold_history (oldest left) between half and the full
+    // REPRODUCTION_ENERGY_THRESHOLD, with the current threshold after it
+    fn render_threshold_graph(&self, x: f32, y: f32, font_size: f32) {
+        if self.threshold_history.is_empty() {
+            return;
+        }
+        let floor = REPRODUCTION_ENERGY_THRESHOLD * 0.5;
+        render_sparkline(
+            (x, y),
+            &self.threshold_history,
+            THRESHOLD_HISTORY,
+            |threshold| (threshold - floor) / floor,
+            Color::new(1.0, 0.7, 0.4, 1.0),
+            &format!(
+                "Repro threshold: {:.0}",
+                self.dynamic_reproduction_threshold
+            ),
+            font_size,
+        );
+    }
+
+    // Sparkline of best_lineage_deltas (oldest left) scaled to the largest delta shown,
+    // with the latest delta and most changed weight after it
+    fn render_mutation_graph(&self, x: f32, y: f32, font_size: f32) {
+        let Some(&latest) = self.best_lineage_deltas.back() else {
+            return;
+        };
+        let peak = self
+            .best_lineage_deltas
+            .iter()
+            .copied()
+            .fold(f32::MIN_POSITIVE, f32::max);
+        let mut label = format!("Mutation delta: {:.2}", latest);
+        if let Some(change) = self.last_weight_change {
+            label += &format!(" (max {} {:.3})", change.label(), change.magnitude);
+        }
+        render_sparkline(
+            (x, y),
+            &self.best_lineage_deltas,
+            MUTATION_DELTA_HISTORY,
+            |delta| delta / peak,
+            Color::new(0.6, 0.8, 1.0, 1.0),
+            &label,
+            font_size,
+        );
+    }
+
+    // Top-left stats column; returns the y just below its last line
+    fn render_stats(&self, cells_in_viewport: usize) -> f32 {
+        // Count active cells (state == Alive)
+        let active_cells = self
+            .cells
+            .iter()
+            .filter(|cell| cell.state == CellState::Alive)
+            .count();
+        let total_cells = self.cells.len();
+
+        // Render stats in top-left corner
+        let padding = 20.0;
+        let font_size = 24.0;
+        let line_height = 30.0;
+        let text_color = WHITE;
+
+        // Line 1: FPS, with the recent frame times graphed after it
+        let line1 = format!("FPS: {:.1}", self.current_fps);
+        draw_text(&line1, padding, padding + font_size, font_size, text_color);
+        let graph_x =
+            padding + measure_text("FPS: 000.0", None, font_size as u16, 1.0).width + 12.0;
+        self.render_frame_time_graph(graph_x, padding + font_size - FRAME_GRAPH_HEIGHT + 4.0);
+
+        // Line 2: Total active cells / total cells / max cap
+        let cap_mode = if self.config.max_cells.is_some() || !self.auto_cell_cap {
+            "manual"
+        } else {
+            "auto"
+        };
+        let mut line2 = format!(
+            "Cells: {} / {} (cap: {} {})",
+            active_cells, total_cells, self.max_cells, cap_mode
+        );
+        if self.config.nutrient_recycle_fraction > 0.0 {
+            line2 += &format!(
+                " | Food: {} ({:.0} energy)",
+                self.food.pellets.len(),
+                self.food.total_energy()
+            );
+        }
+        draw_text(
+            &line2,
+            padding,
+            padding + font_size + line_height,
+            font_size,
+            text_color,
+        );
+        // Reproduction threshold history after it (use_dynamic_threshold only)
+        let graph_x = padding + measure_text(&line2, None, font_size as u16, 1.0).width + 12.0;
+        self.render_threshold_graph(
+            graph_x,
+            padding + font_size + line_height - SPARKLINE_HEIGHT + 4.0,
+            font_size,
+        );
+
+        // Line 3: Cells in viewport
+        let mut line3 = format!(
+            "Viewport: {} | Species: {} | Era: {}",
+            cells_in_viewport,
+            self.species_count,
+            self.eras.current_era()
+        );
+        if let Some(previous) = self.eras.previous() {
+            line3 += &format!(" (prev peak {:.1})", previous.peak_fitness);
+        }
+        if self.config.energy.brain_cost_per_weight > 0.0 {
+            line3 += &format!(" | Brain cost: {:.3}/tick", self.mean_brain_cost);
+        }
+        draw_text(
+            &line3,
+            padding,
+            padding + font_size + line_height * 2.0,
+            font_size,
+            text_color,
+        );
+
+        // Line 4: Simulation state (paused/speed/recording)
+        let state_text = if self.replay.is_recording() {
+            format!("REC (Speed: {:.1}x)", self.simulation_speed)
+        } else if self.paused {
+            format!("PAUSED (Speed: {:.1}x)", self.simulation_speed)
+        } else {
+            format!("Speed: {:.1}x", self.simulation_speed)
+        };
+        let state_color = if self.replay.is_recording() {
+            RED
+        } else if self.paused {
+            YELLOW
+        } else {
+            WHITE
+        };
+        draw_text(
+            &state_text,
+            padding,
+            padding + font_size + line_height * 3.0,
+            font_size,
+            state_color,
+        );
+        // Best lineage mutation deltas after it (nothing until the best cell reproduces)
+        let graph_x = padding
+            + measure_text("PAUSED (Speed: 0.0x)", None, font_size as u16, 1.0).width
+            + 12.0;
+        self.render_mutation_graph(
+            graph_x,
+            padding + font_size + line_height * 3.0 - SPARKLINE_HEIGHT + 4.0,
+            font_size,
+        );
+
+        // Line 5: Ticks since the last reset, then the whole run's ticks and simulated time
+        let mut ticks_text = format!(
+            "Ticks: {} ({} resets) | Total: {} ticks, {}",
+            self.tick_count,
+            self.reset_count,
+            self.total_ticks,
+            format_hms(self.sim_time)
+        );
+        if self.bottleneck_count > 0 {
+            ticks_text += &format!(" | Bottlenecks: {}", self.bottleneck_count);
+        }
+        if self.bloom_count > 0 {
+            ticks_text += &format!(" | Blooms: {}", self.bloom_count);
+        }
+        draw_text(
+            &ticks_text,
+            padding,
+            padding + font_size + line_height * 4.0,
+            font_size,
+            text_color,
+        );
+
+        // Lines 6-10: Per-tier population bars + total
+        let bar_max_width = 200.0_f32;
+        let bar_height = 14.0_f32;
+        // Base hue per tier: 180 + tier * 90 (same as Cell::spawn)
+        let tier_hues = [180.0_f32, 270.0, 0.0, 90.0];
+        let total_alive = self.tier_cell_counts.iter().sum::<usize>().max(1);
+
+        // Sort tiers by best saved score (descending)
+        let mut tier_indices: Vec<usize> = (0..4).collect();
+        tier_indices.sort_unstable_by(|&a, &b| {
+            self.best_saved_scores[b]
+                .partial_cmp(&self.best_saved_scores[a])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        for (display_idx, &tier) in tier_indices.iter().enumerate() {
+            let tier_hue = tier_hues[tier];
+            let y_base = padding + font_size + line_height * (5.0 + display_idx as f32);
+            let count = self.tier_cell_counts[tier];
+            let max_score = self.best_saved_scores[tier];
+
+            // Tier label color derived from base hue (s=0.8, v=0.9)
+            let h = tier_hue;
+            let tier_color = {
+                let s = 0.8_f32;
+                let v = 0.9_f32;
+                let c = v * s;
+                let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+                let m = v - c;
+                let (r, g, b) = if h < 60.0 {
+                    (c, x, 0.0)
+                } else if h < 120.0 {
+                    (x, c, 0.0)
+                } else if h < 180.0 {
+                    (0.0, c, x)
+                } else if h < 240.0 {
+                    (0.0, x, c)
+                } else if h < 300.0 {
+                    (x, 0.0, c)
+                } else {
+                    (c, 0.0, x)
+                };
+                Color::new(r + m, g + m, b + m, 1.0)
+            };
+
+            let label = format!("m{}:", tier);
+            let label_width = measure_text(&label, self.font.as_ref(), font_size as u16, 1.0).width;
+            draw_text_ex(
+                &label,
+                padding,
+                y_base,
+                TextParams {
+                    font: self.font.as_ref(),
+                    font_size: font_size as u16,
+                    color: tier_color,
+                    ..Default::default()
+                },
+            );
+
+            // Progress bar
+            let bar_x = padding + label_width + 6.0;
+            let bar_y = y_base - bar_height + 2.0;
+            let fill_width = (count as f32 / total_alive as f32) * bar_max_width;
+            // Background track
+            draw_rectangle(
+                bar_x,
+                bar_y,
+                bar_max_width,
+                bar_height,
+                Color::new(1.0, 1.0, 1.0, 0.1),
+            );
+            // Filled portion
+            if fill_width > 0.0 {
+                draw_rectangle(
+                    bar_x,
+                    bar_y,
+                    fill_width,
+                    bar_height,
+                    Color::new(tier_color.r, tier_color.g, tier_color.b, 0.7),
+                );
+            }
+
+            // Count + max score text
+            let info = format!(" {} cells (top score: {:.0})", count, max_score);
+            let info_x = bar_x + bar_max_width + 4.0;
+            draw_text_ex(
+                &info,
+                info_x,
+                y_base,
+                TextParams {
+                    font: self.font.as_ref(),
+                    font_size: font_size as u16,
+                    color: WHITE,
+                    ..Default::default()
+                },
+            );
+
+            // Add score diff if current best beats saved best
+            let current_best = self.tier_current_best_scores[tier];
+            if current_best > max_score && max_score > 0.0 {
+                let diff = current_best - max_score;
+                let diff_text = format!(" + {:.1}", diff);
+                let info_width =
+                    measure_text(&info, self.font.as_ref(), font_size as u16, 1.0).width;
+                draw_text_ex(
+                    &diff_text,
+                    info_x + info_width,
+                    y_base,
+                    TextParams {
+                        font: self.font.as_ref(),
+                        font_size: font_size as u16,
+                        color: Color::new(0.0, 1.0, 0.0, 1.0), // Bright green
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
+        // Total line
+        let total_diversity = self.tier_diversities.iter().sum::<f32>() / 4.0;
+        let total_y = padding + font_size + line_height * 9.0;
+        let total_text = format!(
+            "total: {} cells ({:.0}% diversity)",
+            total_alive,
+            total_diversity * 100.0
+        );
+        draw_text_ex(
+            &total_text,
+            padding,
+            total_y,
+            TextParams {
+                font: self.font.as_ref(),
+                font_size: font_size as u16,
+                color: Color::new(0.8, 0.8, 0.8, 1.0),
+                ..Default::default()
+            },
+        );
+
+        // Hue species legend: largest species first
+        const LEGEND_ENTRIES: usize = 6;
+        let legend_top = total_y + line_height;
+        let legend_title = format!("Hue species: {}", self.species_alive_counts.len());
+        draw_text(&legend_title, padding, legend_top, font_size, text_color);
+        for (i, species) in self
+            .hue_species
+            .species
+            .iter()
+            .take(LEGEND_ENTRIES)
+            .enumerate()
+        {
+            let y = legend_top + line_height * 0.8 * (i + 1) as f32;
+            let swatch = Cell::hsv_to_rgb(species.mean_hue, 0.8, 0.9);
+            draw_rectangle(padding, y - 12.0, 12.0, 12.0, swatch);
+            draw_text(
+                &format!(
+                    "#{} ({:.0}°): {} cells",
+                    species.id, species.mean_hue, species.alive_count
+                ),
+                padding + 18.0,
+                y,
+                18.0,
+                text_color,
+            );
+        }
+
+        let shown = self.hue_species.species.len().min(LEGEND_ENTRIES);
+        let mut bottom = legend_top + line_height * 0.8 * shown as f32;
+
+        // Islands: alive cells per island (row-major) and corridor state
+        if let Some(islands) = &self.islands {
+            let y = legend_top + line_height * 0.8 * (shown + 1) as f32 + line_height * 0.5;
+            bottom = y;
+            let corridors = if islands.corridors_open(self.elapsed_time) {
+                "open"
+            } else {
+                "closed"
+            };
+            let counts: Vec<String> = self.island_counts.iter().map(|c| c.to_string()).collect();
+            draw_text(
+                &format!("Islands ({}): {}", corridors, counts.join(" | ")),
+                padding,
+                y,
+                font_size,
+                text_color,
+            );
+        }
+
+        // Controls help (bottom-left)
+        let help_y = screen_height() - padding - font_size * 3.0;
+        let help_font_size = 18.0;
+        let help_color = Color::new(0.7, 0.7, 0.7, 1.0);
+        draw_text(
+            "Controls: SPACE=Pause | R=Reset | +/-=Speed | 1=Normal Speed | H=Heatmap | Ctrl+R=Record | Ctrl+P=Replay",
+            padding,
+            help_y,
+            help_font_size,
+            help_color,
+        );
+
+        bottom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        EnergyConfig, FrozenLayers, ImmigrationConfig, MovementConfig, TemperatureConfig,
+    };
+    use crate::fitness::FitnessFn;
+    use crate::food::PELLETS_PER_CORPSE;
+    use crate::zones::ZoneKind;
+
+    // Storage off: tests neither load nor write brains and logs in the working directory
+    fn test_config() -> SimulationConfig {
+        SimulationConfig {
+            world_width: 2000.0,
+            world_height: 2000.0,
+            initial_cell_count: 200,
+            use_storage: false,
+            ..SimulationConfig::default()
+        }
+    }
+
+    // 100 alive adult cells packed into a 60x60 square around (1000, 1000), at rest
+    fn crowded_world(push_force: f32) -> World {
+        let mut config = SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            ..test_config()
+        };
+        config.movement.push_force = push_force;
+        let mut world = World::new(config);
+        for i in 0..100 {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            cell.x = 970.0 + (i % 10) as f32 * 6.0 + rand::gen_range(0.0, 1.0);
+            cell.y = 970.0 + (i / 10) as f32 * 6.0 + rand::gen_range(0.0, 1.0);
+            cell.age = 40.0;
+            cell.energy = 80.0;
+            (cell.velocity_x, cell.velocity_y) = (0.0, 0.0);
+            cell.id = world.next_id();
+            world.cells.push(cell);
+        }
+        world.rebuild_spatial_grid();
+        world
+    }
+
+    #[test]
+    fn test_sensor_line_mode_cycles_and_follows_the_overlay_flag() {
+        let mut mode = SensorLineMode::All;
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            mode = mode.next();
+            seen.push(mode);
+        }
+        assert_eq!(
+            seen,
+            [
+                SensorLineMode::SelectedOnly,
+                SensorLineMode::Off,
+                SensorLineMode::All
+            ]
+        );
+
+        // The config flag turns the overlay off, and back on at selected-only
+        assert_eq!(SensorLineMode::All.with_overlay(false), SensorLineMode::Off);
+        assert_eq!(
+            SensorLineMode::Off.with_overlay(true),
+            SensorLineMode::SelectedOnly
+        );
+        assert_eq!(SensorLineMode::All.with_overlay(true), SensorLineMode::All);
+        let world = World::new(test_config());
+        assert_eq!(world.sensor_lines(), SensorLineMode::SelectedOnly);
+    }
+
+    #[test]
+    fn test_corpse_sensors_report_remaining_energy() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        let mut observer = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        (observer.x, observer.y) = (1000.0, 1000.0);
+        observer.id = world.next_id();
+        world.cells.push(observer);
+        // Same mass, same distance, one fresh and one nearly eaten
+        for (x, energy) in [(1100.0, 150.0), (900.0, 5.0)] {
+            let mut corpse = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            (corpse.x, corpse.y) = (x, 1000.0);
+            corpse.state = CellState::Corpse;
+            corpse.mass = 200.0;
+            corpse.energy = energy;
+            corpse.id = world.next_id();
+            world.cells.push(corpse);
+        }
+        let (fresh_id, eaten_id) = (world.cells[1].id, world.cells[2].id);
+        world.rebuild_spatial_grid();
+        world.update_sensors();
+
+        let observer = &world.cells[0];
+        let inputs = observer.normalize_sensors();
+        let energy_input = |id: u64| {
+            let slot = observer
+                .nearest_cells
+                .iter()
+                .position(|reading| reading.id == id)
+                .expect("corpse sensed");
+            inputs.as_slice()[slot * 4 + 2]
+        };
+        assert!(energy_input(fresh_id) > energy_input(eaten_id));
+    }
+
+    #[test]
+    fn test_overlapping_alive_cells_push_each_other_apart() {
+        let mut world = crowded_world(MovementConfig::default().push_force);
+        world.check_collisions();
+
+        // Pushed outward from the crowd, and gently: far below a forward impulse per neighbor
+        let mut outward = 0.0;
+        for cell in &world.cells {
+            assert!(cell.velocity_x.is_finite() && cell.velocity_y.is_finite());
+            assert!(cell.velocity_x.hypot(cell.velocity_y) < 1.0);
+            outward += (cell.x - 1000.0) * cell.velocity_x + (cell.y - 1000.0) * cell.velocity_y;
+        }
+        assert!(outward > 0.0);
+        let pushed = world
+            .cells
+            .iter()
+            .filter(|cell| cell.velocity_x != 0.0 || cell.velocity_y != 0.0)
+            .count();
+        assert!(pushed > 90);
+
+        // A pair pushes symmetrically for equal masses
+        let mut pair = crowded_world(20.0);
+        pair.cells.truncate(2);
+        (pair.cells[0].x, pair.cells[0].y) = (500.0, 500.0);
+        (pair.cells[1].x, pair.cells[1].y) = (505.0, 500.0);
+        pair.cells[1].mass = pair.cells[0].mass;
+        pair.rebuild_spatial_grid();
+        pair.check_collisions();
+        assert!(pair.cells[0].velocity_x < 0.0);
+        assert!((pair.cells[0].velocity_x + pair.cells[1].velocity_x).abs() < 1e-6);
+        assert_eq!(pair.cells[0].velocity_y, 0.0);
+
+        // No push force: alive cells pass through each other
+        let mut ghosts = crowded_world(0.0);
+        ghosts.check_collisions();
+        assert!(
+            ghosts
+                .cells
+                .iter()
+                .all(|cell| cell.velocity_x == 0.0 && cell.velocity_y == 0.0)
+        );
+    }
+
+    #[test]
+    fn test_cell_collisions_separate_overlaps_by_mass_across_the_wrap() {
+        let mut pair = crowded_world(0.0);
+        pair.cells.truncate(2);
+        // Overlapping across the world edge: the wrapped separation is 4 units
+        (pair.cells[0].x, pair.cells[0].y) = (1998.0, 500.0);
+        (pair.cells[1].x, pair.cells[1].y) = (2.0, 500.0);
+        pair.cells[0].mass = 3.0;
+        pair.cells[1].mass = 1.0;
+        pair.config.movement.max_collision_push = 100.0;
+        pair.rebuild_spatial_grid();
+        let overlap = pair.cells[0].get_current_radius() + pair.cells[1].get_current_radius() - 4.0;
+        assert!(overlap > 0.0);
+        pair.separate_alive_cells();
+
+        // Pushed apart along x only, the heavy cell moving a quarter of the overlap
+        let heavy_move = 1998.0 - pair.cells[0].x;
+        let light_move = pair.cells[1].x - 2.0;
+        assert!((heavy_move - overlap * 0.25).abs() < 1e-3, "{}", heavy_move);
+        assert!((light_move - overlap * 0.75).abs() < 1e-3, "{}", light_move);
+        assert_eq!(pair.cells[0].y, 500.0);
+
+        // A dense clump relaxes with every move capped
+        let mut crowd = crowded_world(0.0);
+        let before: Vec<(f32, f32)> = crowd.cells.iter().map(|c| (c.x, c.y)).collect();
+        let total_overlap = |world: &World| {
+            let data = world.collision_snapshot();
+            let mut total = 0.0;
+            for (i, a) in data.iter().enumerate() {
+                for b in &data[i + 1..] {
+                    total += (a.radius + b.radius - (a.x - b.x).hypot(a.y - b.y)).max(0.0);
+                }
+            }
+            total
+        };
+        let initial_overlap = total_overlap(&crowd);
+        crowd.separate_alive_cells();
+        let cap = crowd.config.movement.max_collision_push;
+        for (cell, (x, y)) in crowd.cells.iter().zip(before) {
+            assert!((cell.x - x).hypot(cell.y - y) <= cap + 1e-3);
+        }
+        assert!(total_overlap(&crowd) < initial_overlap);
+    }
+
+    #[test]
+    fn test_walls_clamp_cells_and_stop_sensing_across_edges() {
+        let mut pair = crowded_world(0.0);
+        pair.cells.truncate(2);
+        pair.config.movement.wrapping = false;
+        (pair.cells[0].x, pair.cells[0].y) = (1998.0, 500.0);
+        (pair.cells[1].x, pair.cells[1].y) = (2.0, 500.0);
+        pair.rebuild_spatial_grid();
+        pair.update_sensors();
+        assert!(pair.cells.iter().all(|cell| cell.nearest_cells.is_empty()));
+        pair.separate_alive_cells();
+        assert_eq!((pair.cells[0].x, pair.cells[1].x), (1998.0, 2.0));
+
+        // Moving past the right edge stops at the wall and bounces back
+        let movement = pair.config.movement;
+        let cell = &mut pair.cells[0];
+        cell.velocity_x = 10.0;
+        cell.update(
+            2000.0,
+            2000.0,
+            1.0,
+            &EnergyConfig::default(),
+            &movement,
+            &TemperatureConfig::default(),
+        );
+        assert_eq!(cell.x, 2000.0);
+        assert!(cell.velocity_x < 0.0);
+
+        // The same pair senses each other across the edge of a wrapping world
+        pair.config.movement.wrapping = true;
+        pair.cells[0].x = 1998.0;
+        pair.rebuild_spatial_grid();
+        pair.update_sensors();
+        assert!(pair.cells.iter().all(|cell| !cell.nearest_cells.is_empty()));
+    }
+
+    #[test]
+    fn test_camera_goes_around_the_world_only_when_it_wraps() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            ..test_config()
+        });
+        world.camera.x = 100.0;
+        world.camera.y = 100.0;
+        assert_eq!(world.camera_delta_to(1900.0, 300.0), (-200.0, 200.0));
+
+        world.config.movement.wrapping = false;
+        assert_eq!(world.camera_delta_to(1900.0, 300.0), (1800.0, 200.0));
+    }
+
+    #[test]
+    fn test_parasites_drain_attached_hosts_and_never_eat_corpses() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            ..test_config()
+        });
+        // Host and parasite touch, the corpse touches only the parasite
+        for (x, radius, energy, cell_type) in [
+            (500.0, 10.0, 80.0, CellType::Normal),
+            (514.0, 6.0, 20.0, CellType::Parasite),
+            (524.0, 6.0, 50.0, CellType::Normal),
+        ] {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            (cell.x, cell.y) = (x, 500.0);
+            (cell.radius, cell.energy, cell.cell_type) = (radius, energy, cell_type);
+            cell.age = 40.0;
+            cell.id = world.next_id();
+            world.cells.push(cell);
+        }
+        world.cells[2].state = CellState::Corpse;
+        world.rebuild_spatial_grid();
+        world.check_collisions();
+
+        let (host, parasite, corpse) = (&world.cells[0], &world.cells[1], &world.cells[2]);
+        assert_eq!(parasite.host_id, Some(host.id));
+        assert!((host.energy - (80.0 - PARASITE_DRAIN_RATE)).abs() < 1e-4);
+        assert!((parasite.energy - (20.0 + PARASITE_DRAIN_RATE * 0.5)).abs() < 1e-4);
+        assert_eq!((parasite.x, parasite.y), (host.x, host.y));
+        assert_eq!(corpse.energy, 50.0);
+        assert_eq!(
+            parasite.spawn_child(1.0, 0.0, false).cell_type,
+            CellType::Parasite
+        );
+
+        // A dead host is let go, and its corpse isn't food for the parasite either
+        world.cells[0].state = CellState::Corpse;
+        let energy = world.cells[1].energy;
+        world.rebuild_spatial_grid();
+        world.check_collisions();
+        assert_eq!(world.cells[1].host_id, None);
+        assert_eq!(world.cells[1].energy, energy);
+    }
+
+    #[test]
+    fn test_crowd_stress_stays_finite() {
+        let mut world = crowded_world(MovementConfig::default().push_force);
+        for _ in 0..100 {
+            world.run_simulation_tick(FIXED_DT);
+        }
+        assert!(
+            world.cells.iter().all(|cell| cell.x.is_finite()
+                && cell.y.is_finite()
+                && cell.velocity_x.is_finite())
+        );
+    }
+
+    // Adult alive cell overlapping a corpse holding `corpse_energy`
+    fn feeding_world(corpse_energy: f32) -> World {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            ..test_config()
+        });
+        let mut alive = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        alive.x = 500.0;
+        alive.y = 500.0;
+        alive.age = 40.0; // Adult: gained energy is stored, not spent on growth
+        alive.energy = 50.0;
+        alive.species_multiplier = 2.0;
+        alive.id = world.next_id();
+
+        let mut corpse = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        corpse.x = 505.0;
+        corpse.y = 500.0;
+        corpse.age = 40.0;
+        corpse.state = CellState::Corpse;
+        corpse.energy = corpse_energy;
+        corpse.id = world.next_id();
+
+        world.cells = vec![alive, corpse];
+        world.rebuild_spatial_grid();
+        world
+    }
+
+    #[test]
+    fn test_energy_brush_finds_alive_cells_within_its_radius() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        // (x, y, alive): inside, across the left edge, corpse inside, outside
+        let layout = [
+            (30.0, 500.0, true),
+            (1990.0, 500.0, true),
+            (10.0, 510.0, false),
+            (100.0, 500.0, true),
+        ];
+        for (x, y, alive) in layout {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            (cell.x, cell.y) = (x, y);
+            cell.age = 40.0;
+            cell.energy = 50.0;
+            if !alive {
+                cell.state = CellState::Corpse;
+            }
+            cell.id = world.next_id();
+            world.cells.push(cell);
+        }
+        world.rebuild_spatial_grid();
+
+        let mut found = world.alive_cells_within(5.0, 500.0, 40.0);
+        found.sort_unstable();
+        assert_eq!(found, [0, 1]);
+        // Positions outside the world wrap like the cells do
+        assert_eq!(world.alive_cells_within(2030.0, 500.0, 5.0), [0]);
+
+        world.energy_brush = Some((5.0, 500.0));
+        world.energy_brush_radius = 40.0;
+        world.apply_energy_brush();
+        let energies: Vec<f32> = world.cells.iter().map(|c| c.energy).collect();
+        let boosted = 50.0 + ENERGY_BRUSH_AMOUNT;
+        assert_eq!(energies, [boosted, boosted, 50.0, 50.0]);
+    }
+
+    #[test]
+    fn test_donations_reach_nearby_kin_only() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        let donor_brain = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells).brain;
+        // (dx, dy, kin, age): adult kin, young kin, stranger, kin out of range
+        let layout = [
+            (0.0, 0.0, true, 40.0),
+            (15.0, 0.0, true, 40.0),
+            (-15.0, 0.0, true, 5.0),
+            (0.0, 12.0, false, 40.0),
+            (60.0, 0.0, true, 40.0),
+        ];
+        for (dx, dy, kin, age) in layout {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            (cell.x, cell.y) = (500.0 + dx, 500.0 + dy);
+            cell.radius = 4.0; // Nobody touches, so only donations move energy
+            cell.age = age;
+            cell.energy = 50.0;
+            if kin {
+                cell.brain = donor_brain.clone();
+            }
+            cell.id = world.next_id();
+            world.cells.push(cell);
+        }
+        assert!(world.cells[0].genome_similarity(&world.cells[3]) <= DONATE_KIN_SIMILARITY);
+        world.cells[0].last_action = Some(DONATE_ACTION);
+        world.rebuild_spatial_grid();
+        world.check_collisions();
+
+        let energies: Vec<f32> = world.cells.iter().map(|c| c.energy).collect();
+        // Two kin in range share the cost; the young one grows instead of storing it
+        assert_eq!(energies, [40.0, 55.0, 50.0, 50.0, 50.0]);
+        assert_eq!(world.cells[2].total_energy_accumulated, 105.0);
+        assert_eq!(world.cells[0].energy_donated, DONATE_COST);
+        assert_eq!(world.cells[0].donate_pulse, DONATE_PULSE_TICKS);
+
+        // Without kin in range (or enough energy) nothing is paid
+        world.cells[0].energy = DONATE_COST;
+        world.check_collisions();
+        assert_eq!(world.cells[0].energy, DONATE_COST);
+    }
+
+    #[test]
+    fn test_feeding_conserves_energy() {
+        let mut world = feeding_world(10.0);
+        let total_before: f32 = world.cells.iter().map(|c| c.energy).sum();
+
+        world.check_collisions();
+
+        let total_after: f32 = world.cells.iter().map(|c| c.energy).sum();
+        assert!(total_after <= total_before + 1e-4);
+        // The corpse held less than one chunk: it is emptied and removed
+        assert_eq!(world.cells.len(), 1);
+        assert!((world.cells[0].energy - 60.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_simulation_tick_never_creates_energy() {
+        let mut world = World::new(SimulationConfig {
+            nutrient_recycle_fraction: 0.5,
+            ..test_config()
+        });
+        assert!(world.config.strict_energy_conservation);
+        // Starve a quarter of the population (some of it young, with body energy
+        // to hand back) so deaths, feeding, decay and recycling all happen
+        for (i, cell) in world
+            .cells
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, _)| i % 4 == 0)
+        {
+            cell.energy = 0.5;
+            cell.age = if i % 8 == 0 { 5.0 } else { 40.0 };
+            cell.body_energy = 30.0;
+        }
+
+        // Everything that holds energy: cells (alive and corpses), what their bodies
+        // hold, the pellets, and the recyclable share of corpse decay not yet paid out
+        let recycle_fraction = world.config.nutrient_recycle_fraction;
+        let total = |world: &World| -> f32 {
+            let cells: f32 = world
+                .cells
+                .iter()
+                .map(|c| c.energy.max(0.0) + c.body_energy + c.decayed_energy * recycle_fraction)
+                .sum();
+            cells + world.food.total_energy()
+        };
+
+        let mut deaths = 0;
+        for _ in 0..100 {
+            let before = total(&world);
+            world.run_simulation_tick(FIXED_DT);
+            let after = total(&world);
+            deaths += world.cells.iter().filter(|c| c.just_died()).count();
+            assert!(
+                after <= before * (1.0 + 1e-5),
+                "energy grew from {before} to {after} in tick {}",
+                world.tick_count
+            );
+        }
+        assert!(deaths > 0);
+    }
+
+    #[test]
+    fn test_feeding_multiplier_bounded_by_corpse_energy() {
+        let mut world = feeding_world(500.0);
+        let total_before: f32 = world.cells.iter().map(|c| c.energy).sum();
+
+        world.check_collisions();
+
+        let total_after: f32 = world.cells.iter().map(|c| c.energy).sum();
+        assert!((total_after - total_before).abs() < 1e-3);
+        // Multiplier is extraction efficiency: the corpse pays for the full gain
+        let gained = world.cells[0].energy - 50.0;
+        assert!(gained > 0.0);
+        assert!((world.cells[1].energy - (500.0 - gained)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_best_lineage_mutation_deltas_are_capped_history() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            ..test_config()
+        });
+        let mut parent = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        parent.age = 5.0;
+        parent.id = world.next_id();
+        world.last_best_cell_id = Some(parent.id);
+        world.cells = vec![parent];
+        world.max_cells = 1000;
+        world.rebuild_spatial_grid();
+
+        for _ in 0..MUTATION_DELTA_HISTORY + 5 {
+            world.cells.truncate(1);
+            world.cells[0].energy = REPRODUCTION_ENERGY_THRESHOLD * 4.0;
+            world.cells[0].reproduction_cooldown = 0.0;
+            world.handle_reproduction(REPRODUCTION_ENERGY_THRESHOLD);
+        }
+        assert_eq!(world.best_lineage_deltas.len(), MUTATION_DELTA_HISTORY);
+
+        let child = &world.cells[1];
+        let parent = &world.cells[0];
+        assert_eq!(
+            world.best_lineage_deltas.back(),
+            Some(&child.brain.mutation_delta(&parent.brain))
+        );
+        assert_eq!(
+            world.last_weight_change,
+            child.brain.most_changed_weight(&parent.brain)
+        );
+    }
+
+    #[test]
+    fn test_dynamic_threshold_lowers_with_the_population() {
+        assert_eq!(
+            dynamic_reproduction_threshold(100, 100),
+            REPRODUCTION_ENERGY_THRESHOLD
+        );
+        assert_eq!(
+            dynamic_reproduction_threshold(75, 100),
+            REPRODUCTION_ENERGY_THRESHOLD * 0.75
+        );
+        // Never below half, even when extinct
+        assert_eq!(
+            dynamic_reproduction_threshold(0, 100),
+            REPRODUCTION_ENERGY_THRESHOLD * 0.5
+        );
+        // Nor above the full threshold when the cap shrank under the population
+        assert_eq!(
+            dynamic_reproduction_threshold(150, 100),
+            REPRODUCTION_ENERGY_THRESHOLD
+        );
+
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_dynamic_threshold: true,
+            ..test_config()
+        });
+        let mut parent = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        parent.age = 5.0;
+        parent.energy = REPRODUCTION_ENERGY_THRESHOLD * 0.8; // Not enough at a full cap
+        parent.reproduction_cooldown = 0.0;
+        parent.id = world.next_id();
+        world.cells = vec![parent];
+        world.max_cells = 100;
+
+        world.update_reproduction_threshold();
+        assert_eq!(
+            world.dynamic_reproduction_threshold,
+            REPRODUCTION_ENERGY_THRESHOLD * 0.5
+        );
+        assert_eq!(world.threshold_history.len(), 1);
+        world.handle_reproduction(world.dynamic_reproduction_threshold);
+        assert_eq!(world.cells.len(), 2);
+
+        // Disabled: back to the constant, the graph empties
+        world.config.use_dynamic_threshold = false;
+        world.update_reproduction_threshold();
+        assert_eq!(
+            world.dynamic_reproduction_threshold,
+            REPRODUCTION_ENERGY_THRESHOLD
+        );
+        assert!(world.threshold_history.is_empty());
+    }
+
+    #[test]
+    fn test_children_stay_on_their_parents_island() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            islands: crate::config::IslandConfig {
+                enabled: true,
+                columns: 2,
+                rows: 1,
+                ..Default::default()
+            },
+            ..test_config()
+        });
+        // Right next to the barrier at x = 1000, far from its corridor, corridors closed
+        let mut parent = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        (parent.x, parent.y, parent.age) = (995.0, 200.0, 5.0);
+        parent.id = world.next_id();
+        world.cells = vec![parent];
+        world.max_cells = 1000;
+        for _ in 0..40 {
+            world.cells[0].energy = REPRODUCTION_ENERGY_THRESHOLD * 4.0;
+            world.cells[0].reproduction_cooldown = 0.0;
+            world.handle_reproduction(REPRODUCTION_ENERGY_THRESHOLD);
+        }
+        let islands = world.islands.unwrap();
+        assert_eq!(world.cells.len(), 41);
+        assert!(world.cells.iter().all(|c| islands.island_of(c.x, c.y) == 0));
+    }
+
+    #[test]
+    fn test_director_targets_the_busiest_feeding_or_birth_region() {
+        // 2000 x 2000 world in 2 x 2 director regions of 1000 x 1000
+        let mut world = feeding_world(500.0);
+        world.check_collisions();
+        world.director.start();
+        assert_eq!(world.director.next_shot(0.1), Some((500.0, 500.0)));
+
+        // Two births in the far corner outweigh the one feeding
+        let mut parent = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        (parent.x, parent.y, parent.age) = (1800.0, 1800.0, 5.0);
+        parent.id = world.next_id();
+        world.cells = vec![parent];
+        world.max_cells = 1000;
+        for _ in 0..2 {
+            world.cells[0].energy = REPRODUCTION_ENERGY_THRESHOLD * 4.0;
+            world.cells[0].reproduction_cooldown = 0.0;
+            world.handle_reproduction(REPRODUCTION_ENERGY_THRESHOLD);
+        }
+        assert_eq!(world.cells.len(), 3);
+        let dwell = world.config.director.dwell_seconds;
+        assert_eq!(world.director.next_shot(dwell), Some((1500.0, 1500.0)));
+
+        // A reset forgets the old hot spots
+        world.respawn_from_best();
+        assert_eq!(world.director.activity.hottest(), None);
+    }
+
+    #[test]
+    fn test_reproduction_cooldown_skips_recent_parent() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        let mut parent = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        parent.age = 5.0;
+        parent.energy = REPRODUCTION_ENERGY_THRESHOLD * 4.0;
+        parent.id = world.next_id();
+        world.cells = vec![parent];
+        world.max_cells = 100;
+        world.rebuild_spatial_grid();
+
+        world.handle_reproduction(REPRODUCTION_ENERGY_THRESHOLD);
+        assert_eq!(world.cells.len(), 2);
+        let cooldown = world.config.reproduction_cooldown_ticks;
+        assert_eq!(world.cells[0].reproduction_cooldown, cooldown);
+        assert_eq!(world.cells[1].reproduction_cooldown, cooldown);
+
+        // Still above the threshold on the next tick, but cooling down
+        world.cells[0].energy = REPRODUCTION_ENERGY_THRESHOLD * 4.0;
+        world.cells[0].update(
+            2000.0,
+            2000.0,
+            1.0,
+            &EnergyConfig::default(),
+            &MovementConfig::default(),
+            &TemperatureConfig::default(),
+        );
+        assert!(world.cells[0].energy > REPRODUCTION_ENERGY_THRESHOLD);
+        world.handle_reproduction(REPRODUCTION_ENERGY_THRESHOLD);
+        assert_eq!(world.cells.len(), 2);
+        assert_eq!(world.cells[0].children_count, 1);
+
+        // Allowed again once the cooldown has elapsed
+        world.cells[0].reproduction_cooldown = 0.0;
+        world.handle_reproduction(REPRODUCTION_ENERGY_THRESHOLD);
+        assert_eq!(world.cells[0].children_count, 2);
+    }
+
+    #[test]
+    fn test_reset_records_an_era_summary() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            ..test_config()
+        });
+        for _ in 0..3 {
+            let mut parent = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            parent.age = 5.0;
+            parent.energy = REPRODUCTION_ENERGY_THRESHOLD * 4.0;
+            parent.generation = 4;
+            parent.id = world.next_id();
+            world.cells.push(parent);
+        }
+        world.max_cells = 100;
+        world.rebuild_spatial_grid();
+        world.handle_reproduction(REPRODUCTION_ENERGY_THRESHOLD);
+        world.update_stats();
+        world.tick_count = 250;
+        assert_eq!(world.eras.current_era(), 1);
+
+        world.respawn_from_best();
+        assert_eq!(world.eras.current_era(), 2);
+        let era = world.eras.previous().unwrap();
+        assert_eq!(era.era, 1);
+        assert_eq!(era.ticks, 250);
+        assert_eq!(era.reproductions, 3);
+        assert_eq!(era.peak_population, 6);
+        // Three parents at generation 4 and their three children at 5
+        assert!((era.mean_generation - 4.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_reproduction_splits_energy_per_configured_ratios() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            child_energy_ratio: 0.25,
+            parent_energy_ratio: 0.5,
+            ..test_config()
+        });
+        let mut parent = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        parent.age = 5.0;
+        parent.energy = 400.0;
+        parent.id = world.next_id();
+        world.cells = vec![parent];
+        world.max_cells = 100;
+        world.rebuild_spatial_grid();
+
+        // A quarter goes to the child, half stays, the last quarter is lost
+        world.handle_reproduction(REPRODUCTION_ENERGY_THRESHOLD);
+        assert_eq!(world.cells.len(), 2);
+        assert!((world.cells[1].energy - 100.0).abs() < 1e-4);
+        assert!((world.cells[0].energy - 200.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_configured_sensor_range_limits_sensing() {
+        let mut world = feeding_world(10.0);
+        world.config.sensor_range = 100.0;
+
+        // Exactly at the configured range: still sensed
+        world.cells[1].x = 600.0;
+        world.rebuild_spatial_grid();
+        world.update_sensors();
+        assert_eq!(world.cells[0].sensor_range, 100.0);
+        assert_eq!(world.cells[0].nearest_cells.len(), 1);
+        assert!((world.cells[0].dead_center_distance - 100.0).abs() < 1e-3);
+
+        // Just past it: nothing detected, reported at the configured max range
+        world.cells[1].x = 610.0;
+        world.rebuild_spatial_grid();
+        world.update_sensors();
+        assert!(world.cells[0].nearest_cells.is_empty());
+        assert_eq!(world.cells[0].dead_center_distance, 100.0);
+    }
+
+    #[test]
+    fn test_cells_deposit_and_smell_pheromones() {
+        let mut world = feeding_world(10.0);
+        world.cells.truncate(1); // Only the alive cell at (500, 500)
+        world.cells[0].angle = 0.0;
+        world.rebuild_spatial_grid();
+
+        world.update_pheromones();
+        let deposited = world.pheromones.total();
+        assert!(deposited > 0.0);
+        assert!(world.pheromones.concentration(500.0, 500.0) > 0.0);
+
+        // A stronger trail in the next bucket to the right (+x): straight ahead
+        world.pheromones.deposit(650.0, 500.0, 5.0);
+        world.update_sensors();
+        assert!(world.cells[0].pheromone_level > 0.0);
+        assert!(world.cells[0].pheromone_angle.abs() < 1e-4);
+
+        // Facing down (+y), the trail is to the left
+        world.cells[0].angle = std::f32::consts::FRAC_PI_2;
+        world.update_sensors();
+        let angle = world.cells[0].pheromone_angle;
+        assert!(
+            (angle + std::f32::consts::FRAC_PI_2).abs() < 1e-4,
+            "{}",
+            angle
+        );
+
+        // Disabled: the inputs read nothing and the field is left alone
+        world.config.pheromones.enabled = false;
+        world.update_pheromones();
+        world.update_sensors();
+        assert_eq!(world.cells[0].pheromone_level, 0.0);
+        assert_eq!(world.cells[0].pheromone_angle, 0.0);
+        assert!((world.pheromones.total() - deposited - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_corpse_past_max_age_is_removed_with_energy_left() {
+        let mut world = feeding_world(80.0);
+        world.config.corpse_max_age = 100.0;
+        // Out of the alive cell's reach, so nothing eats it
+        world.cells[1].x = 900.0;
+        world.cells[1].corpse_age = 99.0;
+        world.rebuild_spatial_grid();
+
+        world.check_collisions();
+        assert_eq!(world.cells.len(), 2);
+
+        // One more tick as a corpse reaches the limit
+        let energy = world.config.energy;
+        let movement = world.config.movement;
+        world.cells[1].update(
+            2000.0,
+            2000.0,
+            1.0,
+            &energy,
+            &movement,
+            &TemperatureConfig::default(),
+        );
+        assert_eq!(world.cells[1].corpse_age, 100.0);
+        assert!(world.cells[1].energy > DEPLETED_CELL_ENERGY);
+        world.rebuild_spatial_grid();
+        world.check_collisions();
+        assert_eq!(world.cells.len(), 1);
+        assert_eq!(world.cells[0].state, CellState::Alive);
+
+        // 0 disables the limit
+        let mut world = feeding_world(80.0);
+        world.config.corpse_max_age = 0.0;
+        world.cells[1].x = 900.0;
+        world.cells[1].corpse_age = 1.0e6;
+        world.rebuild_spatial_grid();
+        world.check_collisions();
+        assert_eq!(world.cells.len(), 2);
+    }
+
+    #[test]
+    fn test_cell_in_fertile_zone_loses_less_energy_per_tick() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        world.zones = vec![Zone {
+            x: 500.0,
+            y: 500.0,
+            radius: 200.0,
+            kind: ZoneKind::Fertile,
+            metabolism_multiplier: 0.5,
+            food_multiplier: 1.0,
+        }];
+        for x in [500.0, 1500.0] {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            cell.x = x;
+            cell.y = 500.0;
+            cell.energy = 50.0;
+            cell.id = world.next_id();
+            world.cells.push(cell);
+        }
+        world.rebuild_spatial_grid();
+
+        world.run_simulation_tick(FIXED_DT);
+        let inside = 50.0 - world.cells[0].energy;
+        let outside = 50.0 - world.cells[1].energy;
+        assert!(
+            inside > 0.0 && inside < outside,
+            "{} vs {}",
+            inside,
+            outside
+        );
+        assert!(
+            (inside / outside - 0.5).abs() < 1e-3,
+            "{} vs {}",
+            inside,
+            outside
+        );
+    }
+
+    #[test]
+    fn test_succession_regions_scale_metabolism() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            succession: crate::config::SuccessionConfig {
+                enabled: true,
+                columns: 2,
+                rows: 1,
+                transition_chance: 0.0,
+                ..Default::default()
+            },
+            ..test_config()
+        });
+        let regions = world.regions.as_mut().expect("succession enabled");
+        regions.regions[0].energy_multiplier = 2.0; // Rich: half the metabolism
+        regions.regions[1].energy_multiplier = 0.5; // Poor: twice the metabolism
+        for x in [500.0, 1500.0] {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            cell.x = x;
+            cell.y = 500.0;
+            cell.energy = 50.0;
+            cell.id = world.next_id();
+            world.cells.push(cell);
+        }
+        world.rebuild_spatial_grid();
+
+        world.run_simulation_tick(FIXED_DT);
+        let rich = 50.0 - world.cells[0].energy;
+        let poor = 50.0 - world.cells[1].energy;
+        assert!((rich / poor - 0.25).abs() < 1e-3, "{} vs {}", rich, poor);
+        let regions = world.regions.as_ref().unwrap();
+        assert_eq!(regions.food_multiplier(500.0, 500.0), 2.0);
+    }
+
+    #[test]
+    fn test_ticks_push_events_once_and_frames_drain_them() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        for i in 0..5 {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            cell.x = 200.0 + i as f32 * 300.0;
+            cell.y = 500.0;
+            cell.energy = 50.0;
+            cell.id = world.next_id();
+            world.cells.push(cell);
+        }
+        world.cells[0].energy = 0.0; // Dies on the next update
+        world.cells[1].generation = GENERATION_MILESTONE_INTERVAL + 3;
+        let dead_id = world.cells[0].id;
+        world.rebuild_spatial_grid();
+
+        world.run_simulation_tick(FIXED_DT);
+        let events: Vec<SimEvent> = world.events.drain().collect();
+        assert_eq!(events.len(), 3, "{:?}", events);
+        assert!(matches!(events[0], SimEvent::CellDied { id, .. } if id == dead_id));
+        assert!(events.contains(&SimEvent::ExtinctionNear { alive_count: 4 }));
+        assert!(events.contains(&SimEvent::GenerationMilestone {
+            generation: GENERATION_MILESTONE_INTERVAL
+        }));
+
+        // The corpse is not reported again, the warning and the milestone not repeated
+        world.run_simulation_tick(FIXED_DT);
+        assert_eq!(world.events.drain().count(), 0);
+
+        // Frames hand everything to the consumers
+        world.events.push(SimEvent::NewRecord {
+            tier: 0,
+            fitness: 1.0,
+        });
+        world.update(FIXED_DT);
+        assert_eq!(world.events.drain().count(), 0);
+    }
+
+    #[test]
+    fn test_temperature_zones_scale_metabolism_decay_and_feeding() {
+        // Sampled from position: cold at the x = 0 edge, hot mid-world
+        let mut world = feeding_world(500.0);
+        world.config.temperature.enabled = true;
+        world.cells[0].x = 1000.0;
+        world.cells[1].x = 0.0;
+        world.rebuild_spatial_grid();
+        world.update_sensors();
+        assert!((world.cells[0].temperature - 1.0).abs() < 1e-6);
+        assert!(world.cells[1].temperature.abs() < 1e-6);
+
+        // Eating a cold corpse extracts a smaller chunk than a hot one
+        let temperature = world.config.temperature;
+        let mut extracted = Vec::new();
+        for corpse_temperature in [0.0, 1.0] {
+            let mut world = feeding_world(500.0);
+            world.config.temperature = temperature;
+            world.cells[0].energy_chunk_size = 10.0;
+            world.cells[1].temperature = corpse_temperature;
+            world.check_collisions();
+            assert!(world.cells[0].energy > 50.0);
+            extracted.push(500.0 - world.cells[1].energy);
+        }
+        let expected = temperature.cold_feeding / temperature.hot_feeding;
+        assert!(
+            (extracted[0] / extracted[1] - expected).abs() < 1e-4,
+            "{:?}",
+            extracted
+        );
+
+        // Hot zones drain the living and rot corpses faster
+        let energy = EnergyConfig::default();
+        let movement = MovementConfig::default();
+        let mut lost = Vec::new();
+        for (state, cell_temperature) in [
+            (CellState::Alive, 0.0),
+            (CellState::Alive, 1.0),
+            (CellState::Corpse, 0.0),
+            (CellState::Corpse, 1.0),
+        ] {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            cell.state = state;
+            cell.energy = 100.0;
+            cell.temperature = cell_temperature;
+            cell.update(2000.0, 2000.0, 1.0, &energy, &movement, &temperature);
+            lost.push(100.0 - cell.energy);
+        }
+        // Metabolism also carries the hunger ramp, so compare cold against hot
+        let expected = temperature.cold_metabolism / temperature.hot_metabolism;
+        assert!((lost[0] / lost[1] - expected).abs() < 1e-3, "{:?}", lost);
+        let decay = energy.corpse_decay_rate;
+        assert!((lost[2] - decay * temperature.cold_corpse_decay).abs() < 1e-5);
+        assert!((lost[3] - decay * temperature.hot_corpse_decay).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_decayed_corpse_recycles_into_edible_pellets() {
+        let mut world = feeding_world(0.0);
+        world.config.nutrient_recycle_fraction = 0.5;
+        world.cells[1].decayed_energy = 60.0;
+        // Pellets land within the corpse radius, all inside the alive cell's reach
+        world.cells[0].radius = 20.0;
+        world.cells[1].radius = 10.0;
+
+        // The empty corpse is removed and leaves half its decayed energy as pellets
+        world.check_collisions();
+        assert_eq!(world.cells.len(), 1);
+        assert_eq!(world.food.pellets.len(), PELLETS_PER_CORPSE);
+        assert!((world.food.total_energy() - 30.0).abs() < 1e-4);
+
+        // Pellets are sensed as food (not alive) and never resolve to a cell
+        world.food.rebuild_grid();
+        world.rebuild_spatial_grid();
+        world.update_sensors();
+        let readings = &world.cells[0].nearest_cells;
+        assert_eq!(readings.len(), PELLETS_PER_CORPSE);
+        assert!(
+            readings
+                .iter()
+                .all(|r| !r.is_alive && world.index_of(r.id).is_none())
+        );
+
+        // The alive cell overlaps them all and eats them
+        let energy_before = world.cells[0].energy;
+        world.eat_food_pellets();
+        assert!(world.food.pellets.is_empty());
+        assert!((world.cells[0].energy - energy_before - 30.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_food_bloom_quadruples_recycled_food_until_it_ends() {
+        // Legacy feeding: the corpse is removed once drained past DEPLETED_CELL_ENERGY
+        let mut world = feeding_world(DEPLETED_CELL_ENERGY - 1.0);
+        world.config.use_storage = false;
+        world.config.strict_energy_conservation = false;
+        world.config.nutrient_recycle_fraction = 0.5;
+        world.cells[1].decayed_energy = 60.0;
+        world.start_food_bloom();
+        assert_eq!(world.bloom_count, 1);
+        assert_eq!(world.food_regen_multiplier(), FOOD_BLOOM_MULTIPLIER);
+        assert!(
+            world
+                .events
+                .drain()
+                .any(|event| matches!(event, SimEvent::FoodBloom { .. }))
+        );
+
+        world.check_collisions();
+        assert!((world.food.total_energy() - 30.0 * FOOD_BLOOM_MULTIPLIER).abs() < 1e-3);
+
+        // The countdown runs in simulation time (no new bloom starts while one runs)
+        let mut ticks = 0;
+        while world.food_bloom.unwrap().duration_remaining > FIXED_DT {
+            world.run_simulation_tick(FIXED_DT);
+            ticks += 1;
+        }
+        let expected = crate::events::FOOD_BLOOM_DURATION / FIXED_DT;
+        assert!((ticks as f32 - expected).abs() <= 2.0);
+        assert_eq!(world.bloom_count, 1);
+        world.count_down_food_bloom(FIXED_DT);
+        assert_eq!(world.food_bloom, None);
+        assert_eq!(world.food_regen_multiplier(), 1.0);
+    }
+
+    #[test]
+    fn test_food_bloom_recycles_as_usual_under_strict_conservation() {
+        let mut world = feeding_world(0.0);
+        world.config.nutrient_recycle_fraction = 0.5;
+        world.cells[1].decayed_energy = 60.0;
+        world.start_food_bloom();
+        assert_eq!(world.food_regen_multiplier(), 1.0);
+
+        world.check_collisions();
+        assert!((world.food.total_energy() - 30.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_kin_corpse_yields_bonus_energy() {
+        let mut stranger = feeding_world(500.0);
+        let chunk = stranger.cells[0].energy_chunk_size;
+        stranger.check_collisions();
+        let stranger_gain = stranger.cells[0].energy - 50.0;
+        assert!((stranger_gain - chunk * 2.0).abs() < 1e-3);
+
+        // Same brain: similarity 1.0, full KIN_BONUS
+        let mut kin = feeding_world(500.0);
+        kin.cells[1].brain = kin.cells[0].brain.clone();
+        kin.cells[0].energy_chunk_size = chunk;
+        kin.check_collisions();
+        let kin_gain = kin.cells[0].energy - 50.0;
+        assert!((kin_gain - chunk * 2.0 * (1.0 + KIN_BONUS)).abs() < 1e-3);
+
+        // A reset forgets the cached pair, so reused ids can't inherit it
+        assert!(kin.kin_cache.slots.iter().any(Option::is_some));
+        kin.config.use_storage = false;
+        kin.respawn_from_best();
+        assert!(kin.kin_cache.slots.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_plague_strikes_dominant_hue_cluster() {
+        let mut world = World::new(SimulationConfig {
+            plague_infection_fraction: 1.0,
+            use_storage: false,
+            ..test_config()
+        });
+        // Default spawns share their tier's hue: dominance depends on the tier mix
+        for cell in world.cells.iter_mut() {
+            cell.color = Color::new(0.18, 0.66, 0.9, 1.0);
+        }
+        let minority = world.cells.len() / 10;
+        for cell in world.cells.iter_mut().take(minority) {
+            cell.color = Color::new(0.9, 0.3, 0.18, 1.0);
+        }
+
+        let infected = world.try_start_plague();
+        assert_eq!(infected, world.cells.len() - minority);
+        assert!(world.cells[..minority].iter().all(|c| c.infected.is_none()));
+
+        // No cluster above the threshold: nothing happens
+        for cell in world.cells.iter_mut() {
+            cell.infected = None;
+        }
+        let half = world.cells.len() / 2;
+        for cell in world.cells.iter_mut().take(half) {
+            cell.color = Color::new(0.9, 0.3, 0.18, 1.0);
+        }
+        assert_eq!(world.try_start_plague(), 0);
+    }
+
+    #[test]
+    fn test_immigrants_arrive_after_diversity_stays_low() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            immigration: ImmigrationConfig {
+                enabled: true,
+                stagnation_seconds: FIXED_DT * 3.0,
+                fraction: 0.05,
+                ..ImmigrationConfig::default()
+            },
+            ..test_config()
+        });
+        world.max_cells = world.cells.len() * 2; // Room for the immigrants
+        // One hue species only (hue 200°): diversity 1/24, under the 5% threshold
+        for cell in world.cells.iter_mut() {
+            cell.color = Cell::hsv_to_rgb(200.0, 0.8, 0.9);
+        }
+        world.update_stats();
+        assert!(world.color_diversity < 0.05);
+
+        for _ in 0..2 {
+            world.run_simulation_tick(FIXED_DT);
+        }
+        assert!(world.cells.iter().all(|c| c.immigrant_marker == 0.0));
+        world.run_simulation_tick(FIXED_DT);
+
+        let expected = (world.max_cells as f32 * 0.05).round() as usize;
+        let immigrants: Vec<&Cell> = world
+            .cells
+            .iter()
+            .filter(|c| c.immigrant_marker > 0.0)
+            .collect();
+        assert_eq!(immigrants.len(), expected);
+        for immigrant in immigrants {
+            assert_eq!(immigrant.generation, 0);
+            let (hue, _, _) = Cell::rgb_to_hsv_public(immigrant.color);
+            assert!((hue - 20.0).abs() <= 15.5, "hue {}", hue);
+        }
+        assert_eq!(world.low_diversity_time, 0.0);
+
+        // Never past the cell cap
+        world.max_cells = world.alive_count() + 2;
+        assert_eq!(world.inject_immigrants(), 2);
+        assert_eq!(world.inject_immigrants(), 0);
+        assert_eq!(world.alive_count(), world.max_cells);
+
+        // Disabled by default: the timer never runs
+        let mut quiet = World::new(SimulationConfig {
+            use_storage: false,
+            ..test_config()
+        });
+        quiet.run_simulation_tick(FIXED_DT);
+        assert_eq!(quiet.low_diversity_time, 0.0);
+    }
+
+    #[test]
+    fn test_plague_spreads_on_contact_and_drains_energy() {
+        let mut world = feeding_world(500.0);
+        world.cells[1].infected = Some(PLAGUE_DURATION_TICKS);
+
+        world.check_collisions();
+        assert_eq!(world.cells[0].infected, Some(PLAGUE_DURATION_TICKS));
+
+        let cell = &mut world.cells[0];
+        let energy_before = cell.energy;
+        let mut healthy = cell.clone();
+        healthy.infected = None;
+        let energy = world.config.energy;
+        cell.update(
+            2000.0,
+            2000.0,
+            1.0,
+            &energy,
+            &MovementConfig::default(),
+            &TemperatureConfig::default(),
+        );
+        healthy.update(
+            2000.0,
+            2000.0,
+            1.0,
+            &energy,
+            &MovementConfig::default(),
+            &TemperatureConfig::default(),
+        );
+        assert!(cell.energy < healthy.energy);
+        assert!(cell.energy < energy_before);
+        assert_eq!(cell.infected, Some(PLAGUE_DURATION_TICKS - 1.0));
+    }
+
+    #[test]
+    fn test_world_creation_reads_each_brain_slot_once() {
+        let saved = NeuralNetwork::new(VisionMode::NearestCells.input_count(), 5);
+        let mut loads = 0;
+        let mut world = World::with_brain_loader(
+            SimulationConfig {
+                initial_cell_count: 1000,
+                use_storage: true,
+                ..test_config()
+            },
+            |_, _| {
+                loads += 1;
+                Some((saved.clone(), 3, 10.0))
+            },
+        );
+        // One read per tier slot, however many cells start from those brains
+        assert_eq!(loads, 4);
+        assert_eq!(world.cells.len(), 1000);
+        assert_eq!(world.best_saved_scores, [10.0; 4]);
+
+        // Resets spawn from the cache: each tier's elite is the loaded brain
+        world.config.use_storage = false; // No era log in the working directory
+        world.respawn_from_best();
+        for tier in 0..4 {
+            let elite = world.cells.iter().find(|c| c.brain_tier == tier).unwrap();
+            assert_eq!(elite.brain, saved);
+        }
+
+        let mut loads = 0;
+        World::with_brain_loader(test_config(), |_, _| {
+            loads += 1;
+            None
+        });
+        assert_eq!(loads, 0);
+    }
+
+    #[test]
+    fn test_import_genome_injects_cell() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        let shared = Cell::spawn(2000.0, 2000.0, 1, &None, VisionMode::NearestCells);
+        let json = shared.to_genome_json();
+
+        let cell = crate::storage::genome_from_json(&json, &world.config).expect("fits");
+        let id = world.import_genome(cell);
+        assert_eq!(world.cells.len(), 1);
+        assert_eq!(world.cells[0].id, id);
+        assert_eq!(world.cells[0].brain_tier, 1);
+
+        // Brains from the other vision mode are rejected with a clear error
+        let raycast = Cell::spawn(2000.0, 2000.0, 1, &None, VisionMode::Raycast);
+        let Err(error) = crate::storage::genome_from_json(&raycast.to_genome_json(), &world.config)
+        else {
+            panic!("mismatched vision mode accepted");
+        };
+        assert!(error.contains("Raycast vision (28 inputs)"), "{}", error);
+        assert!(crate::storage::genome_from_json("{}", &world.config).is_err());
+    }
+
+    #[test]
+    fn test_spawn_at_mouse_position() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        world.max_cells = 0; // The spawn tool ignores the cap
+        world.camera.x = 1900.0;
+        world.camera.y = 50.0;
+
+        // Screen center of an 800x600 window, past the right world edge
+        let (x, y) = world.camera.screen_to_world(400.0, 300.0, 800.0, 600.0);
+        assert_eq!((x, y), (2300.0, 350.0));
+
+        let id = world.spawn_at(x, y);
+        let cell = &world.cells[0];
+        assert_eq!(cell.id, id);
+        assert_eq!((cell.x, cell.y), (300.0, 350.0));
+        assert_eq!(cell.energy, SPAWN_TOOL_ENERGY);
+        assert_eq!(world.cells.len(), 1);
+
+        // Behind walls it lands against the right wall instead
+        world.config.movement.wrapping = false;
+        world.spawn_at(x, y);
+        assert_eq!((world.cells[1].x, world.cells[1].y), (2000.0, 350.0));
+    }
+
+    #[test]
+    fn test_cull_weakest_removes_lower_half() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            ..test_config()
+        });
+        // Ten cells whose score is 100 * children_count, plus a corpse that is never culled
+        for children in 0..10 {
+            let id = world.spawn_at(100.0 * children as f32, 500.0);
+            let cell = world.cells.last_mut().unwrap();
+            assert_eq!(cell.id, id);
+            cell.children_count = children;
+            cell.energy_from_cells = 0.0;
+            cell.age = 0.0;
+            cell.tracking_score = 0.0;
+            cell.density_penalty = 0.0;
+        }
+        world.spawn_at(50.0, 50.0);
+        world.cells[10].state = CellState::Corpse;
+        world.rebuild_spatial_grid();
+        world.update_stats();
+        assert!(world.best_cell_genome.is_some());
+
+        assert_eq!(world.cull_weakest(0.5), 5);
+        let mut remaining: Vec<usize> = world
+            .cells
+            .iter()
+            .filter(|c| c.state == CellState::Alive)
+            .map(|c| c.children_count)
+            .collect();
+        remaining.sort_unstable();
+        assert_eq!(remaining, vec![5, 6, 7, 8, 9]);
+        assert_eq!(world.cells.len(), 6);
+        assert_eq!(world.best_cell_genome.as_ref().unwrap().children_count, 9);
+        assert!(world.cells.iter().all(|c| world.index_of(c.id).is_some()));
+    }
+
+    #[test]
+    fn test_extinction_without_genome_restarts_with_fresh_cells() {
+        let extinct_world = |auto_restart_on_extinction: bool| {
+            let mut world = World::new(SimulationConfig {
+                use_storage: false,
+                initial_cell_count: 20,
+                auto_restart_on_extinction,
+                ..test_config()
+            });
+            for cell in &mut world.cells {
+                cell.energy = 0.0; // Everyone dies on the first tick
+            }
+            world
+        };
+
+        let mut world = extinct_world(true);
+        assert!(world.best_cell_genome.is_none());
+        world.run_simulation_tick(FIXED_DT);
+        assert_eq!(world.alive_count(), 20);
+        assert_eq!(world.cells.len(), 20);
+        assert_eq!(world.reset_count, 1);
+        assert_eq!(world.tick_count, 0);
+        assert!(world.cells.iter().all(|c| c.id > 20 && c.generation == 0));
+        assert!(world.cells.iter().all(|c| world.index_of(c.id).is_some()));
+
+        let mut world = extinct_world(false);
+        world.run_simulation_tick(FIXED_DT);
+        assert_eq!(world.alive_count(), 0);
+        assert_eq!(world.reset_count, 0);
+    }
+
+    #[test]
+    fn test_clear_all_keeps_world_empty_until_reset() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            ..test_config()
+        });
+        world.update(FIXED_DT);
+        assert!(world.best_cell_genome.is_some());
+
+        world.clear_all();
+        assert!(world.cells.is_empty());
+        world.update(FIXED_DT);
+        assert!(
+            world.cells.is_empty(),
+            "no extinction respawn after a clear"
+        );
+        assert!(world.best_cell_genome.is_some());
+
+        world.respawn_from_best();
+        assert!(!world.cells.is_empty());
+    }
+
+    #[test]
+    fn test_seed_from_genome_replaces_fraction_of_population() {
+        let action_bias = [0.5, 0.0, 0.0, -0.5];
+        let freeze_layers = FrozenLayers {
+            bias_h: true,
+            ..FrozenLayers::default()
+        };
+        let mut world = World::new(SimulationConfig {
+            action_bias,
+            freeze_layers,
+            ..test_config()
+        });
+        let total = world.cells.len();
+        let ids: Vec<u64> = world.cells.iter().map(|c| c.id).collect();
+        let mut genome = Cell::spawn(2000.0, 2000.0, 3, &None, VisionMode::NearestCells);
+        genome.generation = 99;
+        genome.energy = 3.0; // An exported genome carries the energy it was saved with
+
+        let seeded = world.seed_from_genome(&genome, 0.25);
+        assert_eq!(seeded, total / 4);
+        assert_eq!(world.cells.len(), total);
+        assert_eq!(
+            world.cells.iter().filter(|c| c.generation == 99).count(),
+            seeded
+        );
+        // Copies are set up like every other spawn
+        for cell in world.cells.iter().filter(|c| c.generation == 99) {
+            assert_eq!(cell.brain.action_bias, action_bias);
+            assert!(cell.brain.bias_h_frozen && !cell.brain.ih_frozen);
+            assert_eq!(cell.energy, world.config.energy.starting_energy(cell.mass));
+        }
+        // Ids are kept, so they stay unique
+        assert_eq!(world.cells.iter().map(|c| c.id).collect::<Vec<_>>(), ids);
+    }
+
+    #[test]
+    fn test_density_map_counts_alive_cells_per_bucket() {
+        let mut world = feeding_world(10.0);
+        let mut neighbour = world.cells[0].clone();
+        neighbour.id = world.next_id();
+        neighbour.x = 1500.0;
+        world.cells.push(neighbour);
+        world.rebuild_spatial_grid();
+
+        let density = world.compute_density_map();
+        let (grid_w, grid_h) = world.spatial_grid.dimensions();
+        assert_eq!(density.len(), grid_w * grid_h);
+
+        // One alive cell per occupied bucket; the corpse sharing the first bucket is ignored
+        let first = world.spatial_grid.get_bucket_index(500.0, 500.0);
+        let second = world.spatial_grid.get_bucket_index(1500.0, 500.0);
+        assert_eq!(density[first], 1.0);
+        assert_eq!(density[second], 1.0);
+        assert_eq!(density.iter().filter(|&&d| d > 0.0).count(), 2);
+    }
+
+    #[test]
+    fn test_update_species_caches_species_by_id() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        let mut first = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        first.id = world.next_id();
+        let mut clone = first.clone();
+        clone.id = world.next_id();
+        world.cells = vec![first, clone];
+
+        world.update_species();
+        assert_eq!(world.species_count, 1);
+        let species = world.species_by_id[&world.cells[0].id];
+        assert_eq!(world.species_by_id[&world.cells[1].id], species);
+
+        let cell = &world.cells[0];
+        assert_ne!(
+            cell.display_color(ColorMode::Species, 0, Some(species)),
+            cell.display_color(ColorMode::Species, 0, Some(species + 1))
+        );
+        assert_eq!(cell.display_color(ColorMode::Species, 0, None), GRAY);
+        assert_eq!(
+            cell.display_color(ColorMode::Inherited, 0, None),
+            cell.color
+        );
+    }
+
+    #[test]
+    fn test_energy_config_drives_metabolism() {
+        let cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        let drain = |energy: EnergyConfig| {
+            let mut cell = cell.clone();
+            let before = cell.energy;
+            cell.update(
+                2000.0,
+                2000.0,
+                1.0,
+                &energy,
+                &MovementConfig::default(),
+                &TemperatureConfig::default(),
+            );
+            before - cell.energy
+        };
+
+        let normal = drain(EnergyConfig::default());
+        let doubled = drain(EnergyConfig {
+            metabolism_energy_loss: EnergyConfig::default().metabolism_energy_loss * 2.0,
+            ..EnergyConfig::default()
+        });
+        assert!(normal > 0.0);
+        assert!((doubled - normal - EnergyConfig::default().metabolism_energy_loss).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_genome_diversity_averages_same_tier_pair_distances() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            genome_diversity: true,
+            ..test_config()
+        });
+        assert_eq!(world.compute_genome_diversity(), 0.0);
+
+        // Clones of one brain, plus a tier 1 cell that can't be compared with them
+        let founder = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        world.cells = vec![founder.clone(), founder.clone(), founder.clone()];
+        world.cells.push(Cell::spawn(
+            2000.0,
+            2000.0,
+            1,
+            &None,
+            VisionMode::NearestCells,
+        ));
+        assert_eq!(world.compute_genome_diversity(), 0.0);
+
+        let mut mutant = founder.clone();
+        mutant.brain.mutate(0.5, 0.0);
+        let distance = founder.brain.distance(&mutant.brain);
+        world.cells.push(mutant);
+        // 3 of the 6 tier 0 pairs involve the mutant
+        let expected = distance * 3.0 / 6.0;
+        assert!((world.compute_genome_diversity() - expected).abs() < 1e-4);
+
+        world.genome_diversity = world.compute_genome_diversity();
+        assert_eq!(world.diversity(), world.genome_diversity);
+        world.config.genome_diversity = false;
+        assert_eq!(world.diversity(), world.color_diversity);
+
+        // Big populations are sampled
+        world.cells = (0..200)
+            .map(|_| Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells))
+            .collect();
+        assert!(world.compute_genome_diversity() > 0.0);
+    }
+
+    #[test]
+    fn test_brain_cost_scales_metabolism_with_parameter_count() {
+        let cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        let params = cell.brain.parameter_count();
+        let drain = |energy: EnergyConfig| {
+            let mut cell = cell.clone();
+            let before = cell.energy;
+            cell.update(
+                2000.0,
+                2000.0,
+                1.0,
+                &energy,
+                &MovementConfig::default(),
+                &TemperatureConfig::default(),
+            );
+            before - cell.energy
+        };
+
+        // The default (zero) cost leaves metabolism exactly as it was
+        let normal = drain(EnergyConfig::default());
+        let free = drain(EnergyConfig {
+            brain_cost_per_weight: 0.0,
+            ..EnergyConfig::default()
+        });
+        assert_eq!(normal, free);
+
+        // The brain cost adds to the base cost (scaled by hunger, 1.01x on the first tick)
+        let energy = EnergyConfig {
+            brain_cost_per_weight: 0.0001,
+            ..EnergyConfig::default()
+        };
+        let brain_cost = energy.brain_cost(params);
+        let extra = drain(energy) - normal;
+        assert!(brain_cost > 0.1);
+        assert!((extra - brain_cost * 1.01).abs() < 1e-3);
+    }
+
+    // World whose tier 2 saved brain (the only one) is the elite
+    fn elite_world() -> (World, crate::neural_network::NeuralNetwork) {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            ..test_config()
+        });
+        let elite = Cell::spawn(2000.0, 2000.0, 2, &None, VisionMode::NearestCells).brain;
+        world.cached_best_brains = [None, None, Some((elite.clone(), 12)), None];
+        world.best_saved_scores = [0.0, 0.0, 500.0, 0.0];
+        (world, elite)
+    }
+
+    #[test]
+    fn test_switching_fitness_lets_the_new_metric_save_brains() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            ..test_config()
+        });
+        let mut cell = Cell::spawn(2000.0, 2000.0, 1, &None, VisionMode::NearestCells);
+        (cell.age, cell.children_count, cell.energy_from_cells) = (30.0, 3, 200.0);
+        cell.id = world.next_id();
+        world.cells = vec![cell];
+        // A record from the previous metric, far above anything the next one scores
+        world.best_saved_scores = [1e9; 4];
+        world.save_best_brain();
+        assert!(world.cached_best_brains[1].is_none());
+
+        world.cycle_fitness();
+        world.save_best_brain();
+        let score = world.fitness().score(&world.cells[0]);
+        assert!(world.cached_best_brains[1].is_some());
+        assert_eq!(world.best_saved_scores[1], score);
+    }
+
+    #[test]
+    fn test_respawn_keeps_pristine_elite_genome() {
+        let (mut world, elite) = elite_world();
+        world.respawn_from_best();
+
+        let tier_cells: Vec<&Cell> = world
+            .cells
+            .iter()
+            .filter(|c| c.brain_tier == 2 && c.state == CellState::Alive)
+            .collect();
+        assert!(tier_cells.iter().any(|c| c.brain == elite));
+        // The rest are mutated variants
+        assert!(tier_cells.iter().any(|c| c.brain != elite));
+
+        // The pristine copy is tracked as the elite carrier
+        world.rebuild_spatial_grid();
+        let carrier = world.index_of(world.elite_cell_id.unwrap()).unwrap();
+        assert_eq!(world.cells[carrier].brain, elite);
+    }
+
+    #[test]
+    fn test_top_up_elite_respawns_dead_carrier() {
+        let (mut world, elite) = elite_world();
+        world.max_cells = world.cells.len() + 10;
+        world.config.energy.initial_energy = 321.0;
+        world.rebuild_spatial_grid();
+
+        let id = world.top_up_elite().expect("no carrier yet");
+        world.rebuild_spatial_grid();
+        assert_eq!(world.cells.last().unwrap().brain, elite);
+        assert_eq!(world.cells.last().unwrap().generation, 12);
+        let mass = world.cells.last().unwrap().mass;
+        let starting = world.config.energy.starting_energy(mass);
+        assert_eq!(world.cells.last().unwrap().energy, starting);
+
+        // Carrier alive: nothing to do
+        assert!(world.top_up_elite().is_none());
+
+        // Carrier died: a fresh pristine copy is spawned
+        let idx = world.index_of(id).unwrap();
+        world.cells[idx].state = CellState::Corpse;
+        assert!(world.top_up_elite().is_some());
+        assert_eq!(world.cells.last().unwrap().brain, elite);
+    }
+
+    #[test]
+    fn test_trails_only_recorded_when_enabled() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            ..test_config()
+        });
+        for _ in 0..TRAIL_SAMPLE_INTERVAL * 3 {
+            world.run_simulation_tick(FIXED_DT);
+        }
+        assert!(world.cells.iter().all(|c| c.trail.is_empty()));
+
+        world.config.show_trails = true;
+        for _ in 0..TRAIL_SAMPLE_INTERVAL * 3 {
+            world.run_simulation_tick(FIXED_DT);
+        }
+        assert!(
+            world
+                .cells
+                .iter()
+                .filter(|c| c.state == CellState::Alive)
+                .any(|c| !c.trail.is_empty())
+        );
+        // Corpses drop their trail on the next sample
+        assert!(
+            world
+                .cells
+                .iter()
+                .filter(|c| c.state == CellState::Corpse)
+                .all(|c| c.trail.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_cell_ids_are_unique() {
+        let world = World::new(test_config());
+        let mut ids: Vec<u64> = world.cells.iter().map(|c| c.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), world.cells.len());
+        assert!(!ids.contains(&0));
+    }
+
+    #[test]
+    fn test_auto_save_appends_stats_rows() {
+        let path = std::env::temp_dir().join(format!("cells_stats_log_{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut world = World::new(test_config());
+        world.stats_log_path = path.to_string_lossy().into_owned();
+        // The stats log needs storage; keep brain saves out of the working directory
+        world.config.use_storage = true;
+        world.best_saved_scores = [f32::INFINITY; 4];
+
+        let interval = world.config.auto_save_interval;
+        for _ in 0..10 {
+            world.update_auto_save(interval / 2.0);
+            world.update_auto_save(interval / 2.0);
+        }
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 11); // Header + one row per interval
+        assert_eq!(lines[0], crate::storage::STATS_LOG_HEADER);
+    }
+
+    #[test]
+    fn test_compute_species_clusters_by_brain_distance() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            ..test_config()
+        });
+
+        let original = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        let distinct = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        let mut near_duplicate = original.clone();
+        near_duplicate.brain.mutate(0.05, 0.0);
+        world.cells = vec![original, distinct, near_duplicate];
+
+        let species = world.compute_species();
+        assert_eq!(species.len(), 3);
+        assert_eq!(species[0], species[2]);
+        assert_ne!(species[0], species[1]);
+        assert_eq!(world.count_alive_species(&species), 2);
+    }
+
+    #[test]
+    fn test_tick_hash_tracks_cell_state() {
+        let mut world = World::new(test_config());
+        let hash = world.tick_hash();
+        assert_eq!(hash, world.tick_hash());
+
+        world.cells[0].energy += 1.0;
+        let changed = world.tick_hash();
+        assert_ne!(hash, changed);
+
+        world.cells[0].energy -= 1.0;
+        assert_eq!(hash, world.tick_hash());
+    }
+
+    #[test]
+    fn test_raycast_vision_feeds_brains() {
+        let mut world = World::new(SimulationConfig {
+            vision_mode: VisionMode::Raycast,
+            ..test_config()
+        });
+        world.rebuild_spatial_grid();
+        world.update_sensors();
+
+        for cell in &mut world.cells {
+            assert_eq!(cell.brain.input_size, VisionMode::Raycast.input_count());
+            assert_eq!(cell.ray_hits.len(), RAY_COUNT);
+            // Panics on an input size mismatch
+            cell.update(
+                2000.0,
+                2000.0,
+                1.0,
+                &EnergyConfig::default(),
+                &MovementConfig::default(),
+                &TemperatureConfig::default(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_sensor_references_resolve_after_removal() {
+        let mut world = World::new(test_config());
+        world.rebuild_spatial_grid();
+        world.update_sensors();
+
+        // Remove 50% of cells; swap_remove reorders the survivors
+        for i in (0..world.cells.len()).rev().step_by(2) {
+            world.cells.swap_remove(i);
+        }
+        world.rebuild_spatial_grid();
+
+        // Stale references resolve either to the same cell or to nothing
+        for cell in &world.cells {
+            for &SensorReading { id, .. } in cell.nearest_cells.iter() {
+                if let Some(idx) = world.index_of(id) {
+                    assert_eq!(world.cells[idx].id, id);
+                }
+            }
+        }
+
+        // Once sensors are rebuilt, every reference resolves
+        world.update_sensors();
+        let mut references = 0;
+        for cell in &world.cells {
+            for &SensorReading { id, .. } in cell.nearest_cells.iter() {
+                let idx = world.index_of(id).expect("sensor target should resolve");
+                assert_eq!(world.cells[idx].id, id);
+                references += 1;
+            }
+        }
+        assert!(references > 0);
+    }
+
+    #[test]
+    fn test_removed_cell_ids_never_resolve_to_swapped_in_cell() {
+        let mut world = World::new(test_config());
+        world.rebuild_spatial_grid();
+        world.update_sensors();
+        world.update_stats();
+        let selected_id = world
+            .selected_cell_id
+            .expect("best cell is selected by default");
+
+        // Mid-frame removal without any rebuild: the last cell moves into slot 0
+        let removed_id = world.cells[0].id;
+        let moved_id = world.cells.last().unwrap().id;
+        world.cells.swap_remove(0);
+
+        assert_eq!(world.index_of(removed_id), None);
+        for cell in &world.cells {
+            for &SensorReading { id, .. } in cell.nearest_cells.iter() {
+                if let Some(idx) = world.index_of(id) {
+                    assert_eq!(world.cells[idx].id, id);
+                }
+            }
+        }
+
+        // The selection follows its id, not the slot it used to occupy
+        world.rebuild_spatial_grid();
+        assert_eq!(world.cells[world.index_of(moved_id).unwrap()].id, moved_id);
+        if selected_id != removed_id {
+            let idx = world.index_of(selected_id).unwrap();
+            assert_eq!(world.cells[idx].id, selected_id);
+        }
+    }
+
+    #[test]
+    fn test_sensor_overlay_colors_and_arrowheads() {
+        assert_eq!(sensor_line_color(Some(true), 0.5).r, 1.0);
+        assert_eq!(sensor_line_color(Some(false), 0.5).g, 1.0);
+        let empty = sensor_line_color(None, 0.25);
+        assert_eq!((empty.r, empty.g, empty.b, empty.a), (1.0, 1.0, 1.0, 0.25));
+
+        // Empty slots fan out symmetrically, the middle one straight ahead
+        assert_eq!(empty_slot_angle(2), 0.0);
+        assert!((empty_slot_angle(0) + empty_slot_angle(4)).abs() < 1e-6);
+
+        // Arrowhead behind the tip, spread evenly across the line
+        let (left, right) = arrowhead_points(vec2(0.0, 0.0), vec2(10.0, 0.0), 4.0).unwrap();
+        assert_eq!(left.x, 6.0);
+        assert_eq!(right.x, 6.0);
+        assert_eq!(left.y, -right.y);
+        assert_eq!((left.y - right.y).abs(), 4.0);
+        assert!(arrowhead_points(vec2(1.0, 1.0), vec2(1.0, 1.0), 4.0).is_none());
+    }
+
+    #[test]
+    fn test_configured_action_bias_reaches_spawned_cells() {
+        let action_bias = [-1.0, 0.0, 0.0, 0.5];
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 8,
+            action_bias,
+            ..test_config()
+        });
+        assert!(
+            world
+                .cells
+                .iter()
+                .all(|c| c.brain.action_bias == action_bias)
         );
+
+        let id = world.spawn_at(100.0, 100.0);
+        let spawned = world.cells.iter().find(|c| c.id == id).unwrap();
+        assert_eq!(spawned.brain.action_bias, action_bias);
+        // Children inherit it through the brain, mutation leaves it alone
+        let child = spawned.spawn_child(1.0, 0.0, false);
+        assert_eq!(child.brain.action_bias, action_bias);
     }
 
-    fn render_stats(&self, cells_in_viewport: usize) {
-        // Count active cells (state == Alive)
-        let active_cells = self
-            .cells
-            .iter()
-            .filter(|cell| cell.state == CellState::Alive)
-            .count();
-        let total_cells = self.cells.len();
+    #[test]
+    fn test_configured_frozen_layers_reach_spawned_cells() {
+        let freeze_layers = FrozenLayers {
+            weights_ih: true,
+            ..FrozenLayers::default()
+        };
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 8,
+            freeze_layers,
+            ..test_config()
+        });
+        assert!(
+            world
+                .cells
+                .iter()
+                .all(|c| c.brain.ih_frozen && !c.brain.bias_h_frozen && !c.brain.ho_frozen)
+        );
 
-        // Render stats in top-left corner
-        let padding = 20.0;
-        let font_size = 24.0;
-        let line_height = 30.0;
-        let text_color = WHITE;
+        // The config wins over flags carried by the genome a cell is spawned from
+        let mut best = world.cells[0].clone();
+        best.brain.ho_frozen = true;
+        world.best_cell_genome = Some(best);
+        let id = world.spawn_at(100.0, 100.0);
+        let spawned = world.cells.iter().find(|c| c.id == id).unwrap();
+        assert!(spawned.brain.ih_frozen && !spawned.brain.ho_frozen);
+        let child = spawned.spawn_child(1.0, 0.0, false);
+        assert!(child.brain.ih_frozen);
+    }
 
-        // Line 1: FPS
-        let line1 = format!("FPS: {:.1}", self.current_fps);
-        draw_text(&line1, padding, padding + font_size, font_size, text_color);
+    #[test]
+    fn test_cycling_fitness_re_picks_the_best_cell() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        let mut parent = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        parent.id = 1;
+        parent.children_count = 5;
+        parent.energy = 100.0;
+        let mut forager = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        forager.id = 2;
+        forager.energy_from_cells = 2000.0;
+        forager.energy = 100.0;
+        world.cells = vec![parent, forager];
+        world.paused = true;
+
+        world.refresh_after_population_change();
+        assert_eq!(world.config.fitness, FitnessFn::Composite);
+        assert_eq!(world.last_best_cell_id, Some(2));
+
+        // Composite -> energy + children: 5 children outweigh the forager's energy
+        world.cycle_fitness();
+        assert_eq!(world.last_best_cell_id, Some(1));
+
+        // -> lifetime energy, picked without a tick
+        world.cycle_fitness();
+        assert_eq!(world.config.fitness, FitnessFn::LifetimeEnergy);
+        assert_eq!(world.last_best_cell_id, Some(2));
+        assert_eq!(world.tick_count, 0);
+
+        // A plugin overrides the configured function until cycled away
+        world.set_fitness_plugin(Some(|cell| cell.children_count as f32));
+        assert_eq!(world.last_best_cell_id, Some(1));
+        world.cycle_fitness();
+        assert_eq!(world.config.fitness, FitnessFn::ChildrenOnly);
+        assert_eq!(world.fitness().name(), "children only");
+    }
 
-        // Line 2: Total active cells / total cells / max cap
-        let line2 = format!(
-            "Cells: {} / {} (cap: {})",
-            active_cells, total_cells, self.max_cells
+    #[test]
+    fn test_host_commands_control_the_world() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 10,
+            ..test_config()
+        });
+
+        world.apply_command(SimCommand::Pause(true));
+        world.apply_command(SimCommand::SetSpeed(20.0));
+        world.apply_command(SimCommand::SetSpeed(f32::NAN));
+        let stats = world.sim_stats();
+        assert!(stats.paused);
+        assert_eq!(stats.speed, MAX_SIMULATION_SPEED);
+        assert_eq!(stats.population, 10);
+
+        // Partial config: nested sections keep their other fields
+        let metabolism = world.config.energy.metabolism_energy_loss;
+        world.apply_command(SimCommand::SetConfigJson(
+            r#"{"weight_decay": 0.5, "energy": {"corpse_decay_rate": 0.25}}"#.to_owned(),
+        ));
+        assert_eq!(world.config.weight_decay, 0.5);
+        assert_eq!(world.config.energy.corpse_decay_rate, 0.25);
+        assert_eq!(world.config.energy.metabolism_energy_loss, metabolism);
+        world.apply_command(SimCommand::SetConfigJson(
+            r#"{"weight_decay": "x"}"#.to_owned(),
+        ));
+        assert_eq!(world.config.weight_decay, 0.5);
+
+        // A bare brain joins as a newborn; anything else is rejected
+        let brain = world.cells[0].brain.to_json();
+        world.apply_command(SimCommand::InjectBrainJson(brain));
+        world.apply_command(SimCommand::InjectBrainJson("{}".to_owned()));
+        assert_eq!(world.cells.len(), 11);
+        assert_eq!(world.cells[10].brain, world.cells[0].brain);
+        assert_eq!(world.cells[10].brain_tier, world.cells[0].brain_tier);
+
+        // Resizing the world rebuilds it around the new size
+        world.apply_command(SimCommand::SetConfigJson(
+            r#"{"world_width": 3000.0}"#.to_owned(),
+        ));
+        assert_eq!(world.config.world_width, 3000.0);
+        assert_eq!(world.config.weight_decay, 0.5);
+        assert!(world.cells.iter().all(|cell| cell.x < 3000.0));
+        assert!(!world.paused);
+    }
+
+    #[test]
+    fn test_clicked_cell_overrides_best_cell_until_cleared() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        let mut best = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        best.id = 1;
+        best.children_count = 10;
+        (best.x, best.y) = (500.0, 500.0);
+        let mut edge = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        edge.id = 2;
+        (edge.x, edge.y) = (5.0, 300.0);
+        world.cells = vec![best, edge];
+        world.refresh_after_population_change();
+        assert_eq!(world.last_best_cell_id, Some(1));
+
+        // Nearest within reach, across the world edge; nothing in empty space
+        assert_eq!(world.find_cell_at(-2.0, 300.0, 10.0), Some(1));
+        assert_eq!(world.find_cell_at(1000.0, 1000.0, 10.0), None);
+        assert_eq!(world.pick_cell_at(1000.0, 1000.0, 10.0), None);
+
+        // Behind walls a click past the right wall no longer reaches the left edge
+        world.config.movement.wrapping = false;
+        world.rebuild_spatial_grid();
+        assert_eq!(world.find_cell_at(-2.0, 300.0, 10.0), Some(1));
+        assert_eq!(world.find_cell_at(2005.0, 300.0, 10.0), None);
+        world.config.movement.wrapping = true;
+        world.rebuild_spatial_grid();
+
+        assert_eq!(world.pick_cell_at(1995.0, 300.0, 10.0), Some(2));
+        world.update_stats();
+        assert_eq!(world.selected_cell_id, Some(2));
+        // The best cell is still tracked for saving brains
+        assert_eq!(world.last_best_cell_id, Some(1));
+
+        world.clear_picked_cell();
+        assert_eq!(world.picked_cell_id, None);
+        assert_ne!(world.selected_cell_id, Some(2));
+
+        // A picked cell that leaves the world hands the box back to the best cell
+        world.pick_cell_at(5.0, 300.0, 10.0);
+        world.cells.truncate(1);
+        world.refresh_after_population_change();
+        assert_eq!(world.picked_cell_id, None);
+    }
+
+    #[test]
+    fn test_shift_clicked_cell_is_compared_until_empty_space_is_clicked() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        for (id, x) in [(1, 500.0), (2, 800.0)] {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            cell.id = id;
+            (cell.x, cell.y) = (x, 500.0);
+            cell.children_count = id as usize * 5;
+            world.cells.push(cell);
+        }
+        world.refresh_after_population_change();
+
+        world.pick_cell_at(500.0, 500.0, 10.0);
+        assert_eq!(world.compare_cell_at(800.0, 500.0, 10.0), Some(2));
+        let comparison = world.cell_comparison().unwrap();
+        assert_eq!(comparison.cells[0].id, 1);
+        assert_eq!(comparison.cells[1].id, 2);
+        assert_eq!(comparison.cells[1].children_count, 10);
+        assert_eq!(
+            comparison.weight_diff,
+            crate::neural_network::NeuralNetwork::weight_diff(
+                &world.cells[0].brain,
+                &world.cells[1].brain
+            )
         );
-        draw_text(
-            &line2,
-            padding,
-            padding + font_size + line_height,
-            font_size,
-            text_color,
+
+        // Empty space closes the comparison, the picked cell stays selected
+        assert_eq!(world.compare_cell_at(1500.0, 1500.0, 10.0), None);
+        assert!(world.cell_comparison().is_none());
+        assert_eq!(world.selected_cell_id, Some(1));
+
+        // The comparison closes when one of the cells leaves the world
+        world.compare_cell_at(800.0, 500.0, 10.0);
+        world.cells.truncate(1);
+        world.refresh_after_population_change();
+        assert_eq!(world.compared_cell_ids, None);
+    }
+
+    #[test]
+    fn test_top_cells_rank_alive_cells_by_fitness() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            fitness: FitnessFn::ChildrenOnly,
+            ..test_config()
+        });
+        for (id, children) in [(1, 3), (2, 9), (3, 1), (4, 20), (5, 7), (6, 5), (7, 2)] {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            cell.id = id;
+            cell.children_count = children;
+            world.cells.push(cell);
+        }
+        // The fittest cell is dead: not on the board
+        world.cells[3].state = CellState::Corpse;
+        world.rebuild_spatial_grid();
+
+        let top = world.compute_top_cells(5);
+        let ids: Vec<u64> = top.iter().map(|entry| entry.id).collect();
+        assert_eq!(ids, vec![2, 5, 6, 1, 7]);
+        assert_eq!(top[0].rank, 1);
+        assert_eq!(top[4].rank, 5);
+        assert!(
+            top.windows(2)
+                .all(|pair| pair[0].fitness >= pair[1].fitness)
         );
+        assert_eq!(world.compute_top_cells(10).len(), 6);
 
-        // Line 3: Cells in viewport
-        let line3 = format!("Viewport: {}", cells_in_viewport);
-        draw_text(
-            &line3,
-            padding,
-            padding + font_size + line_height * 2.0,
-            font_size,
-            text_color,
+        // Clicking a row selects its cell
+        assert!(world.select_cell(5));
+        assert_eq!(world.selected_cell_id, Some(5));
+        assert!(!world.select_cell(99));
+    }
+
+    #[test]
+    fn test_bottleneck_keeps_the_fittest_and_repopulates_from_them() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            fitness: FitnessFn::ChildrenOnly,
+            ..test_config()
+        });
+        assert_eq!(world.trigger_bottleneck(), 0);
+
+        for id in 1..=30 {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            cell.id = id;
+            cell.children_count = id as usize;
+            cell.energy = 42.0;
+            (cell.x, cell.y) = (100.0, 100.0);
+            world.cells.push(cell);
+        }
+        world.cells[0].state = CellState::Corpse; // Corpses are left alone
+        world.next_cell_id = 31;
+        world.refresh_after_population_change();
+        world.events.drain().count();
+
+        assert_eq!(world.trigger_bottleneck(), 19);
+        assert_eq!(world.bottleneck_count, 1);
+        for id in 21..=30 {
+            let survivor = &world.cells[world.index_of(id).unwrap()];
+            assert_eq!(survivor.energy, 42.0);
+            assert_eq!((survivor.x, survivor.y), (100.0, 100.0));
+        }
+        assert!(world.index_of(1).is_some());
+        assert!(world.index_of(20).is_none());
+
+        // Refilled to the respawn population with the survivors' offspring, spread out
+        let offspring: Vec<&Cell> = world.cells.iter().filter(|c| c.id > 30).collect();
+        assert_eq!(world.alive_count(), 100);
+        assert_eq!(offspring.len(), 90);
+        assert!(
+            offspring
+                .iter()
+                .all(|c| c.generation == 1 && c.energy == 100.0)
         );
+        let (min_x, max_x) = offspring.iter().fold((f32::MAX, f32::MIN), |(lo, hi), c| {
+            (lo.min(c.x), hi.max(c.x))
+        });
+        assert!(max_x - min_x > 1000.0);
 
-        // Line 4: Simulation state (paused/speed)
-        let state_text = if self.paused {
-            format!("PAUSED (Speed: {:.1}x)", self.simulation_speed)
-        } else {
-            format!("Speed: {:.1}x", self.simulation_speed)
+        let events: Vec<SimEvent> = world.events.drain().collect();
+        assert!(events.contains(&SimEvent::Bottleneck {
+            survivors: 10,
+            offspring: 90
+        }));
+    }
+
+    #[test]
+    fn test_respawn_starting_energy_scales_with_mass_when_enabled() {
+        let mut config = SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            ..test_config()
         };
-        let state_color = if self.paused { YELLOW } else { WHITE };
-        draw_text(
-            &state_text,
-            padding,
-            padding + font_size + line_height * 3.0,
-            font_size,
-            state_color,
+        config.energy.scale_initial_energy_by_mass = true;
+        let mut world = World::new(config);
+        world.respawn_from_best();
+
+        let by_mass = |heaviest: bool| {
+            world
+                .cells
+                .iter()
+                .filter(|c| c.state == CellState::Alive)
+                .max_by(|a, b| {
+                    let order = a.mass.total_cmp(&b.mass);
+                    if heaviest { order } else { order.reverse() }
+                })
+                .unwrap()
+        };
+        let (heavy, light) = (by_mass(true), by_mass(false));
+        assert!(heavy.mass > light.mass);
+        assert!(heavy.energy > light.energy);
+        assert!((heavy.energy / light.energy - heavy.mass / light.mass).abs() < 1e-4);
+        assert!(
+            world
+                .cells
+                .iter()
+                .filter(|c| c.state == CellState::Alive)
+                .all(|c| c.energy == 0.5 * c.mass)
         );
+    }
 
-        // Line 5: Cumulative ticks
-        let ticks_text = format!("Ticks: {} ({} resets)", self.tick_count, self.reset_count);
-        draw_text(
-            &ticks_text,
-            padding,
-            padding + font_size + line_height * 4.0,
-            font_size,
-            text_color,
+    #[test]
+    fn test_config_reload_keeps_the_old_config_on_parse_errors() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            ..test_config()
+        });
+        let width = world.config.world_width;
+
+        world.apply_config_reload(
+            "cells.json",
+            SimulationConfig::from_json("{ broken").map(|c| (c.clone(), c)),
         );
+        assert_eq!(world.config.weight_decay, test_config().weight_decay);
+
+        let file = world.config.clone();
+        let edited = file
+            .with_overrides(r#"{"weight_decay": 0.25, "world_width": 10.0}"#)
+            .unwrap();
+        world.apply_config_reload("cells.json", Ok((file, edited)));
+        assert_eq!(world.config.weight_decay, 0.25);
+        assert_eq!(world.config.world_width, width); // Needs a restart
+    }
 
-        // Lines 6-10: Per-tier population bars + total
-        let bar_max_width = 200.0_f32;
-        let bar_height = 14.0_f32;
-        // Base hue per tier: 180 + tier * 90 (same as Cell::spawn)
-        let tier_hues = [180.0_f32, 270.0, 0.0, 90.0];
-        let total_alive = self.tier_cell_counts.iter().sum::<usize>().max(1);
+    #[test]
+    fn test_tab_cycles_top_cells_with_stable_ranks() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            fitness: FitnessFn::ChildrenOnly,
+            ..test_config()
+        });
+        // Cell id n has n children: ids 12 down to 3 are the top 10
+        for id in 1..=12 {
+            let mut cell = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+            cell.id = id;
+            cell.children_count = id as usize;
+            world.cells.push(cell);
+        }
+        world.refresh_after_population_change();
+        assert_eq!(world.top_cell_ids.len(), TRACKED_TOP_CELLS);
+
+        assert_eq!(world.cycle_tracked_cell(true), Some(12));
+        assert_eq!(world.cycle_tracked_cell(true), Some(11));
+        assert_eq!(world.cycle_tracked_cell(false), Some(12));
+        // Shift+Tab from the top wraps to the 10th
+        assert_eq!(world.cycle_tracked_cell(false), Some(3));
+        assert_eq!(world.tracked_rank, Some(9));
+        assert!(world.stats.is_selected());
+
+        // Rankings shuffling within the second don't move the cycle order,
+        // and cells that died since the re-rank are skipped
+        world.cells[0].children_count = 100;
+        world.cells[11].state = CellState::Corpse;
+        world.update_stats();
+        assert_eq!(world.cycle_tracked_cell(true), Some(11));
+
+        // Home: back to the best cell, Escape: stop following
+        world.track_best_cell();
+        assert_eq!(world.picked_cell_id, None);
+        assert_eq!(world.tracked_rank, None);
+        world.stop_following();
+        assert!(!world.stats.is_selected());
+    }
 
-        // Sort tiers by best saved score (descending)
-        let mut tier_indices: Vec<usize> = (0..4).collect();
-        tier_indices.sort_unstable_by(|&a, &b| {
-            self.best_saved_scores[b]
-                .partial_cmp(&self.best_saved_scores[a])
-                .unwrap_or(std::cmp::Ordering::Equal)
+    #[test]
+    fn test_selected_cell_detail_reports_sensors_and_last_action() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            initial_cell_count: 0,
+            ..test_config()
         });
+        let mut watched = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        watched.id = 1;
+        (watched.x, watched.y) = (500.0, 500.0);
+        watched.age = 50.0;
+        watched.last_action = Some(3);
+        let mut neighbor = Cell::spawn(2000.0, 2000.0, 0, &None, VisionMode::NearestCells);
+        neighbor.id = 2;
+        (neighbor.x, neighbor.y) = (600.0, 500.0);
+        world.cells = vec![watched, neighbor];
+        world.refresh_after_population_change();
+        world.update_sensors();
+        world.selected_cell_id = None;
+        assert!(world.selected_cell_detail().is_none());
+
+        world.pick_cell_at(500.0, 500.0, 10.0);
+        let detail = world.selected_cell_detail().expect("a cell is selected");
+        assert_eq!(detail.id, 1);
+        assert_eq!(detail.last_action, Some("forward"));
+        assert!((detail.age_energy_drain - 0.15).abs() < 1e-6);
+        assert_eq!(detail.sensors.len(), 1);
+        assert!(detail.sensors[0].is_alive);
+        assert!((detail.sensors[0].distance - 100.0).abs() < 1e-3);
+    }
 
-        for (display_idx, &tier) in tier_indices.iter().enumerate() {
-            let tier_hue = tier_hues[tier];
-            let y_base = padding + font_size + line_height * (5.0 + display_idx as f32);
-            let count = self.tier_cell_counts[tier];
-            let max_score = self.best_saved_scores[tier];
+    #[test]
+    fn test_run_totals_freeze_while_paused_and_survive_resets() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            ..test_config()
+        });
+        world.update(FIXED_DT * 3.5);
+        assert_eq!(world.total_ticks, 3);
+
+        world.paused = true;
+        world.update(1.0);
+        assert_eq!(world.total_ticks, 3);
+        world.step_paused();
+        assert_eq!(world.total_ticks, 4);
+        assert!((world.sim_time - 4.0 * FIXED_DT as f64).abs() < 1e-9);
+
+        world.respawn_from_best();
+        assert_eq!(world.tick_count, 0);
+        assert_eq!(world.total_ticks, 4);
+    }
 
-            // Tier label color derived from base hue (s=0.8, v=0.9)
-            let h = tier_hue;
-            let tier_color = {
-                let s = 0.8_f32;
-                let v = 0.9_f32;
-                let c = v * s;
-                let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
-                let m = v - c;
-                let (r, g, b) = if h < 60.0 {
-                    (c, x, 0.0)
-                } else if h < 120.0 {
-                    (x, c, 0.0)
-                } else if h < 180.0 {
-                    (0.0, c, x)
-                } else if h < 240.0 {
-                    (0.0, x, c)
-                } else if h < 300.0 {
-                    (x, 0.0, c)
-                } else {
-                    (c, 0.0, x)
-                };
-                Color::new(r + m, g + m, b + m, 1.0)
-            };
+    #[test]
+    fn test_step_advances_one_tick_while_paused() {
+        let mut world = World::new(SimulationConfig {
+            use_storage: false,
+            ..test_config()
+        });
+        world.paused = true;
+        world.update(1.0);
+        assert_eq!(world.tick_count, 0);
+
+        world.step_paused();
+        assert_eq!(world.tick_count, 1);
+        assert!(world.paused);
+
+        // Running worlds ignore the step key
+        world.paused = false;
+        world.step_paused();
+        assert_eq!(world.tick_count, 1);
+
+        // Holding: 10 steps per second regardless of frame rate, remainder carried
+        let mut held = 0.0;
+        assert_eq!(
+            held_key_repeats(&mut held, 0.05, STEP_KEY_REPEAT_INTERVAL),
+            0
+        );
+        assert_eq!(
+            held_key_repeats(&mut held, 0.06, STEP_KEY_REPEAT_INTERVAL),
+            1
+        );
+        assert_eq!(
+            held_key_repeats(&mut held, 0.25, STEP_KEY_REPEAT_INTERVAL),
+            2
+        );
+        assert!((held - 0.06).abs() < 1e-4);
+    }
 
-            let label = format!("m{}:", tier);
-            let label_width = measure_text(&label, self.font.as_ref(), font_size as u16, 1.0).width;
-            draw_text_ex(
-                &label,
-                padding,
-                y_base,
-                TextParams {
-                    font: self.font.as_ref(),
-                    font_size: font_size as u16,
-                    color: tier_color,
-                    ..Default::default()
-                },
-            );
+    #[test]
+    fn test_fixed_timestep_one_step_per_frame_at_normal_speed() {
+        let mut accumulator = 0.0;
+        for _ in 0..120 {
+            let (steps, leftover) = fixed_timestep_steps(accumulator, FIXED_DT, 1.0);
+            assert_eq!(steps, 1);
+            accumulator = leftover;
+        }
+    }
 
-            // Progress bar
-            let bar_x = padding + label_width + 6.0;
-            let bar_y = y_base - bar_height + 2.0;
-            let fill_width = (count as f32 / total_alive as f32) * bar_max_width;
-            // Background track
-            draw_rectangle(
-                bar_x,
-                bar_y,
-                bar_max_width,
-                bar_height,
-                Color::new(1.0, 1.0, 1.0, 0.1),
-            );
-            // Filled portion
-            if fill_width > 0.0 {
-                draw_rectangle(
-                    bar_x,
-                    bar_y,
-                    fill_width,
-                    bar_height,
-                    Color::new(tier_color.r, tier_color.g, tier_color.b, 0.7),
-                );
-            }
+    #[test]
+    fn test_fixed_timestep_speed_multiplies_steps() {
+        let (steps, _) = fixed_timestep_steps(0.0, FIXED_DT, 2.0);
+        assert_eq!(steps, 2);
+        let (steps, _) = fixed_timestep_steps(0.0, FIXED_DT, 8.0);
+        assert_eq!(steps, 8);
+    }
 
-            // Count + max score text
-            let info = format!(" {} cells (top score: {:.0})", count, max_score);
-            let info_x = bar_x + bar_max_width + 4.0;
-            draw_text_ex(
-                &info,
-                info_x,
-                y_base,
-                TextParams {
-                    font: self.font.as_ref(),
-                    font_size: font_size as u16,
-                    color: WHITE,
-                    ..Default::default()
-                },
-            );
+    #[test]
+    fn test_fixed_timestep_slow_frames_run_more_steps() {
+        // A 30 FPS frame at 1x covers two fixed steps
+        let (steps, _) = fixed_timestep_steps(0.0, 2.0 * FIXED_DT, 1.0);
+        assert_eq!(steps, 2);
+    }
 
-            // Add score diff if current best beats saved best
-            let current_best = self.tier_current_best_scores[tier];
-            if current_best > max_score && max_score > 0.0 {
-                let diff = current_best - max_score;
-                let diff_text = format!(" + {:.1}", diff);
-                let info_width =
-                    measure_text(&info, self.font.as_ref(), font_size as u16, 1.0).width;
-                draw_text_ex(
-                    &diff_text,
-                    info_x + info_width,
-                    y_base,
-                    TextParams {
-                        font: self.font.as_ref(),
-                        font_size: font_size as u16,
-                        color: Color::new(0.0, 1.0, 0.0, 1.0), // Bright green
-                        ..Default::default()
-                    },
-                );
-            }
-        }
+    #[test]
+    fn test_fixed_timestep_fast_frames_carry_leftover() {
+        // 120 FPS: a step every other frame
+        let (steps, leftover) = fixed_timestep_steps(0.0, FIXED_DT / 2.0, 1.0);
+        assert_eq!(steps, 0);
+        let (steps, leftover) = fixed_timestep_steps(leftover, FIXED_DT / 2.0, 1.0);
+        assert_eq!(steps, 1);
+        assert!(leftover < STEP_EPSILON);
+    }
 
-        // Total line
-        let total_diversity = self.tier_diversities.iter().sum::<f32>() / 4.0;
-        let total_y = padding + font_size + line_height * 9.0;
-        let total_text = format!(
-            "total: {} cells ({:.0}% diversity)",
-            total_alive,
-            total_diversity * 100.0
+    #[test]
+    fn test_fixed_timestep_caps_steps_and_drops_backlog() {
+        let (steps, leftover) = fixed_timestep_steps(0.0, 1.0, 8.0);
+        assert_eq!(steps, MAX_STEPS_PER_FRAME);
+        assert_eq!(leftover, 0.0);
+    }
+
+    #[test]
+    fn test_cell_cap_hysteresis() {
+        let config = SimulationConfig::default();
+        // Inside the band: hold and reset the streak
+        assert_eq!(next_cell_cap(1000, 50.0, false, 1, &config), (1000, 0));
+        // Below the band: shrink immediately
+        assert_eq!(next_cell_cap(1000, 40.0, false, 1, &config), (900, 0));
+        // Above the band: grow only once sustained
+        assert_eq!(next_cell_cap(1000, 60.0, true, 0, &config), (1000, 1));
+        assert_eq!(next_cell_cap(1000, 60.0, true, 1, &config), (1020, 2));
+        // Lots of headroom grows quickly, unless vsync hides it
+        assert_eq!(next_cell_cap(1000, 300.0, false, 1, &config), (1100, 2));
+        assert_eq!(next_cell_cap(1000, 300.0, true, 1, &config), (1020, 2));
+        // Clamped to the cap range
+        assert_eq!(
+            next_cell_cap(50, 10.0, false, 0, &config).0,
+            config.min_cells
         );
-        draw_text_ex(
-            &total_text,
-            padding,
-            total_y,
-            TextParams {
-                font: self.font.as_ref(),
-                font_size: font_size as u16,
-                color: Color::new(0.8, 0.8, 0.8, 1.0),
-                ..Default::default()
-            },
+        assert_eq!(
+            next_cell_cap(config.max_cells_limit, 300.0, false, 1, &config).0,
+            config.max_cells_limit
         );
+    }
 
-        // Controls help (bottom-left)
-        let help_y = screen_height() - padding - font_size * 3.0;
-        let help_font_size = 18.0;
-        let help_color = Color::new(0.7, 0.7, 0.7, 1.0);
-        draw_text(
-            "Controls: SPACE=Pause | R=Reset | +/-=Speed | 1=Normal Speed",
-            padding,
-            help_y,
-            help_font_size,
-            help_color,
-        );
+    #[test]
+    fn test_adjust_cell_cap_never_drops_below_min_cells() {
+        let mut world = World::new(SimulationConfig {
+            initial_cell_count: 0,
+            use_storage: false,
+            min_cells: 300,
+            ..SimulationConfig::default()
+        });
+        world.max_cells = 2000;
+
+        // Sustained low FPS: every adjustment interval shrinks the cap
+        for _ in 0..100 {
+            world.frame_times = (0..FPS_SAMPLE_SIZE).map(|_| 1.0 / 10.0).collect();
+            world.update_fps(1.0 / 10.0);
+            world.last_adjustment_time = ADJUSTMENT_INTERVAL;
+            world.adjust_cell_cap();
+            assert!(world.max_cells >= 300);
+        }
+        assert_eq!(world.max_cells, 300);
+    }
+
+    #[test]
+    fn test_vsync_detection() {
+        let steady: VecDeque<f32> = (0..FPS_SAMPLE_SIZE)
+            .map(|i| 1.0 / 60.0 + if i % 2 == 0 { 0.0002 } else { -0.0002 })
+            .collect();
+        assert!(is_vsync_locked(&steady));
+
+        let jittery: VecDeque<f32> = (0..FPS_SAMPLE_SIZE)
+            .map(|i| if i % 2 == 0 { 0.010 } else { 0.024 })
+            .collect();
+        assert!(!is_vsync_locked(&jittery));
+
+        // Steady but not at a refresh rate (e.g. CPU-bound at 50 FPS)
+        let unlocked: VecDeque<f32> = (0..FPS_SAMPLE_SIZE).map(|_| 1.0 / 50.0).collect();
+        assert!(!is_vsync_locked(&unlocked));
+    }
+
+    #[test]
+    fn test_frame_time_height_is_log_scaled_and_clamped() {
+        assert_eq!(frame_time_height(FRAME_GRAPH_FASTEST, 24.0), 0.0);
+        assert_eq!(frame_time_height(0.0, 24.0), 0.0);
+        assert!((frame_time_height(FRAME_GRAPH_SLOWEST, 24.0) - 24.0).abs() < 1e-4);
+        assert_eq!(frame_time_height(1.0, 24.0), 24.0); // A one second hitch
+        // Each halving of the frame rate climbs the same distance (32x range = 5 steps)
+        let fps_240 = frame_time_height(1.0 / 240.0, 24.0);
+        let fps_120 = frame_time_height(1.0 / 120.0, 24.0);
+        assert!((fps_240 - 24.0 / 5.0).abs() < 1e-3, "{}", fps_240);
+        assert!((fps_120 - fps_240 * 2.0).abs() < 1e-3, "{}", fps_120);
+    }
+
+    #[test]
+    fn test_metabolism_multiplier_peaks_at_midnight() {
+        let peak = metabolism_multiplier_at(0.0, 1.5);
+        assert!((peak - 1.5).abs() < 1e-5);
+        // Wraps: end of the cycle is midnight again
+        let wrapped = metabolism_multiplier_at(1.0, 1.5);
+        assert!((wrapped - 1.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_metabolism_multiplier_troughs_at_noon() {
+        let trough = metabolism_multiplier_at(0.5, 1.5);
+        assert!((trough - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_metabolism_multiplier_midpoint_at_dawn_and_dusk() {
+        let dawn = metabolism_multiplier_at(0.25, 1.5);
+        let dusk = metabolism_multiplier_at(0.75, 1.5);
+        assert!((dawn - 1.25).abs() < 1e-5);
+        assert!((dusk - 1.25).abs() < 1e-5);
     }
 }
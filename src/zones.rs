@@ -0,0 +1,154 @@
+use crate::camera::ViewBounds;
+use crate::config::ZoneConfig;
+use macroquad::prelude::*;
+
+const FERTILE_COLOR: Color = Color::new(0.3, 0.9, 0.4, 0.08);
+const BARREN_COLOR: Color = Color::new(0.8, 0.5, 0.2, 0.08);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZoneKind {
+    Fertile, // More recycled food, cheaper metabolism
+    Barren,  // Less recycled food, costlier metabolism
+}
+
+/// Circular energy region. Cells inside pay `metabolism_multiplier` times the base
+/// metabolism, and corpses removed inside recycle `food_multiplier` times the
+/// usual energy into pellets. Overlapping zones multiply.
+#[derive(Debug, Clone, Copy)]
+pub struct Zone {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub kind: ZoneKind,
+    pub metabolism_multiplier: f32,
+    pub food_multiplier: f32,
+}
+
+impl Zone {
+    // Distance measured the short way around the wrapped world
+    pub fn contains(&self, x: f32, y: f32, world_width: f32, world_height: f32) -> bool {
+        let mut dx = (x - self.x).abs() % world_width;
+        let mut dy = (y - self.y).abs() % world_height;
+        dx = dx.min(world_width - dx);
+        dy = dy.min(world_height - dy);
+        dx * dx + dy * dy <= self.radius * self.radius
+    }
+}
+
+/// Random zones at uniform positions, `fertile_fraction` of them fertile.
+/// Empty (and no random numbers drawn) when `count` is 0.
+pub fn generate(config: &ZoneConfig, world_width: f32, world_height: f32) -> Vec<Zone> {
+    let min_radius = config.min_radius.max(1.0);
+    let max_radius = config.max_radius.max(min_radius);
+    (0..config.count)
+        .map(|_| {
+            let kind = if rand::gen_range(0.0, 1.0) < config.fertile_fraction {
+                ZoneKind::Fertile
+            } else {
+                ZoneKind::Barren
+            };
+            let (metabolism_multiplier, food_multiplier) = match kind {
+                ZoneKind::Fertile => (config.fertile_metabolism, config.fertile_food),
+                ZoneKind::Barren => (config.barren_metabolism, config.barren_food),
+            };
+            Zone {
+                x: rand::gen_range(0.0, world_width),
+                y: rand::gen_range(0.0, world_height),
+                radius: rand::gen_range(min_radius, max_radius),
+                kind,
+                metabolism_multiplier,
+                food_multiplier,
+            }
+        })
+        .collect()
+}
+
+/// Combined metabolism multiplier of every zone covering (x, y), 1.0 outside all
+pub fn metabolism_multiplier(zones: &[Zone], x: f32, y: f32, world_size: (f32, f32)) -> f32 {
+    zones
+        .iter()
+        .filter(|zone| zone.contains(x, y, world_size.0, world_size.1))
+        .map(|zone| zone.metabolism_multiplier)
+        .product()
+}
+
+/// Combined food multiplier of every zone covering (x, y), 1.0 outside all
+pub fn food_multiplier(zones: &[Zone], x: f32, y: f32, world_size: (f32, f32)) -> f32 {
+    zones
+        .iter()
+        .filter(|zone| zone.contains(x, y, world_size.0, world_size.1))
+        .map(|zone| zone.food_multiplier)
+        .product()
+}
+
+// Faint tinted discs, repeated across the wrapped world edges like the cells
+pub fn render(
+    zones: &[Zone],
+    camera_x: f32,
+    camera_y: f32,
+    view: &ViewBounds,
+    world_width: f32,
+    world_height: f32,
+) {
+    for zone in zones {
+        let color = match zone.kind {
+            ZoneKind::Fertile => FERTILE_COLOR,
+            ZoneKind::Barren => BARREN_COLOR,
+        };
+        for offset_x in [-world_width, 0.0, world_width] {
+            for offset_y in [-world_height, 0.0, world_height] {
+                let x = zone.x + offset_x - camera_x;
+                let y = zone.y + offset_y - camera_y;
+                if view.contains(x, y, zone.radius) {
+                    draw_circle(x, y, zone.radius, color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(x: f32, y: f32, radius: f32, metabolism: f32) -> Zone {
+        Zone {
+            x,
+            y,
+            radius,
+            kind: ZoneKind::Fertile,
+            metabolism_multiplier: metabolism,
+            food_multiplier: 2.0,
+        }
+    }
+
+    #[test]
+    fn test_zones_wrap_and_overlaps_multiply() {
+        let world = (1000.0, 800.0);
+        let zones = [zone(20.0, 400.0, 50.0, 0.5), zone(60.0, 400.0, 50.0, 0.8)];
+
+        // Across the left world edge
+        assert!(zones[0].contains(990.0, 400.0, world.0, world.1));
+        assert_eq!(metabolism_multiplier(&zones, 990.0, 400.0, world), 0.5);
+        assert_eq!(metabolism_multiplier(&zones, 40.0, 400.0, world), 0.4);
+        assert_eq!(food_multiplier(&zones, 40.0, 400.0, world), 4.0);
+        assert_eq!(metabolism_multiplier(&zones, 500.0, 400.0, world), 1.0);
+    }
+
+    #[test]
+    fn test_generate_respects_count_and_kinds() {
+        let mut config = ZoneConfig::default();
+        assert!(generate(&config, 1000.0, 1000.0).is_empty());
+
+        config.count = 12;
+        config.fertile_fraction = 1.0;
+        let zones = generate(&config, 1000.0, 1000.0);
+        assert_eq!(zones.len(), 12);
+        for zone in &zones {
+            assert_eq!(zone.kind, ZoneKind::Fertile);
+            assert_eq!(zone.metabolism_multiplier, config.fertile_metabolism);
+            assert!((config.min_radius..=config.max_radius).contains(&zone.radius));
+            assert!((0.0..1000.0).contains(&zone.x));
+        }
+    }
+}